@@ -5,7 +5,7 @@ use chain_indexer::{
 };
 use clap::Parser;
 use futures::{Stream, StreamExt, TryStreamExt};
-use std::{pin::Pin, time::Duration};
+use std::{num::NonZeroUsize, pin::Pin, time::Duration};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -33,9 +33,14 @@ impl Cli {
     async fn run(self) -> anyhow::Result<()> {
         let config = Config {
             url: self.node,
+            fallback_urls: vec![],
             reconnect_max_delay: Duration::from_secs(1),
             reconnect_max_attempts: 1,
             subscription_recovery_timeout: Duration::from_secs(30),
+            prefetch_window: NonZeroUsize::new(16).unwrap(),
+            health_check_interval: Duration::from_secs(10),
+            max_lag: 10,
+            quorum: false,
         };
         let mut node = SubxtNode::new(config).await.context("create SubxtNode")?;
 
@@ -12,6 +12,8 @@
 // limitations under the License.
 
 mod metrics;
+pub mod pending_transactions;
+pub mod snapshot;
 
 use crate::{
     application::metrics::Metrics,
@@ -24,9 +26,9 @@ use crate::{
 use anyhow::{Context, bail};
 use async_stream::stream;
 use fastrace::{Span, future::FutureExt, prelude::SpanContext, trace};
-use futures::{Stream, StreamExt, TryStreamExt, future::ok};
+use futures::{Stream, StreamExt, TryStreamExt, future};
 use indexer_common::domain::{
-    BlockIndexed, BridgeEventIndexed, LedgerVersion, NetworkId, Publisher,
+    BlockIndexed, BlocksFinalized, BridgeEventIndexed, LedgerVersion, NetworkId, Publisher,
     SerializedLedgerStateKey, UnshieldedUtxoIndexed,
 };
 use log::{debug, info, warn};
@@ -64,6 +66,17 @@ pub struct Config {
     /// is unpersisted. Must comfortably exceed indexer-api's block-hash snapshot reads, e.g.
     /// the dust generations subscription's max_snapshot_age, or those reads hit culled state.
     pub ledger_state_retention: NonZeroUsize,
+
+    /// Whether to also index best, i.e. not yet finalized, blocks. These are saved as not
+    /// finalized, marked finalized once the node has finalized them and rolled back if abandoned
+    /// by a reorg, which therefore must not be deeper than ledger_state_retention. Notice that
+    /// all data of not yet finalized blocks is visible to indexer-api and wallet-indexer.
+    #[serde(default)]
+    pub follow_best_blocks: bool,
+
+    /// Follow the node's transaction pool and index its pending transactions; disabled if omitted.
+    #[serde(default)]
+    pub pending_transactions: Option<pending_transactions::Config>,
 }
 
 pub async fn run(
@@ -80,10 +93,26 @@ pub async fn run(
         caught_up_leeway,
         gc_bound,
         ledger_state_retention,
+        follow_best_blocks,
+        pending_transactions,
     } = config;
 
+    // Exclude other chain-indexers, e.g. the previous one during a rolling update, or a rewind.
+    let _indexer_lock = match storage
+        .try_lock_indexer()
+        .await
+        .context("try to lock indexer")?
+    {
+        Some(indexer_lock) => indexer_lock,
+
+        None => {
+            info!("waiting for indexer lock held by another chain-indexer");
+            storage.lock_indexer().await.context("lock indexer")?
+        }
+    };
+
     // Get info from highest block.
-    let highest_block_ref = storage
+    let mut highest_block_ref = storage
         .get_highest_block()
         .await
         .context("get highest block")?
@@ -97,7 +126,7 @@ pub async fn run(
     // parent block time. Without this, `parent_block_timestamp` would be seeded from the resumed
     // block's own timestamp, over-bumping `tblock` and risking a spurious `IntentTtlExpired`
     // rejection of a transaction the node accepted. `0` (empty DB) keeps the genesis behavior.
-    let initial_parent_block_timestamp = storage
+    let mut initial_parent_block_timestamp = storage
         .get_highest_block_timestamp()
         .await
         .context("get highest block timestamp")?
@@ -118,53 +147,9 @@ pub async fn run(
         contract_action_count,
     );
 
-    // Load/initialize ledger state. Seed the retention window with the newest blocks' keys
-    // (oldest first, each with the ledger version it was persisted under) so a restart keeps
-    // balancing the previous run's persists instead of stranding them as permanent gc roots;
-    // per block, persist() is then balanced by an unpersist() once the key leaves the window,
-    // letting gc-v1 reclaim orphan nodes while recent snapshots stay loadable for indexer-api's
-    // block-hash reads (e.g. the dust generations subscription). Keys whose roots are no
-    // longer persisted are skipped: after a retention increase, older keys have already been
-    // unpersisted, and unpersisting them again would corrupt the root counts.
-    let newest_ledger_state_keys = storage
-        .get_newest_ledger_state_keys(ledger_state_retention)
-        .await
-        .context("get newest ledger state keys")?
-        .into_iter()
-        .map(|(protocol_version, key)| {
-            let ledger_version = protocol_version.ledger_version();
-            LedgerState::root_hash_bytes(&key, ledger_version)
-                .map(|root_hash| (key, ledger_version, root_hash))
-        })
-        .collect::<Result<Vec<_>, _>>()
-        .context("get ledger state root hashes")?;
-    let newest_count = newest_ledger_state_keys.len();
-
-    // Must precede the seeding below, and is idempotent, hence unconditional; see
-    // `LedgerState::repair_root_counts`.
-    let repair = LedgerState::repair_root_counts(
-        newest_ledger_state_keys
-            .iter()
-            .map(|(key, ledger_version, _)| (key, *ledger_version)),
-    )
-    .context("repair ledger state root counts")?;
-    if repair.raised_roots > 0 || repair.culled_roots > 0 {
-        warn!(repair:?; "repaired under-counted ledger state gc roots");
-    } else {
-        info!(repair:?; "ledger state gc root counts are consistent");
-    }
-
-    let persisted_root_hashes = LedgerState::persisted_root_hashes();
-    let mut persisted_ledger_state_keys = newest_ledger_state_keys
-        .into_iter()
-        .filter(|(_, _, root_hash)| persisted_root_hashes.contains(root_hash))
-        .map(|(key, ledger_version, _)| (key, ledger_version))
-        .collect::<VecDeque<_>>();
-    info!(
-        seeded = persisted_ledger_state_keys.len(),
-        skipped_unrooted = newest_count - persisted_ledger_state_keys.len();
-        "seeded ledger state retention window"
-    );
+    // Load/initialize ledger state.
+    let mut persisted_ledger_state_keys =
+        get_persisted_ledger_state_keys(ledger_state_retention, &mut storage).await?;
 
     let mut ledger_state = match persisted_ledger_state_keys.back() {
         Some((ledger_state_key, ledger_version)) => {
@@ -180,33 +165,69 @@ pub async fn run(
             .context("create ledger state")?,
     };
 
+    // When following best blocks, stored blocks which are not yet finalized may have been
+    // abandoned by a reorg while not running, hence roll back to the highest finalized one.
+    if follow_best_blocks
+        && let Some(highest_block) = highest_block_ref
+        && let Some(finalized_block) = storage
+            .get_highest_finalized_block()
+            .await
+            .context("get highest finalized block")?
+        && finalized_block.height < highest_block.height
+    {
+        let (new_ledger_state, rolled_back_blocks) = roll_back(
+            finalized_block.height,
+            highest_block.height,
+            &mut persisted_ledger_state_keys,
+            &mut storage,
+        )
+        .await?;
+        ledger_state = new_ledger_state;
+        metrics.record_roll_back(rolled_back_blocks);
+
+        highest_block_ref = Some(finalized_block);
+        initial_parent_block_timestamp = storage
+            .get_highest_block_timestamp()
+            .await
+            .context("get highest block timestamp")?
+            .unwrap_or(0);
+    }
+
     let highest_block_on_node = Arc::new(RwLock::new(None));
 
     // Spawn task to set info for highest block on node.
     let mut highest_block_on_node_task = task::spawn({
         let node = node.clone();
         let highest_block_on_node = highest_block_on_node.clone();
+        let mut storage = storage.clone();
+        let publisher = publisher.clone();
 
         async move {
             let highest_blocks = node
                 .highest_blocks()
                 .await
                 .context("get stream of highest blocks")?;
+            let mut highest_blocks = pin!(highest_blocks);
 
-            highest_blocks
-                .try_for_each(|block_info| {
-                    info!(
-                        hash:% = block_info.hash,
-                        height = block_info.height;
-                        "highest finalized block on node"
-                    );
-
-                    *highest_block_on_node.write() = Some(block_info);
-
-                    ok(())
-                })
+            while let Some(block_info) = highest_blocks
+                .try_next()
                 .await
-                .context("get next block of highest_blocks")?;
+                .context("get next block of highest_blocks")?
+            {
+                info!(
+                    hash:% = block_info.hash,
+                    height = block_info.height;
+                    "highest finalized block on node"
+                );
+
+                *highest_block_on_node.write() = Some(block_info);
+
+                // When following best blocks, already indexed blocks are marked finalized as soon
+                // as the node has finalized them, not only once the next block gets indexed.
+                if follow_best_blocks {
+                    finalize_blocks(block_info, &mut storage, &publisher).await?;
+                }
+            }
 
             warn!("highest_block_on_node_task completed");
 
@@ -214,24 +235,47 @@ pub async fn run(
         }
     });
 
+    // Spawn task to index pending transactions, if enabled.
+    let mut pending_transactions_task = task::spawn({
+        let node = node.clone();
+        let storage = storage.clone();
+        let publisher = publisher.clone();
+
+        async move {
+            match pending_transactions {
+                Some(config) => pending_transactions::run(config, node, storage, publisher).await,
+                None => future::pending().await,
+            }
+        }
+    });
+
     // Spawn task to index blocks.
     let mut index_blocks_task = task::spawn({
         let node = node.clone();
 
         async move {
-            let blocks = node_blocks(highest_block_ref, node.clone())
-                .map(ready)
-                .buffered(blocks_buffer);
+            let blocks = node_blocks(
+                highest_block_ref,
+                node.clone(),
+                follow_best_blocks,
+                ledger_state_retention,
+            )
+            .map(ready)
+            .buffered(blocks_buffer);
             let mut blocks = pin!(blocks);
             let mut caught_up = false;
             let mut parent_block_timestamp = initial_parent_block_timestamp;
+            let mut highest_block = highest_block_ref;
 
             loop {
                 let (next_ledger_state, new_ledger_state_key) = get_and_index_block(
                     caught_up_max_distance,
                     caught_up_leeway,
+                    follow_best_blocks,
                     &mut blocks,
                     ledger_state,
+                    &mut highest_block,
+                    &mut persisted_ledger_state_keys,
                     &network_id,
                     &highest_block_on_node,
                     &mut caught_up,
@@ -284,6 +328,7 @@ pub async fn run(
                 .context("highest_block_on_node_task panicked")
                 .and_then(|r| r.context("highest_block_on_node_task failed"));
             index_blocks_task.abort();
+            pending_transactions_task.abort();
             result
         },
 
@@ -292,6 +337,16 @@ pub async fn run(
                 .context("index_blocks_task panicked")
                 .and_then(|r: anyhow::Result<()>| r.context("index_blocks_task failed"));
             highest_block_on_node_task.abort();
+            pending_transactions_task.abort();
+            result
+        },
+
+        result = &mut pending_transactions_task => {
+            let result = result
+                .context("pending_transactions_task panicked")
+                .and_then(|r| r.context("pending_transactions_task failed"));
+            highest_block_on_node_task.abort();
+            index_blocks_task.abort();
             result
         },
 
@@ -299,23 +354,33 @@ pub async fn run(
             warn!("SIGTERM received");
             highest_block_on_node_task.abort();
             index_blocks_task.abort();
+            pending_transactions_task.abort();
             Ok(())
         }
     }
 }
 
 /// An infinite stream of node blocks, neither with duplicates, nor with gaps or otherwise
-/// unexpected blocks.
+/// unexpected blocks. When following best blocks, a block the parent of which is one of the
+/// `max_reorg_depth` recently yielded blocks is not unexpected, but signals a reorg.
 fn node_blocks<N>(
     mut highest_block: Option<BlockRef>,
     mut node: N,
+    follow_best_blocks: bool,
+    max_reorg_depth: NonZeroUsize,
 ) -> impl Stream<Item = Result<node::Block, N::Error>>
 where
     N: Node,
 {
     stream! {
+        let mut recent_blocks = highest_block.into_iter().collect::<VecDeque<_>>();
+
         loop {
-            let blocks = node.finalized_blocks(highest_block);
+            let blocks = if follow_best_blocks {
+                node.best_blocks(highest_block).left_stream()
+            } else {
+                node.finalized_blocks(highest_block).right_stream()
+            };
             let mut blocks = pin!(blocks);
 
             while let Some(block) = blocks.next().await {
@@ -325,20 +390,46 @@ where
                         .map(|BlockRef { hash, height }| (hash, height))
                         .unzip();
 
-                    // In case of unexpected blocks, e.g. because of a gap or the node lagging
-                    // behind, break and rerun the `finalized_blocks` stream.
                     if parent_hash != highest_hash.unwrap_or_default() {
-                        warn!(
-                            parent_hash:%,
-                            height = block.height,
-                            highest_hash:?,
-                            highest_height:?;
-                            "unexpected block"
-                        );
-                        break;
+                        let fork_point = follow_best_blocks
+                            .then(|| recent_blocks.iter().position(|b| b.hash == parent_hash))
+                            .flatten();
+
+                        match fork_point {
+                            Some(index) => {
+                                warn!(
+                                    parent_hash:%,
+                                    height = block.height,
+                                    highest_hash:?,
+                                    highest_height:?;
+                                    "reorg"
+                                );
+                                recent_blocks.truncate(index + 1);
+                            }
+
+                            // In case of unexpected blocks, e.g. because of a gap or the node
+                            // lagging behind, break and rerun the blocks stream.
+                            None => {
+                                warn!(
+                                    parent_hash:%,
+                                    height = block.height,
+                                    highest_hash:?,
+                                    highest_height:?;
+                                    "unexpected block"
+                                );
+                                break;
+                            }
+                        }
                     }
 
                     highest_block = Some(block.into());
+
+                    if follow_best_blocks {
+                        recent_blocks.push_back(block.into());
+                        while recent_blocks.len() > max_reorg_depth.get() {
+                            recent_blocks.pop_front();
+                        }
+                    }
                 }
 
                 yield block;
@@ -355,8 +446,11 @@ where
 async fn get_and_index_block<E, N>(
     caught_up_max_distance: u32,
     caught_up_leeway: u32,
+    follow_best_blocks: bool,
     blocks: &mut (impl Stream<Item = Result<node::Block, E>> + Unpin),
-    ledger_state: LedgerState,
+    mut ledger_state: LedgerState,
+    highest_block: &mut Option<BlockRef>,
+    persisted_ledger_state_keys: &mut VecDeque<(SerializedLedgerStateKey, LedgerVersion)>,
     network_id: &NetworkId,
     highest_block_on_node: &Arc<RwLock<Option<BlockRef>>>,
     caught_up: &mut bool,
@@ -370,11 +464,40 @@ where
     E: StdError + Send + Sync + 'static,
     N: Node,
 {
+    let started = Instant::now();
     let block = get_next_block(blocks).await?;
+    metrics.record_block_fetch(started.elapsed());
+
+    // A block not above the highest indexed one means a reorg of best blocks: roll back to its
+    // parent, which `node_blocks` has verified to be one of the recently indexed blocks.
+    if let Some(BlockRef {
+        height: highest_height,
+        ..
+    }) = *highest_block
+        && block.height <= highest_height
+    {
+        let (new_ledger_state, rolled_back_blocks) = roll_back(
+            block.height - 1,
+            highest_height,
+            persisted_ledger_state_keys,
+            storage,
+        )
+        .await?;
+        ledger_state = new_ledger_state;
+        metrics.record_roll_back(rolled_back_blocks);
+
+        *parent_block_timestamp = storage
+            .get_highest_block_timestamp()
+            .await
+            .context("get highest block timestamp")?
+            .unwrap_or(0);
+    }
+    *highest_block = Some((&block).into());
 
     let result = index_block(
         caught_up_max_distance,
         caught_up_leeway,
+        follow_best_blocks,
         block,
         ledger_state,
         network_id,
@@ -391,6 +514,196 @@ where
     Ok(result)
 }
 
+/// Rewind to the given height, i.e. roll back the stored blocks above it together with all their
+/// related data and unpersist their ledger states, such that indexing resumes with the ledger state
+/// persisted at that height, which must be within the retention window. Fails if another
+/// chain-indexer holds the indexer lock, i.e. blocks are being indexed.
+pub async fn rewind(
+    to_height: u64,
+    ledger_state_retention: NonZeroUsize,
+    mut storage: impl Storage,
+) -> anyhow::Result<()> {
+    let _indexer_lock = storage
+        .try_lock_indexer()
+        .await
+        .context("try to lock indexer")?
+        .context("indexer lock held by another chain-indexer, which must be stopped to rewind")?;
+
+    let (highest_block, _, _) = storage
+        .get_highest_block()
+        .await
+        .context("get highest block")?
+        .context("no blocks to rewind")?;
+
+    if to_height >= highest_block.height {
+        info!(to_height, highest_height = highest_block.height; "nothing to rewind");
+        return Ok(());
+    }
+
+    let mut persisted_ledger_state_keys =
+        get_persisted_ledger_state_keys(ledger_state_retention, &mut storage).await?;
+
+    let (_, rolled_back_blocks) = roll_back(
+        to_height,
+        highest_block.height,
+        &mut persisted_ledger_state_keys,
+        &mut storage,
+    )
+    .await?;
+
+    info!(to_height, rolled_back_blocks; "rewound");
+
+    Ok(())
+}
+
+/// Get the ledger state keys of the retention window, i.e. the newest blocks' keys, oldest first,
+/// each with the ledger version it was persisted under.
+async fn get_persisted_ledger_state_keys(
+    ledger_state_retention: NonZeroUsize,
+    storage: &mut impl Storage,
+) -> anyhow::Result<VecDeque<(SerializedLedgerStateKey, LedgerVersion)>> {
+    // Seeding the retention window with the newest blocks' keys lets a restart keep balancing the
+    // previous run's persists instead of stranding them as permanent gc roots; per block,
+    // persist() is then balanced by an unpersist() once the key leaves the window, letting gc-v1
+    // reclaim orphan nodes while recent snapshots stay loadable for indexer-api's block-hash
+    // reads (e.g. the dust generations subscription). Keys whose roots are no longer persisted
+    // are skipped: after a retention increase, older keys have already been unpersisted, and
+    // unpersisting them again would corrupt the root counts.
+    let newest_ledger_state_keys = storage
+        .get_newest_ledger_state_keys(ledger_state_retention)
+        .await
+        .context("get newest ledger state keys")?
+        .into_iter()
+        .map(|(protocol_version, key)| {
+            let ledger_version = protocol_version.ledger_version();
+            LedgerState::root_hash_bytes(&key, ledger_version)
+                .map(|root_hash| (key, ledger_version, root_hash))
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .context("get ledger state root hashes")?;
+    let newest_count = newest_ledger_state_keys.len();
+
+    // Finish a rollback interrupted after deleting the blocks; must precede the seeding below.
+    unpersist_rolled_back_ledger_states(
+        newest_ledger_state_keys
+            .iter()
+            .map(|(key, ledger_version, _)| (key, *ledger_version)),
+        storage,
+    )
+    .await?;
+
+    // Must precede the seeding below, and is idempotent, hence unconditional; see
+    // `LedgerState::repair_root_counts`.
+    let repair = LedgerState::repair_root_counts(
+        newest_ledger_state_keys
+            .iter()
+            .map(|(key, ledger_version, _)| (key, *ledger_version)),
+    )
+    .context("repair ledger state root counts")?;
+    if repair.raised_roots > 0 || repair.culled_roots > 0 {
+        warn!(repair:?; "repaired under-counted ledger state gc roots");
+    } else {
+        info!(repair:?; "ledger state gc root counts are consistent");
+    }
+
+    let persisted_root_hashes = LedgerState::persisted_root_hashes();
+    let persisted_ledger_state_keys = newest_ledger_state_keys
+        .into_iter()
+        .filter(|(_, _, root_hash)| persisted_root_hashes.contains(root_hash))
+        .map(|(key, ledger_version, _)| (key, ledger_version))
+        .collect::<VecDeque<_>>();
+    info!(
+        seeded = persisted_ledger_state_keys.len(),
+        skipped_unrooted = newest_count - persisted_ledger_state_keys.len();
+        "seeded ledger state retention window"
+    );
+
+    Ok(persisted_ledger_state_keys)
+}
+
+/// Roll back the stored blocks above the given height, unpersisting their ledger state keys, and
+/// return the ledger state at that height as well as the number of rolled back blocks.
+#[trace(properties = { "height": "{height}", "highest_height": "{highest_height}" })]
+async fn roll_back(
+    height: u64,
+    highest_height: u64,
+    persisted_ledger_state_keys: &mut VecDeque<(SerializedLedgerStateKey, LedgerVersion)>,
+    storage: &mut impl Storage,
+) -> anyhow::Result<(LedgerState, u64)> {
+    // The ledger state at the given height must still be persisted, i.e. within the retention
+    // window, which holds one key per block, the newest last.
+    let depth = highest_height - height;
+    if depth >= persisted_ledger_state_keys.len() as u64 {
+        bail!(
+            "cannot roll back {depth} blocks to height {height}: deeper than the ledger state \
+             retention window"
+        );
+    }
+
+    let rolled_back_blocks = storage
+        .roll_back_blocks(height)
+        .await
+        .context("roll back blocks")?;
+
+    let retained_ledger_state_keys = persisted_ledger_state_keys
+        .len()
+        .saturating_sub(rolled_back_blocks as usize);
+    persisted_ledger_state_keys.truncate(retained_ledger_state_keys);
+    unpersist_rolled_back_ledger_states(
+        persisted_ledger_state_keys
+            .iter()
+            .map(|(key, ledger_version)| (key, *ledger_version)),
+        storage,
+    )
+    .await?;
+
+    let (key, version) = persisted_ledger_state_keys
+        .back()
+        .context("no ledger state to roll back to")?;
+    let ledger_state = LedgerState::load(key, *version).context("load ledger state")?;
+
+    warn!(height, rolled_back_blocks; "rolled back blocks");
+
+    Ok((ledger_state, rolled_back_blocks))
+}
+
+/// Unpersist the ledger states of the rolled back blocks, i.e. lower their gc root counts to match
+/// the given retention window, and then delete their keys. Idempotent, hence a rollback
+/// interrupted in between is finished by calling this again.
+async fn unpersist_rolled_back_ledger_states<'a>(
+    window: impl IntoIterator<Item = (&'a SerializedLedgerStateKey, LedgerVersion)>,
+    storage: &mut impl Storage,
+) -> anyhow::Result<()> {
+    let rolled_back_ledger_state_keys = storage
+        .get_rolled_back_ledger_state_keys()
+        .await
+        .context("get rolled back ledger state keys")?;
+    if rolled_back_ledger_state_keys.is_empty() {
+        return Ok(());
+    }
+
+    let unpersisted = LedgerState::lower_root_counts(
+        window,
+        rolled_back_ledger_state_keys
+            .iter()
+            .map(|(protocol_version, key)| (key, protocol_version.ledger_version())),
+    )
+    .context("unpersist ledger states of rolled back blocks")?;
+
+    storage
+        .delete_rolled_back_ledger_state_keys()
+        .await
+        .context("delete rolled back ledger state keys")?;
+
+    info!(
+        rolled_back_blocks = rolled_back_ledger_state_keys.len(),
+        unpersisted;
+        "unpersisted ledger states of rolled back blocks"
+    );
+
+    Ok(())
+}
+
 #[trace]
 async fn get_next_block<E>(
     blocks: &mut (impl Stream<Item = Result<node::Block, E>> + Unpin),
@@ -410,6 +723,7 @@ where
 async fn index_block<N>(
     caught_up_max_distance: u32,
     caught_up_leeway: u32,
+    follow_best_blocks: bool,
     block: node::Block,
     mut ledger_state: LedgerState,
     network_id: &NetworkId,
@@ -430,6 +744,7 @@ where
 
     let (mut block, transactions) = block.try_into().context("convert node block into domain")?;
 
+    let apply_started = Instant::now();
     let ledger_version = block.protocol_version.ledger_version();
     ledger_state = if block.height == 0 {
         // The genesis block establishes the chain's ledger version. The inherited
@@ -573,10 +888,17 @@ where
         info!(caught_up:%; "caught-up status changed")
     }
 
+    // When following best blocks, blocks are saved as not finalized, because even a best block
+    // not above the finalized height may be on an abandoned fork. They are marked finalized by
+    // hash below or by highest_block_on_node_task once the node has finalized them or a descendant.
+    let highest_finalized_block = *highest_block_on_node.read();
+    block.finalized = !follow_best_blocks;
+
     // Persist ledger state.
     let (new_ledger_state, ledger_state_key) =
         ledger_state.0.persist().context("persist ledger state")?;
     ledger_state = new_ledger_state.into();
+    metrics.record_block_apply(apply_started.elapsed());
 
     // Determine system parameters change if any.
     let system_parameters_change = determine_system_parameters_change(&block, storage, node)
@@ -584,6 +906,7 @@ where
         .context("determine system parameters change")?;
 
     // Save the block with its related data and system parameters atomically.
+    let save_started = Instant::now();
     let max_transaction_id = storage
         .save_block(
             &block,
@@ -594,6 +917,14 @@ where
         )
         .await
         .context("save block")?;
+    metrics.record_block_save(save_started.elapsed());
+
+    if follow_best_blocks
+        && let Some(finalized_block) = highest_finalized_block
+        && finalized_block.height <= block.height
+    {
+        finalize_blocks(finalized_block, storage, publisher).await?;
+    }
 
     // Publish BlockIndexed.
     publisher
@@ -654,7 +985,32 @@ where
     Ok((ledger_state, ledger_state_key))
 }
 
-/// Fetch system parameters from the node and determine if they changed.
+/// Mark the stored blocks up to the given finalized one as finalized and publish BlocksFinalized if
+/// any have been finalized, such that subscribers get to see them.
+async fn finalize_blocks(
+    finalized_block: BlockRef,
+    storage: &mut impl Storage,
+    publisher: &impl Publisher,
+) -> anyhow::Result<()> {
+    let finalized = storage
+        .finalize_blocks(finalized_block)
+        .await
+        .context("finalize blocks")?;
+
+    if finalized > 0 {
+        debug!(height = finalized_block.height, finalized; "blocks finalized");
+
+        publisher
+            .publish(&BlocksFinalized {
+                height: finalized_block.height,
+            })
+            .await
+            .context("publish BlocksFinalized event")?;
+    }
+
+    Ok(())
+}
+
 #[trace]
 async fn determine_system_parameters_change<N>(
     block: &Block,
@@ -750,11 +1106,11 @@ mod tests {
         },
         error::BoxError,
     };
-    use std::{convert::Infallible, sync::LazyLock};
+    use std::{convert::Infallible, num::NonZeroUsize, sync::LazyLock};
 
     #[tokio::test]
     async fn test_blocks() -> Result<(), BoxError> {
-        let blocks = node_blocks(None, MockNode);
+        let blocks = node_blocks(None, MockNode, false, NonZeroUsize::MIN);
         let heights = blocks
             .take(4)
             .map_ok(|block| block.height)
@@ -765,6 +1121,27 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_best_blocks_with_reorg() -> Result<(), BoxError> {
+        let blocks = node_blocks(None, MockNode, true, NonZeroUsize::new(10).unwrap());
+        let blocks = blocks
+            .take(4)
+            .map_ok(|block| (block.height, block.hash))
+            .try_collect::<Vec<_>>()
+            .await?;
+        assert_eq!(
+            blocks,
+            vec![
+                (0, BLOCK_0_HASH),
+                (1, BLOCK_1_HASH),
+                (2, BLOCK_2_HASH),
+                (2, BLOCK_2_FORK_HASH)
+            ]
+        );
+
+        Ok(())
+    }
+
     #[derive(Clone)]
     struct MockNode;
 
@@ -785,6 +1162,14 @@ mod tests {
                 .map(|block| Ok(block.to_owned()))
         }
 
+        fn best_blocks(
+            &mut self,
+            _highest_block: Option<BlockRef>,
+        ) -> impl Stream<Item = Result<node::Block, Self::Error>> {
+            stream::iter([&*BLOCK_0, &*BLOCK_1, &*BLOCK_2, &*BLOCK_2_FORK])
+                .map(|block| Ok(block.to_owned()))
+        }
+
         async fn fetch_system_parameters(
             &self,
             block_hash: BlockHash,
@@ -804,6 +1189,12 @@ mod tests {
         async fn fetch_genesis_ledger_state(&self) -> Result<ByteVec, Self::Error> {
             Ok(Default::default())
         }
+
+        async fn fetch_pending_transactions(
+            &self,
+        ) -> Result<Vec<node::PendingTransaction>, Self::Error> {
+            Ok(vec![])
+        }
     }
 
     static BLOCK_0: LazyLock<node::Block> = LazyLock::new(|| node::Block {
@@ -862,12 +1253,27 @@ mod tests {
         bridge_events: Default::default(),
     });
 
+    static BLOCK_2_FORK: LazyLock<node::Block> = LazyLock::new(|| node::Block {
+        hash: BLOCK_2_FORK_HASH,
+        height: 2,
+        protocol_version: *PROTOCOL_VERSION,
+        parent_hash: BLOCK_1_HASH,
+        author: Default::default(),
+        timestamp: Default::default(),
+        zswap_merkle_tree_root: ZswapMerkleTreeRoot::V8(Faker.fake()),
+        ledger_state_root: None,
+        transactions: Default::default(),
+        dust_registration_events: Default::default(),
+        bridge_events: Default::default(),
+    });
+
     const ZERO_HASH: BlockHash = ByteArray([0; 32]);
 
     const BLOCK_0_HASH: BlockHash = ByteArray([1; 32]);
     const BLOCK_1_HASH: BlockHash = ByteArray([2; 32]);
     const BLOCK_2_HASH: BlockHash = ByteArray([3; 32]);
     const BLOCK_3_HASH: BlockHash = ByteArray([3; 32]);
+    const BLOCK_2_FORK_HASH: BlockHash = ByteArray([5; 32]);
 
     #[allow(clippy::zero_prefixed_literal)]
     static PROTOCOL_VERSION: LazyLock<ProtocolVersion> =
@@ -27,6 +27,10 @@ pub struct Metrics {
     gc_run_count: Counter,
     gc_culled_node_count: Counter,
     gc_duration_seconds: Histogram,
+    rolled_back_block_count: Counter,
+    block_fetch_duration_seconds: Histogram,
+    block_apply_duration_seconds: Histogram,
+    block_save_duration_seconds: Histogram,
 }
 
 impl Metrics {
@@ -46,6 +50,10 @@ impl Metrics {
             gc_run_count: counter!("indexer_gc_run_count"),
             gc_culled_node_count: counter!("indexer_gc_culled_node_count"),
             gc_duration_seconds: histogram!("indexer_gc_duration_seconds"),
+            rolled_back_block_count: counter!("indexer_rolled_back_block_count"),
+            block_fetch_duration_seconds: histogram!("indexer_block_fetch_duration_seconds"),
+            block_apply_duration_seconds: histogram!("indexer_block_apply_duration_seconds"),
+            block_save_duration_seconds: histogram!("indexer_block_save_duration_seconds"),
         };
 
         if let Some(block_height) = block_height {
@@ -148,4 +156,28 @@ impl Metrics {
         self.gc_culled_node_count.increment(nodes_culled as u64);
         self.gc_duration_seconds.record(duration.as_secs_f64());
     }
+
+    /// Record the time waited for the next block from the node; while catching up, this should be
+    /// small compared to the apply duration, else indexing is bound by fetching blocks.
+    pub fn record_block_fetch(&self, duration: Duration) {
+        self.block_fetch_duration_seconds
+            .record(duration.as_secs_f64());
+    }
+
+    /// Record the time taken to apply a block to the ledger state, including persisting the latter.
+    pub fn record_block_apply(&self, duration: Duration) {
+        self.block_apply_duration_seconds
+            .record(duration.as_secs_f64());
+    }
+
+    /// Record the time taken to save a block with its related data.
+    pub fn record_block_save(&self, duration: Duration) {
+        self.block_save_duration_seconds
+            .record(duration.as_secs_f64());
+    }
+
+    /// Record the rollback of best blocks abandoned by a reorg.
+    pub fn record_roll_back(&self, rolled_back_blocks: u64) {
+        self.rolled_back_block_count.increment(rolled_back_blocks);
+    }
 }
@@ -0,0 +1,133 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::domain::{
+    PendingTransaction,
+    node::{self, Node},
+    storage::Storage,
+};
+use anyhow::Context;
+use indexer_common::{
+    domain::{PendingTransactionsUpdated, Publisher, ledger},
+    error::StdErrorExt,
+};
+use log::{debug, warn};
+use serde::Deserialize;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::time::{MissedTickBehavior, interval};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Config {
+    /// Interval for polling the pending extrinsics of the node's transaction pool.
+    #[serde(with = "humantime_serde")]
+    pub poll_interval: Duration,
+
+    /// Time after which a pending transaction no longer seen in the node's transaction pool, e.g.
+    /// because it has been dropped, is deleted. Must exceed poll_interval.
+    #[serde(with = "humantime_serde")]
+    pub ttl: Duration,
+}
+
+/// Follow the node's transaction pool: save its pending transactions and delete the ones which have
+/// meanwhile been included in a block or not been seen for the configured TTL.
+pub async fn run(
+    config: Config,
+    node: impl Node,
+    mut storage: impl Storage,
+    publisher: impl Publisher,
+) -> anyhow::Result<()> {
+    let Config { poll_interval, ttl } = config;
+
+    let mut interval = interval(poll_interval);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        interval.tick().await;
+
+        // Failing to fetch pending transactions must not stop indexing blocks, hence only warn.
+        let pending_transactions = match node.fetch_pending_transactions().await {
+            Ok(pending_transactions) => pending_transactions,
+
+            Err(error) => {
+                warn!(error = error.as_chain(); "cannot fetch pending transactions");
+                continue;
+            }
+        };
+
+        let pending_transactions = pending_transactions
+            .into_iter()
+            .filter_map(make_pending_transaction)
+            .collect::<Vec<_>>();
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .context("get current time")?
+            .as_millis() as u64;
+
+        let saved = storage
+            .save_pending_transactions(&pending_transactions, now)
+            .await
+            .context("save pending transactions")?;
+        let deleted = storage
+            .delete_pending_transactions(now.saturating_sub(ttl.as_millis() as u64))
+            .await
+            .context("delete pending transactions")?;
+
+        if saved > 0 || deleted > 0 {
+            debug!(saved, deleted; "pending transactions updated");
+
+            publisher
+                .publish(&PendingTransactionsUpdated { saved, deleted })
+                .await
+                .context("publish PendingTransactionsUpdated event")?;
+        }
+    }
+}
+
+/// Make a [PendingTransaction] from the given node one; pending transactions which cannot be
+/// deserialized are skipped, because the node validates them only when building a block.
+fn make_pending_transaction(
+    pending_transaction: node::PendingTransaction,
+) -> Option<PendingTransaction> {
+    let node::PendingTransaction {
+        protocol_version,
+        raw,
+    } = pending_transaction;
+
+    let ledger_transaction =
+        match ledger::Transaction::deserialize(&raw, protocol_version.ledger_version()) {
+            Ok(ledger_transaction) => ledger_transaction,
+
+            Err(error) => {
+                warn!(error = error.as_chain(); "cannot deserialize pending transaction");
+                return None;
+            }
+        };
+
+    let contract_addresses = match ledger_transaction.contract_addresses() {
+        Ok(contract_addresses) => contract_addresses,
+
+        Err(error) => {
+            warn!(error = error.as_chain(); "cannot get contract addresses of pending transaction");
+            return None;
+        }
+    };
+
+    Some(PendingTransaction {
+        hash: ledger_transaction.hash(),
+        protocol_version,
+        raw,
+        contract_addresses,
+        unshielded_addresses: ledger_transaction.unshielded_addresses(),
+    })
+}
@@ -0,0 +1,393 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Snapshots bootstrap the chain-indexer from a trusted checkpoint instead of replaying the chain
+//! from genesis. A snapshot file is a gzip compressed sequence of length-prefixed frames: the
+//! [SnapshotHeader] as JSON, batches of the arena nodes of the retained ledger states, each node
+//! as a hash and an object frame, and batches of [SnapshotRows] as JSON, the nodes and the rows
+//! each terminated by an empty frame.
+//!
+//! The rows are read from one consistent snapshot of the storage without blocking the
+//! chain-indexer, hence may include blocks above the snapshot's block; importing rolls these back
+//! like a reorg, which also reverts all data derived from them.
+
+use crate::domain::{
+    LedgerState,
+    snapshot::{SNAPSHOT_FORMAT_VERSION, SnapshotHeader, SnapshotRows},
+    storage::Storage,
+};
+use anyhow::{Context, bail};
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use futures::TryStreamExt;
+use indexer_common::{
+    domain::{LedgerVersion, NetworkId, SerializedLedgerStateKey, ledger::ArenaNode},
+    error::StdErrorExt,
+};
+use log::{info, warn};
+use std::{
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Cursor, Read, Write},
+    num::NonZeroUsize,
+    path::Path,
+    pin::pin,
+};
+
+const MAGIC: &[u8; 8] = b"MNIDXSNP";
+
+const BATCH_SIZE: NonZeroUsize = NonZeroUsize::new(1_000).unwrap();
+
+/// Export a snapshot of the indexed chain data and the retained ledger states as of the block at
+/// the given height into the given file. The ledger states of the newest blocks up to that height
+/// are retained, as long as these are still persisted, but at most `ledger_state_retention` ones.
+pub async fn export(
+    network_id: &NetworkId,
+    height: u64,
+    ledger_state_retention: NonZeroUsize,
+    file: &Path,
+    storage: &impl Storage,
+) -> anyhow::Result<()> {
+    let (block_ref, protocol_version, _) = storage
+        .get_block_at(height)
+        .await
+        .context("get block")?
+        .with_context(|| format!("no block at height {height}"))?;
+
+    let ledger_state_keys =
+        get_retained_ledger_state_keys(height, ledger_state_retention, storage).await?;
+    if ledger_state_keys.is_empty() {
+        bail!(
+            "ledger state at height {height} is no longer available, export a height within the \
+             ledger state retention window"
+        );
+    }
+
+    let header = SnapshotHeader {
+        format_version: SNAPSHOT_FORMAT_VERSION,
+        rows_format: storage.snapshot_rows_format().to_owned(),
+        network_id: network_id.to_string(),
+        block_hash: block_ref.hash.to_string(),
+        height,
+        protocol_version: protocol_version.into(),
+        ledger_state_count: ledger_state_keys.len() as u64,
+    };
+    info!(header:?; "exporting snapshot");
+
+    // Write to a temporary file first to never leave a partial snapshot behind.
+    let partial_file = file.with_extension("partial");
+    let mut writer = GzEncoder::new(
+        BufWriter::new(File::create(&partial_file).context("create snapshot file")?),
+        Compression::default(),
+    );
+    writer.write_all(MAGIC).context("write magic")?;
+    write_frame(
+        &mut writer,
+        &serde_json::to_vec(&header).context("serialize header")?,
+    )
+    .context("write header")?;
+
+    let nodes = LedgerState::arena_nodes(
+        ledger_state_keys
+            .iter()
+            .map(|(key, ledger_version)| (key, *ledger_version)),
+        BATCH_SIZE,
+    )
+    .context("get arena nodes")?;
+    let mut node_count = 0;
+    for nodes in nodes {
+        // Nodes only go missing if gc culls a ledger state which meanwhile left the retention
+        // window of a running chain-indexer.
+        let nodes = nodes.context("get next arena nodes, export a higher height")?;
+        node_count += nodes.len();
+
+        let mut frame = vec![];
+        for ArenaNode { hash, object } in nodes {
+            write_frame(&mut frame, &hash).context("write arena node hash")?;
+            write_frame(&mut frame, &object).context("write arena node object")?;
+        }
+        write_frame(&mut writer, &frame).context("write arena nodes")?;
+    }
+    write_frame(&mut writer, &[]).context("write end of arena nodes")?;
+
+    let rows = storage.get_snapshot_rows(BATCH_SIZE);
+    let mut rows = pin!(rows);
+    let mut row_count = 0;
+    while let Some(rows) = rows.try_next().await.context("get next snapshot rows")? {
+        row_count += rows.rows.len();
+        write_frame(
+            &mut writer,
+            &serde_json::to_vec(&rows).context("serialize rows")?,
+        )
+        .context("write rows")?;
+    }
+
+    write_frame(&mut writer, &[]).context("write end of snapshot")?;
+    writer
+        .finish()
+        .and_then(|mut writer| writer.flush())
+        .context("finish snapshot file")?;
+    fs::rename(&partial_file, file).context("rename snapshot file")?;
+
+    info!(height, node_count, row_count; "exported snapshot");
+
+    Ok(())
+}
+
+/// Import a snapshot from the given file into the empty storage, roll back the blocks above the
+/// snapshot's block and persist the retained ledger states, so that the chain-indexer continues
+/// indexing after the snapshot's block. If importing fails, the imported rows are deleted again,
+/// so that importing can be retried.
+pub async fn import(
+    network_id: &NetworkId,
+    file: &Path,
+    storage: &mut impl Storage,
+) -> anyhow::Result<()> {
+    if storage
+        .get_highest_block()
+        .await
+        .context("get highest block")?
+        .is_some()
+    {
+        bail!("cannot import snapshot into non-empty storage");
+    }
+
+    let result = import_into_empty(network_id, file, storage).await;
+
+    if result.is_err()
+        && let Err(error) = storage.delete_snapshot_rows().await
+    {
+        warn!(error = error.as_chain(); "cannot delete rows of failed snapshot import");
+    }
+
+    result
+}
+
+async fn import_into_empty(
+    network_id: &NetworkId,
+    file: &Path,
+    storage: &mut impl Storage,
+) -> anyhow::Result<()> {
+    let mut reader = GzDecoder::new(BufReader::new(
+        File::open(file).context("open snapshot file")?,
+    ));
+
+    let mut magic = [0; MAGIC.len()];
+    reader.read_exact(&mut magic).context("read magic")?;
+    if &magic != MAGIC {
+        bail!("not a snapshot file");
+    }
+
+    let header = read_frame(&mut reader).context("read header")?;
+    let header = serde_json::from_slice::<SnapshotHeader>(&header).context("deserialize header")?;
+    info!(header:?; "importing snapshot");
+
+    if header.format_version != SNAPSHOT_FORMAT_VERSION {
+        bail!(
+            "unsupported snapshot format version {}, expected {SNAPSHOT_FORMAT_VERSION}",
+            header.format_version
+        );
+    }
+    if header.rows_format != storage.snapshot_rows_format() {
+        bail!(
+            "snapshot rows format {} does not match storage rows format {}",
+            header.rows_format,
+            storage.snapshot_rows_format()
+        );
+    }
+    if header.network_id != network_id.to_string() {
+        bail!(
+            "snapshot network ID {} does not match configured network ID {network_id}",
+            header.network_id
+        );
+    }
+    let ledger_state_count = usize::try_from(header.ledger_state_count)
+        .ok()
+        .and_then(NonZeroUsize::new)
+        .context("snapshot has no ledger states")?;
+
+    let mut node_count = 0;
+    loop {
+        let frame = read_frame(&mut reader).context("read arena nodes")?;
+        if frame.is_empty() {
+            break;
+        }
+
+        let mut nodes = vec![];
+        let mut frame = Cursor::new(frame);
+        while (frame.position() as usize) < frame.get_ref().len() {
+            let hash = read_frame(&mut frame).context("read arena node hash")?;
+            let object = read_frame(&mut frame).context("read arena node object")?;
+            nodes.push(ArenaNode {
+                hash: hash.into(),
+                object: object.into(),
+            });
+        }
+
+        node_count += nodes.len();
+        LedgerState::save_arena_nodes(nodes).context("save arena nodes")?;
+    }
+
+    let mut row_count = 0;
+    loop {
+        let rows = read_frame(&mut reader).context("read rows")?;
+        if rows.is_empty() {
+            break;
+        }
+
+        let rows = serde_json::from_slice::<SnapshotRows>(&rows).context("deserialize rows")?;
+        storage
+            .save_snapshot_rows(&rows)
+            .await
+            .with_context(|| format!("save snapshot rows for table {}", rows.table))?;
+        row_count += rows.rows.len();
+    }
+    storage
+        .finish_snapshot_rows()
+        .await
+        .context("finish snapshot rows")?;
+
+    // The ledger states of the rolled back blocks are not part of the snapshot, hence there is
+    // nothing to unpersist.
+    let rolled_back_blocks = storage
+        .roll_back_blocks(header.height)
+        .await
+        .context("roll back blocks above snapshot height")?;
+    storage
+        .delete_rolled_back_ledger_state_keys()
+        .await
+        .context("delete rolled back ledger state keys")?;
+
+    let (block_ref, protocol_version, ledger_state_key) = storage
+        .get_block_at(header.height)
+        .await
+        .context("get block")?
+        .with_context(|| format!("snapshot has no block at height {}", header.height))?;
+    if block_ref.hash.to_string() != header.block_hash {
+        bail!(
+            "snapshot block hash {} does not match imported block hash {}",
+            header.block_hash,
+            block_ref.hash
+        );
+    }
+
+    // Persisting once per block makes the ledger states the retention window the chain-indexer
+    // resumes from, like after indexing these blocks.
+    let ledger_state_keys = storage
+        .get_newest_ledger_state_keys(ledger_state_count)
+        .await
+        .context("get newest ledger state keys")?;
+    if ledger_state_keys.len() != ledger_state_count.get() {
+        bail!("snapshot has fewer blocks than ledger states");
+    }
+    for (protocol_version, key) in &ledger_state_keys {
+        LedgerState::persist_key(key, protocol_version.ledger_version())
+            .context("persist ledger state")?;
+    }
+    LedgerState::load(&ledger_state_key, protocol_version.ledger_version())
+        .context("load ledger state of snapshot block")?;
+
+    info!(
+        height = header.height,
+        node_count,
+        row_count,
+        rolled_back_blocks;
+        "imported snapshot"
+    );
+
+    Ok(())
+}
+
+/// Get the ledger state keys of the newest blocks up to the given height, oldest first, as long as
+/// their ledger states are persisted, but at most `ledger_state_retention` ones.
+async fn get_retained_ledger_state_keys(
+    height: u64,
+    ledger_state_retention: NonZeroUsize,
+    storage: &impl Storage,
+) -> anyhow::Result<Vec<(SerializedLedgerStateKey, LedgerVersion)>> {
+    let persisted_root_hashes = LedgerState::persisted_root_hashes();
+
+    let mut ledger_state_keys = vec![];
+    for height in (0..=height).rev().take(ledger_state_retention.get()) {
+        let Some((_, protocol_version, key)) =
+            storage.get_block_at(height).await.context("get block")?
+        else {
+            break;
+        };
+
+        let ledger_version = protocol_version.ledger_version();
+        let root_hash = LedgerState::root_hash_bytes(&key, ledger_version)
+            .context("get ledger state root hash")?;
+        if !persisted_root_hashes.contains(&root_hash) {
+            break;
+        }
+
+        ledger_state_keys.push((key, ledger_version));
+    }
+    ledger_state_keys.reverse();
+
+    Ok(ledger_state_keys)
+}
+
+fn write_frame(writer: &mut impl Write, bytes: &[u8]) -> io::Result<()> {
+    writer.write_all(&(bytes.len() as u64).to_be_bytes())?;
+    writer.write_all(bytes)
+}
+
+fn read_frame(reader: &mut impl Read) -> io::Result<Vec<u8>> {
+    let mut len = [0; 8];
+    reader.read_exact(&mut len)?;
+    let len = u64::from_be_bytes(len);
+
+    // The length comes from the file, hence only allocate for the bytes actually read.
+    let mut bytes = vec![];
+    reader.take(len).read_to_end(&mut bytes)?;
+    if bytes.len() as u64 != len {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            format!(
+                "frame of length {len} truncated after {} bytes",
+                bytes.len()
+            ),
+        ));
+    }
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::application::snapshot::{read_frame, write_frame};
+    use std::io::{self, Cursor};
+
+    #[test]
+    fn test_frames() {
+        let mut bytes = vec![];
+        write_frame(&mut bytes, b"header").unwrap();
+        write_frame(&mut bytes, b"rows").unwrap();
+        write_frame(&mut bytes, &[]).unwrap();
+
+        let mut reader = Cursor::new(bytes);
+        assert_eq!(read_frame(&mut reader).unwrap(), b"header");
+        assert_eq!(read_frame(&mut reader).unwrap(), b"rows");
+        assert!(read_frame(&mut reader).unwrap().is_empty());
+        assert!(read_frame(&mut reader).is_err());
+    }
+
+    #[test]
+    fn test_read_frame_with_bogus_length() {
+        let mut bytes = u64::MAX.to_be_bytes().to_vec();
+        bytes.extend_from_slice(b"rows");
+
+        let error = read_frame(&mut Cursor::new(bytes)).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+    }
+}
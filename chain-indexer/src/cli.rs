@@ -0,0 +1,64 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+
+/// Command line interface; without a command, blocks are indexed.
+#[derive(Debug, Parser)]
+#[command()]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Record the blocks, system parameters and genesis ledger state received from the node into
+    /// the given archive file.
+    #[arg(long, conflicts_with = "replay")]
+    pub record: Option<PathBuf>,
+
+    /// Replay the given archive file recorded with `--record` instead of connecting to the node.
+    #[arg(long)]
+    pub replay: Option<PathBuf>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Export or import a snapshot to bootstrap indexing from a trusted checkpoint.
+    #[command(subcommand)]
+    Snapshot(SnapshotCommand),
+
+    /// Roll back all blocks above the given height, e.g. to re-index data corrupted by a bug.
+    Rewind {
+        #[arg(long)]
+        to_height: u64,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SnapshotCommand {
+    /// Export a snapshot as of the block at the given height into the given file.
+    Export {
+        #[arg(long)]
+        height: u64,
+
+        #[arg(long)]
+        file: PathBuf,
+    },
+
+    /// Import a snapshot from the given file into an empty database. If the import fails, the
+    /// already imported rows are deleted, such that the import can be retried.
+    Import {
+        #[arg(long)]
+        file: PathBuf,
+    },
+}
@@ -12,12 +12,14 @@
 // limitations under the License.
 
 pub mod node;
+pub mod snapshot;
 pub mod storage;
 
 mod block;
 mod contract_action;
 mod dust;
 mod ledger_state;
+mod pending_transaction;
 mod system_parameters;
 mod transaction;
 
@@ -25,5 +27,6 @@ pub use block::*;
 pub use contract_action::*;
 pub use dust::*;
 pub use ledger_state::*;
+pub use pending_transaction::*;
 pub use system_parameters::*;
 pub use transaction::*;
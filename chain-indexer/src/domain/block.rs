@@ -11,13 +11,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::domain::DustRegistrationEvent;
+use crate::domain::{DustRegistrationEvent, Transaction};
 use indexer_common::domain::{
-    BlockAuthor, BlockHash, ByteVec, ProtocolVersion, SerializedDustCommitmentMerkleTreeRoot,
+    BlockAuthor, BlockHash, ByteVec, ContractAttributes, LedgerEvent, LedgerEventGrouping,
+    ProtocolVersion, SerializedDustCommitmentMerkleTreeRoot,
     SerializedDustGenerationMerkleTreeRoot, SerializedLedgerParameters,
-    SerializedZswapMerkleTreeRoot, bridge::BridgeEvent,
+    SerializedZswapMerkleTreeRoot, UnshieldedAddress, bridge::BridgeEvent, fee_percentile,
 };
-use std::fmt::Debug;
+use std::{collections::HashSet, fmt::Debug};
+
+/// Milliseconds per day, used to determine the UTC day of a block timestamp.
+const DAY_MILLIS: u64 = 24 * 60 * 60 * 1_000;
 
 #[derive(Debug, Clone)]
 pub struct Block {
@@ -36,6 +40,8 @@ pub struct Block {
     /// the node 2.0+ runtime (`infra/subxt_node/runtimes/v2_0_0.rs`); always empty for earlier
     /// runtimes, where the pallet does not exist.
     pub bridge_events: Vec<BridgeEvent>,
+    /// Whether the block is finalized; always true unless following the best blocks.
+    pub finalized: bool,
 
     // These fields are set after applying all transactions of this block to the ledger state.
     pub ledger_parameters: SerializedLedgerParameters,
@@ -46,8 +52,135 @@ pub struct Block {
     pub dust_generation_merkle_tree_root: SerializedDustGenerationMerkleTreeRoot,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct BlockRef {
     pub hash: BlockHash,
     pub height: u64,
 }
+
+/// Fee statistics of a block. The fees are the paid fees of its regular transactions, hence the
+/// minimum, median and 90th percentile are `None` for blocks without any.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockStats {
+    pub transaction_count: u64,
+    pub transaction_bytes: u64,
+    pub fee_sum: u128,
+    pub fee_min: Option<u128>,
+    pub fee_median: Option<u128>,
+    pub fee_p90: Option<u128>,
+}
+
+impl BlockStats {
+    /// Compute the statistics for the given transactions of a block after these have been applied.
+    pub fn new(transactions: &[Transaction]) -> Self {
+        let mut fees = transactions
+            .iter()
+            .filter_map(|transaction| match transaction {
+                Transaction::Regular(transaction) => Some(transaction.paid_fees),
+                Transaction::System(_) => None,
+            })
+            .collect::<Vec<_>>();
+        fees.sort_unstable();
+
+        Self {
+            transaction_count: transactions.len() as u64,
+            transaction_bytes: transactions
+                .iter()
+                .map(|transaction| transaction.raw().len() as u64)
+                .sum(),
+            fee_sum: fees.iter().fold(0, |sum, fee| sum.saturating_add(*fee)),
+            fee_min: fees.first().copied(),
+            fee_median: fee_percentile(&fees, 50),
+            fee_p90: fee_percentile(&fees, 90),
+        }
+    }
+}
+
+/// Chain statistics of a block, rolled up per block and per UTC day.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlockRollup {
+    pub regular_transaction_count: u64,
+    pub system_transaction_count: u64,
+    pub contract_deploy_count: u64,
+    pub contract_call_count: u64,
+    pub contract_update_count: u64,
+    pub unshielded_utxos_created: u64,
+    pub unshielded_utxos_spent: u64,
+    pub zswap_ledger_event_count: u64,
+    pub dust_ledger_event_count: u64,
+
+    /// The distinct owners of the unshielded UTXOs created or spent by the transactions.
+    pub active_addresses: Vec<UnshieldedAddress>,
+}
+
+impl BlockRollup {
+    /// Compute the rollup for the given transactions of a block after these have been applied.
+    pub fn new(transactions: &[Transaction]) -> Self {
+        let mut rollup = Self::default();
+        let mut active_addresses = HashSet::new();
+
+        for transaction in transactions {
+            match transaction {
+                Transaction::Regular(transaction) => {
+                    rollup.regular_transaction_count += 1;
+
+                    for contract_action in &transaction.contract_actions {
+                        match contract_action.attributes {
+                            ContractAttributes::Deploy => rollup.contract_deploy_count += 1,
+                            ContractAttributes::Call { .. } => rollup.contract_call_count += 1,
+                            ContractAttributes::Update => rollup.contract_update_count += 1,
+                        }
+                    }
+
+                    rollup.unshielded_utxos_created +=
+                        transaction.created_unshielded_utxos.len() as u64;
+                    rollup.unshielded_utxos_spent +=
+                        transaction.spent_unshielded_utxos.len() as u64;
+                    active_addresses.extend(
+                        transaction
+                            .created_unshielded_utxos
+                            .iter()
+                            .chain(&transaction.spent_unshielded_utxos)
+                            .map(|utxo| utxo.owner),
+                    );
+
+                    rollup.add_ledger_events(&transaction.ledger_events);
+                }
+
+                Transaction::System(transaction) => {
+                    rollup.system_transaction_count += 1;
+
+                    rollup.unshielded_utxos_created +=
+                        transaction.created_unshielded_utxos.len() as u64;
+                    active_addresses.extend(
+                        transaction
+                            .created_unshielded_utxos
+                            .iter()
+                            .map(|utxo| utxo.owner),
+                    );
+
+                    rollup.add_ledger_events(&transaction.ledger_events);
+                }
+            }
+        }
+
+        rollup.active_addresses = active_addresses.into_iter().collect();
+        rollup
+    }
+
+    fn add_ledger_events(&mut self, ledger_events: &[LedgerEvent]) {
+        for ledger_event in ledger_events {
+            match ledger_event.grouping {
+                LedgerEventGrouping::Zswap => self.zswap_ledger_event_count += 1,
+                LedgerEventGrouping::Dust => self.dust_ledger_event_count += 1,
+                LedgerEventGrouping::Contract => {}
+            }
+        }
+    }
+}
+
+/// Get the UNIX timestamp in milliseconds of the start of the UTC day of the given UNIX timestamp
+/// in milliseconds.
+pub fn day_start(timestamp: u64) -> u64 {
+    timestamp - timestamp % DAY_MILLIS
+}
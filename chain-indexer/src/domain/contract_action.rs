@@ -15,9 +15,10 @@ use indexer_common::domain::{
     ContractAttributes, ContractBalance, SerializedContractAddress, SerializedContractState,
     SerializedZswapState,
 };
+use serde::{Deserialize, Serialize};
 
 /// A contract action.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ContractAction {
     pub address: SerializedContractAddress,
     pub state: SerializedContractState,
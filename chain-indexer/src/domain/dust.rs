@@ -12,9 +12,10 @@
 // limitations under the License.
 
 use indexer_common::domain::{CardanoRewardAddress, DustPublicKey, DustUtxoId};
+use serde::{Deserialize, Serialize};
 
 /// Domain representation of DUST registration events from the NativeTokenObservation pallet.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum DustRegistrationEvent {
     /// Cardano stake key registered with DUST address.
     Registration {
@@ -17,9 +17,9 @@ use fastrace::trace;
 use indexer_common::domain::{
     ApplyRegularTransactionOutcome, ApplySystemTransactionOutcome, BlockHash, LedgerVersion,
     NetworkId, SerializedContractAddress, SerializedLedgerStateKey, TransactionHash,
-    ledger::{self, LedgerParameters, RootCountRepair},
+    ledger::{self, ArenaNode, ArenaNodes, LedgerParameters, RootCountRepair},
 };
-use std::{collections::HashSet, ops::DerefMut};
+use std::{collections::HashSet, num::NonZeroUsize, ops::DerefMut};
 use thiserror::Error;
 
 /// Amount, in milliseconds, by which the first regular transaction's dust-validity `tblock` is
@@ -90,6 +90,29 @@ impl LedgerState {
         indexer_common::domain::ledger::LedgerState::root_hash_bytes(key, ledger_version)
     }
 
+    /// See [`indexer_common::domain::ledger::LedgerState::arena_nodes`].
+    pub fn arena_nodes<'a>(
+        keys: impl IntoIterator<Item = (&'a SerializedLedgerStateKey, LedgerVersion)>,
+        batch_size: NonZeroUsize,
+    ) -> Result<ArenaNodes, indexer_common::domain::ledger::Error> {
+        indexer_common::domain::ledger::LedgerState::arena_nodes(keys, batch_size)
+    }
+
+    /// See [`indexer_common::domain::ledger::LedgerState::save_arena_nodes`].
+    pub fn save_arena_nodes(
+        nodes: Vec<ArenaNode>,
+    ) -> Result<(), indexer_common::domain::ledger::Error> {
+        indexer_common::domain::ledger::LedgerState::save_arena_nodes(nodes)
+    }
+
+    /// See [`indexer_common::domain::ledger::LedgerState::persist_key`].
+    pub fn persist_key(
+        key: &SerializedLedgerStateKey,
+        ledger_version: LedgerVersion,
+    ) -> Result<(), indexer_common::domain::ledger::Error> {
+        indexer_common::domain::ledger::LedgerState::persist_key(key, ledger_version)
+    }
+
     /// The raw arena hash bytes of all currently persisted gc roots, fetched from the ledger DB.
     pub fn persisted_root_hashes() -> HashSet<Vec<u8>> {
         indexer_common::domain::ledger::LedgerState::persisted_root_hashes()
@@ -102,6 +125,15 @@ impl LedgerState {
         indexer_common::domain::ledger::LedgerState::repair_root_counts(window)
     }
 
+    /// See [`indexer_common::domain::ledger::LedgerState::lower_root_counts`].
+    pub fn lower_root_counts<'a, 'b>(
+        window: impl IntoIterator<Item = (&'a SerializedLedgerStateKey, LedgerVersion)>,
+        rolled_back: impl IntoIterator<Item = (&'b SerializedLedgerStateKey, LedgerVersion)>,
+    ) -> Result<u32, Error> {
+        indexer_common::domain::ledger::LedgerState::lower_root_counts(window, rolled_back)
+            .map_err(Error::Unpersist)
+    }
+
     /// Run a time-bounded mark-and-sweep gc on the ledger DB and return the
     /// number of arena nodes culled.
     pub fn gc(bound: std::time::Duration) -> usize {
@@ -20,6 +20,7 @@ use indexer_common::domain::{
     SerializedTransactionIdentifier, TransactionHash,
     ledger::{self, ZswapMerkleTreeRoot},
 };
+use serde::{Deserialize, Serialize};
 use std::{error::Error as StdError, fmt::Debug};
 
 /// Node abstraction.
@@ -43,6 +44,15 @@ where
         after: Option<BlockRef>,
     ) -> impl Stream<Item = Result<Block, Self::Error>>;
 
+    /// A stream of best, i.e. possibly not yet finalized, [Block]s starting after the given block.
+    /// Blocks are yielded in parent-child order, but on a reorg the stream continues with the first
+    /// block of the new best chain above the fork point, i.e. with a block the parent of which is
+    /// not the previously yielded block but one of its ancestors.
+    fn best_blocks(
+        &mut self,
+        after: Option<BlockRef>,
+    ) -> impl Stream<Item = Result<Block, Self::Error>>;
+
     /// Fetch system parameters (D-Parameter and Terms & Conditions) at a given block.
     async fn fetch_system_parameters(
         &self,
@@ -55,6 +65,10 @@ where
     /// Fetch serialized genesis ledger state from the chain spec's system properties.
     /// Returns the raw bytes of the genesis `LedgerState`, errs if unavailable.
     async fn fetch_genesis_ledger_state(&self) -> Result<ByteVec, Self::Error>;
+
+    /// Fetch the Midnight transactions of the extrinsics currently pending in the node's
+    /// transaction pool, i.e. submitted but not yet included in a block.
+    async fn fetch_pending_transactions(&self) -> Result<Vec<PendingTransaction>, Self::Error>;
 }
 
 #[derive(Debug, Clone)]
@@ -91,6 +105,7 @@ impl TryFrom<Block> for (domain::Block, Vec<Transaction>) {
             ledger_state_root: block.ledger_state_root,
             dust_registration_events: block.dust_registration_events,
             bridge_events: block.bridge_events,
+            finalized: true,
             ledger_parameters: Default::default(),
             zswap_end_index: 0,
             dust_commitment_end_index: 0,
@@ -103,13 +118,13 @@ impl TryFrom<Block> for (domain::Block, Vec<Transaction>) {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Transaction {
     Regular(RegularTransaction),
     System(SystemTransaction),
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct RegularTransaction {
     pub hash: TransactionHash,
     pub protocol_version: ProtocolVersion,
@@ -118,13 +133,20 @@ pub struct RegularTransaction {
     pub contract_actions: Vec<ContractAction>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SystemTransaction {
     pub hash: TransactionHash,
     pub protocol_version: ProtocolVersion,
     pub raw: SerializedTransaction,
 }
 
+/// A Midnight transaction pending in the node's transaction pool.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingTransaction {
+    pub protocol_version: ProtocolVersion,
+    pub raw: SerializedTransaction,
+}
+
 impl From<&Block> for BlockRef {
     fn from(block: &Block) -> Self {
         Self {
@@ -0,0 +1,30 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use derive_more::Debug;
+use indexer_common::domain::{
+    ProtocolVersion, SerializedContractAddress, SerializedTransaction, TransactionHash,
+    UnshieldedAddress,
+};
+
+/// A transaction pending in the node's transaction pool, i.e. submitted but not yet included in a
+/// block, together with the addresses it touches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingTransaction {
+    pub hash: TransactionHash,
+    pub protocol_version: ProtocolVersion,
+    #[debug(skip)]
+    pub raw: SerializedTransaction,
+    pub contract_addresses: Vec<SerializedContractAddress>,
+    pub unshielded_addresses: Vec<UnshieldedAddress>,
+}
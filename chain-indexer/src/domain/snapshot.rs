@@ -0,0 +1,48 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use serde::{Deserialize, Serialize};
+
+/// Version of the snapshot file format, to be increased on incompatible changes, including
+/// changes of the exported tables.
+pub const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+
+/// Leading metadata of a snapshot, which bootstraps the chain-indexer at the given block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotHeader {
+    pub format_version: u32,
+
+    /// The format of the rows, which depends on the storage backend, see
+    /// [Storage::snapshot_rows_format](crate::domain::storage::Storage::snapshot_rows_format).
+    pub rows_format: String,
+
+    pub network_id: String,
+
+    /// The hex-encoded hash of the block the snapshot was taken at.
+    pub block_hash: String,
+
+    pub height: u64,
+
+    pub protocol_version: u32,
+
+    /// The number of the newest blocks up to and including the snapshot's block whose ledger
+    /// states are part of the snapshot, i.e. those retained when it was taken.
+    pub ledger_state_count: u64,
+}
+
+/// A batch of rows of a single table, each one a JSON object.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotRows {
+    pub table: String,
+    pub rows: Vec<serde_json::Value>,
+}
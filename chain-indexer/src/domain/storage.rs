@@ -11,12 +11,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use futures::Stream;
 use indexer_common::domain::{ProtocolVersion, SerializedLedgerStateKey};
 use std::num::NonZeroUsize;
 
 use crate::domain::{
-    Block, BlockRef, DParameter, DustRegistrationEvent, SystemParametersChange, TermsAndConditions,
-    Transaction,
+    Block, BlockRef, DParameter, DustRegistrationEvent, PendingTransaction, SystemParametersChange,
+    TermsAndConditions, Transaction, snapshot::SnapshotRows,
 };
 
 /// Storage abstraction.
@@ -25,6 +26,15 @@ pub trait Storage
 where
     Self: Clone + Send + Sync + 'static,
 {
+    /// The lock excluding other chain-indexers from indexing or rewinding, held until dropped.
+    type IndexerLock: Send;
+
+    /// Acquire the indexer lock, waiting while another chain-indexer holds it.
+    async fn lock_indexer(&self) -> Result<Self::IndexerLock, sqlx::Error>;
+
+    /// Try to acquire the indexer lock; `None` if another chain-indexer holds it.
+    async fn try_lock_indexer(&self) -> Result<Option<Self::IndexerLock>, sqlx::Error>;
+
     /// Save the given block with parameters and return the max regular transaction ID.
     async fn save_block(
         &mut self,
@@ -35,11 +45,41 @@ where
         system_parameters_change: Option<&SystemParametersChange>,
     ) -> Result<Option<u64>, sqlx::Error>;
 
+    /// Mark the given finalized block and its ancestors, found by walking the parent hashes of the
+    /// stored blocks, as finalized; does nothing if the given block is not stored. Returns the
+    /// number of newly finalized blocks.
+    async fn finalize_blocks(&mut self, finalized_block: BlockRef) -> Result<u64, sqlx::Error>;
+
+    /// Delete all blocks above the given height together with all their related data, reverting
+    /// spent unshielded UTXOs and DUST generation dtimes and resetting wallets which have already
+    /// indexed deleted transactions. The ledger state keys of the deleted blocks are saved as
+    /// rolled back in the same transaction. Returns the number of deleted blocks.
+    async fn roll_back_blocks(&mut self, height: u64) -> Result<u64, sqlx::Error>;
+
+    /// Get the protocol versions and ledger state keys of the rolled back blocks whose ledger
+    /// states have not yet been unpersisted.
+    async fn get_rolled_back_ledger_state_keys(
+        &self,
+    ) -> Result<Vec<(ProtocolVersion, SerializedLedgerStateKey)>, sqlx::Error>;
+
+    /// Delete the ledger state keys of the rolled back blocks once their ledger states have been
+    /// unpersisted.
+    async fn delete_rolled_back_ledger_state_keys(&mut self) -> Result<(), sqlx::Error>;
+
+    /// Get the block ref of the highest stored finalized block.
+    async fn get_highest_finalized_block(&self) -> Result<Option<BlockRef>, sqlx::Error>;
+
     /// Get the block ref, ledger state key and protocol version of the highest stored block.
     async fn get_highest_block(
         &self,
     ) -> Result<Option<(BlockRef, ProtocolVersion, SerializedLedgerStateKey)>, sqlx::Error>;
 
+    /// Get the block ref, ledger state key and protocol version of the block at the given height.
+    async fn get_block_at(
+        &self,
+        height: u64,
+    ) -> Result<Option<(BlockRef, ProtocolVersion, SerializedLedgerStateKey)>, sqlx::Error>;
+
     /// Get the timestamp (milliseconds) of the highest stored block, if any. Used on resume to seed
     /// the parent-block timestamp, so the first block processed after a restart bumps its first
     /// regular transaction's well-formed `tblock` off the true parent block time rather than off its
@@ -66,4 +106,39 @@ where
     async fn get_latest_terms_and_conditions(
         &self,
     ) -> Result<Option<TermsAndConditions>, sqlx::Error>;
+
+    /// Save the given pending transactions seen at the given time (milliseconds): known ones are
+    /// marked as seen, unknown ones are saved unless already included in a block. Returns the
+    /// number of newly saved pending transactions.
+    async fn save_pending_transactions(
+        &mut self,
+        pending_transactions: &[PendingTransaction],
+        seen_at: u64,
+    ) -> Result<u64, sqlx::Error>;
+
+    /// Delete the pending transactions which have meanwhile been included in a block or have not
+    /// been seen since the given time (milliseconds). Returns the number of deleted pending
+    /// transactions.
+    async fn delete_pending_transactions(&mut self, seen_before: u64) -> Result<u64, sqlx::Error>;
+
+    /// The format of the snapshot rows, which differs between storage backends.
+    fn snapshot_rows_format(&self) -> &'static str;
+
+    /// Get a stream of batches of the rows of all tables holding indexed chain data, read from one
+    /// consistent snapshot of the storage. Referenced rows come before the rows referencing them.
+    fn get_snapshot_rows(
+        &self,
+        batch_size: NonZeroUsize,
+    ) -> impl Stream<Item = Result<SnapshotRows, sqlx::Error>> + Send;
+
+    /// Save the given batch of snapshot rows into an empty storage; batches must be saved in the
+    /// order of [Storage::get_snapshot_rows].
+    async fn save_snapshot_rows(&mut self, rows: &SnapshotRows) -> Result<(), sqlx::Error>;
+
+    /// Finish saving snapshot rows, e.g. by advancing ID sequences beyond the saved IDs.
+    async fn finish_snapshot_rows(&mut self) -> Result<(), sqlx::Error>;
+
+    /// Delete all rows saved from a snapshot, e.g. after a failed import, such that importing can
+    /// be retried into the then again empty storage.
+    async fn delete_snapshot_rows(&mut self) -> Result<(), sqlx::Error>;
 }
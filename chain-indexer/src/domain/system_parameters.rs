@@ -14,23 +14,24 @@
 //! System parameters domain types for chain-indexer.
 
 use indexer_common::domain::{BlockHash, TermsAndConditionsHash};
+use serde::{Deserialize, Serialize};
 
 /// D-Parameter from the node.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DParameter {
     pub num_permissioned_candidates: u16,
     pub num_registered_candidates: u16,
 }
 
 /// Terms and Conditions from the node.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TermsAndConditions {
     pub hash: TermsAndConditionsHash,
     pub url: String,
 }
 
 /// System parameters change detected during block processing.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemParametersChange {
     pub block_height: u64,
     pub block_hash: BlockHash,
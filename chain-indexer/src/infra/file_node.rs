@@ -0,0 +1,827 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! [Node] implementations recording the blocks, genesis ledger state and system parameters
+//! provided by another node into an archive file and replaying such an archive, e.g. to index
+//! recorded chain segments deterministically without network access.
+//!
+//! The archive is a gzip compressed sequence of records, each one a JSON document prefixed with
+//! its big-endian u64 length. Records are flushed as soon as they are written, hence a truncated
+//! archive, e.g. of a crashed recording, remains readable up to its last complete record.
+
+use crate::domain::{
+    BlockRef, DustRegistrationEvent, SystemParametersChange,
+    node::{Block, Node, PendingTransaction, Transaction},
+};
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use futures::{Stream, StreamExt, TryStreamExt, future::ready, stream};
+use indexer_common::domain::{
+    BlockAuthor, BlockHash, ByteVec, NodeVersion, ProtocolVersion,
+    bridge::BridgeEvent,
+    ledger::{self, ZswapMerkleTreeRoot},
+};
+use log::debug;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+    sync::Arc,
+};
+use thiserror::Error;
+
+const MAGIC: &[u8; 8] = b"MNIDXARC";
+
+/// Upper bound for the length of a record, way beyond the size of any block, to not allocate
+/// arbitrary amounts of memory for a corrupt length prefix.
+const MAX_RECORD_LEN: u64 = 1024 * 1024 * 1024;
+
+/// A [Node] implementation wrapping another one and recording all blocks, system parameters and
+/// the genesis ledger state it provides into an archive file which can be replayed with
+/// [FileNode].
+#[derive(Clone)]
+pub struct RecordingNode<N> {
+    node: N,
+    writer: Arc<ArchiveWriter>,
+}
+
+impl<N> RecordingNode<N>
+where
+    N: Node,
+{
+    /// Create a new [RecordingNode] wrapping the given node and recording into a newly created or
+    /// truncated archive file at the given path.
+    pub fn new(node: N, file: impl AsRef<Path>) -> Result<Self, RecordingNodeError<N::Error>> {
+        let writer = ArchiveWriter::create(file.as_ref()).map_err(RecordingNodeError::Create)?;
+
+        Ok(Self {
+            node,
+            writer: Arc::new(writer),
+        })
+    }
+}
+
+impl<N> Node for RecordingNode<N>
+where
+    N: Node,
+{
+    type Error = RecordingNodeError<N::Error>;
+
+    async fn highest_blocks(
+        &self,
+    ) -> Result<impl Stream<Item = Result<BlockRef, Self::Error>> + Send, Self::Error> {
+        let highest_blocks = self
+            .node
+            .highest_blocks()
+            .await
+            .map_err(RecordingNodeError::Node)?
+            .map_err(RecordingNodeError::Node);
+
+        Ok(highest_blocks)
+    }
+
+    fn finalized_blocks(
+        &mut self,
+        after: Option<BlockRef>,
+    ) -> impl Stream<Item = Result<Block, Self::Error>> {
+        let writer = self.writer.clone();
+
+        self.node
+            .finalized_blocks(after)
+            .map_err(RecordingNodeError::Node)
+            .and_then(move |block| ready(writer.write_block(block, Record::FinalizedBlock)))
+    }
+
+    fn best_blocks(
+        &mut self,
+        after: Option<BlockRef>,
+    ) -> impl Stream<Item = Result<Block, Self::Error>> {
+        let writer = self.writer.clone();
+
+        self.node
+            .best_blocks(after)
+            .map_err(RecordingNodeError::Node)
+            .and_then(move |block| ready(writer.write_block(block, Record::BestBlock)))
+    }
+
+    async fn fetch_system_parameters(
+        &self,
+        block_hash: BlockHash,
+        block_height: u64,
+        timestamp: u64,
+        node_version: NodeVersion,
+    ) -> Result<SystemParametersChange, Self::Error> {
+        let system_parameters_change = self
+            .node
+            .fetch_system_parameters(block_hash, block_height, timestamp, node_version)
+            .await
+            .map_err(RecordingNodeError::Node)?;
+
+        self.writer
+            .write(&Record::SystemParameters(system_parameters_change.clone()))
+            .map_err(RecordingNodeError::Write)?;
+
+        Ok(system_parameters_change)
+    }
+
+    async fn fetch_genesis_ledger_state(&self) -> Result<ByteVec, Self::Error> {
+        let genesis_ledger_state = self
+            .node
+            .fetch_genesis_ledger_state()
+            .await
+            .map_err(RecordingNodeError::Node)?;
+
+        self.writer
+            .write(&Record::GenesisLedgerState(genesis_ledger_state.clone()))
+            .map_err(RecordingNodeError::Write)?;
+
+        Ok(genesis_ledger_state)
+    }
+
+    async fn fetch_pending_transactions(&self) -> Result<Vec<PendingTransaction>, Self::Error> {
+        // Pending transactions are transient, hence not recorded.
+        self.node
+            .fetch_pending_transactions()
+            .await
+            .map_err(RecordingNodeError::Node)
+    }
+}
+
+/// Error possibly returned by [RecordingNode].
+#[derive(Debug, Error)]
+pub enum RecordingNodeError<E> {
+    #[error(transparent)]
+    Node(E),
+
+    #[error("cannot create archive file")]
+    Create(#[source] io::Error),
+
+    #[error("cannot write archive record")]
+    Write(#[source] io::Error),
+
+    #[error("cannot serialize zswap merkle tree root")]
+    SerializeZswapMerkleTreeRoot(#[source] ledger::Error),
+}
+
+/// A [Node] implementation replaying an archive file recorded by [RecordingNode].
+///
+/// Once all recorded blocks have been yielded, the block streams stay pending, like the ones of a
+/// live node without new blocks.
+#[derive(Clone)]
+pub struct FileNode {
+    archive: Arc<Archive>,
+}
+
+impl FileNode {
+    /// Create a new [FileNode] by loading the archive file at the given path.
+    pub fn new(file: impl AsRef<Path>) -> Result<Self, FileNodeError> {
+        let archive = Archive::load(file.as_ref())?;
+        debug!(
+            finalized_blocks = archive.finalized_blocks.len(),
+            best_blocks = archive.best_blocks.len(),
+            system_parameters = archive.system_parameters.len();
+            "archive loaded"
+        );
+
+        Ok(Self {
+            archive: Arc::new(archive),
+        })
+    }
+}
+
+impl Node for FileNode {
+    type Error = FileNodeError;
+
+    async fn highest_blocks(
+        &self,
+    ) -> Result<impl Stream<Item = Result<BlockRef, Self::Error>> + Send, Self::Error> {
+        let highest_block = self.archive.finalized_blocks.last().map(BlockRef::from);
+
+        let highest_blocks = stream::iter(highest_block).map(Ok).chain(stream::pending());
+
+        Ok(highest_blocks)
+    }
+
+    fn finalized_blocks(
+        &mut self,
+        after: Option<BlockRef>,
+    ) -> impl Stream<Item = Result<Block, Self::Error>> {
+        let blocks = &self.archive.finalized_blocks;
+
+        match self.archive.first_finalized_block_index(after) {
+            Ok(start) => stream::iter(blocks[start..].iter().cloned())
+                .map(Ok)
+                .chain(stream::pending())
+                .left_stream(),
+
+            Err(error) => stream::once(ready(Err(error))).right_stream(),
+        }
+    }
+
+    fn best_blocks(
+        &mut self,
+        after: Option<BlockRef>,
+    ) -> impl Stream<Item = Result<Block, Self::Error>> {
+        // Skip the recorded best blocks up to and including the given one, if recorded at all.
+        let blocks = &self.archive.best_blocks;
+        let start = after
+            .and_then(|after| blocks.iter().rposition(|block| block.hash == after.hash))
+            .map(|index| index + 1)
+            .unwrap_or_default();
+
+        stream::iter(blocks[start..].iter().cloned())
+            .map(Ok)
+            .chain(stream::pending())
+    }
+
+    async fn fetch_system_parameters(
+        &self,
+        block_hash: BlockHash,
+        _block_height: u64,
+        _timestamp: u64,
+        _node_version: NodeVersion,
+    ) -> Result<SystemParametersChange, Self::Error> {
+        self.archive
+            .system_parameters
+            .get(&block_hash)
+            .cloned()
+            .ok_or(FileNodeError::MissingSystemParameters(block_hash))
+    }
+
+    async fn fetch_genesis_ledger_state(&self) -> Result<ByteVec, Self::Error> {
+        self.archive
+            .genesis_ledger_state
+            .clone()
+            .ok_or(FileNodeError::MissingGenesisLedgerState)
+    }
+
+    async fn fetch_pending_transactions(&self) -> Result<Vec<PendingTransaction>, Self::Error> {
+        Ok(vec![])
+    }
+}
+
+/// Error possibly returned by [FileNode].
+#[derive(Debug, Error)]
+pub enum FileNodeError {
+    #[error("cannot read archive file")]
+    Read(#[source] io::Error),
+
+    #[error("not an archive file")]
+    NoArchive,
+
+    #[error("cannot deserialize archive record")]
+    DeserializeRecord(#[source] serde_json::Error),
+
+    #[error("cannot deserialize zswap merkle tree root")]
+    DeserializeZswapMerkleTreeRoot(#[source] ledger::Error),
+
+    #[error("no system parameters recorded for block {0}")]
+    MissingSystemParameters(BlockHash),
+
+    #[error("no genesis ledger state recorded")]
+    MissingGenesisLedgerState,
+
+    #[error("no finalized block recorded at height {0}")]
+    MissingFinalizedBlock(u64),
+
+    #[error("finalized block recorded at height {0} does not link to block {1}")]
+    UnlinkedFinalizedBlock(u64, BlockHash),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+enum Record {
+    GenesisLedgerState(ByteVec),
+    FinalizedBlock(ArchivedBlock),
+    BestBlock(ArchivedBlock),
+    SystemParameters(SystemParametersChange),
+}
+
+/// A [Block] with its zswap merkle tree root serialized.
+#[derive(Debug, Serialize, Deserialize)]
+struct ArchivedBlock {
+    hash: BlockHash,
+    height: u64,
+    protocol_version: ProtocolVersion,
+    parent_hash: BlockHash,
+    author: Option<BlockAuthor>,
+    timestamp: u64,
+    zswap_merkle_tree_root: ByteVec,
+    ledger_state_root: Option<ByteVec>,
+    transactions: Vec<Transaction>,
+    dust_registration_events: Vec<DustRegistrationEvent>,
+    bridge_events: Vec<BridgeEvent>,
+}
+
+impl TryFrom<Block> for ArchivedBlock {
+    type Error = ledger::Error;
+
+    fn try_from(block: Block) -> Result<Self, Self::Error> {
+        Ok(Self {
+            hash: block.hash,
+            height: block.height,
+            protocol_version: block.protocol_version,
+            parent_hash: block.parent_hash,
+            author: block.author,
+            timestamp: block.timestamp,
+            zswap_merkle_tree_root: block.zswap_merkle_tree_root.serialize()?,
+            ledger_state_root: block.ledger_state_root,
+            transactions: block.transactions,
+            dust_registration_events: block.dust_registration_events,
+            bridge_events: block.bridge_events,
+        })
+    }
+}
+
+impl TryFrom<ArchivedBlock> for Block {
+    type Error = ledger::Error;
+
+    fn try_from(block: ArchivedBlock) -> Result<Self, Self::Error> {
+        let zswap_merkle_tree_root = ZswapMerkleTreeRoot::deserialize(
+            block.zswap_merkle_tree_root,
+            block.protocol_version.ledger_version(),
+        )?;
+
+        Ok(Self {
+            hash: block.hash,
+            height: block.height,
+            protocol_version: block.protocol_version,
+            parent_hash: block.parent_hash,
+            author: block.author,
+            timestamp: block.timestamp,
+            zswap_merkle_tree_root,
+            ledger_state_root: block.ledger_state_root,
+            transactions: block.transactions,
+            dust_registration_events: block.dust_registration_events,
+            bridge_events: block.bridge_events,
+        })
+    }
+}
+
+struct ArchiveWriter(Mutex<GzEncoder<BufWriter<File>>>);
+
+impl ArchiveWriter {
+    fn create(file: &Path) -> io::Result<Self> {
+        let mut writer =
+            GzEncoder::new(BufWriter::new(File::create(file)?), Compression::default());
+        writer.write_all(MAGIC)?;
+        writer.flush()?;
+
+        Ok(Self(Mutex::new(writer)))
+    }
+
+    fn write(&self, record: &Record) -> io::Result<()> {
+        let bytes = serde_json::to_vec(record)?;
+
+        let mut writer = self.0.lock();
+        writer.write_all(&(bytes.len() as u64).to_be_bytes())?;
+        writer.write_all(&bytes)?;
+        writer.flush()
+    }
+
+    fn write_block<E>(
+        &self,
+        block: Block,
+        make_record: fn(ArchivedBlock) -> Record,
+    ) -> Result<Block, RecordingNodeError<E>> {
+        let archived_block = ArchivedBlock::try_from(block.clone())
+            .map_err(RecordingNodeError::SerializeZswapMerkleTreeRoot)?;
+        self.write(&make_record(archived_block))
+            .map_err(RecordingNodeError::Write)?;
+
+        Ok(block)
+    }
+}
+
+/// The content of an archive file.
+#[derive(Default)]
+struct Archive {
+    genesis_ledger_state: Option<ByteVec>,
+    finalized_blocks: Vec<Block>,
+    best_blocks: Vec<Block>,
+    system_parameters: HashMap<BlockHash, SystemParametersChange>,
+}
+
+impl Archive {
+    fn load(file: &Path) -> Result<Self, FileNodeError> {
+        let mut reader = GzDecoder::new(BufReader::new(
+            File::open(file).map_err(FileNodeError::Read)?,
+        ));
+
+        let mut magic = [0; MAGIC.len()];
+        reader.read_exact(&mut magic).map_err(FileNodeError::Read)?;
+        if &magic != MAGIC {
+            return Err(FileNodeError::NoArchive);
+        }
+
+        let mut archive = Archive::default();
+        while let Some(bytes) = read_record(&mut reader).map_err(FileNodeError::Read)? {
+            let record =
+                serde_json::from_slice(&bytes).map_err(FileNodeError::DeserializeRecord)?;
+
+            match record {
+                Record::GenesisLedgerState(genesis_ledger_state) => {
+                    archive.genesis_ledger_state = Some(genesis_ledger_state);
+                }
+
+                Record::FinalizedBlock(block) => {
+                    let block = Block::try_from(block)
+                        .map_err(FileNodeError::DeserializeZswapMerkleTreeRoot)?;
+
+                    // Re-subscriptions of the recorded node may have yielded blocks repeatedly.
+                    let highest_height = archive.finalized_blocks.last().map(|block| block.height);
+                    if highest_height.is_none_or(|height| block.height > height) {
+                        archive.finalized_blocks.push(block);
+                    }
+                }
+
+                Record::BestBlock(block) => {
+                    let block = Block::try_from(block)
+                        .map_err(FileNodeError::DeserializeZswapMerkleTreeRoot)?;
+                    archive.best_blocks.push(block);
+                }
+
+                Record::SystemParameters(system_parameters_change) => {
+                    archive.system_parameters.insert(
+                        system_parameters_change.block_hash,
+                        system_parameters_change,
+                    );
+                }
+            }
+        }
+
+        Ok(archive)
+    }
+
+    /// The index of the first recorded finalized block to be yielded after the given one which
+    /// must either be the highest recorded one or be the parent of the recorded one at its next
+    /// height.
+    fn first_finalized_block_index(&self, after: Option<BlockRef>) -> Result<usize, FileNodeError> {
+        let Some(after) = after else {
+            return Ok(0);
+        };
+
+        let blocks = &self.finalized_blocks;
+        if blocks.last().is_some_and(|block| block.hash == after.hash) {
+            return Ok(blocks.len());
+        }
+
+        let height = after.height + 1;
+        let index = blocks
+            .iter()
+            .position(|block| block.height == height)
+            .ok_or(FileNodeError::MissingFinalizedBlock(height))?;
+        if blocks[index].parent_hash != after.hash {
+            return Err(FileNodeError::UnlinkedFinalizedBlock(height, after.hash));
+        }
+
+        Ok(index)
+    }
+}
+
+/// Read the next record, returning `None` at the end of the archive or at a truncated record.
+fn read_record(reader: &mut impl Read) -> io::Result<Option<Vec<u8>>> {
+    let mut len = [0; 8];
+    match reader.read_exact(&mut len) {
+        Ok(()) => {}
+        Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(error) => return Err(error),
+    }
+
+    let len = u64::from_be_bytes(len);
+    if len > MAX_RECORD_LEN {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("record length {len} exceeds maximum of {MAX_RECORD_LEN}"),
+        ));
+    }
+
+    let mut bytes = vec![0; len as usize];
+    match reader.read_exact(&mut bytes) {
+        Ok(()) => Ok(Some(bytes)),
+        Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => Ok(None),
+        Err(error) => Err(error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        domain::{
+            BlockRef, SystemParametersChange,
+            node::{Block, Node, PendingTransaction},
+        },
+        infra::file_node::{FileNode, FileNodeError, MAGIC, RecordingNode},
+    };
+    use fake::{Fake, Faker};
+    use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+    use futures::{FutureExt, Stream, StreamExt, TryStreamExt, stream};
+    use indexer_common::{
+        domain::{
+            BlockHash, ByteArray, ByteVec, NodeVersion, ProtocolVersion,
+            ledger::ZswapMerkleTreeRoot,
+        },
+        error::BoxError,
+    };
+    use std::{
+        convert::Infallible,
+        fs::File,
+        io::{self, Read, Write},
+        path::Path,
+        sync::LazyLock,
+    };
+
+    #[tokio::test]
+    async fn test_record_and_replay() -> Result<(), BoxError> {
+        let dir = tempfile::tempdir()?;
+        let file = dir.path().join("archive");
+
+        let mut recording_node = RecordingNode::new(MockNode, &file)?;
+        let blocks = recording_node
+            .finalized_blocks(None)
+            .try_collect::<Vec<_>>()
+            .await?;
+        assert_eq!(blocks.len(), 4);
+        recording_node
+            .fetch_system_parameters(BLOCK_1.hash, 1, 0, NodeVersion::V0_22)
+            .await?;
+        recording_node.fetch_genesis_ledger_state().await?;
+        drop(recording_node);
+
+        let mut file_node = FileNode::new(&file)?;
+
+        let heights = file_node
+            .finalized_blocks(Some(BlockRef::from(&*BLOCK_0)))
+            .take(2)
+            .map_ok(|block| block.height)
+            .try_collect::<Vec<_>>()
+            .await?;
+        assert_eq!(heights, [1, 2]);
+
+        let block = file_node
+            .finalized_blocks(None)
+            .try_next()
+            .await?
+            .expect("first block is replayed");
+        assert_eq!(block.hash, BLOCK_0.hash);
+        assert_eq!(block.zswap_merkle_tree_root, BLOCK_0.zswap_merkle_tree_root);
+
+        let highest_block = file_node.highest_blocks().await?.try_next().await?;
+        assert_eq!(highest_block.map(|block| block.hash), Some(BLOCK_2.hash));
+
+        let system_parameters_change = file_node
+            .fetch_system_parameters(BLOCK_1.hash, 1, 0, NodeVersion::V0_22)
+            .await?;
+        assert_eq!(system_parameters_change.block_hash, BLOCK_1.hash);
+        assert!(
+            file_node
+                .fetch_system_parameters(BLOCK_2.hash, 2, 0, NodeVersion::V0_22)
+                .await
+                .is_err()
+        );
+
+        let genesis_ledger_state = file_node.fetch_genesis_ledger_state().await?;
+        assert_eq!(genesis_ledger_state, ByteVec::from(vec![1, 2, 3]));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_replay_best_blocks() -> Result<(), BoxError> {
+        let dir = tempfile::tempdir()?;
+        let file = dir.path().join("archive");
+
+        let mut recording_node = RecordingNode::new(MockNode, &file)?;
+        let blocks = recording_node
+            .best_blocks(None)
+            .try_collect::<Vec<_>>()
+            .await?;
+        assert_eq!(blocks.len(), 3);
+        drop(recording_node);
+
+        let mut file_node = FileNode::new(&file)?;
+
+        let heights = file_node
+            .best_blocks(None)
+            .take(3)
+            .map_ok(|block| block.height)
+            .try_collect::<Vec<_>>()
+            .await?;
+        assert_eq!(heights, [0, 1, 2]);
+
+        let heights = file_node
+            .best_blocks(Some(BlockRef::from(&*BLOCK_1)))
+            .take(1)
+            .map_ok(|block| block.height)
+            .try_collect::<Vec<_>>()
+            .await?;
+        assert_eq!(heights, [2]);
+
+        // Nothing is recorded after the highest best block, hence the stream stays pending.
+        let block = file_node
+            .best_blocks(Some(BlockRef::from(&*BLOCK_2)))
+            .next()
+            .now_or_never();
+        assert!(block.is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_replay_unlinked_finalized_blocks() -> Result<(), BoxError> {
+        let dir = tempfile::tempdir()?;
+        let file = dir.path().join("archive");
+
+        let mut recording_node = RecordingNode::new(MockNode, &file)?;
+        recording_node
+            .finalized_blocks(None)
+            .try_collect::<Vec<_>>()
+            .await?;
+        drop(recording_node);
+
+        let mut file_node = FileNode::new(&file)?;
+
+        // The recorded block at height 1 does not link to a block other than block 0.
+        let after = BlockRef {
+            hash: ByteArray([42; 32]),
+            height: 0,
+        };
+        let result = file_node.finalized_blocks(Some(after)).try_next().await;
+        assert!(matches!(
+            result,
+            Err(FileNodeError::UnlinkedFinalizedBlock(1, hash)) if hash == after.hash
+        ));
+
+        // There is no recorded block at height 4.
+        let after = BlockRef {
+            hash: ByteArray([42; 32]),
+            height: 3,
+        };
+        let result = file_node.finalized_blocks(Some(after)).try_next().await;
+        assert!(matches!(
+            result,
+            Err(FileNodeError::MissingFinalizedBlock(4))
+        ));
+
+        // Nothing is recorded after the highest finalized block, hence the stream stays pending.
+        let block = file_node
+            .finalized_blocks(Some(BlockRef::from(&*BLOCK_2)))
+            .next()
+            .now_or_never();
+        assert!(block.is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_replay_truncated_archive() -> Result<(), BoxError> {
+        let dir = tempfile::tempdir()?;
+        let file = dir.path().join("archive");
+
+        let mut recording_node = RecordingNode::new(MockNode, &file)?;
+        recording_node
+            .finalized_blocks(None)
+            .try_collect::<Vec<_>>()
+            .await?;
+        drop(recording_node);
+
+        // Cut off the end of the last record, i.e. of block 2, like after a crashed recording.
+        let mut bytes = vec![];
+        GzDecoder::new(File::open(&file)?).read_to_end(&mut bytes)?;
+        bytes.truncate(bytes.len() - 42);
+        write_archive(&file, &bytes)?;
+
+        let mut file_node = FileNode::new(&file)?;
+
+        let heights = file_node
+            .finalized_blocks(None)
+            .take(2)
+            .map_ok(|block| block.height)
+            .try_collect::<Vec<_>>()
+            .await?;
+        assert_eq!(heights, [0, 1]);
+
+        let highest_block = file_node.highest_blocks().await?.try_next().await?;
+        assert_eq!(highest_block.map(|block| block.hash), Some(BLOCK_1.hash));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_invalid_archive() -> Result<(), BoxError> {
+        let dir = tempfile::tempdir()?;
+        let file = dir.path().join("archive");
+
+        write_archive(&file, b"NOTANARCHIVE")?;
+        assert!(matches!(
+            FileNode::new(&file),
+            Err(FileNodeError::NoArchive)
+        ));
+
+        // A bogus length must not make loading allocate that many bytes.
+        let bytes = [MAGIC.as_slice(), &u64::MAX.to_be_bytes()].concat();
+        write_archive(&file, &bytes)?;
+        assert!(matches!(
+            FileNode::new(&file),
+            Err(FileNodeError::Read(error)) if error.kind() == io::ErrorKind::InvalidData
+        ));
+
+        Ok(())
+    }
+
+    fn write_archive(file: &Path, bytes: &[u8]) -> io::Result<()> {
+        let mut writer = GzEncoder::new(File::create(file)?, Compression::default());
+        writer.write_all(bytes)?;
+        writer.finish()?;
+        Ok(())
+    }
+
+    #[derive(Clone)]
+    struct MockNode;
+
+    impl Node for MockNode {
+        type Error = Infallible;
+
+        async fn highest_blocks(
+            &self,
+        ) -> Result<impl Stream<Item = Result<BlockRef, Self::Error>> + Send, Self::Error> {
+            Ok(stream::empty())
+        }
+
+        fn finalized_blocks(
+            &mut self,
+            _after: Option<BlockRef>,
+        ) -> impl Stream<Item = Result<Block, Self::Error>> {
+            // Block 1 is yielded twice like after a re-subscription.
+            stream::iter([
+                BLOCK_0.clone(),
+                BLOCK_1.clone(),
+                BLOCK_1.clone(),
+                BLOCK_2.clone(),
+            ])
+            .map(Ok)
+        }
+
+        fn best_blocks(
+            &mut self,
+            _after: Option<BlockRef>,
+        ) -> impl Stream<Item = Result<Block, Self::Error>> {
+            stream::iter([BLOCK_0.clone(), BLOCK_1.clone(), BLOCK_2.clone()]).map(Ok)
+        }
+
+        async fn fetch_system_parameters(
+            &self,
+            block_hash: BlockHash,
+            block_height: u64,
+            timestamp: u64,
+            _node_version: NodeVersion,
+        ) -> Result<SystemParametersChange, Self::Error> {
+            Ok(SystemParametersChange {
+                block_height,
+                block_hash,
+                timestamp,
+                d_parameter: None,
+                terms_and_conditions: None,
+            })
+        }
+
+        async fn fetch_genesis_ledger_state(&self) -> Result<ByteVec, Self::Error> {
+            Ok(vec![1, 2, 3].into())
+        }
+
+        async fn fetch_pending_transactions(&self) -> Result<Vec<PendingTransaction>, Self::Error> {
+            Ok(vec![])
+        }
+    }
+
+    static BLOCK_0: LazyLock<Block> = LazyLock::new(|| make_block(0, ByteArray([0; 32])));
+    static BLOCK_1: LazyLock<Block> = LazyLock::new(|| make_block(1, BLOCK_0.hash));
+    static BLOCK_2: LazyLock<Block> = LazyLock::new(|| make_block(2, BLOCK_1.hash));
+
+    fn make_block(height: u64, parent_hash: BlockHash) -> Block {
+        Block {
+            hash: ByteArray([height as u8 + 1; 32]),
+            height,
+            protocol_version: ProtocolVersion::try_from(0_022_000_u32).unwrap(),
+            parent_hash,
+            author: Default::default(),
+            timestamp: Default::default(),
+            zswap_merkle_tree_root: ZswapMerkleTreeRoot::V8(Faker.fake()),
+            ledger_state_root: None,
+            transactions: Default::default(),
+            dust_registration_events: Default::default(),
+            bridge_events: Default::default(),
+        }
+    }
+}
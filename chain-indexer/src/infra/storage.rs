@@ -11,12 +11,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod snapshot;
+
 use crate::domain::{
-    self, Block, BlockRef, ContractAction, DParameter, DustRegistrationEvent, RegularTransaction,
-    SystemParametersChange, SystemTransaction, TermsAndConditions, Transaction,
+    self, Block, BlockRef, BlockRollup, BlockStats, ContractAction, DParameter,
+    DustRegistrationEvent, PendingTransaction, RegularTransaction, SystemParametersChange,
+    SystemTransaction, TermsAndConditions, Transaction, day_start, snapshot::SnapshotRows,
 };
 use fastrace::trace;
-use futures::TryFutureExt;
+use futures::{Stream, TryFutureExt};
 use indexer_common::{
     domain::{
         BlockHash, ByteVec, ContractAttributes, ContractBalance, LedgerEvent,
@@ -26,10 +29,14 @@ use indexer_common::{
     infra::sqlx::U128BeBytes,
 };
 use indoc::indoc;
+use itertools::Itertools;
 use log::debug;
 use serde::{Deserialize, Serialize};
-use sqlx::{QueryBuilder, Type, types::Json};
-use std::num::NonZeroUsize;
+use sqlx::{QueryBuilder, Row, Type, types::Json};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    num::NonZeroUsize,
+};
 
 #[cfg(feature = "cloud")]
 /// Sqlx transaction for Postgres.
@@ -39,6 +46,23 @@ type SqlxTransaction = sqlx::Transaction<'static, sqlx::Postgres>;
 /// Sqlx transaction for Sqlite.
 type SqlxTransaction = sqlx::Transaction<'static, sqlx::Sqlite>;
 
+/// The count columns shared by the block and daily rollups, see [rollup_counts].
+const ROLLUP_COUNT_COLUMNS: [&str; 9] = [
+    "regular_transaction_count",
+    "system_transaction_count",
+    "contract_deploy_count",
+    "contract_call_count",
+    "contract_update_count",
+    "unshielded_utxos_created",
+    "unshielded_utxos_spent",
+    "zswap_ledger_event_count",
+    "dust_ledger_event_count",
+];
+
+/// The key of the PostgreSQL advisory lock held by the chain-indexer while indexing or rewinding.
+#[cfg(feature = "cloud")]
+const INDEXER_LOCK_ID: i64 = 0x6368_6169_6e2d_6978; // "chain-ix"
+
 /// Unified storage implementation for PostgreSQL (cloud) and SQLite (standalone). Uses Cargo
 /// features to select the appropriate database backend at build time.
 #[derive(Debug, Clone)]
@@ -63,6 +87,51 @@ impl Storage {
 }
 
 impl domain::storage::Storage for Storage {
+    #[cfg(feature = "cloud")]
+    type IndexerLock = sqlx::PgConnection;
+
+    #[cfg(feature = "standalone")]
+    type IndexerLock = ();
+
+    #[cfg(feature = "cloud")]
+    #[trace]
+    async fn lock_indexer(&self) -> Result<Self::IndexerLock, sqlx::Error> {
+        let mut connection = self.pool.acquire().await?.detach();
+
+        sqlx::query("SELECT pg_advisory_lock($1)")
+            .bind(INDEXER_LOCK_ID)
+            .execute(&mut connection)
+            .await?;
+
+        Ok(connection)
+    }
+
+    #[cfg(feature = "standalone")]
+    async fn lock_indexer(&self) -> Result<Self::IndexerLock, sqlx::Error> {
+        // SQLite doesn't support advisory locks like PostgreSQL. But in standalone mode (single
+        // instance) there are no other chain-indexers, i.e. "locking" is always successful.
+        Ok(())
+    }
+
+    #[cfg(feature = "cloud")]
+    #[trace]
+    async fn try_lock_indexer(&self) -> Result<Option<Self::IndexerLock>, sqlx::Error> {
+        let mut connection = self.pool.acquire().await?.detach();
+
+        let lock_acquired = sqlx::query_scalar::<_, bool>("SELECT pg_try_advisory_lock($1)")
+            .bind(INDEXER_LOCK_ID)
+            .fetch_one(&mut connection)
+            .await?;
+
+        Ok(lock_acquired.then_some(connection))
+    }
+
+    #[cfg(feature = "standalone")]
+    async fn try_lock_indexer(&self) -> Result<Option<Self::IndexerLock>, sqlx::Error> {
+        // See lock_indexer: in standalone mode "locking" is always successful.
+        Ok(Some(()))
+    }
+
     #[trace]
     async fn save_block(
         &mut self,
@@ -92,6 +161,116 @@ impl domain::storage::Storage for Storage {
         Ok(max_transaction_id)
     }
 
+    #[trace(properties = { "finalized_block": "{finalized_block:?}" })]
+    async fn finalize_blocks(&mut self, finalized_block: BlockRef) -> Result<u64, sqlx::Error> {
+        // Walk the parent hashes from the finalized block down to the highest finalized ancestor.
+        let query = indoc! {"
+            WITH RECURSIVE finalized_blocks (hash, parent_hash, finalized) AS (
+                SELECT hash, parent_hash, finalized
+                FROM blocks
+                WHERE hash = $1
+                UNION ALL
+                SELECT blocks.hash, blocks.parent_hash, blocks.finalized
+                FROM blocks
+                JOIN finalized_blocks ON blocks.hash = finalized_blocks.parent_hash
+                WHERE finalized_blocks.finalized = FALSE
+            )
+            UPDATE blocks
+            SET finalized = TRUE
+            WHERE finalized = FALSE
+            AND hash IN (SELECT hash FROM finalized_blocks)
+        "};
+
+        let rows_affected = sqlx::query(query)
+            .bind(finalized_block.hash.as_ref())
+            .execute(&*self.pool)
+            .await?
+            .rows_affected();
+
+        Ok(rows_affected)
+    }
+
+    #[trace(properties = { "height": "{height}" })]
+    async fn roll_back_blocks(&mut self, height: u64) -> Result<u64, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let query = indoc! {"
+            INSERT INTO rolled_back_ledger_state_keys (protocol_version, ledger_state_key)
+            SELECT protocol_version, ledger_state_key
+            FROM blocks
+            WHERE height > $1
+            ORDER BY height
+        "};
+        sqlx::query(query)
+            .bind(height as i64)
+            .execute(&mut *tx)
+            .await?;
+
+        let deleted_blocks = delete_blocks_above(height, &mut tx).await?;
+
+        tx.commit().await?;
+
+        Ok(deleted_blocks)
+    }
+
+    #[trace]
+    async fn get_rolled_back_ledger_state_keys(
+        &self,
+    ) -> Result<Vec<(ProtocolVersion, SerializedLedgerStateKey)>, sqlx::Error> {
+        let query = indoc! {"
+            SELECT protocol_version, ledger_state_key
+            FROM rolled_back_ledger_state_keys
+            ORDER BY id
+        "};
+
+        sqlx::query_as::<_, (i64, SerializedLedgerStateKey)>(query)
+            .fetch_all(&*self.pool)
+            .await?
+            .into_iter()
+            .map(|(protocol_version, key)| {
+                let protocol_version = ProtocolVersion::try_from(protocol_version)
+                    .map_err(|error| sqlx::Error::Decode(error.into()))?;
+                Ok((protocol_version, key))
+            })
+            .collect()
+    }
+
+    #[trace]
+    async fn delete_rolled_back_ledger_state_keys(&mut self) -> Result<(), sqlx::Error> {
+        let query = indoc! {"
+            DELETE FROM rolled_back_ledger_state_keys
+        "};
+
+        sqlx::query(query).execute(&*self.pool).await?;
+
+        Ok(())
+    }
+
+    #[trace]
+    async fn get_highest_finalized_block(&self) -> Result<Option<BlockRef>, sqlx::Error> {
+        let query = indoc! {"
+            SELECT hash, height
+            FROM blocks
+            WHERE finalized = TRUE
+            ORDER BY height DESC
+            LIMIT 1
+        "};
+
+        sqlx::query_as::<_, (ByteVec, i64)>(query)
+            .fetch_optional(&*self.pool)
+            .await?
+            .map(|(hash, height)| {
+                let hash = BlockHash::try_from(hash.as_ref())
+                    .map_err(|error| sqlx::Error::Decode(error.into()))?;
+
+                Ok(BlockRef {
+                    hash,
+                    height: height as u64,
+                })
+            })
+            .transpose()
+    }
+
     #[trace]
     async fn get_highest_block(
         &self,
@@ -123,6 +302,38 @@ impl domain::storage::Storage for Storage {
             .transpose()
     }
 
+    #[trace(properties = { "height": "{height}" })]
+    async fn get_block_at(
+        &self,
+        height: u64,
+    ) -> Result<Option<(BlockRef, ProtocolVersion, SerializedLedgerStateKey)>, sqlx::Error> {
+        let query = indoc! {"
+            SELECT hash, height, protocol_version, ledger_state_key
+            FROM blocks
+            WHERE height = $1
+        "};
+
+        sqlx::query_as::<_, (ByteVec, i64, i64, SerializedLedgerStateKey)>(query)
+            .bind(height as i64)
+            .fetch_optional(&*self.pool)
+            .await?
+            .map(|(hash, height, protocol_version, key)| {
+                let hash = BlockHash::try_from(hash.as_ref())
+                    .map_err(|error| sqlx::Error::Decode(error.into()))?;
+
+                let block_ref = BlockRef {
+                    hash,
+                    height: height as u64,
+                };
+
+                let protocol_version = ProtocolVersion::try_from(protocol_version)
+                    .map_err(|error| sqlx::Error::Decode(error.into()))?;
+
+                Ok((block_ref, protocol_version, key))
+            })
+            .transpose()
+    }
+
     #[trace]
     async fn get_highest_block_timestamp(&self) -> Result<Option<u64>, sqlx::Error> {
         let query = indoc! {"
@@ -246,6 +457,106 @@ impl domain::storage::Storage for Storage {
             })
             .transpose()
     }
+
+    #[trace(properties = { "seen_at": "{seen_at}" })]
+    async fn save_pending_transactions(
+        &mut self,
+        pending_transactions: &[PendingTransaction],
+        seen_at: u64,
+    ) -> Result<u64, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let mut saved = 0;
+
+        for pending_transaction in pending_transactions {
+            let query = indoc! {"
+                UPDATE pending_transactions
+                SET last_seen = $1
+                WHERE hash = $2
+            "};
+
+            let rows_affected = sqlx::query(query)
+                .bind(seen_at as i64)
+                .bind(pending_transaction.hash.as_ref())
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+
+            if rows_affected == 0 {
+                saved += save_pending_transaction(pending_transaction, seen_at, &mut tx).await?;
+            }
+        }
+
+        tx.commit().await?;
+
+        Ok(saved)
+    }
+
+    #[trace(properties = { "seen_before": "{seen_before}" })]
+    async fn delete_pending_transactions(&mut self, seen_before: u64) -> Result<u64, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let query = indoc! {"
+            SELECT id
+            FROM pending_transactions
+            WHERE last_seen < $1
+            OR EXISTS (
+                SELECT 1
+                FROM transactions
+                WHERE transactions.hash = pending_transactions.hash
+            )
+        "};
+
+        let ids = sqlx::query_as::<_, (i64,)>(query)
+            .bind(seen_before as i64)
+            .fetch_all(&mut *tx)
+            .await?
+            .into_iter()
+            .map(|(id,)| id)
+            .collect::<Vec<_>>();
+
+        for id in &ids {
+            let query = indoc! {"
+                DELETE FROM pending_transaction_addresses
+                WHERE pending_transaction_id = $1
+            "};
+
+            sqlx::query(query).bind(id).execute(&mut *tx).await?;
+
+            let query = indoc! {"
+                DELETE FROM pending_transactions
+                WHERE id = $1
+            "};
+
+            sqlx::query(query).bind(id).execute(&mut *tx).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(ids.len() as u64)
+    }
+
+    fn snapshot_rows_format(&self) -> &'static str {
+        snapshot::ROWS_FORMAT
+    }
+
+    fn get_snapshot_rows(
+        &self,
+        batch_size: NonZeroUsize,
+    ) -> impl Stream<Item = Result<SnapshotRows, sqlx::Error>> {
+        self.get_snapshot_rows(batch_size)
+    }
+
+    async fn save_snapshot_rows(&mut self, rows: &SnapshotRows) -> Result<(), sqlx::Error> {
+        self.save_snapshot_rows(rows).await
+    }
+
+    async fn finish_snapshot_rows(&mut self) -> Result<(), sqlx::Error> {
+        self.finish_snapshot_rows().await
+    }
+
+    async fn delete_snapshot_rows(&mut self) -> Result<(), sqlx::Error> {
+        self.delete_snapshot_rows().await
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
@@ -266,6 +577,16 @@ impl From<&ContractAttributes> for ContractActionVariant {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[cfg_attr(
+    feature = "cloud",
+    sqlx(type_name = "PENDING_TRANSACTION_ADDRESS_VARIANT")
+)]
+enum PendingTransactionAddressVariant {
+    Contract,
+    Unshielded,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
 #[cfg_attr(feature = "cloud", sqlx(type_name = "LEDGER_EVENT_VARIANT"))]
 pub enum LedgerEventVariant {
@@ -338,7 +659,8 @@ async fn save_block(
             dust_commitment_end_index,
             dust_generation_end_index,
             dust_commitment_merkle_tree_root,
-            dust_generation_merkle_tree_root
+            dust_generation_merkle_tree_root,
+            finalized
         )
     "};
 
@@ -358,6 +680,7 @@ async fn save_block(
                 dust_generation_end_index,
                 dust_commitment_merkle_tree_root,
                 dust_generation_merkle_tree_root,
+                finalized,
                 ..
             } = block;
 
@@ -374,7 +697,8 @@ async fn save_block(
                 .push_bind(*dust_commitment_end_index as i64)
                 .push_bind(*dust_generation_end_index as i64)
                 .push_bind(dust_commitment_merkle_tree_root.as_ref())
-                .push_bind(dust_generation_merkle_tree_root.as_ref());
+                .push_bind(dust_generation_merkle_tree_root.as_ref())
+                .push_bind(*finalized);
         })
         .push(" RETURNING id")
         .build_query_as::<(i64,)>()
@@ -388,9 +712,297 @@ async fn save_block(
 
     save_bridge_events(&block.bridge_events, block_id, tx).await?;
 
+    save_tokens(transactions, block.height, tx).await?;
+
+    save_block_stats(&BlockStats::new(transactions), block_id, block.height, tx).await?;
+
+    save_block_rollup(&BlockRollup::new(transactions), block_id, block, tx).await?;
+
     Ok(max_transaction_id)
 }
 
+/// Delete all blocks above the given height together with all rows referencing them or their
+/// transactions, children before parents. Unshielded UTXOs spent by deleted transactions become
+/// unspent again, DUST generation dtimes set by deleted transactions are reset and the token
+/// registry is reverted. Returns the number of deleted blocks.
+#[trace(properties = { "height": "{height}" })]
+async fn delete_blocks_above(height: u64, tx: &mut SqlxTransaction) -> Result<u64, sqlx::Error> {
+    let height = height as i64;
+
+    // A dtime update always sets a finite dtime on a generation created earlier, i.e. before
+    // the update the dtime was infinite, stored as NULL.
+    let query = indoc! {"
+        SELECT ledger_events.attributes
+        FROM ledger_events
+        INNER JOIN transactions ON transactions.id = ledger_events.transaction_id
+        INNER JOIN blocks ON blocks.id = transactions.block_id
+        WHERE blocks.height > $1
+        AND ledger_events.variant = $2
+    "};
+    let dtime_updates = sqlx::query_as::<_, (Json<LedgerEventAttributes>,)>(query)
+        .bind(height)
+        .bind(LedgerEventVariant::DustGenerationDtimeUpdate)
+        .fetch_all(&mut **tx)
+        .await?;
+    for (Json(attributes),) in dtime_updates {
+        if let LedgerEventAttributes::DustGenerationDtimeUpdate {
+            generation_info, ..
+        } = attributes
+        {
+            let query = indoc! {"
+                UPDATE dust_generation_info
+                SET dtime = NULL
+                WHERE night_utxo_hash = $1
+            "};
+
+            sqlx::query(query)
+                .bind(generation_info.night_utxo_hash.as_ref())
+                .execute(&mut **tx)
+                .await?;
+        }
+    }
+
+    roll_back_tokens(height, tx).await?;
+
+    let query = indoc! {"
+        UPDATE unshielded_utxos
+        SET spending_transaction_id = NULL
+        WHERE spending_transaction_id IN (
+            SELECT transactions.id
+            FROM transactions
+            INNER JOIN blocks ON blocks.id = transactions.block_id
+            WHERE blocks.height > $1
+        )
+    "};
+    sqlx::query(query).bind(height).execute(&mut **tx).await?;
+
+    roll_back_daily_rollups(height, tx).await?;
+
+    // Tables referencing the deleted transactions, children before parents.
+    #[cfg(feature = "standalone")]
+    let transaction_identifiers = [(
+        "transaction_identifiers",
+        "transaction_id IN (SELECT id FROM deleted_transactions)",
+    )];
+    #[cfg(feature = "cloud")]
+    let transaction_identifiers: [(&str, &str); 0] = [];
+    let transaction_tables = [
+        (
+            "contract_event_indexed_fields",
+            indoc! {"
+                ledger_event_id IN (
+                    SELECT id
+                    FROM ledger_events
+                    WHERE transaction_id IN (SELECT id FROM deleted_transactions)
+                )
+            "},
+        ),
+        (
+            "ledger_events",
+            "transaction_id IN (SELECT id FROM deleted_transactions)",
+        ),
+        (
+            "contract_balances",
+            indoc! {"
+                contract_action_id IN (
+                    SELECT id
+                    FROM contract_actions
+                    WHERE transaction_id IN (SELECT id FROM deleted_transactions)
+                )
+            "},
+        ),
+        (
+            "contract_actions",
+            "transaction_id IN (SELECT id FROM deleted_transactions)",
+        ),
+        (
+            "unshielded_utxos",
+            "creating_transaction_id IN (SELECT id FROM deleted_transactions)",
+        ),
+        (
+            "dust_generation_info",
+            "transaction_id IN (SELECT id FROM deleted_transactions)",
+        ),
+        (
+            "bridge_claims",
+            "transaction_id IN (SELECT id FROM deleted_transactions)",
+        ),
+        (
+            "relevant_transactions",
+            "transaction_id IN (SELECT id FROM deleted_transactions)",
+        ),
+    ]
+    .into_iter()
+    .chain(transaction_identifiers)
+    .chain([(
+        "regular_transactions",
+        "id IN (SELECT id FROM deleted_transactions)",
+    )]);
+
+    // The wallets keep a count of their relevant transactions, some of which are deleted below.
+    let query = format!(
+        "WITH deleted_transactions AS ({DELETED_TRANSACTIONS}) {}",
+        indoc! {"
+            UPDATE wallets
+            SET relevant_transaction_count = relevant_transaction_count - (
+                SELECT COUNT(*)
+                FROM relevant_transactions
+                WHERE relevant_transactions.wallet_id = wallets.id
+                AND transaction_id IN (SELECT id FROM deleted_transactions)
+            )
+            WHERE id IN (
+                SELECT wallet_id
+                FROM relevant_transactions
+                WHERE transaction_id IN (SELECT id FROM deleted_transactions)
+            )
+        "}
+    );
+    sqlx::query(&query).bind(height).execute(&mut **tx).await?;
+
+    for (table, condition) in transaction_tables {
+        let query = format!(
+            "WITH deleted_transactions AS ({DELETED_TRANSACTIONS}) \
+             DELETE FROM {table} WHERE {condition}"
+        );
+        sqlx::query(&query).bind(height).execute(&mut **tx).await?;
+    }
+
+    // Tables referencing the deleted blocks, transactions last.
+    let block_tables = [
+        "block_stats",
+        "block_rollups",
+        "daily_active_addresses",
+        "dust_nullifiers",
+        "zswap_nullifiers",
+        "protocol_bridge_events",
+        "cnight_registrations",
+        "transactions",
+    ];
+    for table in block_tables {
+        let query = format!(
+            "DELETE FROM {table} WHERE block_id IN (SELECT id FROM blocks WHERE height > $1)"
+        );
+        sqlx::query(&query).bind(height).execute(&mut **tx).await?;
+    }
+
+    for table in [
+        "system_parameters_d",
+        "system_parameters_terms_and_conditions",
+    ] {
+        let query = format!("DELETE FROM {table} WHERE block_height > $1");
+        sqlx::query(&query).bind(height).execute(&mut **tx).await?;
+    }
+
+    let query = indoc! {"
+        DELETE FROM blocks
+        WHERE height > $1
+    "};
+    let deleted_blocks = sqlx::query(query)
+        .bind(height)
+        .execute(&mut **tx)
+        .await?
+        .rows_affected();
+
+    // Transaction IDs may be reused after deleting the highest ones, hence wallets must not
+    // consider themselves beyond the highest remaining transaction.
+    for column in [
+        "first_indexed_transaction_id",
+        "last_indexed_transaction_id",
+    ] {
+        let query = format!(
+            "UPDATE wallets SET {column} = (SELECT COALESCE(MAX(id), 0) FROM transactions) \
+             WHERE {column} > (SELECT COALESCE(MAX(id), 0) FROM transactions)"
+        );
+        sqlx::query(&query).execute(&mut **tx).await?;
+    }
+
+    Ok(deleted_blocks)
+}
+
+/// Common table expression selecting the IDs of the transactions of the blocks above height `$1`.
+const DELETED_TRANSACTIONS: &str = indoc! {"
+    SELECT transactions.id
+    FROM transactions
+    INNER JOIN blocks ON blocks.id = transactions.block_id
+    WHERE blocks.height > $1
+"};
+
+/// Save the given pending transaction with the addresses it touches unless it has already been
+/// included in a block, returning the number of saved pending transactions, i.e. 0 or 1.
+#[trace(properties = { "seen_at": "{seen_at}" })]
+async fn save_pending_transaction(
+    pending_transaction: &PendingTransaction,
+    seen_at: u64,
+    tx: &mut SqlxTransaction,
+) -> Result<u64, sqlx::Error> {
+    let query = indoc! {"
+        INSERT INTO pending_transactions (
+            hash,
+            protocol_version,
+            raw,
+            first_seen,
+            last_seen
+        )
+        SELECT $1, $2, $3, $4, $4
+        WHERE NOT EXISTS (
+            SELECT 1
+            FROM transactions
+            WHERE hash = $1
+        )
+        RETURNING id
+    "};
+
+    let pending_transaction_id = sqlx::query_as::<_, (i64,)>(query)
+        .bind(pending_transaction.hash.as_ref())
+        .bind(pending_transaction.protocol_version.into_i64())
+        .bind(pending_transaction.raw.as_ref())
+        .bind(seen_at as i64)
+        .fetch_optional(&mut **tx)
+        .await?;
+    let Some((pending_transaction_id,)) = pending_transaction_id else {
+        return Ok(0);
+    };
+
+    let contract_addresses = pending_transaction
+        .contract_addresses
+        .iter()
+        .map(|address| (PendingTransactionAddressVariant::Contract, address.as_ref()));
+    let unshielded_addresses = pending_transaction
+        .unshielded_addresses
+        .iter()
+        .map(|address| {
+            (
+                PendingTransactionAddressVariant::Unshielded,
+                address.as_ref(),
+            )
+        });
+    let addresses = contract_addresses
+        .chain(unshielded_addresses)
+        .collect::<Vec<_>>();
+
+    if !addresses.is_empty() {
+        let query = indoc! {"
+            INSERT INTO pending_transaction_addresses (
+                pending_transaction_id,
+                variant,
+                address
+            )
+        "};
+
+        QueryBuilder::new(query)
+            .push_values(addresses, |mut q, (variant, address)| {
+                q.push_bind(pending_transaction_id)
+                    .push_bind(variant)
+                    .push_bind(address);
+            })
+            .build()
+            .execute(&mut **tx)
+            .await?;
+    }
+
+    Ok(1)
+}
+
 #[trace(properties = { "block_id": "{block_id}" })]
 async fn save_transactions(
     transactions: &[Transaction],
@@ -1382,30 +1994,685 @@ async fn save_bridge_events(
     Ok(())
 }
 
-#[cfg(test)]
-mod contract_event_variant_tests {
-    use super::*;
-    use indexer_common::domain::{AddressOrContract, ByteVec};
+/// Credits and debits of unshielded token balances per token type and owner and of the balances
+/// held by contracts per token type, together with the minting contract address and domain
+/// separator per token type.
+#[derive(Debug, Default)]
+struct TokenDeltas {
+    balances: BTreeMap<(Vec<u8>, Vec<u8>), (u128, u128)>,
+    contract_balances: BTreeMap<Vec<u8>, (u128, u128)>,
+    mints: BTreeMap<Vec<u8>, (Vec<u8>, Vec<u8>)>,
+}
 
-    fn bv(bytes: &[u8]) -> ByteVec {
-        ByteVec::from(bytes.to_vec())
+impl TokenDeltas {
+    fn credit(&mut self, token_type: &[u8], owner: &[u8], value: u128) {
+        let (credit, _) = self
+            .balances
+            .entry((token_type.to_vec(), owner.to_vec()))
+            .or_default();
+        *credit = credit.saturating_add(value);
     }
 
-    // The From impl maps each ContractEvent attribute variant to the matching
-    // SQL LedgerEventVariant. A missing arm would fail the match exhaustively;
-    // this test pins the mapping so renames don't silently shift names.
-    #[test]
-    fn from_attributes_to_variant_covers_every_contract_event_variant() {
-        let cases: Vec<(LedgerEventAttributes, LedgerEventVariant)> = vec![
-            (
-                LedgerEventAttributes::ContractShieldedSpend {
-                    version: 1,
-                    entry_point: bv(b""),
-                    nullifier: bv(&[0; 32]),
-                },
-                LedgerEventVariant::ShieldedSpend,
-            ),
-            (
+    fn debit(&mut self, token_type: &[u8], owner: &[u8], value: u128) {
+        let (_, debit) = self
+            .balances
+            .entry((token_type.to_vec(), owner.to_vec()))
+            .or_default();
+        *debit = debit.saturating_add(value);
+    }
+
+    /// Credit or debit the differences between the given contract balances per token type before
+    /// and after some blocks; contract unshielded mints and burns as well as tokens sent to or
+    /// received from contracts show up in these.
+    fn add_contract_balances(
+        &mut self,
+        before: BTreeMap<Vec<u8>, u128>,
+        mut after: BTreeMap<Vec<u8>, u128>,
+    ) {
+        for (token_type, before) in before {
+            let after = after.remove(&token_type).unwrap_or_default();
+            let (credit, debit) = self.contract_balances.entry(token_type).or_default();
+            if after > before {
+                *credit = credit.saturating_add(after - before);
+            } else {
+                *debit = debit.saturating_add(before - after);
+            }
+        }
+
+        for (token_type, after) in after {
+            let (credit, _) = self.contract_balances.entry(token_type).or_default();
+            *credit = credit.saturating_add(after);
+        }
+    }
+
+    fn add_mints(&mut self, ledger_events: &[LedgerEvent]) {
+        for ledger_event in ledger_events {
+            if let LedgerEventAttributes::ContractUnshieldedMint {
+                domain_sep,
+                token_type,
+                ..
+            } = &ledger_event.attributes
+                && let Some(contract_address) = &ledger_event.contract_address
+            {
+                self.mints
+                    .entry(token_type.to_vec())
+                    .or_insert_with(|| (contract_address.as_ref().to_vec(), domain_sep.to_vec()));
+            }
+        }
+    }
+}
+
+/// Update the token registry for the given transactions of the block at the given height: new
+/// token types are registered as first seen at that height, holder balances, circulating supplies
+/// and holder counts are adjusted by the created and spent unshielded UTXOs and circulating
+/// supplies also by the changed balances of the contracts. Must be called after the transactions
+/// have been saved.
+#[trace(properties = { "block_height": "{block_height}" })]
+async fn save_tokens(
+    transactions: &[Transaction],
+    block_height: u64,
+    tx: &mut SqlxTransaction,
+) -> Result<(), sqlx::Error> {
+    let mut deltas = TokenDeltas::default();
+    let mut contract_addresses = BTreeSet::new();
+
+    for transaction in transactions {
+        match transaction {
+            Transaction::Regular(transaction) => {
+                contract_addresses.extend(
+                    transaction
+                        .contract_actions
+                        .iter()
+                        .map(|action| action.address.as_ref().to_vec()),
+                );
+                for utxo in &transaction.created_unshielded_utxos {
+                    deltas.credit(utxo.token_type.as_ref(), utxo.owner.as_ref(), utxo.value);
+                }
+                for utxo in &transaction.spent_unshielded_utxos {
+                    deltas.debit(utxo.token_type.as_ref(), utxo.owner.as_ref(), utxo.value);
+                }
+                deltas.add_mints(&transaction.ledger_events);
+            }
+
+            Transaction::System(transaction) => {
+                for utxo in &transaction.created_unshielded_utxos {
+                    deltas.credit(utxo.token_type.as_ref(), utxo.owner.as_ref(), utxo.value);
+                }
+                deltas.add_mints(&transaction.ledger_events);
+            }
+        }
+    }
+
+    let token_types = deltas
+        .balances
+        .keys()
+        .map(|(token_type, _)| token_type)
+        .chain(deltas.mints.keys())
+        .collect::<BTreeSet<_>>();
+    if token_types.is_empty() {
+        return Ok(());
+    }
+
+    let query = indoc! {"
+        INSERT INTO tokens (
+            token_type,
+            first_seen_block_height,
+            circulating_supply,
+            holder_count
+        )
+    "};
+    QueryBuilder::new(query)
+        .push_values(token_types, |mut q, token_type| {
+            q.push_bind(token_type.as_slice())
+                .push_bind(block_height as i64)
+                .push_bind(U128BeBytes::default())
+                .push_bind(0_i64);
+        })
+        .push(" ON CONFLICT (token_type) DO NOTHING")
+        .build()
+        .execute(&mut **tx)
+        .await?;
+
+    if !contract_addresses.is_empty() {
+        let height = block_height as i64;
+        let before = get_contract_balances(&contract_addresses, height - 1, tx).await?;
+        let after = get_contract_balances(&contract_addresses, height, tx).await?;
+        deltas.add_contract_balances(before, after);
+    }
+
+    apply_token_deltas(deltas, tx).await
+}
+
+/// Get the balances held by the contracts with the given addresses per token type as of their
+/// latest contract actions in blocks at or below the given height.
+async fn get_contract_balances(
+    addresses: &BTreeSet<Vec<u8>>,
+    height: i64,
+    tx: &mut SqlxTransaction,
+) -> Result<BTreeMap<Vec<u8>, u128>, sqlx::Error> {
+    let query = indoc! {"
+        SELECT token_type, amount
+        FROM contract_balances
+        WHERE contract_action_id IN (
+            SELECT max(contract_actions.id)
+            FROM contract_actions
+            INNER JOIN transactions ON transactions.id = contract_actions.transaction_id
+            INNER JOIN blocks ON blocks.id = transactions.block_id
+            WHERE contract_actions.address IN (
+    "};
+    let mut query = QueryBuilder::new(query);
+    let mut separated = query.separated(", ");
+    for address in addresses {
+        separated.push_bind(address.as_slice());
+    }
+    separated.push_unseparated(") AND blocks.height <= ");
+    query.push_bind(height);
+    query.push(" GROUP BY contract_actions.address)");
+
+    let rows = query
+        .build_query_as::<(Vec<u8>, U128BeBytes)>()
+        .fetch_all(&mut **tx)
+        .await?;
+
+    let mut balances = BTreeMap::<Vec<u8>, u128>::new();
+    for (token_type, amount) in rows {
+        let balance = balances.entry(token_type).or_default();
+        *balance = balance.saturating_add(amount.into());
+    }
+
+    Ok(balances)
+}
+
+/// Save the given fee statistics of the block with the given ID and height.
+#[trace(properties = { "block_height": "{block_height}" })]
+async fn save_block_stats(
+    stats: &BlockStats,
+    block_id: i64,
+    block_height: u64,
+    tx: &mut SqlxTransaction,
+) -> Result<(), sqlx::Error> {
+    let query = indoc! {"
+        INSERT INTO block_stats (
+            block_id,
+            block_height,
+            transaction_count,
+            transaction_bytes,
+            fee_sum,
+            fee_min,
+            fee_median,
+            fee_p90
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+    "};
+
+    sqlx::query(query)
+        .bind(block_id)
+        .bind(block_height as i64)
+        .bind(stats.transaction_count as i64)
+        .bind(stats.transaction_bytes as i64)
+        .bind(U128BeBytes::from(stats.fee_sum))
+        .bind(stats.fee_min.map(U128BeBytes::from))
+        .bind(stats.fee_median.map(U128BeBytes::from))
+        .bind(stats.fee_p90.map(U128BeBytes::from))
+        .execute(&mut **tx)
+        .await?;
+
+    Ok(())
+}
+
+/// Save the given rollup of the given block with the given ID and add it to the daily rollup of
+/// the UTC day of the block, counting the owners of created or spent unshielded UTXOs not yet
+/// active that day as active addresses.
+#[trace(properties = { "block_id": "{block_id}" })]
+async fn save_block_rollup(
+    rollup: &BlockRollup,
+    block_id: i64,
+    block: &Block,
+    tx: &mut SqlxTransaction,
+) -> Result<(), sqlx::Error> {
+    let counts = rollup_counts(rollup);
+
+    let query = format!(
+        "INSERT INTO block_rollups (block_id, block_height, timestamp, {}) ",
+        ROLLUP_COUNT_COLUMNS.join(", ")
+    );
+    QueryBuilder::new(query)
+        .push_values([()], |mut q, _| {
+            q.push_bind(block_id)
+                .push_bind(block.height as i64)
+                .push_bind(block.timestamp as i64);
+            for count in counts {
+                q.push_bind(count);
+            }
+        })
+        .build()
+        .execute(&mut **tx)
+        .await?;
+
+    let day = day_start(block.timestamp) as i64;
+
+    let active_address_count = if rollup.active_addresses.is_empty() {
+        0
+    } else {
+        let query = indoc! {"
+            INSERT INTO daily_active_addresses (day, address, block_id)
+        "};
+        QueryBuilder::new(query)
+            .push_values(&rollup.active_addresses, |mut q, address| {
+                q.push_bind(day)
+                    .push_bind(address.as_ref())
+                    .push_bind(block_id);
+            })
+            .push(" ON CONFLICT (day, address) DO NOTHING")
+            .build()
+            .execute(&mut **tx)
+            .await?
+            .rows_affected() as i64
+    };
+
+    add_to_daily_rollup(day, 1, counts, active_address_count, tx).await
+}
+
+/// Subtract the block rollups and active addresses of the blocks above the given height from the
+/// daily rollups and delete the daily rollups left without blocks. Must be called before the
+/// block rollups and active addresses of the deleted blocks are deleted.
+#[trace(properties = { "height": "{height}" })]
+async fn roll_back_daily_rollups(height: i64, tx: &mut SqlxTransaction) -> Result<(), sqlx::Error> {
+    // Per day: block count, counts and active address count.
+    let mut deltas = BTreeMap::<i64, (i64, [i64; 9], i64)>::new();
+
+    let query = format!(
+        "SELECT timestamp, {} \
+         FROM block_rollups \
+         WHERE block_id IN (SELECT id FROM blocks WHERE height > $1)",
+        ROLLUP_COUNT_COLUMNS.join(", ")
+    );
+    let rows = sqlx::query(&query)
+        .bind(height)
+        .fetch_all(&mut **tx)
+        .await?;
+    for row in rows {
+        let timestamp = row.try_get::<i64, _>(0)?;
+        let (block_count, counts, _) = deltas
+            .entry(day_start(timestamp as u64) as i64)
+            .or_default();
+        *block_count -= 1;
+        for (n, count) in counts.iter_mut().enumerate() {
+            *count -= row.try_get::<i64, _>(n + 1)?;
+        }
+    }
+
+    let query = indoc! {"
+        SELECT day, count(*)
+        FROM daily_active_addresses
+        WHERE block_id IN (SELECT id FROM blocks WHERE height > $1)
+        GROUP BY day
+    "};
+    let active_address_counts = sqlx::query_as::<_, (i64, i64)>(query)
+        .bind(height)
+        .fetch_all(&mut **tx)
+        .await?;
+    for (day, count) in active_address_counts {
+        let (_, _, active_address_count) = deltas.entry(day).or_default();
+        *active_address_count -= count;
+    }
+
+    for (day, (block_count, counts, active_address_count)) in deltas {
+        add_to_daily_rollup(day, block_count, counts, active_address_count, tx).await?;
+    }
+
+    let query = indoc! {"
+        DELETE FROM daily_rollups
+        WHERE block_count <= 0
+    "};
+    sqlx::query(query).execute(&mut **tx).await?;
+
+    Ok(())
+}
+
+/// Add the given block count, counts (see [ROLLUP_COUNT_COLUMNS]) and active address count to the
+/// daily rollup of the given day, creating it if not yet existing; negative values subtract.
+async fn add_to_daily_rollup(
+    day: i64,
+    block_count: i64,
+    counts: [i64; 9],
+    active_address_count: i64,
+    tx: &mut SqlxTransaction,
+) -> Result<(), sqlx::Error> {
+    let columns = ["block_count"]
+        .into_iter()
+        .chain(ROLLUP_COUNT_COLUMNS)
+        .chain(["active_address_count"]);
+
+    let query = format!(
+        "INSERT INTO daily_rollups (day, {}) ",
+        columns.clone().join(", ")
+    );
+    QueryBuilder::new(query)
+        .push_values([()], |mut q, _| {
+            q.push_bind(day).push_bind(block_count);
+            for count in counts {
+                q.push_bind(count);
+            }
+            q.push_bind(active_address_count);
+        })
+        .push(" ON CONFLICT (day) DO UPDATE SET ")
+        .push(
+            columns
+                .map(|column| format!("{column} = daily_rollups.{column} + EXCLUDED.{column}"))
+                .join(", "),
+        )
+        .build()
+        .execute(&mut **tx)
+        .await?;
+
+    Ok(())
+}
+
+/// The counts of the given rollup in the order of [ROLLUP_COUNT_COLUMNS].
+fn rollup_counts(rollup: &BlockRollup) -> [i64; 9] {
+    [
+        rollup.regular_transaction_count as i64,
+        rollup.system_transaction_count as i64,
+        rollup.contract_deploy_count as i64,
+        rollup.contract_call_count as i64,
+        rollup.contract_update_count as i64,
+        rollup.unshielded_utxos_created as i64,
+        rollup.unshielded_utxos_spent as i64,
+        rollup.zswap_ledger_event_count as i64,
+        rollup.dust_ledger_event_count as i64,
+    ]
+}
+
+/// Revert the token registry changes made by the blocks above the given height: UTXOs created by
+/// their transactions are debited, UTXOs spent by them are credited again, balances of contracts
+/// acting in them are reset to the ones at the given height and token types first seen above the
+/// given height are removed. Must be called before the UTXOs are un-spent or deleted and before
+/// the contract actions are deleted.
+#[trace(properties = { "height": "{height}" })]
+async fn roll_back_tokens(height: i64, tx: &mut SqlxTransaction) -> Result<(), sqlx::Error> {
+    let query = format!(
+        "WITH deleted_transactions AS ({DELETED_TRANSACTIONS}) {}",
+        indoc! {"
+            SELECT
+                token_type,
+                owner,
+                value,
+                creating_transaction_id IN (SELECT id FROM deleted_transactions),
+                COALESCE(spending_transaction_id IN (SELECT id FROM deleted_transactions), FALSE)
+            FROM unshielded_utxos
+            WHERE creating_transaction_id IN (SELECT id FROM deleted_transactions)
+            OR spending_transaction_id IN (SELECT id FROM deleted_transactions)
+        "}
+    );
+    let utxos = sqlx::query_as::<_, (Vec<u8>, Vec<u8>, U128BeBytes, bool, bool)>(&query)
+        .bind(height)
+        .fetch_all(&mut **tx)
+        .await?;
+
+    let mut deltas = TokenDeltas::default();
+    for (token_type, owner, value, created, spent) in utxos {
+        if created {
+            deltas.debit(&token_type, &owner, value.into());
+        }
+        if spent {
+            deltas.credit(&token_type, &owner, value.into());
+        }
+    }
+
+    let query = format!(
+        "WITH deleted_transactions AS ({DELETED_TRANSACTIONS}) {}",
+        indoc! {"
+            SELECT DISTINCT address
+            FROM contract_actions
+            WHERE transaction_id IN (SELECT id FROM deleted_transactions)
+        "}
+    );
+    let contract_addresses = sqlx::query_as::<_, (Vec<u8>,)>(&query)
+        .bind(height)
+        .fetch_all(&mut **tx)
+        .await?
+        .into_iter()
+        .map(|(address,)| address)
+        .collect::<BTreeSet<_>>();
+    if !contract_addresses.is_empty() {
+        let before = get_contract_balances(&contract_addresses, i64::MAX, tx).await?;
+        let after = get_contract_balances(&contract_addresses, height, tx).await?;
+        deltas.add_contract_balances(before, after);
+    }
+
+    apply_token_deltas(deltas, tx).await?;
+
+    let query = indoc! {"
+        DELETE FROM tokens
+        WHERE first_seen_block_height > $1
+    "};
+    sqlx::query(query).bind(height).execute(&mut **tx).await?;
+
+    Ok(())
+}
+
+/// Apply the given deltas to the holder balances as well as the circulating supplies and holder
+/// counts of the tokens which must already exist. Holders whose balance drops to zero are
+/// deleted. Minting contract addresses and domain separators are only set if not yet known.
+#[trace]
+async fn apply_token_deltas(
+    deltas: TokenDeltas,
+    tx: &mut SqlxTransaction,
+) -> Result<(), sqlx::Error> {
+    let TokenDeltas {
+        balances,
+        contract_balances,
+        mut mints,
+    } = deltas;
+
+    let current_balances = get_token_holder_balances(balances.keys(), tx).await?;
+
+    // Circulating supply credit and debit as well as holder count delta per token type.
+    let mut tokens = BTreeMap::<Vec<u8>, (u128, u128, i64)>::new();
+
+    for (holder, (credit, debit)) in balances {
+        let balance = current_balances.get(&holder).copied().unwrap_or_default();
+        let (token_type, owner) = holder;
+        let new_balance = balance.saturating_add(credit).saturating_sub(debit);
+
+        let holder_count_delta = match (balance, new_balance) {
+            (0, 0) => 0,
+
+            (0, _) => {
+                let query = indoc! {"
+                    INSERT INTO token_holders (token_type, owner, balance)
+                    VALUES ($1, $2, $3)
+                "};
+                sqlx::query(query)
+                    .bind(&token_type)
+                    .bind(&owner)
+                    .bind(U128BeBytes::from(new_balance))
+                    .execute(&mut **tx)
+                    .await?;
+                1
+            }
+
+            (_, 0) => {
+                let query = indoc! {"
+                    DELETE FROM token_holders
+                    WHERE token_type = $1
+                    AND owner = $2
+                "};
+                sqlx::query(query)
+                    .bind(&token_type)
+                    .bind(&owner)
+                    .execute(&mut **tx)
+                    .await?;
+                -1
+            }
+
+            _ => {
+                let query = indoc! {"
+                    UPDATE token_holders
+                    SET balance = $3
+                    WHERE token_type = $1
+                    AND owner = $2
+                "};
+                sqlx::query(query)
+                    .bind(&token_type)
+                    .bind(&owner)
+                    .bind(U128BeBytes::from(new_balance))
+                    .execute(&mut **tx)
+                    .await?;
+                0
+            }
+        };
+
+        let (supply_credit, supply_debit, holder_count) = tokens.entry(token_type).or_default();
+        *supply_credit = supply_credit.saturating_add(credit);
+        *supply_debit = supply_debit.saturating_add(debit);
+        *holder_count += holder_count_delta;
+    }
+
+    for (token_type, (credit, debit)) in contract_balances {
+        let (supply_credit, supply_debit, _) = tokens.entry(token_type).or_default();
+        *supply_credit = supply_credit.saturating_add(credit);
+        *supply_debit = supply_debit.saturating_add(debit);
+    }
+
+    let supplies = get_circulating_supplies(tokens.keys(), tx).await?;
+
+    for (token_type, (credit, debit, holder_count_delta)) in tokens {
+        let Some(&supply) = supplies.get(&token_type) else {
+            continue;
+        };
+        let supply = supply.saturating_add(credit).saturating_sub(debit);
+
+        let (contract_address, domain_sep) = mints.remove(&token_type).unzip();
+        let query = indoc! {"
+            UPDATE tokens
+            SET circulating_supply = $2,
+                holder_count = holder_count + $3,
+                contract_address = COALESCE(contract_address, $4),
+                domain_sep = COALESCE(domain_sep, $5)
+            WHERE token_type = $1
+        "};
+        sqlx::query(query)
+            .bind(&token_type)
+            .bind(U128BeBytes::from(supply))
+            .bind(holder_count_delta)
+            .bind(contract_address)
+            .bind(domain_sep)
+            .execute(&mut **tx)
+            .await?;
+    }
+
+    // Mints of token types without any balance changes.
+    for (token_type, (contract_address, domain_sep)) in mints {
+        let query = indoc! {"
+            UPDATE tokens
+            SET contract_address = COALESCE(contract_address, $2),
+                domain_sep = COALESCE(domain_sep, $3)
+            WHERE token_type = $1
+        "};
+        sqlx::query(query)
+            .bind(&token_type)
+            .bind(contract_address)
+            .bind(domain_sep)
+            .execute(&mut **tx)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Get the current balances of the given holders, i.e. pairs of token type and owner; holders
+/// without balance are omitted.
+async fn get_token_holder_balances<'a>(
+    holders: impl IntoIterator<Item = &'a (Vec<u8>, Vec<u8>)>,
+    tx: &mut SqlxTransaction,
+) -> Result<BTreeMap<(Vec<u8>, Vec<u8>), u128>, sqlx::Error> {
+    let mut holders = holders.into_iter().peekable();
+    if holders.peek().is_none() {
+        return Ok(BTreeMap::new());
+    }
+
+    let query = indoc! {"
+        SELECT token_type, owner, balance
+        FROM token_holders
+        WHERE (token_type, owner) IN (
+    "};
+    let mut query = QueryBuilder::new(query);
+    query.push_values(holders, |mut q, (token_type, owner)| {
+        q.push_bind(token_type.as_slice())
+            .push_bind(owner.as_slice());
+    });
+    query.push(")");
+
+    let balances = query
+        .build_query_as::<(Vec<u8>, Vec<u8>, U128BeBytes)>()
+        .fetch_all(&mut **tx)
+        .await?
+        .into_iter()
+        .map(|(token_type, owner, balance)| ((token_type, owner), balance.into()))
+        .collect();
+
+    Ok(balances)
+}
+
+/// Get the circulating supplies of the given token types; unknown token types are omitted.
+async fn get_circulating_supplies<'a>(
+    token_types: impl IntoIterator<Item = &'a Vec<u8>>,
+    tx: &mut SqlxTransaction,
+) -> Result<BTreeMap<Vec<u8>, u128>, sqlx::Error> {
+    let mut token_types = token_types.into_iter().peekable();
+    if token_types.peek().is_none() {
+        return Ok(BTreeMap::new());
+    }
+
+    let query = indoc! {"
+        SELECT token_type, circulating_supply
+        FROM tokens
+        WHERE token_type IN (
+    "};
+    let mut query = QueryBuilder::new(query);
+    let mut separated = query.separated(", ");
+    for token_type in token_types {
+        separated.push_bind(token_type.as_slice());
+    }
+    separated.push_unseparated(")");
+
+    let supplies = query
+        .build_query_as::<(Vec<u8>, U128BeBytes)>()
+        .fetch_all(&mut **tx)
+        .await?
+        .into_iter()
+        .map(|(token_type, supply)| (token_type, supply.into()))
+        .collect();
+
+    Ok(supplies)
+}
+
+#[cfg(test)]
+mod contract_event_variant_tests {
+    use super::*;
+    use indexer_common::domain::{AddressOrContract, ByteVec};
+
+    fn bv(bytes: &[u8]) -> ByteVec {
+        ByteVec::from(bytes.to_vec())
+    }
+
+    // The From impl maps each ContractEvent attribute variant to the matching
+    // SQL LedgerEventVariant. A missing arm would fail the match exhaustively;
+    // this test pins the mapping so renames don't silently shift names.
+    #[test]
+    fn from_attributes_to_variant_covers_every_contract_event_variant() {
+        let cases: Vec<(LedgerEventAttributes, LedgerEventVariant)> = vec![
+            (
+                LedgerEventAttributes::ContractShieldedSpend {
+                    version: 1,
+                    entry_point: bv(b""),
+                    nullifier: bv(&[0; 32]),
+                },
+                LedgerEventVariant::ShieldedSpend,
+            ),
+            (
                 LedgerEventAttributes::ContractShieldedReceive {
                     version: 1,
                     entry_point: bv(b""),
@@ -1653,3 +2920,719 @@ mod contract_event_correlation_tests {
         assert_eq!(correlated, vec![None, None]);
     }
 }
+
+#[cfg(all(test, feature = "standalone"))]
+mod tests {
+    use crate::{
+        application,
+        domain::{
+            Block, BlockRef, ContractAction, PendingTransaction, RegularTransaction, Transaction,
+            snapshot::SnapshotRows, storage::Storage as _,
+        },
+        infra::storage::Storage,
+    };
+    use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+    use futures::TryStreamExt;
+    use indexer_common::{
+        domain::{
+            AddressOrContract, ByteArray, ContractAttributes, ContractBalance, LedgerEvent,
+            LedgerEventAttributes, LedgerEventGrouping, LedgerVersion, NetworkId, ProtocolVersion,
+            UnshieldedUtxo, ledger::LedgerState,
+        },
+        infra::{
+            ledger_db, migrations,
+            pool::sqlite::{Config, SqlitePool},
+            sqlx::U128BeBytes,
+        },
+    };
+    use indoc::indoc;
+    use std::{
+        error::Error as StdError,
+        fs::File,
+        io::{Read, Write},
+        num::NonZeroUsize,
+    };
+
+    const TOKEN_A: u8 = 0xaa;
+    const TOKEN_C: u8 = 0xcc;
+    const CONTRACT: u8 = 0xc1;
+    const DAY: u64 = 24 * 60 * 60 * 1_000;
+
+    async fn new_storage() -> Result<(Storage, SqlitePool), Box<dyn StdError>> {
+        let pool = SqlitePool::new(Config::default()).await?;
+        migrations::sqlite::run(&pool).await?;
+        Ok((Storage::new(pool.clone()), pool))
+    }
+
+    fn protocol_version() -> ProtocolVersion {
+        22_000_u32.try_into().unwrap()
+    }
+
+    fn block(height: u64) -> Block {
+        Block {
+            hash: ByteArray([height as u8 + 1; 32]),
+            height,
+            protocol_version: protocol_version(),
+            parent_hash: ByteArray([height as u8; 32]),
+            author: None,
+            timestamp: height * 6_000,
+            zswap_merkle_tree_root: Default::default(),
+            ledger_state_root: None,
+            dust_registration_events: vec![],
+            bridge_events: vec![],
+            finalized: true,
+            ledger_parameters: Default::default(),
+            zswap_end_index: 0,
+            dust_commitment_end_index: 0,
+            dust_generation_end_index: 0,
+            dust_commitment_merkle_tree_root: Default::default(),
+            dust_generation_merkle_tree_root: Default::default(),
+        }
+    }
+
+    fn regular_transaction(n: u8) -> RegularTransaction {
+        RegularTransaction {
+            hash: ByteArray([n; 32]),
+            protocol_version: protocol_version(),
+            raw: Default::default(),
+            identifiers: vec![],
+            contract_actions: vec![],
+            paid_fees: 0,
+            estimated_fees: 0,
+            transaction_result: Default::default(),
+            zswap_merkle_tree_root: Default::default(),
+            zswap_start_index: 0,
+            zswap_end_index: 0,
+            dust_commitment_start_index: 0,
+            dust_commitment_end_index: 0,
+            dust_generation_start_index: 0,
+            dust_generation_end_index: 0,
+            created_unshielded_utxos: vec![],
+            spent_unshielded_utxos: vec![],
+            ledger_events: vec![],
+            bridge_claim: None,
+        }
+    }
+
+    fn utxo(n: u8, owner: u8, token_type: u8, value: u128) -> UnshieldedUtxo {
+        UnshieldedUtxo {
+            owner: ByteArray([owner; 32]),
+            token_type: ByteArray([token_type; 32]),
+            value,
+            intent_hash: ByteArray([n; 32]),
+            output_index: 0,
+            ctime: None,
+            initial_nonce: ByteArray([n; 32]),
+            registered_for_dust_generation: false,
+        }
+    }
+
+    fn contract_action(address: u8, balances: &[(u8, u128)]) -> ContractAction {
+        ContractAction {
+            address: vec![address; 32].into(),
+            state: Default::default(),
+            zswap_state: Default::default(),
+            extracted_balances: balances
+                .iter()
+                .map(|&(token_type, amount)| ContractBalance {
+                    token_type: ByteArray([token_type; 32]),
+                    amount,
+                })
+                .collect(),
+            attributes: ContractAttributes::Call {
+                entry_point: "entry".to_string(),
+            },
+        }
+    }
+
+    fn contract_event(address: u8, attributes: LedgerEventAttributes) -> LedgerEvent {
+        LedgerEvent {
+            grouping: LedgerEventGrouping::Contract,
+            raw: Default::default(),
+            attributes,
+            contract_action_id: None,
+            contract_address: Some(vec![address; 32].into()),
+        }
+    }
+
+    async fn save_block_at(
+        storage: &mut Storage,
+        height: u64,
+        transactions: Vec<RegularTransaction>,
+    ) -> Result<(), sqlx::Error> {
+        save_block(storage, block(height), transactions).await
+    }
+
+    async fn save_block(
+        storage: &mut Storage,
+        block: Block,
+        transactions: Vec<RegularTransaction>,
+    ) -> Result<(), sqlx::Error> {
+        let transactions = transactions
+            .into_iter()
+            .map(|transaction| Transaction::Regular(Box::new(transaction)))
+            .collect::<Vec<_>>();
+        storage
+            .save_block(&block, &transactions, &[], &Default::default(), None)
+            .await?;
+        Ok(())
+    }
+
+    /// Save the block at the given height of a small token scenario:
+    /// - 0: owner 1 receives 100 of token A.
+    /// - 1: owner 1 sends 60 of token A to owner 2 and the contract mints 1000 of token C into its
+    ///   own balance.
+    /// - 2: owner 1 sends the remaining 40 of token A to the contract and the contract burns 300
+    ///   of token C.
+    async fn save_token_block(storage: &mut Storage, height: u64) -> Result<(), sqlx::Error> {
+        let mut transaction = regular_transaction(height as u8 + 1);
+
+        match height {
+            0 => {
+                transaction.created_unshielded_utxos = vec![utxo(1, 1, TOKEN_A, 100)];
+            }
+
+            1 => {
+                transaction.spent_unshielded_utxos = vec![utxo(1, 1, TOKEN_A, 100)];
+                transaction.created_unshielded_utxos =
+                    vec![utxo(2, 2, TOKEN_A, 60), utxo(3, 1, TOKEN_A, 40)];
+                transaction.contract_actions = vec![contract_action(CONTRACT, &[(TOKEN_C, 1_000)])];
+                transaction.ledger_events = vec![contract_event(
+                    CONTRACT,
+                    LedgerEventAttributes::ContractUnshieldedMint {
+                        version: 1,
+                        entry_point: b"entry".to_vec().into(),
+                        domain_sep: vec![0xd5; 32].into(),
+                        token_type: vec![TOKEN_C; 32].into(),
+                        amount: "1000".to_string(),
+                    },
+                )];
+            }
+
+            _ => {
+                transaction.spent_unshielded_utxos = vec![utxo(3, 1, TOKEN_A, 40)];
+                transaction.contract_actions =
+                    vec![contract_action(CONTRACT, &[(TOKEN_A, 40), (TOKEN_C, 700)])];
+                transaction.ledger_events = vec![contract_event(
+                    CONTRACT,
+                    LedgerEventAttributes::ContractUnshieldedBurn {
+                        version: 1,
+                        entry_point: b"entry".to_vec().into(),
+                        sender: AddressOrContract::Contract(vec![CONTRACT; 32].into()),
+                        token_type: vec![TOKEN_C; 32].into(),
+                        amount: "300".to_string(),
+                    },
+                )];
+            }
+        }
+
+        save_block_at(storage, height, vec![transaction]).await
+    }
+
+    /// First seen block height, minting contract address, circulating supply and holder count.
+    type TokenRow = (i64, Option<Vec<u8>>, u128, i64);
+
+    async fn get_token(pool: &SqlitePool, token_type: u8) -> Result<Option<TokenRow>, sqlx::Error> {
+        let query = indoc! {"
+            SELECT first_seen_block_height, contract_address, circulating_supply, holder_count
+            FROM tokens
+            WHERE token_type = $1
+        "};
+
+        let token = sqlx::query_as::<_, (i64, Option<Vec<u8>>, U128BeBytes, i64)>(query)
+            .bind([token_type; 32].as_slice())
+            .fetch_optional(&**pool)
+            .await?
+            .map(|(height, contract_address, supply, holder_count)| {
+                (height, contract_address, supply.into(), holder_count)
+            });
+
+        Ok(token)
+    }
+
+    /// Holder balances of the given token type by the first byte of the owner.
+    async fn get_token_holders(
+        pool: &SqlitePool,
+        token_type: u8,
+    ) -> Result<Vec<(u8, u128)>, sqlx::Error> {
+        let query = indoc! {"
+            SELECT owner, balance
+            FROM token_holders
+            WHERE token_type = $1
+            ORDER BY owner
+        "};
+
+        let holders = sqlx::query_as::<_, (Vec<u8>, U128BeBytes)>(query)
+            .bind([token_type; 32].as_slice())
+            .fetch_all(&**pool)
+            .await?
+            .into_iter()
+            .map(|(owner, balance)| (owner[0], balance.into()))
+            .collect();
+
+        Ok(holders)
+    }
+
+    #[tokio::test]
+    async fn save_tokens_on_mint_and_spend() -> Result<(), Box<dyn StdError>> {
+        let (mut storage, pool) = new_storage().await?;
+
+        save_token_block(&mut storage, 0).await?;
+        assert_eq!(get_token(&pool, TOKEN_A).await?, Some((0, None, 100, 1)));
+        assert_eq!(get_token_holders(&pool, TOKEN_A).await?, vec![(1, 100)]);
+        assert_eq!(get_token(&pool, TOKEN_C).await?, None);
+
+        // The spend moves balances between holders, the mint into the contract balance counts
+        // towards the supply without any holder.
+        save_token_block(&mut storage, 1).await?;
+        assert_eq!(get_token(&pool, TOKEN_A).await?, Some((0, None, 100, 2)));
+        assert_eq!(
+            get_token_holders(&pool, TOKEN_A).await?,
+            vec![(1, 40), (2, 60)]
+        );
+        assert_eq!(
+            get_token(&pool, TOKEN_C).await?,
+            Some((1, Some(vec![CONTRACT; 32]), 1_000, 0))
+        );
+        assert_eq!(get_token_holders(&pool, TOKEN_C).await?, vec![]);
+
+        // Sending to a contract keeps the supply, burning from a contract reduces it.
+        save_token_block(&mut storage, 2).await?;
+        assert_eq!(get_token(&pool, TOKEN_A).await?, Some((0, None, 100, 1)));
+        assert_eq!(get_token_holders(&pool, TOKEN_A).await?, vec![(2, 60)]);
+        assert_eq!(
+            get_token(&pool, TOKEN_C).await?,
+            Some((1, Some(vec![CONTRACT; 32]), 700, 0))
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn roll_back_tokens() -> Result<(), Box<dyn StdError>> {
+        let (mut storage, pool) = new_storage().await?;
+
+        for height in 0..=2 {
+            save_token_block(&mut storage, height).await?;
+        }
+
+        storage.roll_back_blocks(1).await?;
+        assert_eq!(get_token(&pool, TOKEN_A).await?, Some((0, None, 100, 2)));
+        assert_eq!(
+            get_token_holders(&pool, TOKEN_A).await?,
+            vec![(1, 40), (2, 60)]
+        );
+        assert_eq!(
+            get_token(&pool, TOKEN_C).await?,
+            Some((1, Some(vec![CONTRACT; 32]), 1_000, 0))
+        );
+
+        // Token C was first seen above height 0, hence removed.
+        storage.roll_back_blocks(0).await?;
+        assert_eq!(get_token(&pool, TOKEN_A).await?, Some((0, None, 100, 1)));
+        assert_eq!(get_token_holders(&pool, TOKEN_A).await?, vec![(1, 100)]);
+        assert_eq!(get_token(&pool, TOKEN_C).await?, None);
+
+        // Re-applying after a rollback yields the same state as before.
+        save_token_block(&mut storage, 1).await?;
+        save_token_block(&mut storage, 2).await?;
+        assert_eq!(get_token(&pool, TOKEN_A).await?, Some((0, None, 100, 1)));
+        assert_eq!(
+            get_token(&pool, TOKEN_C).await?,
+            Some((1, Some(vec![CONTRACT; 32]), 700, 0))
+        );
+
+        Ok(())
+    }
+
+    /// Number of rows of the given table.
+    async fn count(pool: &SqlitePool, table: &str) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar::<_, i64>(&format!("SELECT COUNT(*) FROM {table}"))
+            .fetch_one(&**pool)
+            .await
+    }
+
+    /// Heights of the finalized blocks.
+    async fn get_finalized_heights(pool: &SqlitePool) -> Result<Vec<i64>, sqlx::Error> {
+        let query = indoc! {"
+            SELECT height
+            FROM blocks
+            WHERE finalized = TRUE
+            ORDER BY height
+        "};
+
+        sqlx::query_scalar::<_, i64>(query).fetch_all(&**pool).await
+    }
+
+    #[tokio::test]
+    async fn finalize_blocks() -> Result<(), Box<dyn StdError>> {
+        let (mut storage, pool) = new_storage().await?;
+
+        for height in 0..=3 {
+            let block = Block {
+                finalized: false,
+                ..block(height)
+            };
+            save_block(&mut storage, block, vec![]).await?;
+        }
+
+        // A block on another fork at a stored height is not stored, hence finalizes nothing.
+        let fork_block = BlockRef {
+            hash: ByteArray([0xf0; 32]),
+            height: 2,
+        };
+        assert_eq!(storage.finalize_blocks(fork_block).await?, 0);
+        assert_eq!(get_finalized_heights(&pool).await?, Vec::<i64>::new());
+        assert_eq!(storage.get_highest_finalized_block().await?, None);
+
+        let block_1 = BlockRef {
+            hash: block(1).hash,
+            height: 1,
+        };
+        assert_eq!(storage.finalize_blocks(block_1).await?, 2);
+        assert_eq!(get_finalized_heights(&pool).await?, vec![0, 1]);
+
+        // The walk stops at the highest finalized ancestor.
+        let block_3 = BlockRef {
+            hash: block(3).hash,
+            height: 3,
+        };
+        assert_eq!(storage.finalize_blocks(block_3).await?, 2);
+        assert_eq!(get_finalized_heights(&pool).await?, vec![0, 1, 2, 3]);
+        assert_eq!(storage.get_highest_finalized_block().await?, Some(block_3));
+
+        assert_eq!(storage.finalize_blocks(block_3).await?, 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn roll_back_blocks() -> Result<(), Box<dyn StdError>> {
+        let (mut storage, pool) = new_storage().await?;
+
+        let tables = [
+            "blocks",
+            "transactions",
+            "regular_transactions",
+            "transaction_identifiers",
+            "contract_actions",
+            "contract_balances",
+            "ledger_events",
+            "unshielded_utxos",
+            "block_stats",
+            "block_rollups",
+            "daily_rollups",
+            "daily_active_addresses",
+            "tokens",
+            "token_holders",
+        ];
+        let unspent_utxos = indoc! {"
+            (SELECT * FROM unshielded_utxos WHERE spending_transaction_id IS NULL)
+        "};
+
+        save_token_block(&mut storage, 0).await?;
+        let mut counts = vec![];
+        for table in tables.into_iter().chain([unspent_utxos]) {
+            counts.push(count(&pool, table).await?);
+        }
+
+        save_token_block(&mut storage, 1).await?;
+        save_token_block(&mut storage, 2).await?;
+        assert_eq!(count(&pool, "blocks").await?, 3);
+        assert_eq!(count(&pool, "contract_actions").await?, 2);
+        assert_eq!(count(&pool, unspent_utxos).await?, 1);
+
+        // Rolling back restores all dependent tables, including the spent UTXO.
+        assert_eq!(storage.roll_back_blocks(0).await?, 2);
+        for (table, expected_count) in tables.into_iter().chain([unspent_utxos]).zip(counts) {
+            assert_eq!(count(&pool, table).await?, expected_count, "{table}");
+        }
+
+        // Nothing to roll back above the highest block.
+        assert_eq!(storage.roll_back_blocks(0).await?, 0);
+
+        Ok(())
+    }
+
+    /// Rewinding unpersists the ledger states of the rolled back blocks, including those of an
+    /// earlier rollback interrupted after deleting its blocks.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn rewind() -> Result<(), Box<dyn StdError>> {
+        let temp_dir = tempfile::tempdir()?;
+        let cnn_url = temp_dir
+            .path()
+            .join("ledger-db.sqlite")
+            .display()
+            .to_string();
+        ledger_db::init(ledger_db::Config {
+            cache_max_nodes: 1_024,
+            cnn_url,
+        })
+        .await?;
+
+        let (mut storage, pool) = new_storage().await?;
+
+        // Distinct ledger states, one per block, persisted like when indexing.
+        let mut root_hashes = vec![];
+        for height in 0..=4 {
+            let network_id = NetworkId::try_from(format!("network-{height}"))?;
+            let (_, key) = LedgerState::new(network_id, LedgerVersion::V8)?.persist()?;
+            root_hashes.push(LedgerState::root_hash_bytes(&key, LedgerVersion::V8)?);
+            storage
+                .save_block(&block(height), &[], &[], &key, None)
+                .await?;
+        }
+
+        // A rollback interrupted after deleting the blocks leaves their ledger states persisted.
+        storage.roll_back_blocks(3).await?;
+        assert!(LedgerState::persisted_root_hashes().contains(&root_hashes[4]));
+        assert_eq!(count(&pool, "rolled_back_ledger_state_keys").await?, 1);
+
+        application::rewind(1, NonZeroUsize::new(10).unwrap(), storage).await?;
+        assert_eq!(count(&pool, "blocks").await?, 2);
+        assert_eq!(count(&pool, "rolled_back_ledger_state_keys").await?, 0);
+        let persisted_root_hashes = LedgerState::persisted_root_hashes();
+        for (height, root_hash) in root_hashes.iter().enumerate() {
+            assert_eq!(
+                persisted_root_hashes.contains(root_hash),
+                height <= 1,
+                "height {height}"
+            );
+        }
+
+        Ok(())
+    }
+
+    /// All snapshot rows of the given storage.
+    async fn get_snapshot_rows(storage: &Storage) -> Result<Vec<SnapshotRows>, sqlx::Error> {
+        storage
+            .get_snapshot_rows(NonZeroUsize::new(100).unwrap())
+            .try_collect()
+            .await
+    }
+
+    /// Importing a snapshot exported at some height yields the rows of the storage rolled back to
+    /// that height and exactly the arena nodes of the retained ledger states.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn snapshot_round_trip() -> Result<(), Box<dyn StdError>> {
+        let temp_dir = tempfile::tempdir()?;
+        let cnn_url = temp_dir
+            .path()
+            .join("ledger-db.sqlite")
+            .display()
+            .to_string();
+        ledger_db::init(ledger_db::Config {
+            cache_max_nodes: 1_024,
+            cnn_url: cnn_url.clone(),
+        })
+        .await?;
+
+        let (mut storage, pool) = new_storage().await?;
+        let network_id = NetworkId::try_from("undeployed")?;
+
+        // The token scenario with distinct ledger states, persisted like when indexing.
+        let mut ledger_state_keys = vec![];
+        for height in 0..=2 {
+            save_token_block(&mut storage, height).await?;
+
+            let network_id = NetworkId::try_from(format!("network-{height}"))?;
+            let (_, key) = LedgerState::new(network_id, LedgerVersion::V8)?.persist()?;
+            sqlx::query("UPDATE blocks SET ledger_state_key = $1 WHERE height = $2")
+                .bind(&key)
+                .bind(height as i64)
+                .execute(&*pool)
+                .await?;
+            ledger_state_keys.push(key);
+        }
+
+        let file = temp_dir.path().join("snapshot");
+        let ledger_state_retention = NonZeroUsize::new(10).unwrap();
+        application::snapshot::export(&network_id, 1, ledger_state_retention, &file, &storage)
+            .await?;
+
+        // Import as if into a fresh ledger DB, as far as the arena nodes are concerned.
+        let ledger_db_pool = SqlitePool::new(Config::with_url(cnn_url)).await?;
+        sqlx::query("DELETE FROM ledger_db_nodes")
+            .execute(&*ledger_db_pool)
+            .await?;
+
+        // A snapshot missing its end is only detected after all rows have been saved; the failed
+        // import deletes these again, such that importing can be retried.
+        let mut bytes = vec![];
+        GzDecoder::new(File::open(&file)?).read_to_end(&mut bytes)?;
+        bytes.truncate(bytes.len() - 8);
+        let truncated_file = temp_dir.path().join("truncated-snapshot");
+        let mut writer = GzEncoder::new(File::create(&truncated_file)?, Compression::default());
+        writer.write_all(&bytes)?;
+        writer.finish()?;
+
+        let (mut imported_storage, imported_pool) = new_storage().await?;
+        let result =
+            application::snapshot::import(&network_id, &truncated_file, &mut imported_storage)
+                .await;
+        assert!(result.is_err());
+        assert_eq!(count(&imported_pool, "blocks").await?, 0);
+        assert!(get_snapshot_rows(&imported_storage).await?.is_empty());
+
+        application::snapshot::import(&network_id, &file, &mut imported_storage).await?;
+
+        storage.roll_back_blocks(1).await?;
+        assert_eq!(
+            get_snapshot_rows(&imported_storage).await?,
+            get_snapshot_rows(&storage).await?
+        );
+
+        // Walking the DAGs fails on missing nodes, hence these are complete.
+        let node_count = LedgerState::arena_nodes(
+            ledger_state_keys[..2]
+                .iter()
+                .map(|key| (key, LedgerVersion::V8)),
+            NonZeroUsize::new(100).unwrap(),
+        )?
+        .map(|nodes| nodes.map(|nodes| nodes.len()))
+        .sum::<Result<usize, _>>()?;
+        assert_eq!(
+            count(&ledger_db_pool, "ledger_db_nodes").await?,
+            node_count as i64
+        );
+
+        Ok(())
+    }
+
+    /// Block count, regular transaction count, contract call count, created and spent unshielded
+    /// UTXOs and active address count of the daily rollup of the given day.
+    type DailyRollupRow = (i64, i64, i64, i64, i64, i64);
+
+    async fn get_daily_rollup(
+        pool: &SqlitePool,
+        day: u64,
+    ) -> Result<Option<DailyRollupRow>, sqlx::Error> {
+        let query = indoc! {"
+            SELECT
+                block_count,
+                regular_transaction_count,
+                contract_call_count,
+                unshielded_utxos_created,
+                unshielded_utxos_spent,
+                active_address_count
+            FROM daily_rollups
+            WHERE day = $1
+        "};
+
+        sqlx::query_as::<_, DailyRollupRow>(query)
+            .bind(day as i64)
+            .fetch_optional(&**pool)
+            .await
+    }
+
+    /// Save a block at height 3 on the day after the token scenario in which owner 1 receives 5
+    /// of token A.
+    async fn save_next_day_block(storage: &mut Storage) -> Result<(), sqlx::Error> {
+        let block = Block {
+            timestamp: DAY,
+            ..block(3)
+        };
+        let mut transaction = regular_transaction(4);
+        transaction.created_unshielded_utxos = vec![utxo(4, 1, TOKEN_A, 5)];
+
+        save_block(storage, block, vec![transaction]).await
+    }
+
+    #[tokio::test]
+    async fn save_daily_rollups() -> Result<(), Box<dyn StdError>> {
+        let (mut storage, pool) = new_storage().await?;
+
+        save_token_block(&mut storage, 0).await?;
+        assert_eq!(get_daily_rollup(&pool, 0).await?, Some((1, 1, 0, 1, 0, 1)));
+
+        // Owner 1 is already active that day, only owner 2 is added.
+        save_token_block(&mut storage, 1).await?;
+        assert_eq!(get_daily_rollup(&pool, 0).await?, Some((2, 2, 1, 3, 1, 2)));
+
+        save_token_block(&mut storage, 2).await?;
+        assert_eq!(get_daily_rollup(&pool, 0).await?, Some((3, 3, 2, 3, 2, 2)));
+        assert_eq!(count(&pool, "block_rollups").await?, 3);
+
+        // A block on the next day starts a new daily rollup, counting owner 1 as active again.
+        save_next_day_block(&mut storage).await?;
+        assert_eq!(get_daily_rollup(&pool, 0).await?, Some((3, 3, 2, 3, 2, 2)));
+        assert_eq!(
+            get_daily_rollup(&pool, DAY).await?,
+            Some((1, 1, 0, 1, 0, 1))
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn roll_back_daily_rollups() -> Result<(), Box<dyn StdError>> {
+        let (mut storage, pool) = new_storage().await?;
+
+        for height in 0..=2 {
+            save_token_block(&mut storage, height).await?;
+        }
+        save_next_day_block(&mut storage).await?;
+
+        // The daily rollup of the next day is left without blocks, hence deleted.
+        storage.roll_back_blocks(1).await?;
+        assert_eq!(get_daily_rollup(&pool, 0).await?, Some((2, 2, 1, 3, 1, 2)));
+        assert_eq!(get_daily_rollup(&pool, DAY).await?, None);
+
+        // Owner 2 first became active above height 0, owner 1 stays active.
+        storage.roll_back_blocks(0).await?;
+        assert_eq!(get_daily_rollup(&pool, 0).await?, Some((1, 1, 0, 1, 0, 1)));
+        assert_eq!(count(&pool, "daily_active_addresses").await?, 1);
+
+        // Re-applying after a rollback yields the same state as before.
+        save_token_block(&mut storage, 1).await?;
+        save_token_block(&mut storage, 2).await?;
+        assert_eq!(get_daily_rollup(&pool, 0).await?, Some((3, 3, 2, 3, 2, 2)));
+
+        Ok(())
+    }
+
+    fn pending_transaction(n: u8) -> PendingTransaction {
+        PendingTransaction {
+            hash: ByteArray([n; 32]),
+            protocol_version: protocol_version(),
+            raw: Default::default(),
+            contract_addresses: vec![],
+            unshielded_addresses: vec![ByteArray([n; 32])],
+        }
+    }
+
+    #[tokio::test]
+    async fn prune_pending_transactions_once_indexed() -> Result<(), Box<dyn StdError>> {
+        let (mut storage, pool) = new_storage().await?;
+
+        let pending_transactions = [pending_transaction(1), pending_transaction(2)];
+        let saved = storage
+            .save_pending_transactions(&pending_transactions, 1_000)
+            .await?;
+        assert_eq!(saved, 2);
+        assert_eq!(count(&pool, "pending_transaction_addresses").await?, 2);
+
+        // Transaction 1 gets included in a block, hence is pruned although recently seen.
+        save_block_at(&mut storage, 0, vec![regular_transaction(1)]).await?;
+        assert_eq!(storage.delete_pending_transactions(500).await?, 1);
+        let hashes = sqlx::query_scalar::<_, Vec<u8>>("SELECT hash FROM pending_transactions")
+            .fetch_all(&*pool)
+            .await?;
+        assert_eq!(hashes, vec![vec![2; 32]]);
+        assert_eq!(count(&pool, "pending_transaction_addresses").await?, 1);
+
+        // Indexed transactions are not saved as pending again, e.g. on a late pool poll.
+        let saved = storage
+            .save_pending_transactions(&pending_transactions, 1_500)
+            .await?;
+        assert_eq!(saved, 0);
+        assert_eq!(count(&pool, "pending_transactions").await?, 1);
+
+        // Transaction 2 is pruned once no longer seen.
+        assert_eq!(storage.delete_pending_transactions(2_000).await?, 1);
+        assert_eq!(count(&pool, "pending_transactions").await?, 0);
+        assert_eq!(count(&pool, "pending_transaction_addresses").await?, 0);
+
+        Ok(())
+    }
+}
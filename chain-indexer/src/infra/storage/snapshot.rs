@@ -0,0 +1,292 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    domain::snapshot::SnapshotRows,
+    infra::storage::{SqlxTransaction, Storage},
+};
+use async_stream::try_stream;
+use fastrace::trace;
+use futures::Stream;
+use sqlx::types::Json;
+use std::num::NonZeroUsize;
+
+#[cfg(feature = "cloud")]
+pub const ROWS_FORMAT: &str = "postgres-jsonb";
+
+#[cfg(feature = "standalone")]
+pub const ROWS_FORMAT: &str = "sqlite-json";
+
+/// Tables holding indexed chain data, referenced tables before referencing ones. Tables filled by
+/// the wallet-indexer or the spo-indexer as well as the transient pending transactions are not part
+/// of a snapshot.
+#[cfg(feature = "cloud")]
+const TABLES: &[&str] = &[
+    "blocks",
+    "block_stats",
+    "block_rollups",
+    "daily_rollups",
+    "daily_active_addresses",
+    "transactions",
+    "regular_transactions",
+    "contract_actions",
+    "contract_balances",
+    "ledger_events",
+    "contract_event_indexed_fields",
+    "unshielded_utxos",
+    "tokens",
+    "token_holders",
+    "dust_generation_info",
+    "dust_nullifiers",
+    "zswap_nullifiers",
+    "cnight_registrations",
+    "protocol_bridge_events",
+    "bridge_claims",
+    "system_parameters_d",
+    "system_parameters_terms_and_conditions",
+];
+
+/// Tables holding indexed chain data, referenced tables before referencing ones. Tables filled by
+/// the wallet-indexer or the spo-indexer as well as the transient pending transactions are not part
+/// of a snapshot.
+#[cfg(feature = "standalone")]
+const TABLES: &[&str] = &[
+    "blocks",
+    "block_stats",
+    "block_rollups",
+    "daily_rollups",
+    "daily_active_addresses",
+    "transactions",
+    "regular_transactions",
+    "transaction_identifiers",
+    "contract_actions",
+    "contract_balances",
+    "ledger_events",
+    "contract_event_indexed_fields",
+    "unshielded_utxos",
+    "tokens",
+    "token_holders",
+    "dust_generation_info",
+    "dust_nullifiers",
+    "zswap_nullifiers",
+    "cnight_registrations",
+    "protocol_bridge_events",
+    "bridge_claims",
+    "system_parameters_d",
+    "system_parameters_terms_and_conditions",
+];
+
+impl Storage {
+    pub(super) fn get_snapshot_rows(
+        &self,
+        batch_size: NonZeroUsize,
+    ) -> impl Stream<Item = Result<SnapshotRows, sqlx::Error>> {
+        try_stream! {
+            // All rows are read within one read-only transaction, hence from one consistent
+            // snapshot, even if the chain-indexer keeps indexing meanwhile.
+            let mut tx = begin_snapshot(self).await?;
+
+            for table in TABLES {
+                let query = select_rows_query(table, &mut tx).await?;
+
+                let mut id = 0;
+                loop {
+                    let rows = sqlx::query_as::<_, (i64, Json<serde_json::Value>)>(&query)
+                        .bind(id)
+                        .bind(batch_size.get() as i64)
+                        .fetch_all(&mut *tx)
+                        .await?;
+
+                    let Some((last_id, _)) = rows.last() else {
+                        break;
+                    };
+                    id = *last_id;
+
+                    let rows = rows.into_iter().map(|(_, Json(row))| row).collect();
+                    yield SnapshotRows {
+                        table: table.to_string(),
+                        rows,
+                    };
+                }
+            }
+
+            tx.commit().await?;
+        }
+    }
+
+    #[trace]
+    pub(super) async fn save_snapshot_rows(
+        &mut self,
+        rows: &SnapshotRows,
+    ) -> Result<(), sqlx::Error> {
+        let SnapshotRows { table, rows } = rows;
+
+        // The table name ends up in the query, hence it must be a known one.
+        let table = TABLES
+            .iter()
+            .find(|t| *t == table)
+            .ok_or_else(|| sqlx::Error::Protocol(format!("unexpected snapshot table {table}")))?;
+
+        let mut tx = self.pool.begin().await?;
+        let query = insert_rows_query(table, &mut tx).await?;
+        sqlx::query(&query)
+            .bind(Json(rows))
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    #[trace]
+    pub(super) async fn delete_snapshot_rows(&mut self) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        // Referencing tables before referenced ones.
+        for table in TABLES.iter().rev() {
+            sqlx::query(&format!("DELETE FROM {table}"))
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        // Rolling back the blocks above the snapshot height may have recorded ledger state keys.
+        sqlx::query("DELETE FROM rolled_back_ledger_state_keys")
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    #[cfg(feature = "cloud")]
+    #[trace]
+    pub(super) async fn finish_snapshot_rows(&mut self) -> Result<(), sqlx::Error> {
+        // The IDs have been saved explicitly, hence the sequences have not been advanced. Tables
+        // without a sequence yield NULL for `pg_get_serial_sequence`, which `setval` ignores.
+        for table in TABLES {
+            let query = format!(
+                "SELECT setval(pg_get_serial_sequence('{table}', 'id'), COALESCE(MAX(id), 0) + 1, \
+                 FALSE) FROM {table}"
+            );
+            sqlx::query(&query).execute(&*self.pool).await?;
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "standalone")]
+    #[trace]
+    pub(super) async fn finish_snapshot_rows(&mut self) -> Result<(), sqlx::Error> {
+        // SQLite assigns IDs beyond the highest existing one, also for AUTOINCREMENT tables.
+        Ok(())
+    }
+}
+
+#[cfg(feature = "cloud")]
+async fn begin_snapshot(storage: &Storage) -> Result<SqlxTransaction, sqlx::Error> {
+    let mut tx = storage.pool.begin().await?;
+    sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY")
+        .execute(&mut *tx)
+        .await?;
+
+    Ok(tx)
+}
+
+/// A deferred SQLite transaction reads from the snapshot taken by its first read.
+#[cfg(feature = "standalone")]
+async fn begin_snapshot(storage: &Storage) -> Result<SqlxTransaction, sqlx::Error> {
+    storage.pool.begin().await
+}
+
+#[cfg(feature = "cloud")]
+async fn select_rows_query(table: &str, _tx: &mut SqlxTransaction) -> Result<String, sqlx::Error> {
+    Ok(format!(
+        "SELECT id, to_jsonb({table}) FROM {table} WHERE id > $1 ORDER BY id LIMIT $2"
+    ))
+}
+
+/// SQLite JSON cannot hold BLOBs, hence these are hex-encoded.
+#[cfg(feature = "standalone")]
+async fn select_rows_query(table: &str, tx: &mut SqlxTransaction) -> Result<String, sqlx::Error> {
+    let fields = get_columns(table, tx)
+        .await?
+        .into_iter()
+        .map(|(column, blob)| {
+            if blob {
+                format!("'{column}', CASE WHEN {column} IS NULL THEN NULL ELSE hex({column}) END")
+            } else {
+                format!("'{column}', {column}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    Ok(format!(
+        "SELECT id, json_object({fields}) FROM {table} WHERE id > $1 ORDER BY id LIMIT $2"
+    ))
+}
+
+/// Rows of tables are of the table's row type, hence can be populated from the JSON objects
+/// created by `to_jsonb` in [select_rows_query].
+#[cfg(feature = "cloud")]
+async fn insert_rows_query(table: &str, _tx: &mut SqlxTransaction) -> Result<String, sqlx::Error> {
+    Ok(format!(
+        "INSERT INTO {table} SELECT * FROM jsonb_populate_recordset(NULL::{table}, $1)"
+    ))
+}
+
+#[cfg(feature = "standalone")]
+async fn insert_rows_query(table: &str, tx: &mut SqlxTransaction) -> Result<String, sqlx::Error> {
+    let (columns, values) = get_columns(table, tx)
+        .await?
+        .into_iter()
+        .map(|(column, blob)| {
+            let value = if blob {
+                format!("unhex(value ->> '$.{column}')")
+            } else {
+                format!("value ->> '$.{column}'")
+            };
+            (column, value)
+        })
+        .collect::<(Vec<_>, Vec<_>)>();
+    let columns = columns.join(", ");
+    let values = values.join(", ");
+
+    Ok(format!(
+        "INSERT INTO {table} ({columns}) SELECT {values} FROM json_each($1)"
+    ))
+}
+
+/// Get the names of the columns of the given table and whether these are declared as BLOB.
+#[cfg(feature = "standalone")]
+async fn get_columns(
+    table: &str,
+    tx: &mut SqlxTransaction,
+) -> Result<Vec<(String, bool)>, sqlx::Error> {
+    let query = indoc::indoc! {"
+        SELECT name, type
+        FROM pragma_table_info($1)
+        ORDER BY cid
+    "};
+
+    let columns = sqlx::query_as::<_, (String, String)>(query)
+        .bind(table)
+        .fetch_all(&mut **tx)
+        .await?
+        .into_iter()
+        .map(|(name, r#type)| (name, r#type.eq_ignore_ascii_case("BLOB")))
+        .collect();
+
+    Ok(columns)
+}
@@ -11,50 +11,62 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod endpoint;
 mod header;
 mod runtimes;
 
 use crate::{
     domain::{
         BlockRef, SystemParametersChange,
-        node::{Block, Node, RegularTransaction, SystemTransaction, Transaction},
+        node::{
+            Block, Node, PendingTransaction, RegularTransaction, SystemTransaction, Transaction,
+        },
+    },
+    infra::subxt_node::{
+        endpoint::{Endpoint, Endpoints},
+        header::SubstrateHeaderExt,
+        runtimes::BlockDetails,
     },
-    infra::subxt_node::{header::SubstrateHeaderExt, runtimes::BlockDetails},
 };
-use async_stream::try_stream;
+use async_stream::{stream, try_stream};
 use const_hex::FromHexError;
 use fastrace::trace;
-use futures::{Stream, StreamExt, TryStreamExt, stream};
-use http::{
-    HeaderMap,
-    header::{InvalidHeaderValue, USER_AGENT},
-};
+use futures::{SinkExt, Stream, StreamExt, TryStreamExt, channel::mpsc, stream};
+use http::header::InvalidHeaderValue;
 use indexer_common::{
     domain::{
         BlockAuthor, BlockHash, ByteVec, NodeVersion, ProtocolVersion, ProtocolVersionError,
         SerializedContractAddress,
         ledger::{self, ZswapMerkleTreeRoot},
     },
-    error::BoxError,
+    error::{BoxError, StdErrorExt},
 };
+use itertools::Itertools;
 use log::{debug, info, warn};
 use parity_scale_codec::Decode;
 use serde::Deserialize;
-use std::{future::ready, time::Duration};
+use std::{
+    collections::VecDeque,
+    future::ready,
+    iter,
+    num::NonZeroUsize,
+    pin::pin,
+    time::{Duration, Instant},
+};
 use subxt::{
-    OnlineClient, SubstrateConfig,
+    SubstrateConfig,
     config::{
         Hash, RpcConfigFor,
         substrate::{ConsensusEngineId, DigestItem, SubstrateHeader},
     },
-    rpcs::{
-        LegacyRpcMethods,
-        client::{ReconnectingRpcClient, reconnecting_rpc_client::ExponentialBackoff},
-    },
+    rpcs::LegacyRpcMethods,
     utils::H256,
 };
 use thiserror::Error;
-use tokio::time::timeout;
+use tokio::{
+    task,
+    time::{sleep, timeout},
+};
 
 type OnlineClientAtBlock = subxt::client::OnlineClientAtBlock<SubstrateConfig>;
 type SubxtBlock = subxt::client::Block<SubstrateConfig>;
@@ -86,43 +98,67 @@ const CATCH_UP_LOG_INTERVAL: u64 = 1_000;
 /// Blocks further back are fetched by height with parent hash verification.
 const FINALIZATION_SAFETY_MARGIN: u64 = 400;
 
+/// Interval for retrying to cross-check a block with other endpoints in quorum mode, e.g. because
+/// these have not yet imported it.
+const CROSS_CHECK_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
 /// A [Node] implementation based on subxt.
+///
+/// Blocks are subscribed to and fetched from the active one of possibly multiple node endpoints.
+/// If the active endpoint fails, e.g. because of a disconnect, or lags behind the others, the
+/// block streams fail over to the healthiest other endpoint and end, such that they are rerun by
+/// the caller from the last yielded block.
 #[derive(Clone)]
 pub struct SubxtNode {
-    rpc_client: ReconnectingRpcClient,
-    online_client: OnlineClient<SubstrateConfig>,
+    endpoints: Endpoints,
     subscription_recovery_timeout: Duration,
+    prefetch_window: NonZeroUsize,
+    quorum: bool,
 }
 
 impl SubxtNode {
-    /// Create a new [SubxtNode] with the given [Config].
+    /// Create a new [SubxtNode] with the given [Config]. Endpoints which cannot be connected to
+    /// are skipped, unless none can be connected to.
     pub async fn new(config: Config) -> Result<Self, Error> {
         let Config {
             url,
+            fallback_urls,
             reconnect_max_delay: retry_max_delay,
             reconnect_max_attempts: retry_max_attempts,
             subscription_recovery_timeout,
+            prefetch_window,
+            health_check_interval,
+            max_lag,
+            quorum,
         } = config;
 
-        let retry_policy = ExponentialBackoff::from_millis(10)
-            .max_delay(retry_max_delay)
-            .take(retry_max_attempts);
-        let user_agent = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION")).parse()?;
-        let headers = HeaderMap::from_iter([(USER_AGENT, user_agent)]);
-        let rpc_client = ReconnectingRpcClient::builder()
-            .set_headers(headers)
-            .retry_policy(retry_policy)
-            .build(&url)
-            .await
-            .map_err(|error| Error::RpcClient(error.into()))?;
+        let mut endpoints = Vec::with_capacity(1 + fallback_urls.len());
+        let mut first_error = None;
+        for url in iter::once(url).chain(fallback_urls) {
+            match Endpoint::connect(url.clone(), retry_max_delay, retry_max_attempts).await {
+                Ok(endpoint) => endpoints.push(endpoint),
+
+                Err(error) => {
+                    warn!(url, error = error.as_chain(); "cannot connect to node endpoint");
+                    first_error.get_or_insert(error);
+                }
+            }
+        }
 
-        let online_client =
-            OnlineClient::<SubstrateConfig>::from_rpc_client(rpc_client.clone()).await?;
+        if let Some(error) = first_error
+            && endpoints.is_empty()
+        {
+            return Err(error);
+        }
+        if quorum && endpoints.len() < 2 {
+            return Err(Error::NoQuorum);
+        }
 
         Ok(Self {
-            rpc_client,
-            online_client,
+            endpoints: Endpoints::new(endpoints, max_lag, health_check_interval),
             subscription_recovery_timeout,
+            prefetch_window,
+            quorum,
         })
     }
 
@@ -139,6 +175,8 @@ impl SubxtNode {
     ) -> Result<impl Stream<Item = Result<SubxtBlock, SubxtNodeError>> + use<>, SubxtNodeError>
     {
         let finalized_blocks = self
+            .endpoints
+            .active()
             .online_client
             .stream_blocks()
             .await
@@ -182,11 +220,57 @@ impl SubxtNode {
         Ok(finalized_blocks)
     }
 
+    /// Subscribe to best blocks, filtering disconnection errors like
+    /// [subscribe_finalized_blocks](Self::subscribe_finalized_blocks). Unlike finalized blocks,
+    /// best blocks may repeat heights on a reorg, hence duplicates are not filtered here.
+    async fn subscribe_best_blocks(
+        &self,
+    ) -> Result<impl Stream<Item = Result<SubxtBlock, SubxtNodeError>> + use<>, SubxtNodeError>
+    {
+        let best_blocks = self
+            .endpoints
+            .active()
+            .online_client
+            .stream_best_blocks()
+            .await
+            .map_err(|error| SubxtNodeError::SubscribeBestBlocks(error.into()))?
+            .filter(|block| {
+                let pass = !matches!(
+                    block,
+                    Err(subxt::error::BlocksError::CannotGetBlockHeader(
+                        subxt::error::BackendError::Rpc(subxt::error::RpcError::ClientError(
+                            subxt::rpcs::Error::DisconnectedWillReconnect(_),
+                        )),
+                    ))
+                );
+                if !pass {
+                    warn!("node disconnected, reconnecting");
+                }
+
+                ready(pass)
+            })
+            .map_err(|error| SubxtNodeError::ReceiveBlock(error.into()));
+
+        Ok(best_blocks)
+    }
+
+    /// Make a [Block], using and maintaining the given authorities of the preceding blocks.
     async fn make_block(
-        &mut self,
+        &self,
         authorities: &mut Option<Vec<[u8; 32]>>,
         block: OnlineClientAtBlock,
     ) -> Result<Block, SubxtNodeError> {
+        let fetched_block = self.fetch_block(block).await?;
+        fetched_block.into_block(authorities).await
+    }
+
+    /// Fetch all parts of a [Block] but its author, which depends on the authorities of the
+    /// preceding blocks. Hence blocks can be fetched concurrently, but must be turned into [Block]s
+    /// in order; see [FetchedBlock::into_block].
+    async fn fetch_block(
+        &self,
+        block: OnlineClientAtBlock,
+    ) -> Result<FetchedBlock, SubxtNodeError> {
         let hash = block.block_hash().0.into();
         let height = block.block_number();
         let header = block_header(&block).await?;
@@ -232,24 +316,6 @@ impl SubxtNode {
             "making block"
         );
 
-        // Fetch authorities if `None`, either initially or because of a `NewSession` event (below).
-        if authorities.is_none() {
-            *authorities = Some(runtimes::fetch_authorities(state_node_version, &block).await?);
-        }
-        let author = authorities
-            .as_ref()
-            .map(|authorities| {
-                // The state metadata can only be newer than the runtime that authored the
-                // block, so this can never enable BABE recognition too late.
-                let babe_supported = block
-                    .metadata_ref()
-                    .runtime_api_trait_by_name(CONSENSUS_ENGINE_RUNTIME_API)
-                    .is_some();
-                extract_block_author(&header, authorities, content_node_version, babe_supported)
-            })
-            .transpose()?
-            .flatten();
-
         // The node's ledger-9 host API detects the v8 StateKey at the 8->9 enactment block and
         // dispatches this read to the v8 bridge. The MNSV protocol version remains the right
         // decoder here: that block's committed ledger state is still v8 until apply+1.
@@ -272,7 +338,7 @@ impl SubxtNode {
                     SubxtNodeError::GetOnlineClientAt(header.parent_hash, error.into())
                 })?;
             let legacy_rpc_methods = LegacyRpcMethods::<RpcConfigFor<SubstrateConfig>>::new(
-                self.rpc_client.to_owned().into(),
+                self.endpoints.active().rpc_client.to_owned().into(),
             );
             let extrinsic_bodies = legacy_rpc_methods
                 .chain_get_block(Some(block.block_hash()))
@@ -297,13 +363,9 @@ impl SubxtNode {
             transactions,
             mut dust_registration_events,
             bridge_events,
-        } = runtimes::make_block_details(
-            authorities,
-            content_node_version,
-            &block,
-            content_source.as_ref(),
-        )
-        .await?;
+            new_session,
+        } = runtimes::make_block_details(content_node_version, &block, content_source.as_ref())
+            .await?;
 
         // At genesis, Substrate does not emit events (Parity PR #5463). Fetch cNight
         // registrations from pallet storage instead.
@@ -325,12 +387,12 @@ impl SubxtNode {
             .try_collect::<Vec<_>>()
             .await?;
 
-        let block = Block {
+        let made_block = Block {
             hash,
             height,
             parent_hash,
             protocol_version,
-            author,
+            author: None,
             timestamp: timestamp.unwrap_or(0),
             zswap_merkle_tree_root,
             ledger_state_root,
@@ -339,20 +401,57 @@ impl SubxtNode {
             bridge_events,
         };
 
-        debug!(
-            hash:% = block.hash,
-            height = block.height,
-            parent_hash:% = block.parent_hash,
-            transactions_len = block.transactions.len();
-            "block made"
-        );
+        Ok(FetchedBlock {
+            block: made_block,
+            at_block: block,
+            header,
+            content_node_version,
+            state_node_version,
+            new_session,
+        })
+    }
 
-        Ok(block)
+    /// Fetch the blocks at the given heights in order, up to `prefetch_window` concurrently; see
+    /// [prefetch].
+    fn fetch_blocks_at_heights(
+        &self,
+        heights: impl Iterator<Item = u64> + Send + 'static,
+    ) -> impl Stream<Item = Result<FetchedBlock, SubxtNodeError>> + use<> {
+        let node = self.clone();
+        let fetches = stream::iter(heights).map(move |height| {
+            let node = node.clone();
+            async move {
+                let block = node.block_at_height(height).await?;
+                node.fetch_block(block).await
+            }
+        });
+
+        prefetch(fetches, self.prefetch_window)
+    }
+
+    /// Fetch the blocks with the given hashes in order, up to `prefetch_window` concurrently; see
+    /// [prefetch].
+    fn fetch_blocks_at_hashes(
+        &self,
+        hashes: impl Iterator<Item = H256> + Send + 'static,
+    ) -> impl Stream<Item = Result<FetchedBlock, SubxtNodeError>> + use<> {
+        let node = self.clone();
+        let fetches = stream::iter(hashes).map(move |hash| {
+            let node = node.clone();
+            async move {
+                let block = node.block_at(hash).await?;
+                node.fetch_block(block).await
+            }
+        });
+
+        prefetch(fetches, self.prefetch_window)
     }
 
     #[trace]
     async fn block_at(&self, hash: H256) -> Result<OnlineClientAtBlock, SubxtNodeError> {
-        self.online_client
+        self.endpoints
+            .active()
+            .online_client
             .at_block(hash)
             .await
             .map_err(|error| SubxtNodeError::GetOnlineClientAt(hash, error.into()))
@@ -360,34 +459,21 @@ impl SubxtNode {
 
     #[trace]
     async fn block_at_height(&self, height: u64) -> Result<OnlineClientAtBlock, SubxtNodeError> {
-        self.online_client
+        self.endpoints
+            .active()
+            .online_client
             .at_block(height)
             .await
             .map_err(|error| SubxtNodeError::GetOnlineClientAtHeight(height, error.into()))
     }
 }
 
-impl Node for SubxtNode {
-    type Error = SubxtNodeError;
-
-    async fn highest_blocks(
-        &self,
-    ) -> Result<impl Stream<Item = Result<BlockRef, Self::Error>> + Send, Self::Error> {
-        let highest_blocks = self
-            .subscribe_finalized_blocks(None)
-            .await?
-            .map_ok(|block| BlockRef {
-                hash: block.hash().0.into(),
-                height: block.number(),
-            });
-
-        Ok(highest_blocks)
-    }
-
-    fn finalized_blocks<'a>(
-        &'a mut self,
+impl SubxtNode {
+    /// A stream of finalized blocks of the active endpoint; see [Node::finalized_blocks].
+    fn active_finalized_blocks<'a>(
+        &'a self,
         after: Option<BlockRef>,
-    ) -> impl Stream<Item = Result<Block, Self::Error>> + use<'a> {
+    ) -> impl Stream<Item = Result<Block, SubxtNodeError>> + use<'a> {
         let (after_hash, after_height) = after
             .map(|BlockRef { hash, height }| (hash, height))
             .unzip();
@@ -433,7 +519,10 @@ impl Node for SubxtNode {
                 // Initialize from the stored block hash so the first forward-fetched block
                 // is verified against it too.
                 let mut last_forward_hash = after_height.map(|_| H256(after_hash.0));
-                for height in start_height..safe_height {
+                let mut fetched_blocks =
+                    pin!(self.fetch_blocks_at_heights(start_height..safe_height));
+                while let Some(fetched_block) = fetched_blocks.try_next().await? {
+                    let height = fetched_block.block.height;
                     if height % CATCH_UP_LOG_INTERVAL == 0 {
                         info!(
                             highest_stored_height:? = after_height,
@@ -442,9 +531,8 @@ impl Node for SubxtNode {
                             "catching up by height"
                         );
                     }
-                    let block = self.block_at_height(height).await?;
-                    let block_hash = block.block_hash();
-                    let made_block = self.make_block(&mut authorities, block).await?;
+                    let block_hash = H256(fetched_block.block.hash.0);
+                    let made_block = fetched_block.into_block(&mut authorities).await?;
                     if let Some(expected_parent) = last_forward_hash
                         && made_block.parent_hash.0 != expected_parent.0
                     {
@@ -459,7 +547,8 @@ impl Node for SubxtNode {
                 }
 
                 let stop_hash = last_forward_hash.unwrap_or(H256(after_hash.0));
-                let genesis = self.block_at(self.online_client.genesis_hash()).await?;
+                let genesis_hash = self.endpoints.active().online_client.genesis_hash();
+                let genesis = self.block_at(genesis_hash).await?;
                 let genesis_parent_hash = block_header(&genesis).await?.parent_hash;
 
                 let mut hashes = Vec::with_capacity(FINALIZATION_SAFETY_MARGIN as usize);
@@ -470,9 +559,10 @@ impl Node for SubxtNode {
                     hashes.push(parent.block_hash());
                 }
 
-                for hash in hashes.into_iter().rev() {
-                    let block = self.block_at(hash).await?;
-                    yield self.make_block(&mut authorities, block).await?;
+                let mut fetched_blocks =
+                    pin!(self.fetch_blocks_at_hashes(hashes.into_iter().rev()));
+                while let Some(fetched_block) = fetched_blocks.try_next().await? {
+                    yield fetched_block.into_block(&mut authorities).await?;
                 }
 
                 // Then we yield the first finalized block.
@@ -511,6 +601,11 @@ impl Node for SubxtNode {
                     // Stream completed with error.
                     Ok(Err(e)) => Err(e)?,
 
+                    // Timeout with other endpoints to fail over to.
+                    Err(_) if self.endpoints.len() > 1 => {
+                        Err(SubxtNodeError::SubscriptionStuck(recovery_timeout))?
+                    }
+
                     // Timeout: no block received within recovery_timeout => resubscribe.
                     Err(_) => {
                         warn!(
@@ -526,6 +621,384 @@ impl Node for SubxtNode {
         }
     }
 
+    /// A stream of best blocks of the active endpoint; see [Node::best_blocks].
+    fn active_best_blocks<'a>(
+        &'a self,
+        after: Option<BlockRef>,
+    ) -> impl Stream<Item = Result<Block, SubxtNodeError>> + use<'a> {
+        debug!(after:?; "subscribing to best blocks");
+
+        let mut authorities = None;
+
+        try_stream! {
+            let genesis_hash = self.endpoints.active().online_client.genesis_hash();
+            let genesis = self.block_at(genesis_hash).await?;
+            let genesis_parent_hash = block_header(&genesis).await?.parent_hash;
+
+            // Hashes and heights of the recently yielded blocks, oldest first, to filter duplicates
+            // and to find the fork point on a reorg.
+            let mut yielded = after
+                .map(|BlockRef { hash, height }| (H256(hash.0), height))
+                .into_iter()
+                .collect::<VecDeque<_>>();
+
+            let mut best_blocks = self.subscribe_best_blocks().await?;
+            let recovery_timeout = self.subscription_recovery_timeout;
+
+            loop {
+                let block = match timeout(recovery_timeout, receive_block(&mut best_blocks)).await {
+                    Ok(Ok(Some(block))) => block,
+
+                    // Stream completed normally.
+                    Ok(Ok(None)) => break,
+
+                    // Stream completed with error.
+                    Ok(Err(e)) => Err(e)?,
+
+                    // Timeout with other endpoints to fail over to.
+                    Err(_) if self.endpoints.len() > 1 => {
+                        Err(SubxtNodeError::SubscriptionStuck(recovery_timeout))?
+                    }
+
+                    // Timeout: no block received within recovery_timeout => resubscribe.
+                    Err(_) => {
+                        warn!(
+                            recovery_timeout:?;
+                            "best blocks subscription appears stuck, re-subscribing"
+                        );
+                        best_blocks = self.subscribe_best_blocks().await?;
+                        continue;
+                    }
+                };
+
+                let hash = block.hash();
+                let height = block.number();
+                if yielded.iter().any(|&(yielded_hash, _)| yielded_hash == hash) {
+                    continue;
+                }
+                debug!(
+                    hash:%,
+                    height,
+                    parent_hash:% = block.header().parent_hash;
+                    "best block received"
+                );
+
+                // Catch up by height with parent hash verification, like for finalized blocks,
+                // up to FINALIZATION_SAFETY_MARGIN below the received best block.
+                let start_height = yielded.back().map(|&(_, height)| height + 1).unwrap_or(0);
+                let safe_height = height.saturating_sub(FINALIZATION_SAFETY_MARGIN);
+                let mut fetched_blocks =
+                    pin!(self.fetch_blocks_at_heights(start_height..safe_height));
+                while let Some(fetched_block) = fetched_blocks.try_next().await? {
+                    let height = fetched_block.block.height;
+                    if height % CATCH_UP_LOG_INTERVAL == 0 {
+                        info!(
+                            current_height = height,
+                            best_height = safe_height + FINALIZATION_SAFETY_MARGIN;
+                            "catching up by height"
+                        );
+                    }
+                    let block_hash = H256(fetched_block.block.hash.0);
+                    let made_block = fetched_block.into_block(&mut authorities).await?;
+                    if let Some(&(expected_parent, _)) = yielded.back()
+                        && made_block.parent_hash.0 != expected_parent.0
+                    {
+                        Err(SubxtNodeError::ParentHashMismatch(
+                            height,
+                            expected_parent,
+                            H256(made_block.parent_hash.0),
+                        ))?;
+                    }
+                    push_yielded(&mut yielded, block_hash, height);
+                    yield made_block;
+                }
+
+                // Walk back from the received block to the fork point, i.e. the youngest yielded
+                // ancestor, collecting the hashes of the blocks to be yielded.
+                let mut hashes = vec![hash];
+                let mut parent_hash = block.header().parent_hash;
+                let fork_point = loop {
+                    if let Some(index) = yielded
+                        .iter()
+                        .position(|&(yielded_hash, _)| yielded_hash == parent_hash)
+                    {
+                        break Some(index);
+                    }
+
+                    if parent_hash == genesis_parent_hash {
+                        break None;
+                    }
+                    let parent_height = height - hashes.len() as u64;
+                    if let Some(&(_, lowest_height)) = yielded.front()
+                        && parent_height < lowest_height
+                    {
+                        Err(SubxtNodeError::ReorgTooDeep(height))?;
+                    }
+
+                    let parent = self.block_at(parent_hash).await?;
+                    hashes.push(parent_hash);
+                    parent_hash = block_header(&parent).await?.parent_hash;
+                };
+
+                if let Some(index) = fork_point
+                    && index + 1 < yielded.len()
+                {
+                    let (fork_hash, fork_height) = yielded[index];
+                    info!(
+                        fork_hash:%,
+                        fork_height,
+                        hash:%,
+                        height;
+                        "reorg of best blocks"
+                    );
+                    yielded.truncate(index + 1);
+
+                    // The abandoned blocks may have changed the authorities.
+                    authorities = None;
+                }
+
+                let mut fetched_blocks =
+                    pin!(self.fetch_blocks_at_hashes(hashes.into_iter().rev()));
+                while let Some(fetched_block) = fetched_blocks.try_next().await? {
+                    let block = fetched_block.into_block(&mut authorities).await?;
+                    push_yielded(&mut yielded, H256(block.hash.0), block.height);
+                    yield block;
+                }
+            }
+        }
+    }
+
+    /// Pass through the given blocks of the active endpoint until that fails with an endpoint
+    /// specific error or lags behind; then fail over to another endpoint, if any, and end. In
+    /// quorum mode each block is cross-checked with the other endpoints before being passed
+    /// through.
+    fn fail_over_on_error<'a>(
+        &'a self,
+        blocks: impl Stream<Item = Result<Block, SubxtNodeError>> + 'a,
+        finalized: bool,
+    ) -> impl Stream<Item = Result<Block, SubxtNodeError>> + 'a {
+        let index = self.endpoints.active_index();
+
+        stream! {
+            let mut blocks = pin!(blocks);
+
+            while let Some(block) = blocks.next().await {
+                let block = match block {
+                    Ok(block) if self.quorum => {
+                        self.cross_check(&block, finalized).await.map(|_| block)
+                    }
+                    block => block,
+                };
+
+                match block {
+                    Ok(block) => {
+                        yield Ok(block);
+
+                        if self.endpoints.is_active_lagging() && self.endpoints.fail_over(index) {
+                            break;
+                        }
+                    }
+
+                    Err(error) if error.is_endpoint_error() && self.endpoints.fail_over(index) => {
+                        warn!(error = error.as_chain(); "cannot receive block from node endpoint");
+                        break;
+                    }
+
+                    Err(error) => {
+                        yield Err(error);
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Cross-check the given block received from the active endpoint with the other endpoints,
+    /// healthiest first, comparing the block hash at its height for finalized blocks as well as
+    /// the ledger state root, until the endpoints which have the block form a majority; see
+    /// [QuorumVotes]. Other endpoints might not yet have imported the block, hence cross-checking
+    /// is retried until the subscription recovery timeout.
+    async fn cross_check(&self, block: &Block, finalized: bool) -> Result<(), SubxtNodeError> {
+        let hash = H256(block.hash.0);
+        let active_block = self.block_at(hash).await?;
+        let state_node_version =
+            ProtocolVersion::try_from(active_block.spec_version())?.node_version();
+        let ledger_state_root =
+            runtimes::get_ledger_state_root(state_node_version, &active_block).await?;
+
+        let mut votes = QuorumVotes::default();
+        let deadline = Instant::now() + self.subscription_recovery_timeout;
+        loop {
+            let others = self.endpoints.others();
+
+            for endpoint in &others {
+                if votes.has_voted(&endpoint.url) {
+                    continue;
+                }
+
+                let other_block = if finalized {
+                    endpoint.online_client.at_block(block.height).await
+                } else {
+                    endpoint.online_client.at_block(hash).await
+                };
+                let Ok(other_block) = other_block else {
+                    debug!(url = endpoint.url, height = block.height; "block not yet available");
+                    continue;
+                };
+
+                if other_block.block_hash() != hash {
+                    votes.disagree(endpoint.url.clone(), "block hash");
+                } else {
+                    let Ok(other_ledger_state_root) =
+                        runtimes::get_ledger_state_root(state_node_version, &other_block).await
+                    else {
+                        continue;
+                    };
+
+                    if other_ledger_state_root != ledger_state_root {
+                        votes.disagree(endpoint.url.clone(), "ledger state root");
+                    } else {
+                        votes.agree(endpoint.url.clone());
+                    }
+                }
+
+                if let Some(verdict) = votes.verdict(block.height) {
+                    return verdict;
+                }
+            }
+
+            if votes.len() == others.len() || Instant::now() >= deadline {
+                return Err(votes.undecided(block.height));
+            }
+            sleep(CROSS_CHECK_RETRY_INTERVAL).await;
+        }
+    }
+}
+
+/// Votes of the other endpoints on a block of the active endpoint in quorum mode, the active one
+/// implicitly agreeing. A majority of agreeing endpoints accepts the block, a majority of
+/// disagreeing ones fails over from the active endpoint. A tie, e.g. with only two endpoints,
+/// cannot be broken by failing over, which would just ping-pong between the endpoints, hence it
+/// needs another endpoint or is fatal.
+#[derive(Debug, Default)]
+struct QuorumVotes {
+    agreeing: Vec<String>,
+    disagreeing: Vec<(String, &'static str)>,
+}
+
+impl QuorumVotes {
+    fn agree(&mut self, url: String) {
+        self.agreeing.push(url);
+    }
+
+    fn disagree(&mut self, url: String, what: &'static str) {
+        self.disagreeing.push((url, what));
+    }
+
+    fn has_voted(&self, url: &str) -> bool {
+        self.agreeing.iter().any(|agreeing| agreeing == url)
+            || self
+                .disagreeing
+                .iter()
+                .any(|(disagreeing, _)| disagreeing == url)
+    }
+
+    fn len(&self) -> usize {
+        self.agreeing.len() + self.disagreeing.len()
+    }
+
+    /// The verdict, if there is a majority including at least one other endpoint.
+    fn verdict(&self, height: u64) -> Option<Result<(), SubxtNodeError>> {
+        let agreeing = self.agreeing.len() + 1;
+        let disagreeing = self.disagreeing.len();
+
+        if agreeing > 1 && agreeing > disagreeing {
+            for (url, what) in &self.disagreeing {
+                warn!(height, url, what; "node endpoint outvoted in quorum");
+            }
+            Some(Ok(()))
+        } else if disagreeing > agreeing {
+            Some(Err(SubxtNodeError::QuorumMismatch(
+                height,
+                self.disagreeing[0].1,
+                self.disagreeing_urls(),
+            )))
+        } else {
+            None
+        }
+    }
+
+    /// The error if no verdict has been reached: a tie, if any endpoint disagrees, or no quorum.
+    fn undecided(&self, height: u64) -> SubxtNodeError {
+        match self.disagreeing.first() {
+            Some((_, what)) => SubxtNodeError::QuorumTie(height, what, self.disagreeing_urls()),
+            None => SubxtNodeError::NoQuorum(height),
+        }
+    }
+
+    fn disagreeing_urls(&self) -> String {
+        self.disagreeing
+            .iter()
+            .map(|(url, _)| url.as_str())
+            .join(", ")
+    }
+}
+
+impl Node for SubxtNode {
+    type Error = SubxtNodeError;
+
+    async fn highest_blocks(
+        &self,
+    ) -> Result<impl Stream<Item = Result<BlockRef, Self::Error>> + Send, Self::Error> {
+        let node = self.clone();
+        let mut index = node.endpoints.active_index();
+        let mut highest_blocks = node.subscribe_finalized_blocks(None).await?;
+
+        let highest_blocks = try_stream! {
+            loop {
+                match highest_blocks.try_next().await {
+                    Ok(Some(block)) => {
+                        yield BlockRef {
+                            hash: block.hash().0.into(),
+                            height: block.number(),
+                        };
+                    }
+
+                    Ok(None) => break,
+
+                    Err(error) if error.is_endpoint_error() && node.endpoints.fail_over(index) => {
+                        warn!(
+                            error = error.as_chain();
+                            "cannot receive highest block, re-subscribing"
+                        );
+                        index = node.endpoints.active_index();
+                        highest_blocks = node.subscribe_finalized_blocks(None).await?;
+                    }
+
+                    Err(error) => Err(error)?,
+                }
+            }
+        };
+
+        Ok(highest_blocks)
+    }
+
+    fn finalized_blocks<'a>(
+        &'a mut self,
+        after: Option<BlockRef>,
+    ) -> impl Stream<Item = Result<Block, Self::Error>> + use<'a> {
+        let node = &*self;
+        node.fail_over_on_error(node.active_finalized_blocks(after), true)
+    }
+
+    fn best_blocks<'a>(
+        &'a mut self,
+        after: Option<BlockRef>,
+    ) -> impl Stream<Item = Result<Block, Self::Error>> + use<'a> {
+        let node = &*self;
+        node.fail_over_on_error(node.active_best_blocks(after), false)
+    }
+
     async fn fetch_system_parameters(
         &self,
         block_hash: BlockHash,
@@ -556,7 +1029,7 @@ impl Node for SubxtNode {
 
     async fn fetch_genesis_ledger_state(&self) -> Result<ByteVec, Self::Error> {
         let legacy_rpc_methods = LegacyRpcMethods::<RpcConfigFor<SubstrateConfig>>::new(
-            self.rpc_client.to_owned().into(),
+            self.endpoints.active().rpc_client.to_owned().into(),
         );
         let properties = legacy_rpc_methods
             .system_properties()
@@ -583,6 +1056,46 @@ impl Node for SubxtNode {
 
         Ok(genesis_ledger_state)
     }
+
+    async fn fetch_pending_transactions(&self) -> Result<Vec<PendingTransaction>, Self::Error> {
+        let legacy_rpc_methods = LegacyRpcMethods::<RpcConfigFor<SubstrateConfig>>::new(
+            self.endpoints.active().rpc_client.to_owned().into(),
+        );
+        let extrinsic_bodies = legacy_rpc_methods
+            .author_pending_extrinsics()
+            .await
+            .map_err(SubxtNodeError::FetchPendingExtrinsics)?
+            .into_iter()
+            .map(|bytes| bytes.0)
+            .collect::<Vec<_>>();
+        if extrinsic_bodies.is_empty() {
+            return Ok(vec![]);
+        }
+
+        // Pending extrinsics are to be included on top of the current chain, hence decode them
+        // with the runtime of the finalized head.
+        let hash = legacy_rpc_methods
+            .chain_get_finalized_head()
+            .await
+            .map_err(SubxtNodeError::FetchFinalizedHead)?;
+        let block = self.block_at(hash).await?;
+        let protocol_version = ProtocolVersion::try_from(block.spec_version())?;
+
+        let pending_transactions = runtimes::make_pending_transactions(
+            protocol_version.node_version(),
+            &block,
+            extrinsic_bodies,
+        )
+        .await
+        .into_iter()
+        .map(|raw| PendingTransaction {
+            protocol_version,
+            raw,
+        })
+        .collect();
+
+        Ok(pending_transactions)
+    }
 }
 
 /// Config for node connection.
@@ -590,6 +1103,10 @@ impl Node for SubxtNode {
 pub struct Config {
     pub url: String,
 
+    /// Further node endpoints to fail over to if the active one fails or lags behind.
+    #[serde(default)]
+    pub fallback_urls: Vec<String>,
+
     #[serde(with = "humantime_serde")]
     pub reconnect_max_delay: Duration,
 
@@ -603,12 +1120,44 @@ pub struct Config {
         default = "default_subscription_recovery_timeout"
     )]
     pub subscription_recovery_timeout: Duration,
+
+    /// Maximum number of blocks fetched concurrently ahead of the yielded one when catching up.
+    /// Defaults to 16.
+    #[serde(default = "default_prefetch_window")]
+    pub prefetch_window: NonZeroUsize,
+
+    /// Interval for checking the health, i.e. the finalized height, of all endpoints, if there
+    /// are multiple ones. Defaults to 10 seconds.
+    #[serde(with = "humantime_serde", default = "default_health_check_interval")]
+    pub health_check_interval: Duration,
+
+    /// Number of blocks the finalized height of the active endpoint may lag behind the highest
+    /// one of all endpoints before failing over. Defaults to 10.
+    #[serde(default = "default_max_lag")]
+    pub max_lag: u64,
+
+    /// Cross-check each block with the majority of endpoints before yielding it; requires at
+    /// least two endpoints and stops on a mismatch between two, hence three are recommended.
+    #[serde(default)]
+    pub quorum: bool,
 }
 
 fn default_subscription_recovery_timeout() -> Duration {
     Duration::from_secs(30)
 }
 
+fn default_prefetch_window() -> NonZeroUsize {
+    NonZeroUsize::new(16).unwrap()
+}
+
+fn default_health_check_interval() -> Duration {
+    Duration::from_secs(10)
+}
+
+fn default_max_lag() -> u64 {
+    10
+}
+
 /// Error possibly returned by [SubxtNode::new].
 #[derive(Debug, Error)]
 pub enum Error {
@@ -620,6 +1169,9 @@ pub enum Error {
 
     #[error("cannot create HTTP header")]
     InvalidHeaderValue(#[from] InvalidHeaderValue),
+
+    #[error("quorum mode requires at least two connected node endpoints")]
+    NoQuorum,
 }
 
 /// Error possibly returned by each item of the [Block]s stream.
@@ -628,6 +1180,27 @@ pub enum SubxtNodeError {
     #[error("cannot subscribe to finalized blocks")]
     SubscribeFinalizedBlocks(#[source] Box<subxt::error::BlocksError>),
 
+    #[error("cannot subscribe to best blocks")]
+    SubscribeBestBlocks(#[source] Box<subxt::error::BlocksError>),
+
+    #[error("reorg of best block at height {0} deeper than the recently yielded blocks")]
+    ReorgTooDeep(u64),
+
+    #[error("no block received within {0:?}")]
+    SubscriptionStuck(Duration),
+
+    #[error("cannot fetch finalized head")]
+    FetchFinalizedHead(#[source] subxt::rpcs::Error),
+
+    #[error("{1} of block at height {0} differs from the majority of node endpoints {2}")]
+    QuorumMismatch(u64, &'static str, String),
+
+    #[error("{1} of block at height {0} differs from node endpoint {2} without tie-breaker")]
+    QuorumTie(u64, &'static str, String),
+
+    #[error("cannot cross-check block at height {0} with any other node endpoint")]
+    NoQuorum(u64),
+
     #[error("cannot receive finalized block")]
     ReceiveBlock(#[source] Box<subxt::error::BlocksError>),
 
@@ -649,6 +1222,9 @@ pub enum SubxtNodeError {
     #[error("block body not found")]
     BlockBodyNotFound,
 
+    #[error("cannot fetch pending extrinsics")]
+    FetchPendingExtrinsics(#[source] subxt::rpcs::Error),
+
     #[error("cannot fetch events")]
     FetchEvents(#[source] Box<subxt::error::EventsError>),
 
@@ -725,6 +1301,119 @@ pub enum SubxtNodeError {
     Ledger(#[from] ledger::Error),
 }
 
+/// A fetched block, i.e. a [Block] without its author; see [SubxtNode::fetch_block].
+struct FetchedBlock {
+    block: Block,
+    at_block: OnlineClientAtBlock,
+    header: SubstrateHeader<H256>,
+    content_node_version: NodeVersion,
+    state_node_version: NodeVersion,
+    new_session: bool,
+}
+
+impl FetchedBlock {
+    /// Turn this fetched block into a [Block] by determining its author from the given authorities
+    /// of the preceding blocks, which are fetched if `None` and reset if a new session started
+    /// with this block.
+    async fn into_block(
+        self,
+        authorities: &mut Option<Vec<[u8; 32]>>,
+    ) -> Result<Block, SubxtNodeError> {
+        let FetchedBlock {
+            mut block,
+            at_block,
+            header,
+            content_node_version,
+            state_node_version,
+            new_session,
+        } = self;
+
+        // Fetch authorities if `None`, either initially or because of a `NewSession` event (below).
+        if authorities.is_none() {
+            *authorities = Some(runtimes::fetch_authorities(state_node_version, &at_block).await?);
+        }
+        block.author = authorities
+            .as_ref()
+            .map(|authorities| {
+                // The state metadata can only be newer than the runtime that authored the
+                // block, so this can never enable BABE recognition too late.
+                let babe_supported = at_block
+                    .metadata_ref()
+                    .runtime_api_trait_by_name(CONSENSUS_ENGINE_RUNTIME_API)
+                    .is_some();
+                extract_block_author(&header, authorities, content_node_version, babe_supported)
+            })
+            .transpose()?
+            .flatten();
+
+        if new_session {
+            *authorities = None;
+        }
+
+        debug!(
+            hash:% = block.hash,
+            height = block.height,
+            parent_hash:% = block.parent_hash,
+            transactions_len = block.transactions.len();
+            "block made"
+        );
+
+        Ok(block)
+    }
+}
+
+impl SubxtNodeError {
+    /// Whether this error is specific to the node endpoint, e.g. caused by a disconnect, such that
+    /// another endpoint is not expected to fail likewise.
+    fn is_endpoint_error(&self) -> bool {
+        matches!(
+            self,
+            Self::SubscribeFinalizedBlocks(_)
+                | Self::SubscribeBestBlocks(_)
+                | Self::SubscriptionStuck(_)
+                | Self::ReceiveBlock(_)
+                | Self::GetOnlineClientAt(..)
+                | Self::GetOnlineClientAtHeight(..)
+                | Self::ParentHashMismatch(..)
+                | Self::FetchBlockBody(_)
+                | Self::BlockBodyNotFound
+                | Self::GetBlockHeader(_)
+                | Self::FetchFinalizedHead(_)
+                | Self::QuorumMismatch(..)
+        )
+    }
+}
+
+/// Run the given fetches in order, up to `prefetch_window` concurrently, in a spawned task which
+/// feeds a channel bounded by `prefetch_window`. Hence fetching progresses while the consumer is
+/// busy with earlier results, e.g. making and applying blocks. The task ends after the first error
+/// or once the returned stream is dropped.
+fn prefetch<T, E, F>(
+    fetches: impl Stream<Item = F> + Send + 'static,
+    prefetch_window: NonZeroUsize,
+) -> impl Stream<Item = Result<T, E>>
+where
+    T: Send + 'static,
+    E: Send + 'static,
+    F: Future<Output = Result<T, E>> + Send + 'static,
+{
+    let prefetch_window = prefetch_window.get();
+    let (mut sender, receiver) = mpsc::channel(prefetch_window);
+
+    task::spawn(async move {
+        let mut results = pin!(fetches.buffered(prefetch_window));
+
+        while let Some(result) = results.next().await {
+            let failed = result.is_err();
+            if sender.send(result).await.is_err() || failed {
+                break;
+            }
+        }
+    });
+
+    receiver
+}
+
 #[trace]
 async fn receive_block(
     finalized_blocks: &mut (impl Stream<Item = Result<SubxtBlock, SubxtNodeError>> + Unpin),
@@ -732,6 +1421,15 @@ async fn receive_block(
     finalized_blocks.try_next().await
 }
 
+/// Remember the given yielded best block, keeping at most [FINALIZATION_SAFETY_MARGIN] blocks,
+/// which bounds the depth of reorgs that can be followed.
+fn push_yielded(yielded: &mut VecDeque<(H256, u64)>, hash: H256, height: u64) {
+    yielded.push_back((hash, height));
+    while yielded.len() > FINALIZATION_SAFETY_MARGIN as usize {
+        yielded.pop_front();
+    }
+}
+
 /// Check an authority set against a block header's digest logs to determine the author of that
 /// block.
 fn extract_block_author<H>(
@@ -906,6 +1604,10 @@ async fn block_header(
 mod tests {
     use super::*;
     use parity_scale_codec::Encode;
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
 
     const AUTHORITIES: [[u8; 32]; 3] = [[1; 32], [2; 32], [3; 32]];
 
@@ -1033,4 +1735,105 @@ mod tests {
 
         assert_eq!(author, None);
     }
+
+    #[test]
+    fn quorum_mismatch_between_two_endpoints_is_a_fatal_tie() {
+        let mut votes = QuorumVotes::default();
+        votes.disagree("ws://b".to_string(), "block hash");
+        assert!(votes.verdict(42).is_none());
+
+        let error = votes.undecided(42);
+        assert!(matches!(
+            &error,
+            SubxtNodeError::QuorumTie(42, "block hash", url) if url == "ws://b"
+        ));
+        assert!(!error.is_endpoint_error());
+    }
+
+    #[test]
+    fn quorum_mismatch_is_broken_by_third_endpoint() {
+        // The third endpoint agrees with the active one, which is accepted.
+        let mut votes = QuorumVotes::default();
+        votes.disagree("ws://b".to_string(), "ledger state root");
+        assert!(votes.verdict(42).is_none());
+        assert!(!votes.has_voted("ws://c"));
+        votes.agree("ws://c".to_string());
+        assert!(matches!(votes.verdict(42), Some(Ok(()))));
+
+        // The third endpoint agrees with the other one, hence the active one is outvoted and
+        // failed over from.
+        let mut votes = QuorumVotes::default();
+        votes.disagree("ws://b".to_string(), "ledger state root");
+        votes.disagree("ws://c".to_string(), "block hash");
+        let error = votes
+            .verdict(42)
+            .expect("verdict")
+            .expect_err("active endpoint outvoted");
+        assert!(matches!(
+            &error,
+            SubxtNodeError::QuorumMismatch(42, "ledger state root", urls)
+                if urls == "ws://b, ws://c"
+        ));
+        assert!(error.is_endpoint_error());
+    }
+
+    #[test]
+    fn quorum_without_votes_is_no_quorum() {
+        let mut votes = QuorumVotes::default();
+        assert!(votes.verdict(42).is_none());
+        assert!(matches!(votes.undecided(42), SubxtNodeError::NoQuorum(42)));
+
+        votes.agree("ws://b".to_string());
+        assert!(matches!(votes.verdict(42), Some(Ok(()))));
+    }
+
+    #[tokio::test]
+    async fn prefetch_progresses_without_polling() {
+        let fetched = Arc::new(AtomicUsize::new(0));
+        let fetches = counted_fetches(&fetched);
+
+        // Without being polled, up to the bounds of the buffer and the channel are fetched.
+        let prefetched = prefetch(fetches, NonZeroUsize::new(4).unwrap());
+        sleep(Duration::from_millis(100)).await;
+        let fetched_before_polling = fetched.load(Ordering::Acquire);
+        assert!(fetched_before_polling >= 4);
+        assert!(fetched_before_polling < 100);
+
+        let results = prefetched.collect::<Vec<_>>().await;
+        assert_eq!(results, (0..100).map(Ok).collect::<Vec<_>>());
+        assert_eq!(fetched.load(Ordering::Acquire), 100);
+    }
+
+    #[tokio::test]
+    async fn prefetch_stops_after_error_or_drop() {
+        let fetches =
+            stream::iter(0..10).map(|n| async move { if n == 3 { Err(n) } else { Ok(n) } });
+        let results = prefetch(fetches, NonZeroUsize::new(4).unwrap())
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(results, vec![Ok(0), Ok(1), Ok(2), Err(3)]);
+
+        let fetched = Arc::new(AtomicUsize::new(0));
+        let fetches = counted_fetches(&fetched);
+        let mut prefetched = Box::pin(prefetch(fetches, NonZeroUsize::new(4).unwrap()));
+        assert_eq!(prefetched.next().await, Some(Ok(0)));
+        drop(prefetched);
+
+        sleep(Duration::from_millis(100)).await;
+        assert!(fetched.load(Ordering::Acquire) < 100);
+    }
+
+    /// Fetches of the numbers 0 to 99, counting the started ones.
+    fn counted_fetches(
+        fetched: &Arc<AtomicUsize>,
+    ) -> impl Stream<Item = impl Future<Output = Result<i32, ()>> + Send> + Send + 'static {
+        let fetched = fetched.clone();
+        stream::iter(0..100).map(move |n| {
+            let fetched = fetched.clone();
+            async move {
+                fetched.fetch_add(1, Ordering::AcqRel);
+                Ok(n)
+            }
+        })
+    }
 }
@@ -0,0 +1,317 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Node endpoints with health scoring, used by [SubxtNode](super::SubxtNode) to fail over from a
+//! lagging or disconnected endpoint to the healthiest other one.
+
+use crate::infra::subxt_node::{Error, SubxtNodeError};
+use futures::future::join_all;
+use http::{HeaderMap, header::USER_AGENT};
+use indexer_common::error::StdErrorExt;
+use log::{debug, warn};
+use std::{
+    sync::{
+        Arc, Weak,
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+use subxt::{
+    OnlineClient, SubstrateConfig,
+    config::RpcConfigFor,
+    rpcs::{
+        LegacyRpcMethods,
+        client::{ReconnectingRpcClient, reconnecting_rpc_client::ExponentialBackoff},
+    },
+};
+use tokio::time::{interval, timeout};
+
+/// A connected node endpoint with its health.
+pub struct Endpoint {
+    pub url: String,
+    pub rpc_client: ReconnectingRpcClient,
+    pub online_client: OnlineClient<SubstrateConfig>,
+    failures: AtomicU64,
+    finalized_height: AtomicU64,
+}
+
+impl Endpoint {
+    /// Connect to the node endpoint with the given URL.
+    pub async fn connect(
+        url: String,
+        retry_max_delay: Duration,
+        retry_max_attempts: usize,
+    ) -> Result<Self, Error> {
+        let retry_policy = ExponentialBackoff::from_millis(10)
+            .max_delay(retry_max_delay)
+            .take(retry_max_attempts);
+        let user_agent = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION")).parse()?;
+        let headers = HeaderMap::from_iter([(USER_AGENT, user_agent)]);
+        let rpc_client = ReconnectingRpcClient::builder()
+            .set_headers(headers)
+            .retry_policy(retry_policy)
+            .build(&url)
+            .await
+            .map_err(|error| Error::RpcClient(error.into()))?;
+
+        let online_client =
+            OnlineClient::<SubstrateConfig>::from_rpc_client(rpc_client.clone()).await?;
+
+        Ok(Self {
+            url,
+            rpc_client,
+            online_client,
+            failures: AtomicU64::new(0),
+            finalized_height: AtomicU64::new(0),
+        })
+    }
+
+    /// Record a failure of this endpoint, lowering its health.
+    pub fn record_failure(&self) {
+        self.failures.fetch_add(1, Ordering::Relaxed);
+    }
+
+    async fn fetch_finalized_height(&self) -> Result<u64, SubxtNodeError> {
+        let legacy_rpc_methods = LegacyRpcMethods::<RpcConfigFor<SubstrateConfig>>::new(
+            self.rpc_client.to_owned().into(),
+        );
+        let hash = legacy_rpc_methods
+            .chain_get_finalized_head()
+            .await
+            .map_err(SubxtNodeError::FetchFinalizedHead)?;
+        let block = self
+            .online_client
+            .at_block(hash)
+            .await
+            .map_err(|error| SubxtNodeError::GetOnlineClientAt(hash, error.into()))?;
+
+        Ok(block.block_number())
+    }
+
+    async fn check_health(&self, check_timeout: Duration) {
+        match timeout(check_timeout, self.fetch_finalized_height()).await {
+            Ok(Ok(height)) => {
+                self.finalized_height.store(height, Ordering::Relaxed);
+                let _ =
+                    self.failures
+                        .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |failures| {
+                            failures.checked_sub(1)
+                        });
+                debug!(url = self.url, height; "node endpoint healthy");
+            }
+
+            Ok(Err(error)) => {
+                self.record_failure();
+                warn!(url = self.url, error = error.as_chain(); "node endpoint unhealthy");
+            }
+
+            Err(_) => {
+                self.record_failure();
+                warn!(url = self.url, check_timeout:?; "node endpoint health check timed out");
+            }
+        }
+    }
+
+    fn health(&self) -> Health {
+        Health {
+            failures: self.failures.load(Ordering::Relaxed),
+            finalized_height: self.finalized_height.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// The node endpoints, one of which is active, i.e. used to subscribe to and fetch blocks.
+#[derive(Clone)]
+pub struct Endpoints {
+    endpoints: Arc<Vec<Endpoint>>,
+    active: Arc<AtomicUsize>,
+    max_lag: u64,
+}
+
+impl Endpoints {
+    /// Create new [Endpoints] with the first of the given (non-empty) endpoints active, spawning a
+    /// task which checks the health of all endpoints at the given interval as long as these
+    /// [Endpoints] are alive.
+    pub fn new(endpoints: Vec<Endpoint>, max_lag: u64, health_check_interval: Duration) -> Self {
+        assert!(!endpoints.is_empty(), "at least one endpoint");
+
+        let endpoints = Arc::new(endpoints);
+        if endpoints.len() > 1 {
+            tokio::spawn(check_health(
+                Arc::downgrade(&endpoints),
+                health_check_interval,
+            ));
+        }
+
+        Self {
+            endpoints,
+            active: Default::default(),
+            max_lag,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.endpoints.len()
+    }
+
+    /// The index of the active endpoint.
+    pub fn active_index(&self) -> usize {
+        self.active.load(Ordering::Acquire)
+    }
+
+    /// The active endpoint.
+    pub fn active(&self) -> &Endpoint {
+        &self.endpoints[self.active_index()]
+    }
+
+    /// All but the active endpoint, healthiest first.
+    pub fn others(&self) -> Vec<&Endpoint> {
+        let active = self.active_index();
+        let mut others = self
+            .endpoints
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| *index != active)
+            .map(|(_, endpoint)| endpoint)
+            .collect::<Vec<_>>();
+        others.sort_by_key(|endpoint| endpoint.health());
+        others
+    }
+
+    /// Whether the finalized height of the active endpoint lags more than the configured maximum
+    /// behind the highest finalized height of all endpoints.
+    pub fn is_active_lagging(&self) -> bool {
+        let healths = self.healths();
+        is_lagging(&healths, self.active_index(), self.max_lag)
+    }
+
+    /// Fail over from the endpoint with the given index, recording a failure for it, to the
+    /// healthiest other one, unless it is no longer active, e.g. because of a concurrent fail
+    /// over. Returns false if there is no other endpoint to fail over to.
+    pub fn fail_over(&self, from: usize) -> bool {
+        let Some(to) = healthiest_other(&self.healths(), from) else {
+            return false;
+        };
+
+        if self
+            .active
+            .compare_exchange(from, to, Ordering::AcqRel, Ordering::Acquire)
+            .is_ok()
+        {
+            self.endpoints[from].record_failure();
+            warn!(
+                from = self.endpoints[from].url,
+                to = self.endpoints[to].url;
+                "failing over to other node endpoint"
+            );
+        }
+
+        true
+    }
+
+    fn healths(&self) -> Vec<Health> {
+        self.endpoints.iter().map(Endpoint::health).collect()
+    }
+}
+
+/// Health of an endpoint; ordered from the healthiest, i.e. the one with the fewest recent
+/// failures and then with the highest finalized height.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Health {
+    failures: u64,
+    finalized_height: u64,
+}
+
+impl PartialOrd for Health {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Health {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.failures
+            .cmp(&other.failures)
+            .then(other.finalized_height.cmp(&self.finalized_height))
+    }
+}
+
+fn healthiest_other(healths: &[Health], index: usize) -> Option<usize> {
+    healths
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| *i != index)
+        .min_by_key(|(_, health)| *health)
+        .map(|(i, _)| i)
+}
+
+fn is_lagging(healths: &[Health], index: usize, max_lag: u64) -> bool {
+    let highest_height = healths
+        .iter()
+        .map(|health| health.finalized_height)
+        .max()
+        .unwrap_or_default();
+
+    healths[index].finalized_height + max_lag < highest_height
+}
+
+async fn check_health(endpoints: Weak<Vec<Endpoint>>, health_check_interval: Duration) {
+    let mut interval = interval(health_check_interval);
+
+    loop {
+        interval.tick().await;
+
+        let Some(endpoints) = endpoints.upgrade() else {
+            break;
+        };
+        join_all(
+            endpoints
+                .iter()
+                .map(|endpoint| endpoint.check_health(health_check_interval)),
+        )
+        .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::infra::subxt_node::endpoint::{Health, healthiest_other, is_lagging};
+
+    #[test]
+    fn test_healthiest_other() {
+        let healths = [
+            health(0, 100),
+            health(1, 110),
+            health(0, 99),
+            health(0, 105),
+        ];
+        assert_eq!(healthiest_other(&healths, 0), Some(3));
+        assert_eq!(healthiest_other(&healths, 3), Some(0));
+        assert_eq!(healthiest_other(&healths[..1], 0), None);
+    }
+
+    #[test]
+    fn test_is_lagging() {
+        let healths = [health(0, 100), health(0, 111), health(0, 110)];
+        assert!(is_lagging(&healths, 0, 10));
+        assert!(!is_lagging(&healths, 0, 11));
+        assert!(!is_lagging(&healths, 2, 10));
+    }
+
+    fn health(failures: u64, finalized_height: u64) -> Health {
+        Health {
+            failures,
+            finalized_height,
+        }
+    }
+}
@@ -36,6 +36,8 @@ pub struct BlockDetails {
     /// `c2m-bridge` pallet exists in the runtime metadata. Empty for earlier
     /// node versions (the pallet did not yet exist there).
     pub bridge_events: Vec<indexer_common::domain::bridge::BridgeEvent>,
+    /// Whether a new session started with this block, possibly changing the authorities.
+    pub new_session: bool,
 }
 
 /// Runtime specific (serialized) transaction.
@@ -46,17 +48,31 @@ pub enum Transaction {
 
 /// Make block details depending on the given protocol version.
 pub async fn make_block_details(
-    authorities: &mut Option<Vec<[u8; 32]>>,
     node_version: NodeVersion,
     block: &OnlineClientAtBlock,
     content: Option<&ContentSource>,
 ) -> Result<BlockDetails, SubxtNodeError> {
     // TODO Replace this often repeated pattern with a macro?
     match node_version {
-        NodeVersion::V0_22 => v0_22_0::make_block_details(authorities, block, content).await,
-        NodeVersion::V1_0 => v1_0_0::make_block_details(authorities, block, content).await,
-        NodeVersion::V2_0 => v2_0_0::make_block_details(authorities, block, content).await,
-        NodeVersion::V2_1 => v2_1_0::make_block_details(authorities, block, content).await,
+        NodeVersion::V0_22 => v0_22_0::make_block_details(block, content).await,
+        NodeVersion::V1_0 => v1_0_0::make_block_details(block, content).await,
+        NodeVersion::V2_0 => v2_0_0::make_block_details(block, content).await,
+        NodeVersion::V2_1 => v2_1_0::make_block_details(block, content).await,
+    }
+}
+
+/// Make the Midnight transactions of the given pending extrinsics depending on the given protocol
+/// version; extrinsics which cannot be decoded or are no Midnight transactions are skipped.
+pub async fn make_pending_transactions(
+    node_version: NodeVersion,
+    block: &OnlineClientAtBlock,
+    extrinsic_bodies: Vec<Vec<u8>>,
+) -> Vec<ByteVec> {
+    match node_version {
+        NodeVersion::V0_22 => v0_22_0::make_pending_transactions(block, extrinsic_bodies).await,
+        NodeVersion::V1_0 => v1_0_0::make_pending_transactions(block, extrinsic_bodies).await,
+        NodeVersion::V2_0 => v2_0_0::make_pending_transactions(block, extrinsic_bodies).await,
+        NodeVersion::V2_1 => v2_1_0::make_pending_transactions(block, extrinsic_bodies).await,
     }
 }
 
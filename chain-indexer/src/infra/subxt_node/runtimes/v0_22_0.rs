@@ -28,7 +28,6 @@ use parity_scale_codec::Decode;
 use subxt::error::RuntimeApiError;
 
 pub async fn make_block_details(
-    authorities: &mut Option<Vec<[u8; 32]>>,
     block: &OnlineClientAtBlock,
     content: Option<&ContentSource>,
 ) -> Result<BlockDetails, SubxtNodeError> {
@@ -100,6 +99,7 @@ pub async fn make_block_details(
         .collect::<Vec<_>>();
 
     let mut dust_registration_events = vec![];
+    let mut new_session = false;
     let mut system_transactions_from_events = vec![];
 
     let events = match content {
@@ -130,7 +130,7 @@ pub async fn make_block_details(
 
         match event {
             Event::Session(NewSession { .. }) => {
-                *authorities = None;
+                new_session = true;
             }
 
             // System transaction created by the node (not from extrinsics).
@@ -193,9 +193,31 @@ pub async fn make_block_details(
         transactions,
         dust_registration_events,
         bridge_events: vec![],
+        new_session,
     })
 }
 
+pub async fn make_pending_transactions(
+    block: &OnlineClientAtBlock,
+    extrinsic_bodies: Vec<Vec<u8>>,
+) -> Vec<ByteVec> {
+    use super::runtime_0_22_0::{
+        Call, runtime_types::pallet_midnight::pallet::Call::send_mn_transaction,
+    };
+
+    block
+        .extrinsics()
+        .from_bytes(extrinsic_bodies)
+        .await
+        .iter()
+        .filter_map(|extrinsic| extrinsic.ok()?.decode_call_data_as::<Call>().ok())
+        .filter_map(|call| match call {
+            Call::Midnight(send_mn_transaction { midnight_tx }) => Some(midnight_tx.into()),
+            _ => None,
+        })
+        .collect()
+}
+
 pub async fn fetch_authorities(
     block: &OnlineClientAtBlock,
 ) -> Result<Vec<[u8; 32]>, SubxtNodeError> {
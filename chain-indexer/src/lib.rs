@@ -14,6 +14,7 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
 pub mod application;
+pub mod cli;
 #[cfg(feature = "cloud")]
 pub mod config;
 pub mod domain;
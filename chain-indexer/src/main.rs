@@ -40,9 +40,15 @@ fn run() -> anyhow::Result<()> {
     use anyhow::Context;
     use chain_indexer::{
         application,
+        cli::{Cli, Command, SnapshotCommand},
         config::Config,
-        infra::{self, subxt_node::SubxtNode},
+        infra::{
+            self,
+            file_node::{FileNode, RecordingNode},
+            subxt_node::SubxtNode,
+        },
     };
+    use clap::Parser;
     use indexer_common::{
         config::ConfigExt,
         infra::{
@@ -58,6 +64,12 @@ fn run() -> anyhow::Result<()> {
         signal::unix::{SignalKind, signal},
     };
 
+    let Cli {
+        command,
+        record,
+        replay,
+    } = Cli::parse();
+
     // Load configuration.
     let config = Config::load().context("load configuration")?;
     info!(config:?; "starting");
@@ -92,10 +104,6 @@ fn run() -> anyhow::Result<()> {
         telemetry::init_tracing(tracing_config);
         telemetry::init_metrics(metrics_config);
 
-        let node = SubxtNode::new(node_config)
-            .await
-            .context("create SubxtNode")?;
-
         let pool = pool::postgres::PostgresPool::new(storage_config)
             .await
             .context("create DB pool for Postgres")?;
@@ -105,15 +113,64 @@ fn run() -> anyhow::Result<()> {
                 .context("run Postgres migrations")?;
         }
 
-        let storage = infra::storage::Storage::new(pool.clone());
+        let mut storage = infra::storage::Storage::new(pool.clone());
 
         ledger_db::init(ledger_db_config, pool);
 
+        match command {
+            Some(Command::Snapshot(SnapshotCommand::Export { height, file })) => {
+                return application::snapshot::export(
+                    &application_config.network_id,
+                    height,
+                    application_config.ledger_state_retention,
+                    &file,
+                    &storage,
+                )
+                .await;
+            }
+
+            Some(Command::Snapshot(SnapshotCommand::Import { file })) => {
+                return application::snapshot::import(
+                    &application_config.network_id,
+                    &file,
+                    &mut storage,
+                )
+                .await;
+            }
+
+            Some(Command::Rewind { to_height }) => {
+                return application::rewind(
+                    to_height,
+                    application_config.ledger_state_retention,
+                    storage,
+                )
+                .await;
+            }
+
+            None => {}
+        }
+
         let publisher = pub_sub::nats::publisher::NatsPublisher::new(pub_sub_config)
             .await
             .context("create NatsPublisher")?;
 
-        application::run(application_config, node, storage, publisher, sigterm).await
+        if let Some(file) = replay {
+            let node = FileNode::new(&file).context("create FileNode")?;
+            return application::run(application_config, node, storage, publisher, sigterm).await;
+        }
+
+        let node = SubxtNode::new(node_config)
+            .await
+            .context("create SubxtNode")?;
+
+        match record {
+            Some(file) => {
+                let node = RecordingNode::new(node, &file).context("create RecordingNode")?;
+                application::run(application_config, node, storage, publisher, sigterm).await
+            }
+
+            None => application::run(application_config, node, storage, publisher, sigterm).await,
+        }
     });
 
     // The implicit runtime drop hangs indefinitely when spawned tasks are inside
@@ -28,7 +28,7 @@ use chain_indexer::{
 };
 use fs_extra::dir::{CopyOptions, copy};
 use futures::TryStreamExt;
-use std::{fs, path::Path, pin::pin, time::Duration};
+use std::{fs, num::NonZeroUsize, path::Path, pin::pin, time::Duration};
 use testcontainers::{
     GenericImage, ImageExt,
     core::{Mount, WaitFor},
@@ -86,9 +86,14 @@ async fn test_finalized_blocks_node_1_0() -> anyhow::Result<()> {
 
     let config = Config {
         url: format!("ws://localhost:{node_port}"),
+        fallback_urls: vec![],
         reconnect_max_delay: Duration::from_secs(1),
         reconnect_max_attempts: 1,
         subscription_recovery_timeout: Duration::from_secs(30),
+        prefetch_window: NonZeroUsize::new(16).unwrap(),
+        health_check_interval: Duration::from_secs(10),
+        max_lag: 10,
+        quorum: false,
     };
     let mut node = SubxtNode::new(config).await.context("create SubxtNode")?;
 
@@ -127,9 +132,14 @@ async fn test_mainnet_runtime_upgrade_boundary() -> anyhow::Result<()> {
 
     let config = Config {
         url: "wss://rpc.mainnet.midnight.network".to_string(),
+        fallback_urls: vec![],
         reconnect_max_delay: Duration::from_secs(1),
         reconnect_max_attempts: 3,
         subscription_recovery_timeout: Duration::from_secs(30),
+        prefetch_window: NonZeroUsize::new(16).unwrap(),
+        health_check_interval: Duration::from_secs(10),
+        max_lag: 10,
+        quorum: false,
     };
     let mut node = SubxtNode::new(config).await.context("create SubxtNode")?;
 
@@ -11,9 +11,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::domain::Api;
+pub mod webhooks;
+
+use crate::domain::{Api, WebhookSender, storage::Storage};
 use anyhow::Context as AnyhowContext;
-use futures::{TryStreamExt, future::ok};
+use futures::{
+    TryStreamExt,
+    future::{self, ok},
+};
 use indexer_common::domain::{BlockIndexed, NetworkId, Subscriber};
 use log::{debug, error, info};
 use serde::Deserialize;
@@ -26,10 +31,23 @@ use tokio::{select, task};
 #[derive(Debug, Clone, Deserialize)]
 pub struct Config {
     pub network_id: NetworkId,
+
+    /// Deliver indexed events to registered webhooks; disabled if omitted.
+    #[serde(default)]
+    pub webhooks: Option<webhooks::Config>,
 }
 
-pub async fn run(config: Config, api: impl Api, subscriber: impl Subscriber) -> anyhow::Result<()> {
-    let Config { network_id } = config;
+pub async fn run(
+    config: Config,
+    api: impl Api,
+    storage: impl Storage,
+    subscriber: impl Subscriber,
+    webhook_sender: impl WebhookSender,
+) -> anyhow::Result<()> {
+    let Config {
+        network_id,
+        webhooks,
+    } = config;
 
     let caught_up = Arc::new(AtomicBool::new(false));
 
@@ -55,6 +73,14 @@ pub async fn run(config: Config, api: impl Api, subscriber: impl Subscriber) ->
         }
     });
 
+    // Spawn task to deliver indexed events to webhooks, if enabled.
+    let mut webhooks_task = task::spawn(async move {
+        match webhooks {
+            Some(config) => webhooks::run(config, storage, subscriber, webhook_sender).await,
+            None => future::pending().await,
+        }
+    });
+
     let mut serve_api_task = {
         task::spawn(async move {
             api.serve(network_id, caught_up)
@@ -72,6 +98,16 @@ pub async fn run(config: Config, api: impl Api, subscriber: impl Subscriber) ->
             let result = result
                 .context("block_indexed_task panicked")
                 .and_then(|r| r.context("block_indexed_task failed"));
+            webhooks_task.abort();
+            serve_api_task.abort();
+            result
+        },
+
+        result = &mut webhooks_task => {
+            let result = result
+                .context("webhooks_task panicked")
+                .and_then(|r| r.context("webhooks_task failed"));
+            block_indexed_task.abort();
             serve_api_task.abort();
             result
         },
@@ -81,6 +117,7 @@ pub async fn run(config: Config, api: impl Api, subscriber: impl Subscriber) ->
                 .context("serve_api_task panicked")
                 .and_then(|r| r.context("serve_api_task failed"));
             block_indexed_task.abort();
+            webhooks_task.abort();
             result
         },
     }
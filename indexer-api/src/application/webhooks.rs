@@ -0,0 +1,556 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::domain::{
+    Block, NewWebhookDelivery, Webhook, WebhookDelivery, WebhookRequest, WebhookSender,
+    storage::{Storage, contract_event::ContractEventFilter},
+};
+use anyhow::Context;
+use futures::{StreamExt, TryStreamExt, stream};
+use indexer_common::{
+    domain::{
+        BlockIndexed, BridgeEventIndexed, ContractAttributes, Subscriber, UnshieldedAddress,
+        UnshieldedUtxoIndexed,
+    },
+    error::StdErrorExt,
+};
+use log::{debug, warn};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::{
+    collections::HashMap,
+    num::NonZeroU32,
+    pin::pin,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::{
+    select,
+    time::{MissedTickBehavior, interval},
+};
+
+const EVENT_HEADER: &str = "X-Midnight-Indexer-Event";
+const DELIVERY_HEADER: &str = "X-Midnight-Indexer-Delivery";
+const TIMESTAMP_HEADER: &str = "X-Midnight-Indexer-Timestamp";
+const SIGNATURE_HEADER: &str = "X-Midnight-Indexer-Signature";
+
+/// Contract events are loaded in pages of this size.
+const CONTRACT_EVENTS_LIMIT: u32 = 100;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Config {
+    /// Interval for polling due deliveries.
+    #[serde(with = "humantime_serde")]
+    pub poll_interval: Duration,
+
+    /// Maximum number of deliveries sent concurrently per poll; also the batch size for loading
+    /// the transactions of an unshielded address.
+    pub batch_size: NonZeroU32,
+
+    /// Attempts after which a delivery is given up and added to the dead letters.
+    pub max_attempts: NonZeroU32,
+
+    /// Delay before retrying after the first failed attempt, doubled after each further one.
+    #[serde(with = "humantime_serde")]
+    pub initial_backoff: Duration,
+
+    /// Upper bound for the delay before retrying.
+    #[serde(with = "humantime_serde")]
+    pub max_backoff: Duration,
+
+    /// Timeout for a single delivery request.
+    #[serde(with = "humantime_serde")]
+    pub request_timeout: Duration,
+}
+
+/// Enqueue deliveries for the indexed events matching the filters of the registered webhooks and
+/// send the due ones. Running this on several replicas is safe: deliveries are deduplicated by
+/// their event key and claimed by a single replica at a time.
+pub async fn run(
+    config: Config,
+    storage: impl Storage,
+    subscriber: impl Subscriber,
+    sender: impl WebhookSender,
+) -> anyhow::Result<()> {
+    select! {
+        result = enqueue_deliveries(config.batch_size, storage.clone(), subscriber) => {
+            result.context("enqueue webhook deliveries")
+        }
+
+        result = send_deliveries(config, storage, sender) => {
+            result.context("send webhook deliveries")
+        }
+    }
+}
+
+#[derive(Debug)]
+enum IndexedEvent {
+    Block(BlockIndexed),
+    UnshieldedUtxo(UnshieldedUtxoIndexed),
+    BridgeEvent(BridgeEventIndexed),
+}
+
+async fn enqueue_deliveries(
+    batch_size: NonZeroU32,
+    storage: impl Storage,
+    subscriber: impl Subscriber,
+) -> anyhow::Result<()> {
+    let block_indexed_events = subscriber
+        .subscribe::<BlockIndexed>()
+        .map_ok(IndexedEvent::Block);
+    let unshielded_utxo_indexed_events = subscriber
+        .subscribe::<UnshieldedUtxoIndexed>()
+        .map_ok(IndexedEvent::UnshieldedUtxo);
+    let bridge_event_indexed_events = subscriber
+        .subscribe::<BridgeEventIndexed>()
+        .map_ok(IndexedEvent::BridgeEvent);
+    let mut events = pin!(stream::select(
+        stream::select(block_indexed_events, unshielded_utxo_indexed_events),
+        bridge_event_indexed_events,
+    ));
+
+    // Webhooks are reloaded for each block, which is published before the other events of that
+    // block, hence webhooks registered meanwhile are considered from the next block on.
+    let mut webhooks = storage.get_webhooks().await.context("get webhooks")?;
+
+    while let Some(event) = events.try_next().await.context("get next indexed event")? {
+        let (deliveries, transaction_ids) = match event {
+            IndexedEvent::Block(BlockIndexed { height, .. }) => {
+                webhooks = storage.get_webhooks().await.context("get webhooks")?;
+                let deliveries = make_block_deliveries(height, &webhooks, &storage).await?;
+                (deliveries, vec![])
+            }
+
+            IndexedEvent::UnshieldedUtxo(UnshieldedUtxoIndexed { address }) => {
+                make_unshielded_deliveries(address, &webhooks, batch_size, &storage).await?
+            }
+
+            IndexedEvent::BridgeEvent(BridgeEventIndexed {
+                block_height,
+                event,
+            }) => {
+                let deliveries = webhooks
+                    .iter()
+                    .filter(|webhook| {
+                        webhook.filter.bridge_recipient.is_some()
+                            && webhook.filter.bridge_recipient.as_ref() == event.recipient()
+                    })
+                    .map(|webhook| {
+                        make_delivery(
+                            webhook,
+                            format!(
+                                "bridge-event:{}:{:?}",
+                                const_hex::encode(event.midnight_tx_hash()),
+                                event.variant()
+                            ),
+                            "BridgeEvent",
+                            json!({ "blockHeight": block_height, "event": event }),
+                        )
+                    })
+                    .collect();
+                (deliveries, vec![])
+            }
+        };
+
+        if !deliveries.is_empty() {
+            debug!(count = deliveries.len(); "enqueueing webhook deliveries");
+
+            storage
+                .enqueue_webhook_deliveries(
+                    &deliveries,
+                    &transaction_ids,
+                    now()?.as_millis() as u64,
+                )
+                .await
+                .context("enqueue webhook deliveries")?;
+
+            // The transaction cursors are only advanced once the deliveries have been enqueued.
+            for (id, transaction_id) in transaction_ids {
+                if let Some(webhook) = webhooks.iter_mut().find(|webhook| webhook.id == id) {
+                    webhook.transaction_id = transaction_id;
+                }
+            }
+        }
+    }
+
+    warn!("indexed event streams completed unexpectedly");
+
+    Ok(())
+}
+
+/// Make the deliveries for the block at the given height: the block itself for webhooks without
+/// filter, the contract actions and contract events for the ones filtering by contract address.
+async fn make_block_deliveries(
+    height: u64,
+    webhooks: &[Webhook],
+    storage: &impl Storage,
+) -> anyhow::Result<Vec<NewWebhookDelivery>> {
+    let relevant = webhooks
+        .iter()
+        .any(|webhook| webhook.filter.is_empty() || webhook.filter.contract_address.is_some());
+    if !relevant {
+        return Ok(vec![]);
+    }
+
+    let Some(block) = storage
+        .get_block_by_height(height as u32)
+        .await
+        .context("get block by height")?
+    else {
+        return Ok(vec![]);
+    };
+
+    let mut deliveries = webhooks
+        .iter()
+        .filter(|webhook| webhook.filter.is_empty())
+        .map(|webhook| {
+            make_delivery(
+                webhook,
+                format!("block:{}", const_hex::encode(block.hash)),
+                "Block",
+                json!({
+                    "hash": const_hex::encode(block.hash),
+                    "height": block.height,
+                    "timestamp": block.timestamp,
+                    "finalized": block.finalized,
+                }),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    if webhooks.iter().any(|webhook| {
+        webhook.filter.contract_address.is_some() && webhook.filter.contract_event_type.is_none()
+    }) {
+        deliveries.extend(make_contract_action_deliveries(&block, webhooks, storage).await?);
+    }
+
+    for webhook in webhooks {
+        if let Some(contract_address) = &webhook.filter.contract_address
+            && let Some(contract_event_type) = webhook.filter.contract_event_type
+        {
+            let filter = ContractEventFilter {
+                contract_address: contract_address.clone(),
+                variants: vec![contract_event_type],
+                field_prefixes: vec![],
+                from_block: Some(block.height),
+                to_block: Some(block.height),
+                transaction_hash: None,
+            };
+
+            let mut offset = 0;
+            loop {
+                let contract_events = storage
+                    .get_contract_events(&filter, CONTRACT_EVENTS_LIMIT, offset)
+                    .await
+                    .context("get contract events")?;
+                let count = contract_events.len() as u32;
+
+                deliveries.extend(contract_events.into_iter().map(|contract_event| {
+                    make_delivery(
+                        webhook,
+                        format!("contract-event:{}", contract_event.id),
+                        "ContractEvent",
+                        json!({
+                            "id": contract_event.id,
+                            "contractAddress": const_hex::encode(&contract_event.contract_address),
+                            "eventType": contract_event_type,
+                            "transactionId": contract_event.transaction_id,
+                            "blockHeight": block.height,
+                            "blockHash": const_hex::encode(block.hash),
+                            "raw": const_hex::encode(&contract_event.raw),
+                        }),
+                    )
+                }));
+
+                if count < CONTRACT_EVENTS_LIMIT {
+                    break;
+                }
+                offset += count;
+            }
+        }
+    }
+
+    Ok(deliveries)
+}
+
+async fn make_contract_action_deliveries(
+    block: &Block,
+    webhooks: &[Webhook],
+    storage: &impl Storage,
+) -> anyhow::Result<Vec<NewWebhookDelivery>> {
+    let transaction_hashes = storage
+        .get_transactions_by_block_ids(&[block.id])
+        .await
+        .context("get transactions by block ID")?
+        .into_iter()
+        .map(|(_, transaction)| (transaction.id(), transaction.hash()))
+        .collect::<HashMap<_, _>>();
+    let transaction_ids = transaction_hashes.keys().copied().collect::<Vec<_>>();
+
+    let contract_actions = storage
+        .get_contract_actions_by_transaction_ids(&transaction_ids)
+        .await
+        .context("get contract actions by transaction IDs")?;
+
+    let deliveries = webhooks
+        .iter()
+        .filter(|webhook| webhook.filter.contract_event_type.is_none())
+        .filter_map(|webhook| {
+            webhook
+                .filter
+                .contract_address
+                .as_ref()
+                .map(|address| (webhook, address))
+        })
+        .flat_map(|(webhook, address)| {
+            contract_actions
+                .iter()
+                .filter(move |contract_action| &contract_action.address == address)
+                .map(move |contract_action| {
+                    let (kind, entry_point) = match &contract_action.attributes {
+                        ContractAttributes::Deploy => ("Deploy", None),
+                        ContractAttributes::Call { entry_point } => ("Call", Some(entry_point)),
+                        ContractAttributes::Update => ("Update", None),
+                    };
+                    let transaction_hash = transaction_hashes
+                        .get(&contract_action.transaction_id)
+                        .map(const_hex::encode);
+
+                    make_delivery(
+                        webhook,
+                        format!("contract-action:{}", contract_action.id),
+                        "ContractAction",
+                        json!({
+                            "address": const_hex::encode(&contract_action.address),
+                            "kind": kind,
+                            "entryPoint": entry_point,
+                            "transactionHash": transaction_hash,
+                            "blockHeight": block.height,
+                            "blockHash": const_hex::encode(block.hash),
+                        }),
+                    )
+                })
+        })
+        .collect();
+
+    Ok(deliveries)
+}
+
+/// Make the deliveries for the not yet delivered transactions of the given unshielded address,
+/// together with the IDs of the respective webhooks and their advanced transaction cursors.
+async fn make_unshielded_deliveries(
+    address: UnshieldedAddress,
+    webhooks: &[Webhook],
+    batch_size: NonZeroU32,
+    storage: &impl Storage,
+) -> anyhow::Result<(Vec<NewWebhookDelivery>, Vec<(u64, u64)>)> {
+    let mut deliveries = vec![];
+    let mut transaction_ids = vec![];
+
+    for webhook in webhooks
+        .iter()
+        .filter(|webhook| webhook.filter.unshielded_address == Some(address))
+    {
+        let transactions = storage
+            .get_transactions_by_unshielded_address(address, webhook.transaction_id, batch_size)
+            .try_collect::<Vec<_>>()
+            .await
+            .context("get transactions by unshielded address")?;
+
+        let Some(max_transaction_id) = transactions.iter().map(|t| t.id()).max() else {
+            continue;
+        };
+
+        deliveries.extend(transactions.into_iter().map(|transaction| {
+            make_delivery(
+                webhook,
+                format!("unshielded-transaction:{}", transaction.id()),
+                "UnshieldedTransaction",
+                json!({
+                    "address": const_hex::encode(address),
+                    "transactionId": transaction.id(),
+                    "transactionHash": const_hex::encode(transaction.hash()),
+                    "blockHash": const_hex::encode(transaction.block_hash()),
+                }),
+            )
+        }));
+
+        transaction_ids.push((webhook.id, max_transaction_id + 1));
+    }
+
+    Ok((deliveries, transaction_ids))
+}
+
+fn make_delivery(
+    webhook: &Webhook,
+    event_key: String,
+    event_type: &'static str,
+    data: Value,
+) -> NewWebhookDelivery {
+    let payload = json!({ "type": event_type, "data": data }).to_string();
+
+    NewWebhookDelivery {
+        webhook_id: webhook.id,
+        event_key,
+        event_type,
+        payload,
+    }
+}
+
+async fn send_deliveries(
+    config: Config,
+    storage: impl Storage,
+    sender: impl WebhookSender,
+) -> anyhow::Result<()> {
+    let Config {
+        poll_interval,
+        batch_size,
+        request_timeout,
+        ..
+    } = config;
+
+    let mut interval = interval(poll_interval);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        interval.tick().await;
+
+        // Claimed deliveries are leased long enough to be sent before being claimable again.
+        let now = now()?.as_millis() as u64;
+        let lease_until = now + (request_timeout + poll_interval).as_millis() as u64;
+        let deliveries = storage
+            .claim_webhook_deliveries(now, lease_until, batch_size.get())
+            .await
+            .context("claim webhook deliveries")?;
+        if deliveries.is_empty() {
+            continue;
+        }
+
+        let webhooks = storage
+            .get_webhooks()
+            .await
+            .context("get webhooks")?
+            .into_iter()
+            .map(|webhook| (webhook.id, webhook))
+            .collect::<HashMap<_, _>>();
+
+        stream::iter(deliveries)
+            .filter_map(|delivery| {
+                let webhook = webhooks.get(&delivery.webhook_id);
+                async move { webhook.map(|webhook| (delivery, webhook)) }
+            })
+            .map(|(delivery, webhook)| send_delivery(delivery, webhook, &config, &storage, &sender))
+            .buffer_unordered(batch_size.get() as usize)
+            .try_collect::<()>()
+            .await?;
+    }
+}
+
+async fn send_delivery(
+    delivery: WebhookDelivery,
+    webhook: &Webhook,
+    config: &Config,
+    storage: &impl Storage,
+    sender: &impl WebhookSender,
+) -> anyhow::Result<()> {
+    let timestamp = now()?.as_secs();
+    let signature = webhook.secret.sign(timestamp, &delivery.payload);
+    let request = WebhookRequest {
+        url: &webhook.url,
+        headers: vec![
+            (EVENT_HEADER, delivery.event_type.clone()),
+            (DELIVERY_HEADER, delivery.id.to_string()),
+            (TIMESTAMP_HEADER, timestamp.to_string()),
+            (
+                SIGNATURE_HEADER,
+                format!("sha256={}", const_hex::encode(signature)),
+            ),
+        ],
+        body: &delivery.payload,
+    };
+
+    let (response_status, error) = match sender.send(request, config.request_timeout).await {
+        Ok(status) if (200..300).contains(&status) => {
+            debug!(id = delivery.id, status; "webhook delivery succeeded");
+
+            storage
+                .complete_webhook_delivery(delivery.id, status, now()?.as_millis() as u64)
+                .await
+                .context("complete webhook delivery")?;
+
+            return Ok(());
+        }
+
+        Ok(status) => (Some(status), format!("unexpected HTTP status {status}")),
+
+        Err(error) => (None, error.as_chain()),
+    };
+
+    let now = now()?.as_millis() as u64;
+    let next_attempt_at = (delivery.attempts < config.max_attempts.get()).then(|| {
+        now + backoff(
+            delivery.attempts,
+            config.initial_backoff,
+            config.max_backoff,
+        )
+        .as_millis() as u64
+    });
+    if next_attempt_at.is_some() {
+        debug!(id = delivery.id, attempts = delivery.attempts, error:%; "webhook delivery failed");
+    } else {
+        warn!(id = delivery.id, attempts = delivery.attempts, error:%; "webhook delivery dead");
+    }
+
+    storage
+        .fail_webhook_delivery(delivery.id, response_status, &error, next_attempt_at, now)
+        .await
+        .context("fail webhook delivery")
+}
+
+/// Exponential backoff after the given number of failed attempts, at least one.
+fn backoff(attempts: u32, initial_backoff: Duration, max_backoff: Duration) -> Duration {
+    let factor = 2_u32.saturating_pow(attempts.saturating_sub(1));
+    initial_backoff.saturating_mul(factor).min(max_backoff)
+}
+
+fn now() -> anyhow::Result<Duration> {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .context("get current time")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::application::webhooks::backoff;
+    use std::time::Duration;
+
+    #[test]
+    fn test_backoff() {
+        let initial_backoff = Duration::from_secs(1);
+        let max_backoff = Duration::from_secs(60);
+
+        assert_eq!(
+            backoff(1, initial_backoff, max_backoff),
+            Duration::from_secs(1)
+        );
+        assert_eq!(
+            backoff(2, initial_backoff, max_backoff),
+            Duration::from_secs(2)
+        );
+        assert_eq!(
+            backoff(4, initial_backoff, max_backoff),
+            Duration::from_secs(8)
+        );
+        assert_eq!(backoff(7, initial_backoff, max_backoff), max_backoff);
+        assert_eq!(backoff(100, initial_backoff, max_backoff), max_backoff);
+    }
+}
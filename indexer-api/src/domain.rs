@@ -16,26 +16,44 @@ pub mod storage;
 mod api;
 mod block;
 pub mod bridge;
+mod chain_stats;
+mod contract_abi;
 mod contract_action;
 mod contract_event;
+mod contract_state_diff;
 pub mod dust;
 mod ledger_event;
 mod ledger_state;
+mod node;
+mod pending_transaction;
 pub mod shielded_nullifier;
 pub mod spo;
 pub mod system_parameters;
+mod token;
 mod transaction;
 mod unshielded;
+mod wallet;
+mod watch_list;
+mod webhook;
 
 pub use api::*;
 pub use block::*;
 pub use bridge::*;
+pub use chain_stats::*;
+pub use contract_abi::*;
 pub use contract_action::*;
 pub use contract_event::*;
+pub use contract_state_diff::*;
 pub use dust::*;
 pub use ledger_event::*;
 pub use ledger_state::*;
+pub use node::*;
+pub use pending_transaction::*;
 pub use shielded_nullifier::*;
 pub use system_parameters::*;
+pub use token::*;
 pub use transaction::*;
 pub use unshielded::*;
+pub use wallet::*;
+pub use watch_list::*;
+pub use webhook::*;
@@ -16,6 +16,7 @@ use indexer_common::domain::{
     SerializedDustGenerationMerkleTreeRoot, SerializedLedgerParameters,
     SerializedZswapMerkleTreeRoot,
 };
+use indexer_common::infra::sqlx::{SqlxOption, U128BeBytes};
 use sqlx::prelude::FromRow;
 
 /// Relevant block data from the perspective of the Indexer API.
@@ -59,4 +60,39 @@ pub struct Block {
     pub dust_commitment_merkle_tree_root: Option<SerializedDustCommitmentMerkleTreeRoot>,
 
     pub dust_generation_merkle_tree_root: Option<SerializedDustGenerationMerkleTreeRoot>,
+
+    pub finalized: bool,
+}
+
+/// Fee statistics of a block, computed by the chain-indexer when saving the block.
+#[derive(Debug, Clone, PartialEq, Eq, FromRow)]
+pub struct BlockStats {
+    #[sqlx(try_from = "i64")]
+    pub block_height: u32,
+
+    #[sqlx(try_from = "i64")]
+    pub transaction_count: u64,
+
+    #[sqlx(try_from = "i64")]
+    pub transaction_bytes: u64,
+
+    #[sqlx(try_from = "U128BeBytes")]
+    pub fee_sum: u128,
+
+    #[sqlx(try_from = "SqlxOption<U128BeBytes>")]
+    pub fee_min: Option<u128>,
+
+    #[sqlx(try_from = "SqlxOption<U128BeBytes>")]
+    pub fee_median: Option<u128>,
+
+    #[sqlx(try_from = "SqlxOption<U128BeBytes>")]
+    pub fee_p90: Option<u128>,
+}
+
+/// The paid fees of the regular transactions in a window of recent blocks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecentFees {
+    pub from_height: u32,
+    pub to_height: u32,
+    pub fees: Vec<u128>,
 }
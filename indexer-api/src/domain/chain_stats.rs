@@ -0,0 +1,71 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use indexer_common::infra::sqlx::SqlxOption;
+use sqlx::prelude::FromRow;
+
+/// The granularity of chain statistics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainStatsGranularity {
+    /// Per block.
+    Block,
+
+    /// Per UTC day.
+    Day,
+}
+
+/// Chain statistics of a block or a UTC day, rolled up by the chain-indexer.
+#[derive(Debug, Clone, PartialEq, Eq, FromRow)]
+pub struct ChainStats {
+    /// The block timestamp or the start of the day, UNIX timestamp in milliseconds.
+    #[sqlx(try_from = "i64")]
+    pub timestamp: u64,
+
+    /// The block height; `None` for days.
+    #[sqlx(try_from = "SqlxOption<i64>")]
+    pub block_height: Option<u32>,
+
+    #[sqlx(try_from = "i64")]
+    pub block_count: u64,
+
+    #[sqlx(try_from = "i64")]
+    pub regular_transaction_count: u64,
+
+    #[sqlx(try_from = "i64")]
+    pub system_transaction_count: u64,
+
+    #[sqlx(try_from = "i64")]
+    pub contract_deploy_count: u64,
+
+    #[sqlx(try_from = "i64")]
+    pub contract_call_count: u64,
+
+    #[sqlx(try_from = "i64")]
+    pub contract_update_count: u64,
+
+    #[sqlx(try_from = "i64")]
+    pub unshielded_utxos_created: u64,
+
+    #[sqlx(try_from = "i64")]
+    pub unshielded_utxos_spent: u64,
+
+    #[sqlx(try_from = "i64")]
+    pub zswap_ledger_event_count: u64,
+
+    #[sqlx(try_from = "i64")]
+    pub dust_ledger_event_count: u64,
+
+    /// The number of distinct owners of unshielded UTXOs created or spent; `None` for blocks.
+    #[sqlx(try_from = "SqlxOption<i64>")]
+    pub active_address_count: Option<u64>,
+}
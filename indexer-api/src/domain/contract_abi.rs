@@ -0,0 +1,468 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use indexer_common::domain::{ByteVec, ledger::ContractStateValue};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+/// The ABI of a contract as far as needed to decode its state, i.e. the layout of its ledger as
+/// compiled by Compact.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContractAbi {
+    pub ledger: Vec<LedgerField>,
+}
+
+impl ContractAbi {
+    /// Decode the given contract state data into a JSON object with one entry per ledger field.
+    pub fn decode(&self, data: &ContractStateValue) -> Result<Value, DecodeContractStateError> {
+        let mut fields = Map::with_capacity(self.ledger.len());
+
+        for field in &self.ledger {
+            let state_value = field
+                .path
+                .iter()
+                .try_fold(data, |state_value, &index| match state_value {
+                    ContractStateValue::Array(state_values) => state_values.get(index),
+                    _ => None,
+                })
+                .ok_or_else(|| DecodeContractStateError::Path(field.name.clone()))?;
+
+            let value = field
+                .ty
+                .decode(state_value)
+                .map_err(|error| DecodeContractStateError::Field(field.name.clone(), error))?;
+            fields.insert(field.name.clone(), value);
+        }
+
+        Ok(Value::Object(fields))
+    }
+}
+
+/// A ledger field of a contract.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LedgerField {
+    pub name: String,
+
+    /// The indices into the nested state arrays at which the state value of this field is stored.
+    pub path: Vec<usize>,
+
+    #[serde(rename = "type")]
+    pub ty: StateType,
+}
+
+/// The type of a ledger state value.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum StateType {
+    /// A cell holding a single value, e.g. a plain ledger field or a `Counter`.
+    Cell { value: ValueType },
+
+    /// A `Set`, decoded into an array of its elements.
+    Set { element: ValueType },
+
+    /// A `Map`, decoded into an object keyed by its rendered keys.
+    Map {
+        key: ValueType,
+        value: Box<StateType>,
+    },
+
+    /// A `MerkleTree` or `HistoricMerkleTree`, decoded into its hex-encoded serialized root.
+    MerkleTree,
+
+    /// Any state value, decoded structurally with hex-encoded atoms.
+    Raw,
+}
+
+impl StateType {
+    fn decode(&self, state_value: &ContractStateValue) -> Result<Value, DecodeValueError> {
+        match (self, state_value) {
+            (Self::Cell { value }, ContractStateValue::Cell(atoms)) => value.decode_all(atoms),
+
+            (Self::Set { element }, ContractStateValue::Map(entries)) => entries
+                .iter()
+                .map(|(key, _)| element.decode_all(key))
+                .collect::<Result<_, _>>()
+                .map(Value::Array),
+
+            (Self::Map { key, value }, ContractStateValue::Map(entries)) => entries
+                .iter()
+                .map(|(k, v)| {
+                    let k = match key.decode_all(k)? {
+                        Value::String(k) => k,
+                        k => k.to_string(),
+                    };
+                    value.decode(v).map(|v| (k, v))
+                })
+                .collect::<Result<_, _>>()
+                .map(Value::Object),
+
+            (Self::MerkleTree, ContractStateValue::MerkleTree { root }) => Ok(raw_root(root)),
+
+            (Self::Raw, state_value) => Ok(decode_raw(state_value)),
+
+            (_, state_value) => Err(DecodeValueError::StateValue(kind(state_value))),
+        }
+    }
+}
+
+/// The type of a value, i.e. of the contents of a cell or of a map key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ValueType {
+    Boolean,
+
+    /// An unsigned integer of at most 128 bits, decoded into a decimal string.
+    Uint,
+
+    /// A field element, decoded into its hex-encoded 32 byte big-endian representation.
+    Field,
+
+    /// `Bytes<length>`, decoded hex-encoded.
+    Bytes {
+        length: usize,
+    },
+
+    /// `Opaque<"string">`.
+    String,
+
+    /// Any other `Opaque` value, decoded hex-encoded.
+    Opaque,
+
+    /// An enum, decoded into the name of its variant.
+    Enum {
+        variants: Vec<String>,
+    },
+
+    /// `Maybe<value>`, decoded into null or the value.
+    Maybe {
+        value: Box<ValueType>,
+    },
+
+    /// `Vector<length, element>`.
+    Vector {
+        length: usize,
+        element: Box<ValueType>,
+    },
+
+    /// A struct, decoded into an object.
+    Struct {
+        fields: Vec<StructField>,
+    },
+}
+
+impl ValueType {
+    fn decode_all(&self, atoms: &[Vec<u8>]) -> Result<Value, DecodeValueError> {
+        let mut atoms = atoms.iter();
+        let value = self.decode(&mut atoms)?;
+
+        if atoms.next().is_some() {
+            return Err(DecodeValueError::TrailingAtoms);
+        }
+
+        Ok(value)
+    }
+
+    fn decode<'a>(
+        &self,
+        atoms: &mut impl Iterator<Item = &'a Vec<u8>>,
+    ) -> Result<Value, DecodeValueError> {
+        match self {
+            Self::Boolean => match next_atom(atoms)?.as_slice() {
+                [] => Ok(Value::Bool(false)),
+                [1] => Ok(Value::Bool(true)),
+                _ => Err(DecodeValueError::InvalidAtom("Boolean")),
+            },
+
+            Self::Uint => {
+                let n =
+                    decode_uint(next_atom(atoms)?).ok_or(DecodeValueError::InvalidAtom("Uint"))?;
+                Ok(Value::String(n.to_string()))
+            }
+
+            Self::Field => {
+                let atom = next_atom(atoms)?;
+                if atom.len() > 32 {
+                    return Err(DecodeValueError::InvalidAtom("Field"));
+                }
+                let mut bytes = [0; 32];
+                bytes[..atom.len()].copy_from_slice(atom);
+                bytes.reverse();
+                Ok(Value::String(const_hex::encode(bytes)))
+            }
+
+            Self::Bytes { length } => {
+                let atom = next_atom(atoms)?;
+                if atom.len() > *length {
+                    return Err(DecodeValueError::InvalidAtom("Bytes"));
+                }
+                // Trailing zero bytes are stripped from atoms.
+                let mut bytes = atom.clone();
+                bytes.resize(*length, 0);
+                Ok(Value::String(const_hex::encode(bytes)))
+            }
+
+            Self::String => {
+                let string = String::from_utf8(next_atom(atoms)?.clone())
+                    .map_err(|_| DecodeValueError::InvalidAtom("String"))?;
+                Ok(Value::String(string))
+            }
+
+            Self::Opaque => Ok(Value::String(const_hex::encode(next_atom(atoms)?))),
+
+            Self::Enum { variants } => decode_uint(next_atom(atoms)?)
+                .and_then(|index| usize::try_from(index).ok())
+                .and_then(|index| variants.get(index))
+                .map(|variant| Value::String(variant.clone()))
+                .ok_or(DecodeValueError::InvalidAtom("Enum")),
+
+            Self::Maybe { value } => {
+                let is_some = Self::Boolean.decode(atoms)?;
+                // The value is always present, default initialized if none.
+                let value = value.decode(atoms)?;
+                Ok(if is_some == Value::Bool(true) {
+                    value
+                } else {
+                    Value::Null
+                })
+            }
+
+            Self::Vector { length, element } => (0..*length)
+                .map(|_| element.decode(atoms))
+                .collect::<Result<_, _>>()
+                .map(Value::Array),
+
+            Self::Struct { fields } => fields
+                .iter()
+                .map(|field| {
+                    field
+                        .ty
+                        .decode(atoms)
+                        .map(|value| (field.name.clone(), value))
+                })
+                .collect::<Result<_, _>>()
+                .map(Value::Object),
+        }
+    }
+}
+
+/// A field of a struct value.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StructField {
+    pub name: String,
+
+    #[serde(rename = "type")]
+    pub ty: ValueType,
+}
+
+#[derive(Debug, Error)]
+pub enum DecodeContractStateError {
+    #[error("no state value at the path of ledger field {0}")]
+    Path(String),
+
+    #[error("cannot decode ledger field {0}")]
+    Field(String, #[source] DecodeValueError),
+}
+
+#[derive(Debug, Error)]
+pub enum DecodeValueError {
+    #[error("unexpected {0} state value")]
+    StateValue(&'static str),
+
+    #[error("missing value atom")]
+    MissingAtom,
+
+    #[error("unexpected trailing value atoms")]
+    TrailingAtoms,
+
+    #[error("invalid {0} value atom")]
+    InvalidAtom(&'static str),
+}
+
+/// Look up the value at the given dot-separated path, e.g. `balances.alice` or `owners.0`, in the
+/// given decoded contract state.
+pub fn state_field<'a>(state: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.')
+        .try_fold(state, |value, segment| match value {
+            Value::Object(fields) => fields.get(segment),
+            Value::Array(values) => segment
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| values.get(index)),
+            _ => None,
+        })
+}
+
+fn next_atom<'a>(
+    atoms: &mut impl Iterator<Item = &'a Vec<u8>>,
+) -> Result<&'a Vec<u8>, DecodeValueError> {
+    atoms.next().ok_or(DecodeValueError::MissingAtom)
+}
+
+/// Decode the given little-endian atom with trailing zero bytes stripped.
+fn decode_uint(atom: &[u8]) -> Option<u128> {
+    (atom.len() <= 16).then(|| {
+        let mut bytes = [0; 16];
+        bytes[..atom.len()].copy_from_slice(atom);
+        u128::from_le_bytes(bytes)
+    })
+}
+
+fn decode_raw(state_value: &ContractStateValue) -> Value {
+    match state_value {
+        ContractStateValue::Null => Value::Null,
+
+        ContractStateValue::MerkleTree { root } => raw_root(root),
+
+        ContractStateValue::Cell(atoms) => raw_atoms(atoms),
+
+        ContractStateValue::Map(entries) => entries
+            .iter()
+            .map(|(key, value)| {
+                let mut entry = Map::with_capacity(2);
+                entry.insert("key".to_string(), raw_atoms(key));
+                entry.insert("value".to_string(), decode_raw(value));
+                Value::Object(entry)
+            })
+            .collect(),
+
+        ContractStateValue::Array(values) => values.iter().map(decode_raw).collect(),
+    }
+}
+
+fn raw_atoms(atoms: &[Vec<u8>]) -> Value {
+    atoms
+        .iter()
+        .map(|atom| Value::String(const_hex::encode(atom)))
+        .collect()
+}
+
+fn raw_root(root: &Option<ByteVec>) -> Value {
+    root.as_ref()
+        .map(|root| Value::String(const_hex::encode(root)))
+        .unwrap_or_default()
+}
+
+fn kind(state_value: &ContractStateValue) -> &'static str {
+    match state_value {
+        ContractStateValue::Null => "Null",
+        ContractStateValue::Cell(_) => "Cell",
+        ContractStateValue::Map(_) => "Map",
+        ContractStateValue::Array(_) => "Array",
+        ContractStateValue::MerkleTree { .. } => "MerkleTree",
+    }
+}
+
+/// The message to be signed by the maintenance authority of the contract with the given address
+/// to register the given ABI, given as JSON text: a domain separator followed by the address, the
+/// little-endian counter of the maintenance authority, which invalidates signatures once the
+/// authority has been updated, and the SHA-256 hash of the ABI.
+pub fn contract_abi_registration_message(address: &[u8], counter: u32, abi: &str) -> Vec<u8> {
+    [
+        CONTRACT_ABI_REGISTRATION_DOMAIN_SEPARATOR,
+        address,
+        &counter.to_le_bytes(),
+        &Sha256::digest(abi.as_bytes()),
+    ]
+    .concat()
+}
+
+const CONTRACT_ABI_REGISTRATION_DOMAIN_SEPARATOR: &[u8] = b"midnight-indexer:contract-abi:";
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::{ContractAbi, contract_abi_registration_message, state_field};
+    use indexer_common::domain::ledger::ContractStateValue;
+    use serde_json::json;
+
+    #[test]
+    fn test_decode() {
+        let abi = serde_json::from_value::<ContractAbi>(json!({
+            "ledger": [
+                {
+                    "name": "round",
+                    "path": [0],
+                    "type": { "kind": "cell", "value": { "type": "uint" } }
+                },
+                {
+                    "name": "owner",
+                    "path": [1, 0],
+                    "type": {
+                        "kind": "cell",
+                        "value": { "type": "maybe", "value": { "type": "bytes", "length": 4 } }
+                    }
+                },
+                {
+                    "name": "balances",
+                    "path": [1, 1],
+                    "type": {
+                        "kind": "map",
+                        "key": { "type": "string" },
+                        "value": { "kind": "cell", "value": { "type": "uint" } }
+                    }
+                },
+                {
+                    "name": "state",
+                    "path": [2],
+                    "type": {
+                        "kind": "cell",
+                        "value": { "type": "enum", "variants": ["open", "closed"] }
+                    }
+                }
+            ]
+        }))
+        .expect("ABI can be deserialized");
+
+        let data = ContractStateValue::Array(vec![
+            ContractStateValue::Cell(vec![vec![42]]),
+            ContractStateValue::Array(vec![
+                ContractStateValue::Cell(vec![vec![1], vec![1, 2]]),
+                ContractStateValue::Map(vec![(
+                    vec![b"alice".to_vec()],
+                    ContractStateValue::Cell(vec![vec![0, 1]]),
+                )]),
+            ]),
+            ContractStateValue::Cell(vec![vec![1]]),
+        ]);
+
+        let state = abi.decode(&data).expect("state can be decoded");
+        assert_eq!(
+            state,
+            json!({
+                "round": "42",
+                "owner": "01020000",
+                "balances": { "alice": "256" },
+                "state": "closed"
+            })
+        );
+
+        assert_eq!(state_field(&state, "balances.alice"), Some(&json!("256")));
+        assert_eq!(state_field(&state, "balances.bob"), None);
+
+        let data = ContractStateValue::Array(vec![ContractStateValue::Cell(vec![vec![42]])]);
+        assert!(abi.decode(&data).is_err());
+    }
+
+    #[test]
+    fn test_contract_abi_registration_message() {
+        let message = contract_abi_registration_message(&[1, 2, 3], 1, r#"{"ledger":[]}"#);
+        assert!(message.starts_with(b"midnight-indexer:contract-abi:\x01\x02\x03\x01\0\0\0"));
+        assert_eq!(message.len(), 30 + 3 + 4 + 32);
+
+        let other_counter = contract_abi_registration_message(&[1, 2, 3], 2, r#"{"ledger":[]}"#);
+        assert_ne!(message, other_counter);
+        let other_abi = contract_abi_registration_message(&[1, 2, 3], 1, r#"{ "ledger": [] }"#);
+        assert_ne!(message, other_abi);
+    }
+}
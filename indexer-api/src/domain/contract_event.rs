@@ -21,6 +21,21 @@ use indexer_common::{
 };
 use sqlx::prelude::FromRow;
 
+/// The `LEDGER_EVENT_VARIANT` values of the events surfaced as contract events.
+pub const CONTRACT_EVENT_VARIANTS: [&str; 11] = [
+    "ShieldedSpend",
+    "ShieldedReceive",
+    "ShieldedMint",
+    "ShieldedBurn",
+    "UnshieldedSpend",
+    "UnshieldedReceive",
+    "UnshieldedMint",
+    "UnshieldedBurn",
+    "Paused",
+    "Unpaused",
+    "Misc",
+];
+
 #[derive(Debug, Clone, PartialEq, Eq, FromRow)]
 pub struct ContractEventRow {
     #[sqlx(try_from = "i64")]
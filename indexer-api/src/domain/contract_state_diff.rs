@@ -0,0 +1,294 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use indexer_common::domain::{
+    ByteVec, ContractBalance, ContractMaintenanceAuthority, TokenType,
+    ledger::{self, ContractState, ContractStateValue},
+};
+use std::collections::BTreeSet;
+
+/// The differences between two states of a contract.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractStateDiff {
+    /// The changed state values, in state order.
+    pub changes: Vec<ContractStateChange>,
+
+    pub balance_deltas: Vec<ContractBalanceDelta>,
+
+    /// The maintenance authorities before and after, if changed.
+    pub maintenance_authority_change:
+        Option<(ContractMaintenanceAuthority, ContractMaintenanceAuthority)>,
+}
+
+impl ContractStateDiff {
+    /// Compute the differences from the given `before` to the given `after` state.
+    pub fn new(before: &ContractState, after: &ContractState) -> Result<Self, ledger::Error> {
+        let mut changes = vec![];
+        diff_state_values(&before.data()?, &after.data()?, &mut vec![], &mut changes);
+
+        let balances_before = before.balances()?;
+        let balances_after = after.balances()?;
+        let mut token_types = Vec::<TokenType>::new();
+        for balance in balances_before.iter().chain(&balances_after) {
+            if !token_types.contains(&balance.token_type) {
+                token_types.push(balance.token_type);
+            }
+        }
+        let balance_deltas = token_types
+            .into_iter()
+            .filter_map(|token_type| {
+                let before = balance_amount(&balances_before, token_type);
+                let after = balance_amount(&balances_after, token_type);
+                (before != after).then_some(ContractBalanceDelta {
+                    token_type,
+                    before,
+                    after,
+                })
+            })
+            .collect();
+
+        let authority_before = before.maintenance_authority()?;
+        let authority_after = after.maintenance_authority()?;
+        let maintenance_authority_change =
+            (authority_before != authority_after).then_some((authority_before, authority_after));
+
+        Ok(Self {
+            changes,
+            balance_deltas,
+            maintenance_authority_change,
+        })
+    }
+}
+
+/// A changed state value, identified by its path from the root of the contract state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractStateChange {
+    pub path: Vec<ContractStatePathSegment>,
+    pub kind: ContractStateChangeKind,
+}
+
+/// A segment of the path to a state value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ContractStatePathSegment {
+    /// An index into an array.
+    Index(u32),
+
+    /// The atoms of a map key.
+    Key(Vec<Vec<u8>>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ContractStateChangeKind {
+    /// The value, i.e. the atoms, of a cell has changed.
+    CellChanged {
+        before: Vec<Vec<u8>>,
+        after: Vec<Vec<u8>>,
+    },
+
+    /// A map entry or array element has been inserted; the path ends with its key or index.
+    Inserted,
+
+    /// A map entry or array element has been removed; the path ends with its key or index.
+    Removed,
+
+    /// The root of a Merkle tree has changed, i.e. its entries have changed.
+    MerkleTreeChanged {
+        before: Option<ByteVec>,
+        after: Option<ByteVec>,
+    },
+
+    /// A state value has been replaced with one of another kind.
+    Replaced,
+}
+
+/// The changed balance of a token type; amounts are zero if there is no balance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContractBalanceDelta {
+    pub token_type: TokenType,
+    pub before: u128,
+    pub after: u128,
+}
+
+fn diff_state_values(
+    before: &ContractStateValue,
+    after: &ContractStateValue,
+    path: &mut Vec<ContractStatePathSegment>,
+    changes: &mut Vec<ContractStateChange>,
+) {
+    match (before, after) {
+        (ContractStateValue::Null, ContractStateValue::Null) => {}
+
+        (ContractStateValue::Cell(before), ContractStateValue::Cell(after)) => {
+            if before != after {
+                changes.push(ContractStateChange {
+                    path: path.clone(),
+                    kind: ContractStateChangeKind::CellChanged {
+                        before: before.to_owned(),
+                        after: after.to_owned(),
+                    },
+                });
+            }
+        }
+
+        (ContractStateValue::Map(before), ContractStateValue::Map(after)) => {
+            let keys = before
+                .iter()
+                .chain(after)
+                .map(|(key, _)| key)
+                .collect::<BTreeSet<_>>();
+
+            for key in keys {
+                path.push(ContractStatePathSegment::Key(key.to_owned()));
+                diff_optional_state_values(
+                    map_value(before, key),
+                    map_value(after, key),
+                    path,
+                    changes,
+                );
+                path.pop();
+            }
+        }
+
+        (ContractStateValue::Array(before), ContractStateValue::Array(after)) => {
+            for index in 0..before.len().max(after.len()) {
+                path.push(ContractStatePathSegment::Index(index as u32));
+                diff_optional_state_values(before.get(index), after.get(index), path, changes);
+                path.pop();
+            }
+        }
+
+        (
+            ContractStateValue::MerkleTree { root: before },
+            ContractStateValue::MerkleTree { root: after },
+        ) => {
+            if before != after {
+                changes.push(ContractStateChange {
+                    path: path.clone(),
+                    kind: ContractStateChangeKind::MerkleTreeChanged {
+                        before: before.to_owned(),
+                        after: after.to_owned(),
+                    },
+                });
+            }
+        }
+
+        _ => changes.push(ContractStateChange {
+            path: path.clone(),
+            kind: ContractStateChangeKind::Replaced,
+        }),
+    }
+}
+
+fn diff_optional_state_values(
+    before: Option<&ContractStateValue>,
+    after: Option<&ContractStateValue>,
+    path: &mut Vec<ContractStatePathSegment>,
+    changes: &mut Vec<ContractStateChange>,
+) {
+    let kind = match (before, after) {
+        (Some(before), Some(after)) => return diff_state_values(before, after, path, changes),
+        (Some(_), None) => ContractStateChangeKind::Removed,
+        (None, Some(_)) => ContractStateChangeKind::Inserted,
+        (None, None) => return,
+    };
+
+    changes.push(ContractStateChange {
+        path: path.clone(),
+        kind,
+    });
+}
+
+fn map_value<'a>(
+    entries: &'a [(Vec<Vec<u8>>, ContractStateValue)],
+    key: &[Vec<u8>],
+) -> Option<&'a ContractStateValue> {
+    entries
+        .iter()
+        .find(|(k, _)| k == key)
+        .map(|(_, value)| value)
+}
+
+fn balance_amount(balances: &[ContractBalance], token_type: TokenType) -> u128 {
+    balances
+        .iter()
+        .find(|balance| balance.token_type == token_type)
+        .map(|balance| balance.amount)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::contract_state_diff::{
+        ContractStateChange, ContractStateChangeKind, ContractStatePathSegment, diff_state_values,
+    };
+    use indexer_common::domain::ledger::ContractStateValue;
+
+    #[test]
+    fn test_diff_state_values() {
+        let before = ContractStateValue::Array(vec![
+            ContractStateValue::Cell(vec![vec![1]]),
+            ContractStateValue::Map(vec![
+                (vec![vec![1]], ContractStateValue::Null),
+                (vec![vec![2]], ContractStateValue::Null),
+            ]),
+            ContractStateValue::Null,
+        ]);
+        let after = ContractStateValue::Array(vec![
+            ContractStateValue::Cell(vec![vec![2]]),
+            ContractStateValue::Map(vec![
+                (vec![vec![2]], ContractStateValue::Null),
+                (vec![vec![3]], ContractStateValue::Null),
+            ]),
+            ContractStateValue::Cell(vec![]),
+            ContractStateValue::Null,
+        ]);
+
+        let mut changes = vec![];
+        diff_state_values(&before, &after, &mut vec![], &mut changes);
+
+        assert_eq!(
+            changes,
+            vec![
+                ContractStateChange {
+                    path: vec![ContractStatePathSegment::Index(0)],
+                    kind: ContractStateChangeKind::CellChanged {
+                        before: vec![vec![1]],
+                        after: vec![vec![2]],
+                    },
+                },
+                ContractStateChange {
+                    path: vec![
+                        ContractStatePathSegment::Index(1),
+                        ContractStatePathSegment::Key(vec![vec![1]]),
+                    ],
+                    kind: ContractStateChangeKind::Removed,
+                },
+                ContractStateChange {
+                    path: vec![
+                        ContractStatePathSegment::Index(1),
+                        ContractStatePathSegment::Key(vec![vec![3]]),
+                    ],
+                    kind: ContractStateChangeKind::Inserted,
+                },
+                ContractStateChange {
+                    path: vec![ContractStatePathSegment::Index(2)],
+                    kind: ContractStateChangeKind::Replaced,
+                },
+                ContractStateChange {
+                    path: vec![ContractStatePathSegment::Index(3)],
+                    kind: ContractStateChangeKind::Inserted,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,57 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{convert::Infallible, error::Error as StdError};
+
+/// Node abstraction for submitting transactions.
+#[trait_variant::make(Send)]
+pub trait Node
+where
+    Self: Clone + Send + Sync + 'static,
+{
+    type Error: StdError + Send + Sync + 'static;
+
+    /// Submit the given serialized and already validated transaction to the node. A transaction
+    /// rejected by the node's transaction pool, e.g. because it is invalid for the current ledger
+    /// state, is not an error, but a [SubmitTransactionOutcome::Rejected].
+    async fn submit_transaction(
+        &self,
+        transaction: &[u8],
+    ) -> Result<SubmitTransactionOutcome, Self::Error>;
+}
+
+/// Outcome of submitting a transaction to the node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SubmitTransactionOutcome {
+    /// The transaction has been accepted by the node's transaction pool.
+    Submitted,
+
+    /// The transaction has been rejected by the node's transaction pool for the given reason.
+    Rejected { reason: String },
+}
+
+/// Just needed as a type argument for `infra::api::export_schema`; see
+/// [NoopStorage](crate::domain::storage::NoopStorage).
+#[derive(Clone, Default)]
+pub struct NoopNode;
+
+impl Node for NoopNode {
+    type Error = Infallible;
+
+    async fn submit_transaction(
+        &self,
+        _transaction: &[u8],
+    ) -> Result<SubmitTransactionOutcome, Self::Error> {
+        Ok(SubmitTransactionOutcome::Submitted)
+    }
+}
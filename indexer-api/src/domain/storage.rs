@@ -12,27 +12,37 @@
 // limitations under the License.
 
 pub mod block;
+pub mod block_stats;
 pub mod bridge;
+pub mod chain_stats;
+pub mod contract_abi;
 pub mod contract_action;
 pub mod contract_event;
 pub mod dust;
 pub mod dust_generations;
 pub mod ledger_events;
 pub mod ledger_state;
+pub mod pending_transaction;
 pub mod shielded_nullifiers;
 pub mod spo;
 pub mod system_parameters;
+pub mod token;
 pub mod transaction;
 pub mod unshielded;
 pub mod wallet;
+pub mod watch_list;
+pub mod webhook;
 
 use crate::domain::storage::{
-    block::BlockStorage, bridge::BridgeStorage, contract_action::ContractActionStorage,
-    contract_event::ContractEventStorage, dust::DustStorage,
-    dust_generations::DustGenerationsStorage, ledger_events::LedgerEventStorage,
-    ledger_state::LedgerStateStorage, shielded_nullifiers::ShieldedNullifiersStorage,
-    spo::SpoStorage, system_parameters::SystemParametersStorage, transaction::TransactionStorage,
-    unshielded::UnshieldedUtxoStorage, wallet::WalletStorage,
+    block::BlockStorage, block_stats::BlockStatsStorage, bridge::BridgeStorage,
+    chain_stats::ChainStatsStorage, contract_abi::ContractAbiStorage,
+    contract_action::ContractActionStorage, contract_event::ContractEventStorage,
+    dust::DustStorage, dust_generations::DustGenerationsStorage, ledger_events::LedgerEventStorage,
+    ledger_state::LedgerStateStorage, pending_transaction::PendingTransactionStorage,
+    shielded_nullifiers::ShieldedNullifiersStorage, spo::SpoStorage,
+    system_parameters::SystemParametersStorage, token::TokenStorage,
+    transaction::TransactionStorage, unshielded::UnshieldedUtxoStorage, wallet::WalletStorage,
+    watch_list::WatchListStorage, webhook::WebhookStorage,
 };
 
 /// Storage abstraction.
@@ -40,18 +50,25 @@ use crate::domain::storage::{
 pub trait Storage
 where
     Self: BlockStorage
+        + BlockStatsStorage
         + BridgeStorage
+        + ChainStatsStorage
+        + ContractAbiStorage
         + ContractActionStorage
         + ContractEventStorage
         + DustStorage
         + DustGenerationsStorage
         + LedgerEventStorage
         + LedgerStateStorage
+        + PendingTransactionStorage
         + SpoStorage
         + SystemParametersStorage
+        + TokenStorage
         + TransactionStorage
         + UnshieldedUtxoStorage
         + WalletStorage
+        + WatchListStorage
+        + WebhookStorage
         + ShieldedNullifiersStorage
         + Clone
         + Send
@@ -68,3 +85,26 @@ where
 pub struct NoopStorage;
 
 impl Storage for NoopStorage {}
+
+/// A keyset page of items ordered by a unique key: at most `limit` items with keys strictly
+/// between `after` and `before`, taken from the lowest keys or, if `backward`, from the highest
+/// ones. Either way the items of a page are returned in ascending key order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page<K = u64> {
+    pub after: Option<K>,
+    pub before: Option<K>,
+    pub limit: u32,
+    pub backward: bool,
+}
+
+impl<K> Page<K> {
+    /// Map the keys of this page, e.g. to the types to bind as query parameters.
+    pub fn map_keys<L>(&self, f: impl Fn(&K) -> L) -> Page<L> {
+        Page {
+            after: self.after.as_ref().map(&f),
+            before: self.before.as_ref().map(&f),
+            limit: self.limit,
+            backward: self.backward,
+        }
+    }
+}
@@ -30,11 +30,24 @@ where
     /// Get a block for the given block height.
     async fn get_block_by_height(&self, height: u32) -> Result<Option<Block>, sqlx::Error>;
 
-    /// Get a stream of all blocks starting at the given height, ordered by block height.
+    /// Get up to `limit` blocks with heights in the given inclusive range, ordered by block height.
+    async fn get_blocks_by_height_range(
+        &self,
+        from_height: u32,
+        to_height: u32,
+        limit: u32,
+    ) -> Result<Vec<Block>, sqlx::Error>;
+
+    /// Get the latest block with a timestamp not after the given one.
+    async fn get_block_by_timestamp(&self, timestamp: u64) -> Result<Option<Block>, sqlx::Error>;
+
+    /// Get a stream of all blocks starting at the given height, ordered by block height. Not yet
+    /// finalized blocks are only included if `include_unfinalized` is set.
     fn get_blocks(
         &self,
         height: u32,
         batch_size: NonZeroU32,
+        include_unfinalized: bool,
     ) -> impl Stream<Item = Result<Block, sqlx::Error>> + Send;
 }
 
@@ -52,10 +65,24 @@ impl BlockStorage for NoopStorage {
         unimplemented!()
     }
 
+    async fn get_blocks_by_height_range(
+        &self,
+        from_height: u32,
+        to_height: u32,
+        limit: u32,
+    ) -> Result<Vec<Block>, sqlx::Error> {
+        unimplemented!()
+    }
+
+    async fn get_block_by_timestamp(&self, timestamp: u64) -> Result<Option<Block>, sqlx::Error> {
+        unimplemented!()
+    }
+
     fn get_blocks(
         &self,
         height: u32,
         batch_size: NonZeroU32,
+        include_unfinalized: bool,
     ) -> impl Stream<Item = Result<Block, sqlx::Error>> {
         stream::empty()
     }
@@ -0,0 +1,56 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::domain::{BlockStats, RecentFees, storage::NoopStorage};
+
+#[trait_variant::make(Send)]
+pub trait BlockStatsStorage
+where
+    Self: Send + Sync,
+{
+    /// Get the fee statistics of the block with the given ID.
+    async fn get_block_stats(&self, block_id: u64) -> Result<Option<BlockStats>, sqlx::Error>;
+
+    /// Get up to `limit` block fee statistics with block heights in the given inclusive range,
+    /// ordered by block height.
+    async fn get_block_stats_by_height_range(
+        &self,
+        from_height: u32,
+        to_height: u32,
+        limit: u32,
+    ) -> Result<Vec<BlockStats>, sqlx::Error>;
+
+    /// Get the paid fees of the regular transactions in the latest `block_count` blocks in
+    /// ascending order; `None` if there are no blocks yet.
+    async fn get_recent_fees(&self, block_count: u32) -> Result<Option<RecentFees>, sqlx::Error>;
+}
+
+#[allow(unused_variables)]
+impl BlockStatsStorage for NoopStorage {
+    async fn get_block_stats(&self, block_id: u64) -> Result<Option<BlockStats>, sqlx::Error> {
+        unimplemented!()
+    }
+
+    async fn get_block_stats_by_height_range(
+        &self,
+        from_height: u32,
+        to_height: u32,
+        limit: u32,
+    ) -> Result<Vec<BlockStats>, sqlx::Error> {
+        unimplemented!()
+    }
+
+    async fn get_recent_fees(&self, block_count: u32) -> Result<Option<RecentFees>, sqlx::Error> {
+        unimplemented!()
+    }
+}
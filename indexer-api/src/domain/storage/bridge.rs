@@ -13,7 +13,7 @@
 
 use crate::domain::{
     bridge::{BridgeBalance, BridgeEvent, BridgePoolSummary, TreasuryReason},
-    storage::NoopStorage,
+    storage::{NoopStorage, Page},
 };
 use indexer_common::domain::{UnshieldedAddress, bridge::BridgeEventVariant};
 
@@ -41,6 +41,13 @@ where
         limit: u64,
     ) -> Result<Vec<BridgeEvent>, sqlx::Error>;
 
+    /// Fetch the given page of bridge events filtered by the given criteria, keyed by event ID.
+    async fn get_bridge_events_page(
+        &self,
+        filter: &BridgeEventFilter,
+        page: &Page,
+    ) -> Result<Vec<BridgeEvent>, sqlx::Error>;
+
     /// Compute deposited and claimed totals for a recipient address.
     async fn get_bridge_balance(
         &self,
@@ -84,6 +91,14 @@ impl BridgeStorage for NoopStorage {
         Ok(vec![])
     }
 
+    async fn get_bridge_events_page(
+        &self,
+        filter: &BridgeEventFilter,
+        page: &Page,
+    ) -> Result<Vec<BridgeEvent>, sqlx::Error> {
+        Ok(vec![])
+    }
+
     async fn get_bridge_balance(
         &self,
         recipient: UnshieldedAddress,
@@ -0,0 +1,44 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::domain::{ChainStats, ChainStatsGranularity, storage::NoopStorage};
+
+#[trait_variant::make(Send)]
+pub trait ChainStatsStorage
+where
+    Self: Send + Sync,
+{
+    /// Get up to `limit` chain statistics of the given granularity overlapping the given inclusive
+    /// range of UNIX timestamps in milliseconds, ordered by timestamp, i.e. of the blocks with
+    /// timestamps within the range or of the UTC days containing any of these.
+    async fn get_chain_stats(
+        &self,
+        granularity: ChainStatsGranularity,
+        from: u64,
+        to: u64,
+        limit: u32,
+    ) -> Result<Vec<ChainStats>, sqlx::Error>;
+}
+
+#[allow(unused_variables)]
+impl ChainStatsStorage for NoopStorage {
+    async fn get_chain_stats(
+        &self,
+        granularity: ChainStatsGranularity,
+        from: u64,
+        to: u64,
+        limit: u32,
+    ) -> Result<Vec<ChainStats>, sqlx::Error> {
+        unimplemented!()
+    }
+}
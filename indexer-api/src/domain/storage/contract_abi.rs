@@ -0,0 +1,53 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::domain::{ContractAbi, storage::NoopStorage};
+use indexer_common::domain::SerializedContractAddress;
+
+#[trait_variant::make(Send)]
+pub trait ContractAbiStorage
+where
+    Self: Send + Sync,
+{
+    /// Register the given ABI for the contract with the given address, replacing a previously
+    /// registered one.
+    async fn register_contract_abi(
+        &self,
+        address: &SerializedContractAddress,
+        abi: &ContractAbi,
+    ) -> Result<(), sqlx::Error>;
+
+    /// Get the ABI registered for the contract with the given address.
+    async fn get_contract_abi(
+        &self,
+        address: &SerializedContractAddress,
+    ) -> Result<Option<ContractAbi>, sqlx::Error>;
+}
+
+#[allow(unused_variables)]
+impl ContractAbiStorage for NoopStorage {
+    async fn register_contract_abi(
+        &self,
+        address: &SerializedContractAddress,
+        abi: &ContractAbi,
+    ) -> Result<(), sqlx::Error> {
+        unimplemented!()
+    }
+
+    async fn get_contract_abi(
+        &self,
+        address: &SerializedContractAddress,
+    ) -> Result<Option<ContractAbi>, sqlx::Error> {
+        unimplemented!()
+    }
+}
@@ -11,7 +11,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::domain::{ContractAction, ContractBalance, storage::NoopStorage};
+use crate::domain::{
+    ContractAction, ContractBalance,
+    storage::{NoopStorage, Page},
+};
 use futures::{Stream, stream};
 use indexer_common::domain::{
     BlockHash, ProtocolVersion, SerializedContractAddress, SerializedTransactionIdentifier,
@@ -36,6 +39,13 @@ where
         address: &SerializedContractAddress,
     ) -> Result<Option<ContractAction>, sqlx::Error>;
 
+    /// Get the contract action for the given address preceding the one with the given ID.
+    async fn get_previous_contract_action_by_address(
+        &self,
+        address: &SerializedContractAddress,
+        id: u64,
+    ) -> Result<Option<ContractAction>, sqlx::Error>;
+
     /// Get the latest contract action for the given address and block hash.
     async fn get_contract_action_by_address_and_block_hash(
         &self,
@@ -111,6 +121,15 @@ where
         variant: Option<&str>,
     ) -> Result<Vec<ContractAction>, sqlx::Error>;
 
+    /// Get the given page of contract actions for the given address, keyed by contract action ID,
+    /// optionally filtered to a single variant ("Deploy" | "Call" | "Update").
+    async fn get_contract_actions_page_by_address(
+        &self,
+        address: &SerializedContractAddress,
+        variant: Option<&str>,
+        page: &Page,
+    ) -> Result<Vec<ContractAction>, sqlx::Error>;
+
     /// Get a stream of contract actions for the given address starting at the given contract_action
     /// ID, ordered by transaction ID.
     fn get_contract_actions_by_address(
@@ -157,6 +176,14 @@ impl ContractActionStorage for NoopStorage {
         unimplemented!()
     }
 
+    async fn get_previous_contract_action_by_address(
+        &self,
+        address: &SerializedContractAddress,
+        id: u64,
+    ) -> Result<Option<ContractAction>, sqlx::Error> {
+        unimplemented!()
+    }
+
     async fn get_contract_action_by_address_and_block_hash(
         &self,
         address: &SerializedContractAddress,
@@ -236,6 +263,15 @@ impl ContractActionStorage for NoopStorage {
         unimplemented!()
     }
 
+    async fn get_contract_actions_page_by_address(
+        &self,
+        address: &SerializedContractAddress,
+        variant: Option<&str>,
+        page: &Page,
+    ) -> Result<Vec<ContractAction>, sqlx::Error> {
+        unimplemented!()
+    }
+
     fn get_contract_actions_by_address(
         &self,
         address: &SerializedContractAddress,
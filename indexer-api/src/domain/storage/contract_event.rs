@@ -11,7 +11,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::domain::{ContractEventRow, storage::NoopStorage};
+use crate::domain::{
+    ContractEventRow,
+    storage::{NoopStorage, Page},
+};
 use futures::{Stream, stream};
 use indexer_common::domain::{ByteVec, SerializedContractAddress, TransactionHash};
 use std::num::NonZeroU32;
@@ -65,6 +68,13 @@ where
         offset: u32,
     ) -> Result<Vec<ContractEventRow>, sqlx::Error>;
 
+    /// Get the given page of contract events matching the filter, keyed by event ID.
+    async fn get_contract_events_page(
+        &self,
+        filter: &ContractEventFilter,
+        page: &Page,
+    ) -> Result<Vec<ContractEventRow>, sqlx::Error>;
+
     /// Get a stream of contract events matching the filter, starting at the given event ID
     /// (inclusive), ordered by ID.
     async fn get_contract_events_from_id(
@@ -94,6 +104,14 @@ impl ContractEventStorage for NoopStorage {
         Ok(vec![])
     }
 
+    async fn get_contract_events_page(
+        &self,
+        filter: &ContractEventFilter,
+        page: &Page,
+    ) -> Result<Vec<ContractEventRow>, sqlx::Error> {
+        Ok(vec![])
+    }
+
     async fn get_contract_events_from_id(
         &self,
         filter: &ContractEventFilter,
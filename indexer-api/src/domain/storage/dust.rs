@@ -15,9 +15,9 @@ use crate::domain::{
     dust::DustGenerationStatus,
     storage::{BlockStorage, NoopStorage},
 };
-use indexer_common::domain::{CardanoRewardAddress, LedgerVersion};
+use indexer_common::domain::{CardanoRewardAddress, DustPublicKey, LedgerVersion};
 
-/// DUST storage abstraction. Currently only supports the dustGenerationStatus query.
+/// DUST storage abstraction.
 #[trait_variant::make(Send)]
 pub trait DustStorage: BlockStorage {
     /// Get DUST generation status for specific Cardano reward addresses.
@@ -26,6 +26,12 @@ pub trait DustStorage: BlockStorage {
         cardano_reward_addresses: &[CardanoRewardAddress],
         ledger_version: LedgerVersion,
     ) -> Result<Vec<DustGenerationStatus>, sqlx::Error>;
+
+    /// Get the Cardano reward addresses with a current registration for the given DUST address.
+    async fn get_cardano_reward_addresses_by_dust_address(
+        &self,
+        dust_address: &DustPublicKey,
+    ) -> Result<Vec<CardanoRewardAddress>, sqlx::Error>;
 }
 
 #[allow(unused_variables)]
@@ -37,4 +43,11 @@ impl DustStorage for NoopStorage {
     ) -> Result<Vec<DustGenerationStatus>, sqlx::Error> {
         Ok(vec![])
     }
+
+    async fn get_cardano_reward_addresses_by_dust_address(
+        &self,
+        dust_address: &DustPublicKey,
+    ) -> Result<Vec<CardanoRewardAddress>, sqlx::Error> {
+        Ok(vec![])
+    }
 }
@@ -0,0 +1,40 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::domain::{PendingTransaction, storage::NoopStorage};
+use indexer_common::domain::{SerializedContractAddress, UnshieldedAddress};
+
+#[trait_variant::make(Send)]
+pub trait PendingTransactionStorage
+where
+    Self: Send + Sync,
+{
+    /// Get the pending transactions ordered by first seen, optionally only the ones touching the
+    /// given contract address and/or unshielded address.
+    async fn get_pending_transactions(
+        &self,
+        contract_address: Option<&SerializedContractAddress>,
+        unshielded_address: Option<UnshieldedAddress>,
+    ) -> Result<Vec<PendingTransaction>, sqlx::Error>;
+}
+
+#[allow(unused_variables)]
+impl PendingTransactionStorage for NoopStorage {
+    async fn get_pending_transactions(
+        &self,
+        contract_address: Option<&SerializedContractAddress>,
+        unshielded_address: Option<UnshieldedAddress>,
+    ) -> Result<Vec<PendingTransaction>, sqlx::Error> {
+        Ok(vec![])
+    }
+}
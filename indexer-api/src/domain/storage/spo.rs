@@ -16,7 +16,7 @@ use crate::domain::{
         CommitteeMember, EpochInfo, EpochPerf, FirstValidEpoch, PoolMetadata, PresenceEvent,
         RegisteredStat, RegisteredTotals, Spo, SpoComposite, SpoIdentity, StakeShare,
     },
-    storage::NoopStorage,
+    storage::{NoopStorage, Page},
 };
 
 /// Storage abstraction for SPO data.
@@ -63,6 +63,13 @@ where
         search: Option<&str>,
     ) -> Result<Vec<Spo>, sqlx::Error>;
 
+    /// Get the given page of SPOs with optional search, keyed by pool ID.
+    async fn get_spo_list_page(
+        &self,
+        search: Option<&str>,
+        page: &Page<String>,
+    ) -> Result<Vec<Spo>, sqlx::Error>;
+
     /// Get composite SPO data (identity + metadata + performance).
     async fn get_spo_composite_by_pool_id(
         &self,
@@ -190,6 +197,14 @@ impl SpoStorage for NoopStorage {
         unimplemented!()
     }
 
+    async fn get_spo_list_page(
+        &self,
+        search: Option<&str>,
+        page: &Page<String>,
+    ) -> Result<Vec<Spo>, sqlx::Error> {
+        unimplemented!()
+    }
+
     async fn get_spo_composite_by_pool_id(
         &self,
         pool_id: &str,
@@ -0,0 +1,56 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::domain::{
+    Token, TokenHolder,
+    storage::{NoopStorage, Page},
+};
+use indexer_common::domain::TokenType;
+
+#[trait_variant::make(Send)]
+pub trait TokenStorage
+where
+    Self: Send + Sync,
+{
+    /// Get the token for the given token type.
+    async fn get_token(&self, token_type: TokenType) -> Result<Option<Token>, sqlx::Error>;
+
+    /// Get the given page of tokens, keyed and ordered by ID, i.e. by when first seen.
+    async fn get_tokens_page(&self, page: &Page) -> Result<Vec<Token>, sqlx::Error>;
+
+    /// Get at most `limit` holders of the given token type, ordered by descending balance.
+    async fn get_token_holders(
+        &self,
+        token_type: TokenType,
+        limit: u32,
+    ) -> Result<Vec<TokenHolder>, sqlx::Error>;
+}
+
+#[allow(unused_variables)]
+impl TokenStorage for NoopStorage {
+    async fn get_token(&self, token_type: TokenType) -> Result<Option<Token>, sqlx::Error> {
+        unimplemented!()
+    }
+
+    async fn get_tokens_page(&self, page: &Page) -> Result<Vec<Token>, sqlx::Error> {
+        unimplemented!()
+    }
+
+    async fn get_token_holders(
+        &self,
+        token_type: TokenType,
+        limit: u32,
+    ) -> Result<Vec<TokenHolder>, sqlx::Error> {
+        unimplemented!()
+    }
+}
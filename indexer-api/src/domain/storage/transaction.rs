@@ -11,12 +11,43 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::domain::{RegularTransaction, Transaction, storage::NoopStorage};
+use crate::domain::{
+    RegularTransaction, Transaction,
+    storage::{NoopStorage, Page},
+};
 use futures::{Stream, stream};
-use indexer_common::domain::{SerializedTransactionIdentifier, TransactionHash, UnshieldedAddress};
+use indexer_common::domain::{
+    SerializedContractAddress, SerializedTransactionIdentifier, TokenType, TransactionHash,
+    TransactionVariant, UnshieldedAddress,
+};
 use std::num::NonZeroU32;
 use uuid::Uuid;
 
+/// Filters for `get_transactions_page_by_filter`. All fields combined with AND; bounds are
+/// inclusive. Result status and paid fees only match regular transactions.
+#[derive(Debug, Default, Clone)]
+pub struct TransactionFilter {
+    pub block_height_from: Option<u32>,
+    pub block_height_to: Option<u32>,
+    pub timestamp_from: Option<u64>,
+    pub timestamp_to: Option<u64>,
+    pub variant: Option<TransactionVariant>,
+    pub result_status: Option<TransactionResultStatus>,
+    pub paid_fees_min: Option<u128>,
+    pub paid_fees_max: Option<u128>,
+    pub contract_address: Option<SerializedContractAddress>,
+    pub unshielded_address: Option<UnshieldedAddress>,
+    pub token_type: Option<TokenType>,
+}
+
+/// The status of a transaction result, i.e. its variant without the segments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionResultStatus {
+    Success,
+    PartialSuccess,
+    Failure,
+}
+
 #[trait_variant::make(Send)]
 pub trait TransactionStorage
 where
@@ -25,6 +56,17 @@ where
     /// Get the transactions for the given IDs.
     async fn get_transactions_by_ids(&self, ids: &[u64]) -> Result<Vec<Transaction>, sqlx::Error>;
 
+    /// Get the given page of all transactions, keyed by transaction ID.
+    async fn get_transactions_page(&self, page: &Page) -> Result<Vec<Transaction>, sqlx::Error>;
+
+    /// Get the given page of the transactions matching the given filter, keyed by transaction ID.
+    /// Unshielded address and token type match transactions creating or spending such UTXOs.
+    async fn get_transactions_page_by_filter(
+        &self,
+        filter: &TransactionFilter,
+        page: &Page,
+    ) -> Result<Vec<Transaction>, sqlx::Error>;
+
     /// Get the transactions for the blocks with the given IDs, ordered by block ID and transaction
     /// ID. Each tuple carries the block ID alongside its transaction for grouping by the caller.
     async fn get_transactions_by_block_ids(
@@ -70,6 +112,9 @@ where
         address: UnshieldedAddress,
     ) -> Result<Option<u64>, sqlx::Error>;
 
+    /// Get the highest regular transaction ID.
+    async fn get_highest_regular_transaction_id(&self) -> Result<Option<u64>, sqlx::Error>;
+
     /// Get a tuple of end indices:
     /// - the highest zswap state end index for all transactions,
     /// - the highest zswap state end index for all transactions checked for relevance and
@@ -87,6 +132,18 @@ impl TransactionStorage for NoopStorage {
         unimplemented!()
     }
 
+    async fn get_transactions_page(&self, page: &Page) -> Result<Vec<Transaction>, sqlx::Error> {
+        unimplemented!()
+    }
+
+    async fn get_transactions_page_by_filter(
+        &self,
+        filter: &TransactionFilter,
+        page: &Page,
+    ) -> Result<Vec<Transaction>, sqlx::Error> {
+        unimplemented!()
+    }
+
     async fn get_transactions_by_block_ids(
         &self,
         _ids: &[u64],
@@ -133,6 +190,10 @@ impl TransactionStorage for NoopStorage {
         unimplemented!()
     }
 
+    async fn get_highest_regular_transaction_id(&self) -> Result<Option<u64>, sqlx::Error> {
+        unimplemented!()
+    }
+
     async fn get_highest_zswap_end_indices(
         &self,
         wallet_id: Uuid,
@@ -12,7 +12,7 @@
 // limitations under the License.
 
 use crate::domain::{
-    UnshieldedUtxo,
+    UnshieldedBalance, UnshieldedUtxo,
     storage::{
         NoopStorage, block::BlockStorage, contract_action::ContractActionStorage,
         transaction::TransactionStorage, wallet::WalletStorage,
@@ -39,6 +39,22 @@ where
         address: UnshieldedAddress,
     ) -> Result<Vec<UnshieldedUtxo>, sqlx::Error>;
 
+    /// Get the unspent unshielded UTXOs for a given address as of the block with the given height
+    /// or, if omitted, as of the latest block, ordered by ID.
+    async fn get_unspent_unshielded_utxos_by_address(
+        &self,
+        address: UnshieldedAddress,
+        height: Option<u32>,
+    ) -> Result<Vec<UnshieldedUtxo>, sqlx::Error>;
+
+    /// Get the balances per token type for a given address as of the block with the given height
+    /// or, if omitted, as of the latest block, ordered by token type.
+    async fn get_unshielded_balances_by_address(
+        &self,
+        address: UnshieldedAddress,
+        height: Option<u32>,
+    ) -> Result<Vec<UnshieldedBalance>, sqlx::Error>;
+
     /// Get unshielded UTXOs created by a specific transaction, ordered by output index.
     async fn get_unshielded_utxos_created_by_transaction(
         &self,
@@ -77,6 +93,22 @@ impl UnshieldedUtxoStorage for NoopStorage {
         unimplemented!()
     }
 
+    async fn get_unspent_unshielded_utxos_by_address(
+        &self,
+        address: UnshieldedAddress,
+        height: Option<u32>,
+    ) -> Result<Vec<UnshieldedUtxo>, Error> {
+        unimplemented!()
+    }
+
+    async fn get_unshielded_balances_by_address(
+        &self,
+        address: UnshieldedAddress,
+        height: Option<u32>,
+    ) -> Result<Vec<UnshieldedBalance>, Error> {
+        unimplemented!()
+    }
+
     async fn get_unshielded_utxos_created_by_transaction(
         &self,
         transaction_id: u64,
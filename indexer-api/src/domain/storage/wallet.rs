@@ -11,7 +11,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::domain::storage::NoopStorage;
+use crate::domain::{WalletStatus, storage::NoopStorage};
 use indexer_common::domain::{SessionId, ViewingKey};
 use uuid::Uuid;
 
@@ -36,6 +36,14 @@ where
 
     /// Refresh the wallet's last active timestamp to avoid timing out.
     async fn keep_wallet_active(&self, wallet_id: Uuid) -> Result<(), sqlx::Error>;
+
+    /// Get the sync status of the wallet with the given ID relative to the given highest regular
+    /// transaction ID.
+    async fn get_wallet_status(
+        &self,
+        wallet_id: Uuid,
+        highest_transaction_id: Option<u64>,
+    ) -> Result<Option<WalletStatus>, sqlx::Error>;
 }
 
 #[allow(unused_variables)]
@@ -59,4 +67,12 @@ impl WalletStorage for NoopStorage {
     async fn keep_wallet_active(&self, wallet_id: Uuid) -> Result<(), sqlx::Error> {
         unimplemented!()
     }
+
+    async fn get_wallet_status(
+        &self,
+        wallet_id: Uuid,
+        highest_transaction_id: Option<u64>,
+    ) -> Result<Option<WalletStatus>, sqlx::Error> {
+        unimplemented!()
+    }
 }
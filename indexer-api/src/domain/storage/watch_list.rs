@@ -0,0 +1,83 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::domain::{WatchList, WatchListKey, storage::NoopStorage};
+use indexer_common::domain::UnshieldedAddress;
+
+#[trait_variant::make(Send)]
+pub trait WatchListStorage
+where
+    Self: Send + Sync,
+{
+    /// Create a watch list with the given name and addresses and return its random key.
+    async fn create_watch_list(
+        &self,
+        name: &str,
+        addresses: &[UnshieldedAddress],
+    ) -> Result<WatchListKey, sqlx::Error>;
+
+    /// Get the watch list for the given key.
+    async fn get_watch_list(&self, key: WatchListKey) -> Result<Option<WatchList>, sqlx::Error>;
+
+    /// Add the given addresses to the watch list for the given key, ignoring already watched ones.
+    async fn add_watch_list_addresses(
+        &self,
+        key: WatchListKey,
+        addresses: &[UnshieldedAddress],
+    ) -> Result<(), sqlx::Error>;
+
+    /// Remove the given addresses from the watch list for the given key.
+    async fn remove_watch_list_addresses(
+        &self,
+        key: WatchListKey,
+        addresses: &[UnshieldedAddress],
+    ) -> Result<(), sqlx::Error>;
+
+    /// Delete the watch list for the given key.
+    async fn delete_watch_list(&self, key: WatchListKey) -> Result<(), sqlx::Error>;
+}
+
+#[allow(unused_variables)]
+impl WatchListStorage for NoopStorage {
+    async fn create_watch_list(
+        &self,
+        name: &str,
+        addresses: &[UnshieldedAddress],
+    ) -> Result<WatchListKey, sqlx::Error> {
+        unimplemented!()
+    }
+
+    async fn get_watch_list(&self, key: WatchListKey) -> Result<Option<WatchList>, sqlx::Error> {
+        unimplemented!()
+    }
+
+    async fn add_watch_list_addresses(
+        &self,
+        key: WatchListKey,
+        addresses: &[UnshieldedAddress],
+    ) -> Result<(), sqlx::Error> {
+        unimplemented!()
+    }
+
+    async fn remove_watch_list_addresses(
+        &self,
+        key: WatchListKey,
+        addresses: &[UnshieldedAddress],
+    ) -> Result<(), sqlx::Error> {
+        unimplemented!()
+    }
+
+    async fn delete_watch_list(&self, key: WatchListKey) -> Result<(), sqlx::Error> {
+        unimplemented!()
+    }
+}
@@ -0,0 +1,165 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::domain::{
+    NewWebhookDelivery, Webhook, WebhookDelivery, WebhookDeliveryStatus, WebhookFilter, WebhookKey,
+    WebhookSecret, storage::NoopStorage,
+};
+
+#[trait_variant::make(Send)]
+pub trait WebhookStorage
+where
+    Self: Send + Sync,
+{
+    /// Register a webhook for the given URL and filter and return its random key and secret.
+    async fn register_webhook(
+        &self,
+        url: &str,
+        filter: &WebhookFilter,
+    ) -> Result<(WebhookKey, WebhookSecret), sqlx::Error>;
+
+    /// Get the ID of the webhook for the given key.
+    async fn get_webhook_id(&self, key: WebhookKey) -> Result<Option<u64>, sqlx::Error>;
+
+    /// Get all registered webhooks.
+    async fn get_webhooks(&self) -> Result<Vec<Webhook>, sqlx::Error>;
+
+    /// Get the number of registered webhooks.
+    async fn get_webhook_count(&self) -> Result<u64, sqlx::Error>;
+
+    /// Delete the webhook for the given key together with its deliveries.
+    async fn delete_webhook(&self, key: WebhookKey) -> Result<(), sqlx::Error>;
+
+    /// Enqueue the given deliveries as due at `now` (milliseconds since the UNIX epoch), ignoring
+    /// ones already enqueued for the same webhook and event key, e.g. by another replica. Within
+    /// the same transaction, advance the next transaction to look at for the unshielded address
+    /// filter of the webhooks with the given IDs to the given transaction IDs, never moving it
+    /// backwards.
+    async fn enqueue_webhook_deliveries(
+        &self,
+        deliveries: &[NewWebhookDelivery],
+        transaction_ids: &[(u64, u64)],
+        now: u64,
+    ) -> Result<(), sqlx::Error>;
+
+    /// Claim up to `limit` pending deliveries due at `now`, oldest first: increment their attempts
+    /// and postpone them to `lease_until` such that they are not claimed again meanwhile.
+    async fn claim_webhook_deliveries(
+        &self,
+        now: u64,
+        lease_until: u64,
+        limit: u32,
+    ) -> Result<Vec<WebhookDelivery>, sqlx::Error>;
+
+    /// Mark the delivery with the given ID as delivered at `now`.
+    async fn complete_webhook_delivery(
+        &self,
+        id: u64,
+        response_status: u16,
+        now: u64,
+    ) -> Result<(), sqlx::Error>;
+
+    /// Record a failed attempt of the delivery with the given ID and retry it at
+    /// `next_attempt_at` or, if `None`, mark it as dead and add it to the dead letters.
+    async fn fail_webhook_delivery(
+        &self,
+        id: u64,
+        response_status: Option<u16>,
+        error: &str,
+        next_attempt_at: Option<u64>,
+        now: u64,
+    ) -> Result<(), sqlx::Error>;
+
+    /// Get up to `limit` most recent deliveries for the webhook with the given ID, optionally only
+    /// the ones with the given status, newest first.
+    async fn get_webhook_deliveries(
+        &self,
+        id: u64,
+        status: Option<WebhookDeliveryStatus>,
+        limit: u32,
+    ) -> Result<Vec<WebhookDelivery>, sqlx::Error>;
+}
+
+#[allow(unused_variables)]
+impl WebhookStorage for NoopStorage {
+    async fn register_webhook(
+        &self,
+        url: &str,
+        filter: &WebhookFilter,
+    ) -> Result<(WebhookKey, WebhookSecret), sqlx::Error> {
+        unimplemented!()
+    }
+
+    async fn get_webhook_id(&self, key: WebhookKey) -> Result<Option<u64>, sqlx::Error> {
+        unimplemented!()
+    }
+
+    async fn get_webhooks(&self) -> Result<Vec<Webhook>, sqlx::Error> {
+        unimplemented!()
+    }
+
+    async fn get_webhook_count(&self) -> Result<u64, sqlx::Error> {
+        unimplemented!()
+    }
+
+    async fn delete_webhook(&self, key: WebhookKey) -> Result<(), sqlx::Error> {
+        unimplemented!()
+    }
+
+    async fn enqueue_webhook_deliveries(
+        &self,
+        deliveries: &[NewWebhookDelivery],
+        transaction_ids: &[(u64, u64)],
+        now: u64,
+    ) -> Result<(), sqlx::Error> {
+        unimplemented!()
+    }
+
+    async fn claim_webhook_deliveries(
+        &self,
+        now: u64,
+        lease_until: u64,
+        limit: u32,
+    ) -> Result<Vec<WebhookDelivery>, sqlx::Error> {
+        unimplemented!()
+    }
+
+    async fn complete_webhook_delivery(
+        &self,
+        id: u64,
+        response_status: u16,
+        now: u64,
+    ) -> Result<(), sqlx::Error> {
+        unimplemented!()
+    }
+
+    async fn fail_webhook_delivery(
+        &self,
+        id: u64,
+        response_status: Option<u16>,
+        error: &str,
+        next_attempt_at: Option<u64>,
+        now: u64,
+    ) -> Result<(), sqlx::Error> {
+        unimplemented!()
+    }
+
+    async fn get_webhook_deliveries(
+        &self,
+        id: u64,
+        status: Option<WebhookDeliveryStatus>,
+        limit: u32,
+    ) -> Result<Vec<WebhookDelivery>, sqlx::Error> {
+        unimplemented!()
+    }
+}
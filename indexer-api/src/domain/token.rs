@@ -0,0 +1,58 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use indexer_common::{
+    domain::{ByteVec, SerializedContractAddress, TokenType, UnshieldedAddress},
+    infra::sqlx::U128BeBytes,
+};
+use sqlx::FromRow;
+
+/// An unshielded token type as maintained in the token registry by the chain-indexer.
+#[derive(Debug, Clone, PartialEq, Eq, FromRow)]
+pub struct Token {
+    #[sqlx(try_from = "i64")]
+    pub id: u64,
+
+    /// Type of token (e.g. NIGHT has all-zero bytes).
+    pub token_type: TokenType,
+
+    /// Height of the block in which this token type was first seen.
+    #[sqlx(try_from = "i64")]
+    pub first_seen_block_height: u32,
+
+    /// Address of the contract that minted this token type, if minted by a contract.
+    pub contract_address: Option<SerializedContractAddress>,
+
+    /// Domain separator used by the minting contract, if minted by a contract.
+    pub domain_sep: Option<ByteVec>,
+
+    /// Sum of the values of the unspent unshielded UTXOs of this token type and the balances of
+    /// this token type held by contracts.
+    #[sqlx(try_from = "U128BeBytes")]
+    pub circulating_supply: u128,
+
+    /// Number of unshielded addresses with a non-zero balance of this token type.
+    #[sqlx(try_from = "i64")]
+    pub holder_count: u64,
+}
+
+/// Balance of a token type held by an unshielded address.
+#[derive(Debug, Clone, PartialEq, Eq, FromRow)]
+pub struct TokenHolder {
+    /// The unshielded address holding the balance.
+    pub owner: UnshieldedAddress,
+
+    /// Balance amount, i.e. the sum of the values of the unspent UTXOs of the owner.
+    #[sqlx(try_from = "U128BeBytes")]
+    pub balance: u128,
+}
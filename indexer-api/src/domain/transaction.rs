@@ -35,6 +35,20 @@ impl Transaction {
             Transaction::System(t) => t.id,
         }
     }
+
+    pub fn hash(&self) -> TransactionHash {
+        match self {
+            Transaction::Regular(t) => t.hash,
+            Transaction::System(t) => t.hash,
+        }
+    }
+
+    pub fn block_hash(&self) -> BlockHash {
+        match self {
+            Transaction::Regular(t) => t.block_hash,
+            Transaction::System(t) => t.block_hash,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, FromRow)]
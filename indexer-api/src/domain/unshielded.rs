@@ -65,3 +65,14 @@ pub struct ContractBalance {
     #[sqlx(try_from = "U128BeBytes")]
     pub amount: u128,
 }
+
+/// Token balance held by an unshielded address, i.e. the sum of the values of its unspent UTXOs of
+/// a token type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnshieldedBalance {
+    /// Token type identifier.
+    pub token_type: TokenType,
+
+    /// Balance amount.
+    pub amount: u128,
+}
@@ -0,0 +1,98 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+/// The sync status of a wallet, i.e. how far the wallet-indexer has scanned the transactions for
+/// it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WalletStatus {
+    pub wanted_start_index: u64,
+
+    pub first_indexed_transaction_id: u64,
+
+    pub last_indexed_transaction_id: u64,
+
+    /// The highest regular transaction ID; `None` if there are no regular transactions yet.
+    pub highest_transaction_id: Option<u64>,
+
+    pub relevant_transaction_count: u64,
+
+    /// Milliseconds since the UNIX epoch.
+    pub last_active: u64,
+
+    /// Transaction IDs per second measured by the wallet-indexer; `None` until measured.
+    pub scan_rate: Option<f64>,
+}
+
+impl WalletStatus {
+    /// The number of transaction IDs still to be scanned, i.e. the backward gap down to the wanted
+    /// start index plus the forward gap up to the highest transaction ID.
+    pub fn remaining_transaction_ids(&self) -> u64 {
+        let backward_gap = self
+            .first_indexed_transaction_id
+            .saturating_sub(self.wanted_start_index);
+        let forward_gap = self
+            .highest_transaction_id
+            .unwrap_or_default()
+            .saturating_sub(self.last_indexed_transaction_id);
+
+        backward_gap + forward_gap
+    }
+
+    /// Estimate the seconds until synced based on the scan rate: zero if already synced, `None`
+    /// if the scan rate has not yet been measured.
+    pub fn eta_seconds(&self) -> Option<u64> {
+        match self.remaining_transaction_ids() {
+            0 => Some(0),
+
+            remaining => self
+                .scan_rate
+                .filter(|&scan_rate| scan_rate > 0.0)
+                .map(|scan_rate| (remaining as f64 / scan_rate).ceil() as u64),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::wallet::WalletStatus;
+
+    #[test]
+    fn test_eta_seconds() {
+        let status = WalletStatus {
+            wanted_start_index: 10,
+            first_indexed_transaction_id: 10,
+            last_indexed_transaction_id: 100,
+            highest_transaction_id: Some(100),
+            relevant_transaction_count: 3,
+            last_active: 0,
+            scan_rate: None,
+        };
+        assert_eq!(status.remaining_transaction_ids(), 0);
+        assert_eq!(status.eta_seconds(), Some(0));
+
+        // Backward and forward gaps, not yet measured.
+        let status = WalletStatus {
+            wanted_start_index: 0,
+            highest_transaction_id: Some(1_000),
+            ..status
+        };
+        assert_eq!(status.remaining_transaction_ids(), 910);
+        assert_eq!(status.eta_seconds(), None);
+
+        let status = WalletStatus {
+            scan_rate: Some(100.0),
+            ..status
+        };
+        assert_eq!(status.eta_seconds(), Some(10));
+    }
+}
@@ -0,0 +1,26 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use indexer_common::domain::{ByteArray, UnshieldedAddress};
+
+/// Random key identifying a watch list.
+pub type WatchListKey = ByteArray<32>;
+
+/// A named server-side list of watched unshielded addresses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatchList {
+    pub name: String,
+
+    /// The watched addresses, ordered bytewise.
+    pub addresses: Vec<UnshieldedAddress>,
+}
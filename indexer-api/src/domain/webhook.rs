@@ -0,0 +1,364 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use chacha20poly1305::{
+    AeadCore, ChaCha20Poly1305,
+    aead::{Aead, OsRng, Payload, rand_core::RngCore},
+};
+use hmac::{Hmac, Mac};
+use indexer_common::{
+    domain::{
+        ByteArray, ByteArrayLenError, ByteVec, SerializedContractAddress, UnshieldedAddress,
+        bridge::BridgeRecipient,
+    },
+    infra::sqlx::SqlxOption,
+};
+use sha2_v0_10::Sha256;
+use sqlx::{FromRow, Type};
+use std::{
+    error::Error as StdError,
+    fmt::{self, Debug},
+    net::IpAddr,
+    time::Duration,
+};
+use thiserror::Error;
+
+pub const WEBHOOK_SECRET_LEN: usize = 32;
+
+/// Random key identifying a webhook.
+pub type WebhookKey = ByteArray<32>;
+
+/// Secret for signing the payloads delivered to a webhook; encrypted at rest.
+/// Attention: Do not accidentally leak the secret!
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct WebhookSecret(ByteArray<WEBHOOK_SECRET_LEN>);
+
+impl WebhookSecret {
+    /// Generate a random secret.
+    pub fn generate() -> Self {
+        let mut secret = [0u8; WEBHOOK_SECRET_LEN];
+        OsRng.fill_bytes(&mut secret);
+        Self(secret.into())
+    }
+
+    /// Expose the secret.
+    pub fn expose_secret(&self) -> ByteArray<WEBHOOK_SECRET_LEN> {
+        self.0
+    }
+
+    /// Sign the given payload sent at the given timestamp (seconds since the UNIX epoch) with
+    /// HMAC-SHA256 over `"{timestamp}.{payload}"`.
+    pub fn sign(&self, timestamp: u64, payload: &str) -> [u8; 32] {
+        hmac_sha256(
+            &self.0.0,
+            &[timestamp.to_string().as_bytes(), b".", payload.as_bytes()],
+        )
+    }
+
+    /// Try to decrypt the given bytes as webhook secret using ChaCha20Poly1305 AEAD with the given
+    /// nonce and ciphertext and the given webhook key.
+    pub fn decrypt(
+        nonce_and_ciphertext: impl AsRef<[u8]>,
+        key: &WebhookKey,
+        cipher: &ChaCha20Poly1305,
+    ) -> Result<Self, DecryptWebhookSecretError> {
+        let nonce_and_ciphertext = nonce_and_ciphertext.as_ref();
+
+        let nonce = &nonce_and_ciphertext[0..12];
+        let ciphertext = &nonce_and_ciphertext[12..];
+
+        let payload = Payload {
+            msg: ciphertext,
+            aad: key.as_ref(),
+        };
+        let bytes = cipher.decrypt(nonce.into(), payload)?.try_into()?;
+
+        Ok(Self(bytes))
+    }
+
+    /// Encrypt this webhook secret using ChaCha20Poly1305 AEAD.
+    pub fn encrypt(
+        &self,
+        key: &WebhookKey,
+        cipher: &ChaCha20Poly1305,
+    ) -> Result<ByteVec, chacha20poly1305::Error> {
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+
+        let payload = Payload {
+            msg: &self.0.0,
+            aad: key.as_ref(),
+        };
+        let mut ciphertext = cipher.encrypt(&nonce, payload)?;
+
+        let mut nonce_and_ciphertext = nonce.to_vec();
+        nonce_and_ciphertext.append(&mut ciphertext);
+
+        Ok(nonce_and_ciphertext.into())
+    }
+}
+
+impl Debug for WebhookSecret {
+    /// Attention: Do not leak the secret!
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "WebhookSecret(REDACTED)")
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum DecryptWebhookSecretError {
+    #[error("cannot decrypt secret")]
+    Decrypt(#[from] chacha20poly1305::Error),
+
+    #[error("cannot convert into webhook secret")]
+    ByteArrayLen(#[from] ByteArrayLenError),
+}
+
+/// Filter selecting the events delivered to a webhook. Each set field subscribes to one kind of
+/// event: `contract_address` to the contract actions of that contract or, together with
+/// `contract_event_type`, to its contract events of that type; `unshielded_address` to the
+/// transactions creating or spending unshielded UTXOs of that address; `bridge_recipient` to the
+/// bridge events for that recipient. Without any field set, each indexed block is delivered.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WebhookFilter {
+    pub contract_address: Option<SerializedContractAddress>,
+
+    /// One of [CONTRACT_EVENT_VARIANTS](crate::domain::CONTRACT_EVENT_VARIANTS); requires
+    /// `contract_address`.
+    pub contract_event_type: Option<&'static str>,
+
+    pub unshielded_address: Option<UnshieldedAddress>,
+
+    pub bridge_recipient: Option<BridgeRecipient>,
+}
+
+impl WebhookFilter {
+    /// Whether no field is set, i.e. each indexed block is delivered.
+    pub fn is_empty(&self) -> bool {
+        self.contract_address.is_none()
+            && self.unshielded_address.is_none()
+            && self.bridge_recipient.is_none()
+    }
+}
+
+/// A registered webhook.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Webhook {
+    pub id: u64,
+
+    pub url: String,
+
+    pub secret: WebhookSecret,
+
+    pub filter: WebhookFilter,
+
+    /// The next transaction to look at for the unshielded address filter.
+    pub transaction_id: u64,
+}
+
+/// The delivery status of an event sent to a webhook.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[sqlx(type_name = "WEBHOOK_DELIVERY_STATUS")]
+pub enum WebhookDeliveryStatus {
+    Pending,
+    Delivered,
+    Dead,
+}
+
+/// A delivery to be enqueued.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewWebhookDelivery {
+    pub webhook_id: u64,
+
+    /// Identifies the event, e.g. `contract-action:42`; deliveries are unique per webhook and key.
+    pub event_key: String,
+
+    pub event_type: &'static str,
+
+    /// The JSON payload.
+    pub payload: String,
+}
+
+/// An enqueued delivery of an event to a webhook.
+#[derive(Debug, Clone, PartialEq, Eq, FromRow)]
+pub struct WebhookDelivery {
+    #[sqlx(try_from = "i64")]
+    pub id: u64,
+
+    #[sqlx(try_from = "i64")]
+    pub webhook_id: u64,
+
+    pub event_key: String,
+
+    pub event_type: String,
+
+    pub payload: String,
+
+    pub status: WebhookDeliveryStatus,
+
+    #[sqlx(try_from = "i64")]
+    pub attempts: u32,
+
+    /// Milliseconds since the UNIX epoch.
+    #[sqlx(try_from = "i64")]
+    pub next_attempt_at: u64,
+
+    #[sqlx(try_from = "SqlxOption<i64>")]
+    pub last_response_status: Option<u64>,
+
+    pub last_error: Option<String>,
+
+    /// Milliseconds since the UNIX epoch.
+    #[sqlx(try_from = "i64")]
+    pub created_at: u64,
+
+    /// Milliseconds since the UNIX epoch.
+    #[sqlx(try_from = "SqlxOption<i64>")]
+    pub delivered_at: Option<u64>,
+}
+
+/// A signed HTTP POST request delivering an event to a webhook.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebhookRequest<'a> {
+    pub url: &'a str,
+
+    /// Additional headers besides the JSON content type.
+    pub headers: Vec<(&'static str, String)>,
+
+    pub body: &'a str,
+}
+
+/// Abstraction for sending webhook requests.
+#[trait_variant::make(Send)]
+pub trait WebhookSender
+where
+    Self: Clone + Send + Sync + 'static,
+{
+    type Error: StdError + Send + Sync + 'static;
+
+    /// Send the given request with the given timeout and return the HTTP status code of the
+    /// response.
+    async fn send(
+        &self,
+        request: WebhookRequest<'_>,
+        timeout: Duration,
+    ) -> Result<u16, Self::Error>;
+}
+
+/// Whether the given IP address is a public one which webhooks may target, i.e. neither an
+/// unspecified, loopback, link-local, private, shared (carrier-grade NAT), multicast, broadcast
+/// nor documentation address. IPv4-mapped IPv6 addresses are checked as IPv4 addresses.
+pub fn is_public_address(address: IpAddr) -> bool {
+    match address {
+        IpAddr::V4(address) => {
+            let [a, b, ..] = address.octets();
+            !(a == 0
+                || address.is_loopback()
+                || address.is_private()
+                || address.is_link_local()
+                || (a == 100 && (b & 0xc0) == 64)
+                || address.is_multicast()
+                || address.is_broadcast()
+                || address.is_documentation())
+        }
+
+        IpAddr::V6(address) => match address.to_ipv4_mapped() {
+            Some(address) => is_public_address(address.into()),
+
+            None => {
+                let first_segment = address.segments()[0];
+                !(address.is_unspecified()
+                    || address.is_loopback()
+                    || (first_segment & 0xffc0) == 0xfe80
+                    || (first_segment & 0xfe00) == 0xfc00
+                    || address.is_multicast()
+                    || (first_segment == 0x2001 && address.segments()[1] == 0x0db8))
+            }
+        },
+    }
+}
+
+/// HMAC-SHA256 (RFC 2104) of the concatenation of the given message parts.
+fn hmac_sha256(key: &[u8], message: &[&[u8]]) -> [u8; 32] {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    for part in message {
+        mac.update(part);
+    }
+
+    mac.finalize().into_bytes().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::webhook::{hmac_sha256, is_public_address};
+
+    // Test cases 1, 2 and 6 from RFC 4231.
+    #[test]
+    fn test_hmac_sha256() {
+        let mac = hmac_sha256(&[0x0b; 20], &[b"Hi There"]);
+        assert_eq!(
+            const_hex::encode(mac),
+            "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7"
+        );
+
+        let mac = hmac_sha256(b"Jefe", &[b"what do ya want ", b"for nothing?"]);
+        assert_eq!(
+            const_hex::encode(mac),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+
+        let mac = hmac_sha256(
+            &[0xaa; 131],
+            &[b"Test Using Larger Than Block-Size Key - Hash Key First"],
+        );
+        assert_eq!(
+            const_hex::encode(mac),
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+    }
+
+    #[test]
+    fn test_is_public_address() {
+        let public = [
+            "93.184.215.14",
+            "100.128.0.1",
+            "2606:2800:21f:cb07:6820:80da:af6b:8b2c",
+        ];
+        for address in public {
+            assert!(is_public_address(address.parse().unwrap()), "{address}");
+        }
+
+        let non_public = [
+            "0.0.0.0",
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "224.0.0.1",
+            "255.255.255.255",
+            "192.0.2.1",
+            "::",
+            "::1",
+            "fe80::1",
+            "fd00::1",
+            "ff02::1",
+            "2001:db8::1",
+            "::ffff:127.0.0.1",
+            "::ffff:169.254.169.254",
+        ];
+        for address in non_public {
+            assert!(!is_public_address(address.parse().unwrap()), "{address}");
+        }
+    }
+}
@@ -12,9 +12,11 @@
 // limitations under the License.
 
 pub mod api;
+pub mod node;
 #[cfg_attr(docsrs, doc(cfg(any(feature = "cloud", feature = "standalone"))))]
 #[cfg(any(feature = "cloud", feature = "standalone"))]
 pub mod storage;
+pub mod webhook;
 
 #[cfg_attr(docsrs, doc(cfg(feature = "cloud")))]
 #[cfg(feature = "cloud")]
@@ -34,5 +36,11 @@ pub struct Config {
     #[serde(rename = "api")]
     pub api_config: api::Config,
 
-    pub secret: secrecy::SecretString,
+    /// Node to submit transactions to; transaction submission is disabled if omitted.
+    #[serde(rename = "submission_node")]
+    pub submission_node_config: Option<node::Config>,
+
+    /// The current secret key to encrypt stored sensitive data and any retired ones.
+    #[serde(flatten)]
+    pub cipher_config: indexer_common::cipher::Config,
 }
@@ -16,7 +16,7 @@ pub mod quota;
 pub mod v4;
 
 use crate::{
-    domain::{Api, LedgerStateCache, storage::Storage},
+    domain::{Api, LedgerStateCache, Node, storage::Storage},
     infra::api::{
         progress_cache::{ProgressCache, ProgressCacheConfig},
         quota::{PerConnectionCounter, QuotaConfig, SubscriptionQuotas},
@@ -39,11 +39,12 @@ use axum::{
 use derive_more::Debug;
 use fastrace_axum::FastraceLayer;
 use indexer_common::{
-    domain::{NetworkId, Subscriber},
+    domain::{NetworkId, Publisher, Subscriber},
     error::StdErrorExt,
 };
 use log::{error, info, warn};
 use metrics::{Gauge, gauge};
+use secrecy::SecretString;
 use serde::Deserialize;
 use std::{
     convert::Infallible,
@@ -71,26 +72,33 @@ use tower_http::{compression::CompressionLayer, cors::CorsLayer, limit::RequestB
 const LENGTH_LIMIT_EXCEEDED_BODY: &[u8] =
     b"Io(Custom { kind: Other, error: \"length limit exceeded\" })";
 
-pub struct AxumApi<S, B> {
+pub struct AxumApi<S, B, P, N> {
     config: Config,
     storage: S,
     subscriber: B,
+    publisher: P,
+    node: Option<N>,
 }
 
-impl<S, B> AxumApi<S, B> {
-    pub fn new(config: Config, storage: S, subscriber: B) -> Self {
+impl<S, B, P, N> AxumApi<S, B, P, N> {
+    /// Create a new [AxumApi]; transaction submission is only enabled if a node is given.
+    pub fn new(config: Config, storage: S, subscriber: B, publisher: P, node: Option<N>) -> Self {
         Self {
             config,
             storage,
             subscriber,
+            publisher,
+            node,
         }
     }
 }
 
-impl<S, B> Api for AxumApi<S, B>
+impl<S, B, P, N> Api for AxumApi<S, B, P, N>
 where
     S: Storage,
     B: Subscriber,
+    P: Publisher,
+    N: Node,
 {
     type Error = AxumApiError;
 
@@ -108,6 +116,8 @@ where
             max_depth,
             subscription_config,
             quota_config,
+            webhooks_config,
+            contract_abis_config,
         } = self.config;
 
         let app = make_app(
@@ -115,11 +125,15 @@ where
             network_id,
             self.storage,
             self.subscriber,
+            self.publisher,
+            self.node,
             request_body_limit as usize,
             max_complexity,
             max_depth,
             subscription_config,
             quota_config,
+            webhooks_config,
+            contract_abis_config,
         );
 
         let listener = TcpListener::bind((address, port))
@@ -134,7 +148,7 @@ where
     }
 }
 
-#[derive(Debug, Clone, Copy, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct Config {
     pub address: IpAddr,
 
@@ -151,6 +165,12 @@ pub struct Config {
 
     #[serde(rename = "quota")]
     pub quota_config: QuotaConfig,
+
+    #[serde(rename = "webhooks", default)]
+    pub webhooks_config: WebhooksConfig,
+
+    #[serde(rename = "contract_abis", default)]
+    pub contract_abis_config: ContractAbisConfig,
 }
 
 #[derive(Debug, Clone, Copy, Deserialize)]
@@ -230,6 +250,48 @@ pub struct UnshieldedTransactionsSubscriptionConfig {
 
     #[serde(with = "humantime_serde")]
     progress_update_interval: Duration,
+
+    /// Maximum number of addresses of a watch list or watched by a single
+    /// `watchedUnshieldedTransactions` subscription.
+    #[serde(default = "max_watched_addresses_default")]
+    max_watched_addresses: usize,
+}
+
+fn max_watched_addresses_default() -> usize {
+    10_000
+}
+
+/// Restrictions for registering and delivering webhooks.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct WebhooksConfig {
+    /// Maximum number of registered webhooks.
+    pub max_webhooks: u64,
+
+    /// Hosts webhooks may be registered for; any host if empty.
+    pub allowed_hosts: Vec<String>,
+
+    /// Whether webhooks may target loopback, link-local, private and other non-public addresses.
+    pub allow_private_addresses: bool,
+}
+
+impl Default for WebhooksConfig {
+    fn default() -> Self {
+        Self {
+            max_webhooks: 100,
+            allowed_hosts: vec![],
+            allow_private_addresses: false,
+        }
+    }
+}
+
+/// Authorization for registering contract ABIs.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ContractAbisConfig {
+    /// Token with which an operator can register or replace the ABI of any contract without the
+    /// signatures of its maintenance authority; disabled if omitted.
+    pub operator_token: Option<SecretString>,
 }
 
 #[derive(Debug, Clone, Copy, Deserialize)]
@@ -262,20 +324,26 @@ impl Default for Metrics {
 }
 
 #[allow(clippy::too_many_arguments)]
-fn make_app<S, B>(
+fn make_app<S, B, P, N>(
     caught_up: Arc<AtomicBool>,
     network_id: NetworkId,
     storage: S,
     subscriber: B,
+    publisher: P,
+    node: Option<N>,
     request_body_limit: usize,
     max_complexity: usize,
     max_depth: usize,
     subscription_config: SubscriptionConfig,
     quota_config: QuotaConfig,
+    webhooks_config: WebhooksConfig,
+    contract_abis_config: ContractAbisConfig,
 ) -> Router
 where
     S: Storage,
     B: Subscriber,
+    P: Publisher,
+    N: Node,
 {
     let ledger_state_cache = LedgerStateCache::default();
     let quotas = SubscriptionQuotas::new(quota_config);
@@ -286,11 +354,15 @@ where
         ledger_state_cache,
         storage,
         subscriber,
+        publisher,
+        node,
         max_complexity,
         max_depth,
         subscription_config,
         quotas,
         progress_cache,
+        webhooks_config,
+        contract_abis_config,
     );
 
     // For some reason the FastraceLayer and RequestBodyLimitLayer cannot be put into a
@@ -422,12 +494,24 @@ trait ContextExt {
     where
         B: Subscriber;
 
+    fn get_publisher<P>(&self) -> &P
+    where
+        P: Publisher;
+
+    fn get_node<N>(&self) -> Option<&N>
+    where
+        N: Node;
+
     fn get_ledger_state_cache(&self) -> &LedgerStateCache;
 
     fn get_metrics(&self) -> &Metrics;
 
     fn get_subscription_config(&self) -> &SubscriptionConfig;
 
+    fn get_webhooks_config(&self) -> &WebhooksConfig;
+
+    fn get_contract_abis_config(&self) -> &ContractAbisConfig;
+
     fn get_subscription_quotas(&self) -> &SubscriptionQuotas;
 
     fn get_progress_cache(&self) -> &ProgressCache;
@@ -499,6 +583,20 @@ impl ContextExt for Context<'_> {
         self.data::<B>().expect("Subscriber is stored in Context")
     }
 
+    fn get_publisher<P>(&self) -> &P
+    where
+        P: Publisher,
+    {
+        self.data::<P>().expect("Publisher is stored in Context")
+    }
+
+    fn get_node<N>(&self) -> Option<&N>
+    where
+        N: Node,
+    {
+        self.data_opt::<N>()
+    }
+
     fn get_ledger_state_cache(&self) -> &LedgerStateCache {
         self.data::<LedgerStateCache>()
             .expect("LedgerStateCache is stored in Context")
@@ -514,6 +612,16 @@ impl ContextExt for Context<'_> {
             .expect("SubscriptionConfig is stored in Context")
     }
 
+    fn get_webhooks_config(&self) -> &WebhooksConfig {
+        self.data::<WebhooksConfig>()
+            .expect("WebhooksConfig is stored in Context")
+    }
+
+    fn get_contract_abis_config(&self) -> &ContractAbisConfig {
+        self.data::<ContractAbisConfig>()
+            .expect("ContractAbisConfig is stored in Context")
+    }
+
     fn get_subscription_quotas(&self) -> &SubscriptionQuotas {
         self.data::<SubscriptionQuotas>()
             .expect("SubscriptionQuotas is stored in Context")
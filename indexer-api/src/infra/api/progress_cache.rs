@@ -11,8 +11,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-//! Cross-subscriber cache for shielded and unshielded progress polling. Concurrent subscribers
-//! for the same wallet or address collapse into a single database hit instead of each polling.
+//! Cross-subscriber cache for shielded, unshielded and wallet status progress polling. Concurrent
+//! subscribers for the same wallet or address collapse into a single database hit instead of each
+//! polling.
 //! Each entry is served for a short time-to-live, after which the next poll re-queries, so a
 //! shared value is at most one time-to-live stale.
 
@@ -31,6 +32,7 @@ type ShieldedIndices = (Option<u64>, Option<u64>, Option<u64>);
 #[derive(Debug, Clone, Copy, Deserialize)]
 pub struct ProgressCacheConfig {
     /// Maximum number of entries, applied independently to the shielded and unshielded caches.
+    /// The chain-wide highest transaction ID is a single entry.
     max_capacity: u64,
 
     /// How long a cached progress value is served before the next poll re-queries. Kept short
@@ -40,13 +42,17 @@ pub struct ProgressCacheConfig {
 }
 
 /// Per-process cache of the most recent progress query result, keyed by wallet ID for
-/// shielded subscriptions and by address for unshielded ones.
+/// shielded subscriptions and by address for unshielded ones, plus the chain-wide highest regular
+/// transaction ID shared by all wallet status queries.
 #[derive(Clone)]
 pub struct ProgressCache {
     shielded: Cache<Uuid, ShieldedIndices>,
 
     /// `UnshieldedAddress` => highest transaction ID for that address.
     unshielded: Cache<UnshieldedAddress, Option<u64>>,
+
+    /// Single entry for the highest regular transaction ID.
+    highest_transaction_id: Cache<(), Option<u64>>,
 }
 
 impl ProgressCache {
@@ -59,10 +65,15 @@ impl ProgressCache {
             .max_capacity(config.max_capacity)
             .time_to_live(config.time_to_live)
             .build();
+        let highest_transaction_id = Cache::builder()
+            .max_capacity(1)
+            .time_to_live(config.time_to_live)
+            .build();
 
         Self {
             shielded,
             unshielded,
+            highest_transaction_id,
         }
     }
 
@@ -94,6 +105,19 @@ impl ProgressCache {
             .await
             .map_err(|error| (*error).clone())
     }
+
+    /// Return the cached highest regular transaction ID, else run `query`, cache and return its
+    /// result. All wallet status queries share this single entry, hence poll the transactions at
+    /// most once per time-to-live.
+    pub async fn highest_transaction_id(
+        &self,
+        query: impl Future<Output = ApiResult<Option<u64>>>,
+    ) -> ApiResult<Option<u64>> {
+        self.highest_transaction_id
+            .try_get_with((), query)
+            .await
+            .map_err(|error| (*error).clone())
+    }
 }
 
 #[cfg(test)]
@@ -215,4 +239,25 @@ mod tests {
             "concurrent cold misses should coalesce into one query"
         );
     }
+
+    #[tokio::test]
+    async fn highest_transaction_id_shared_within_ttl() {
+        let cache = ProgressCache::new(config());
+        let calls = AtomicUsize::new(0);
+        let query = || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Ok::<_, ApiError>(Some(42u64))
+        };
+
+        let first = cache.highest_transaction_id(query()).await.unwrap();
+        let second = cache.highest_transaction_id(query()).await.unwrap();
+
+        assert_eq!(first, Some(42));
+        assert_eq!(second, first);
+        assert_eq!(
+            calls.load(Ordering::SeqCst),
+            1,
+            "second read should hit the cache"
+        );
+    }
 }
@@ -11,15 +11,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-//! GraphQL WebSocket subscription quotas.
+//! GraphQL WebSocket subscription quotas and the transaction submission rate limit.
 //!
-//! Implements two caps:
+//! Implements three caps:
 //!
 //! - Per WebSocket connection, a concurrent count of active subscriptions across all 9 GraphQL
 //!   subscription types. Enforced via a per-connection `AtomicUsize` injected at
 //!   `on_connection_init` and decremented by [`SubscriptionGuard`] on drop.
 //! - Per session id, a creation rate via a token bucket. Only `shielded_transactions` takes a
 //!   `session_id`, so this layer applies there. Rejected attempts do not consume a token.
+//! - Per `indexer-api` instance, a rate of `submitTransaction` mutations via a token bucket, which
+//!   protects the node the transactions are forwarded to.
 //!
 //! Cap hits return [`QuotaError`] which API resolvers convert to `ApiError::client`. The
 //! WebSocket connection itself remains open.
@@ -57,12 +59,17 @@ pub struct QuotaConfig {
     /// as a token bucket whose capacity equals this value and which refills at the same rate
     /// across one minute.
     pub max_session_subscriptions_per_minute: NonZeroU32,
+
+    /// Maximum `submitTransaction` mutations per `indexer-api` instance per minute, implemented
+    /// as a token bucket like the above.
+    pub max_submitted_transactions_per_minute: NonZeroU32,
 }
 
 /// State shared across the entire `indexer-api` instance, held in the async-graphql Schema data.
 pub struct SubscriptionQuotas {
     config: QuotaConfig,
     per_session_buckets: DashMap<SessionId, Arc<Mutex<TokenBucket>>>,
+    submission_bucket: Mutex<TokenBucket>,
     metrics: QuotaMetrics,
 }
 
@@ -71,6 +78,9 @@ impl SubscriptionQuotas {
         Self {
             config,
             per_session_buckets: DashMap::new(),
+            submission_bucket: Mutex::new(TokenBucket::with_rate(
+                config.max_submitted_transactions_per_minute,
+            )),
             metrics: QuotaMetrics::default(),
         }
     }
@@ -117,6 +127,18 @@ impl SubscriptionQuotas {
             active_gauge: self.metrics.active.clone(),
         })
     }
+
+    /// Try to consume a token from the instance-wide transaction submission rate bucket.
+    pub fn try_submit_transaction(&self) -> Result<(), QuotaError> {
+        if !self.submission_bucket.lock().try_take() {
+            self.metrics.rejected_submission_rate.increment(1);
+            return Err(QuotaError::SubmissionRate(
+                self.config.max_submitted_transactions_per_minute.get(),
+            ));
+        }
+
+        Ok(())
+    }
 }
 
 /// RAII handle held by an active subscription. On drop, decrements the per-connection counter and
@@ -141,9 +163,12 @@ pub enum QuotaError {
 
     #[error("per-session rate limit exceeded ({0}/min)")]
     PerSessionRate(u32),
+
+    #[error("submission rate limit exceeded ({0}/min)")]
+    SubmissionRate(u32),
 }
 
-/// Token bucket for per-session creation rate limiting.
+/// Token bucket for per-session creation and transaction submission rate limiting.
 ///
 /// Capacity equals the configured per-minute rate, allowing a fresh session to issue a burst up to
 /// the cap before throttling. Refill is the same number of tokens spread across 60 seconds.
@@ -156,7 +181,11 @@ struct TokenBucket {
 
 impl TokenBucket {
     fn new(config: &QuotaConfig) -> Self {
-        let capacity = f64::from(config.max_session_subscriptions_per_minute.get());
+        Self::with_rate(config.max_session_subscriptions_per_minute)
+    }
+
+    fn with_rate(per_minute: NonZeroU32) -> Self {
+        let capacity = f64::from(per_minute.get());
         Self {
             tokens: capacity,
             capacity,
@@ -186,6 +215,7 @@ struct QuotaMetrics {
     active: Gauge,
     rejected_per_connection: Counter,
     rejected_per_session_rate: Counter,
+    rejected_submission_rate: Counter,
 }
 
 impl Default for QuotaMetrics {
@@ -200,6 +230,7 @@ impl Default for QuotaMetrics {
                 "indexer_subscriptions_rejected_total",
                 "kind" => REJECTION_KIND_PER_SESSION_RATE,
             ),
+            rejected_submission_rate: counter!("indexer_transaction_submissions_rejected_total"),
         }
     }
 }
@@ -213,6 +244,7 @@ mod tests {
         QuotaConfig {
             max_concurrent_per_connection: NonZeroUsize::new(per_connection).unwrap(),
             max_session_subscriptions_per_minute: NonZeroU32::new(per_session_per_minute).unwrap(),
+            max_submitted_transactions_per_minute: NonZeroU32::new(1000).unwrap(),
         }
     }
 
@@ -304,6 +336,25 @@ mod tests {
         assert!(matches!(err, QuotaError::PerSessionRate(1)));
     }
 
+    #[test]
+    fn submission_rate_blocks_after_capacity() {
+        let quotas = SubscriptionQuotas::new(QuotaConfig {
+            max_submitted_transactions_per_minute: NonZeroU32::new(3).unwrap(),
+            ..config(1000, 1000)
+        });
+        let counter = Arc::new(AtomicUsize::new(0));
+
+        for _ in 0..3 {
+            quotas.try_submit_transaction().unwrap();
+        }
+        let err = quotas.try_submit_transaction().unwrap_err();
+        assert!(matches!(err, QuotaError::SubmissionRate(3)));
+
+        // Submissions do not count against subscriptions.
+        let _g = quotas.try_acquire(&counter, Some(session(20))).unwrap();
+        assert_eq!(counter.load(Ordering::Acquire), 1);
+    }
+
     #[test]
     fn token_bucket_refills_over_time() {
         let cfg = config(1000, 60);
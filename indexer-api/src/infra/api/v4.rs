@@ -12,10 +12,15 @@
 // limitations under the License.
 
 pub mod block;
+pub mod block_stats;
 pub mod bridge;
+pub mod chain_stats;
+pub mod connection;
 pub mod contract;
+pub mod contract_abi;
 pub mod contract_action;
 pub mod contract_event;
+pub mod contract_state_diff;
 pub mod dataloader;
 pub mod directives;
 pub mod dust;
@@ -23,22 +28,29 @@ pub mod dust_generations;
 pub mod ledger_events;
 pub mod merkle_tree_collapsed_update;
 pub mod mutation;
+pub mod pending_transaction;
 pub mod query;
+pub mod search;
 pub mod spo;
 pub mod subscription;
 pub mod system_parameters;
+pub mod token;
 pub mod transaction;
 pub mod unshielded;
 pub mod viewing_key;
+pub mod wallet;
+pub mod watch_list;
+pub mod webhook;
 pub mod ws_deflate;
 
 use crate::{
     domain::{
-        LedgerStateCache,
+        LedgerStateCache, Node, NoopNode,
         storage::{NoopStorage, Storage},
     },
     infra::api::{
-        ApiResult, ContextExt, Metrics, OptionExt, ResultExt, SubscriptionConfig,
+        ApiResult, ContextExt, ContractAbisConfig, Metrics, OptionExt, ResultExt,
+        SubscriptionConfig,
         progress_cache::ProgressCache,
         quota::{PerConnectionCounter, SubscriptionQuotas},
         v4::{
@@ -70,7 +82,7 @@ use const_hex::FromHexError;
 use derive_more::{AsRef, Debug, Display};
 use indexer_common::domain::{
     ByteArrayLenError, ByteVec, CardanoRewardAddress as DomainCardanoRewardAddress, NetworkId,
-    NoopSubscriber, SessionId, Subscriber,
+    NoopPublisher, NoopSubscriber, Publisher, SessionId, Subscriber,
 };
 use serde::{Deserialize, Serialize};
 use std::{
@@ -364,30 +376,36 @@ impl From<&NetworkId> for CardanoNetworkId {
 pub fn export_schema() -> String {
     // Once traits with async functions are object safe, `NoopStorage` can be replaced with
     // `<Box<dyn Storage>`.
-    schema_builder::<NoopStorage, NoopSubscriber>()
+    schema_builder::<NoopStorage, NoopSubscriber, NoopPublisher, NoopNode>()
         .finish()
         .sdl()
 }
 
 #[allow(clippy::too_many_arguments)]
-pub fn make_app<S, B>(
+pub fn make_app<S, B, P, N>(
     network_id: NetworkId,
     ledger_state_cache: LedgerStateCache,
     storage: S,
     subscriber: B,
+    publisher: P,
+    node: Option<N>,
     max_complexity: usize,
     max_depth: usize,
     subscription_config: SubscriptionConfig,
     quotas: SubscriptionQuotas,
     progress_cache: ProgressCache,
+    webhooks_config: WebhooksConfig,
+    contract_abis_config: ContractAbisConfig,
 ) -> Router<Arc<AtomicBool>>
 where
     S: Storage,
     B: Subscriber,
+    P: Publisher,
+    N: Node,
 {
     let metrics = Metrics::default();
 
-    let schema = schema_builder::<S, B>()
+    let mut schema = schema_builder::<S, B, P, N>()
         .data(network_id)
         .data(ledger_state_cache)
         .data(DataLoader::new(
@@ -412,18 +430,24 @@ where
         ))
         .data(storage)
         .data(subscriber)
+        .data(publisher)
         .data(metrics)
         .data(subscription_config)
         .data(quotas)
         .data(progress_cache)
+        .data(webhooks_config)
+        .data(contract_abis_config)
         .limit_complexity(max_complexity)
         .limit_depth(max_depth)
-        .limit_recursive_depth(max_depth)
-        .finish();
+        .limit_recursive_depth(max_depth);
+    if let Some(node) = node {
+        schema = schema.data(node);
+    }
+    let schema = schema.finish();
 
     Router::new()
-        .route("/graphql", post(graphql_no_batch::<S, B>))
-        .route("/graphql/ws", get(graphql_ws::<S, B>))
+        .route("/graphql", post(graphql_no_batch::<S, B, P, N>))
+        .route("/graphql/ws", get(graphql_ws::<S, B, P, N>))
         .layer(Extension(schema))
 }
 
@@ -436,14 +460,16 @@ where
 /// zlib-compressed payloads as binary frames (see [`ws_deflate`] for the wire format), all other
 /// clients are byte-for-byte unaffected.
 #[allow(clippy::type_complexity)]
-async fn graphql_ws<S, B>(
-    Extension(schema): Extension<Schema<Query<S>, Mutation<S>, Subscription<S, B>>>,
+async fn graphql_ws<S, B, P, N>(
+    Extension(schema): Extension<Schema<Query<S>, Mutation<S, P, N>, Subscription<S, B>>>,
     protocol: GraphQLProtocol,
     upgrade: WebSocketUpgrade,
 ) -> Response
 where
     S: Storage,
     B: Subscriber,
+    P: Publisher,
+    N: Node,
 {
     upgrade
         .protocols(
@@ -465,15 +491,17 @@ where
 
 /// Runs the GraphQL-over-WebSocket protocol on the given (possibly compression-wrapped) socket,
 /// attaching a fresh [`PerConnectionCounter`] on connection init.
-async fn serve_graphql_ws<St, S, B>(
+async fn serve_graphql_ws<St, S, B, P, N>(
     stream: St,
-    schema: Schema<Query<S>, Mutation<S>, Subscription<S, B>>,
+    schema: Schema<Query<S>, Mutation<S, P, N>, Subscription<S, B>>,
     protocol: GraphQLProtocol,
 ) where
     St: futures::Stream<Item = Result<axum::extract::ws::Message, axum::Error>>
         + futures::Sink<axum::extract::ws::Message, Error = axum::Error>,
     S: Storage,
     B: Subscriber,
+    P: Publisher,
+    N: Node,
 {
     GraphQLWebSocket::new(stream, schema, protocol)
         .on_connection_init(|_payload: serde_json::Value| async move {
@@ -487,25 +515,29 @@ async fn serve_graphql_ws<St, S, B>(
 
 // This prevents batch requests, because `GraphQLRequest` only accepts single requests.
 #[allow(clippy::type_complexity)]
-async fn graphql_no_batch<S, B>(
-    Extension(schema): Extension<Schema<Query<S>, Mutation<S>, Subscription<S, B>>>,
+async fn graphql_no_batch<S, B, P, N>(
+    Extension(schema): Extension<Schema<Query<S>, Mutation<S, P, N>, Subscription<S, B>>>,
     request: GraphQLRequest,
 ) -> GraphQLResponse
 where
     S: Storage,
     B: Subscriber,
+    P: Publisher,
+    N: Node,
 {
     schema.execute(request.into_inner()).await.into()
 }
 
-fn schema_builder<S, B>() -> SchemaBuilder<Query<S>, Mutation<S>, Subscription<S, B>>
+fn schema_builder<S, B, P, N>() -> SchemaBuilder<Query<S>, Mutation<S, P, N>, Subscription<S, B>>
 where
     S: Storage,
     B: Subscriber,
+    P: Publisher,
+    N: Node,
 {
     Schema::build(
         Query::<S>::default(),
-        Mutation::<S>::default(),
+        Mutation::<S, P, N>::default(),
         Subscription::<S, B>::default(),
     )
     .extension(async_graphql::extensions::Tracing)
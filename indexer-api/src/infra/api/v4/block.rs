@@ -17,6 +17,7 @@ use crate::{
         ApiResult, ContextExt, OptionExt, ResultExt,
         v4::{
             HexEncodable, HexEncoded,
+            block_stats::BlockStats,
             directives::beta,
             system_parameters::{DParameter, SystemParameters, TermsAndConditions},
             transaction::Transaction,
@@ -76,6 +77,9 @@ where
     #[graphql(directive = beta::apply())]
     dust_generation_merkle_tree_root: Option<HexEncoded>,
 
+    /// Whether this block is finalized; a not yet finalized block may be replaced by a reorg.
+    is_finalized: bool,
+
     #[graphql(skip)]
     id: u64,
 
@@ -117,6 +121,17 @@ where
         Ok(transactions.into_iter().map(Into::into).collect())
     }
 
+    /// The fee statistics of this block; null for blocks indexed before these were introduced.
+    async fn stats(&self, cx: &Context<'_>) -> ApiResult<Option<BlockStats>> {
+        let stats = cx
+            .get_storage::<S>()
+            .get_block_stats(self.id)
+            .await
+            .map_err_into_server_error(|| format!("get block stats for block id {}", self.id))?;
+
+        Ok(stats.map(Into::into))
+    }
+
     /// The system parameters (governance) at this block height.
     async fn system_parameters(&self, cx: &Context<'_>) -> ApiResult<SystemParameters> {
         let storage = cx.get_storage::<S>();
@@ -226,6 +241,7 @@ where
             dust_generation_end_index,
             dust_commitment_merkle_tree_root,
             dust_generation_merkle_tree_root,
+            finalized,
         } = value;
 
         Block {
@@ -243,6 +259,7 @@ where
                 .map(|root| root.hex_encode()),
             dust_generation_merkle_tree_root: dust_generation_merkle_tree_root
                 .map(|root| root.hex_encode()),
+            is_finalized: finalized,
             id,
             raw_hash: hash,
             parent_hash,
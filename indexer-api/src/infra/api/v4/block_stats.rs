@@ -0,0 +1,105 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::domain;
+use async_graphql::SimpleObject;
+use indexer_common::domain::fee_percentile;
+
+/// Fee statistics of a block. Fees are in SPECK (atomic unit of DUST) as decimal strings and only
+/// regular transactions pay fees.
+#[derive(Debug, Clone, PartialEq, Eq, SimpleObject)]
+pub struct BlockStats {
+    /// The block height.
+    block_height: u32,
+
+    /// The number of transactions, regular and system ones.
+    transaction_count: u64,
+
+    /// The total size of the transactions in bytes.
+    transaction_bytes: u64,
+
+    /// The sum of the paid fees.
+    fee_sum: String,
+
+    /// The minimum paid fees; null if there are no regular transactions.
+    fee_min: Option<String>,
+
+    /// The median (nearest-rank) of the paid fees; null if there are no regular transactions.
+    fee_median: Option<String>,
+
+    /// The 90th percentile (nearest-rank) of the paid fees; null if there are no regular
+    /// transactions.
+    fee_p90: Option<String>,
+}
+
+impl From<domain::BlockStats> for BlockStats {
+    fn from(value: domain::BlockStats) -> Self {
+        let domain::BlockStats {
+            block_height,
+            transaction_count,
+            transaction_bytes,
+            fee_sum,
+            fee_min,
+            fee_median,
+            fee_p90,
+        } = value;
+
+        Self {
+            block_height,
+            transaction_count,
+            transaction_bytes,
+            fee_sum: fee_sum.to_string(),
+            fee_min: fee_min.map(|fee| fee.to_string()),
+            fee_median: fee_median.map(|fee| fee.to_string()),
+            fee_p90: fee_p90.map(|fee| fee.to_string()),
+        }
+    }
+}
+
+/// A fee estimate based on the paid fees of the regular transactions in a window of recent blocks.
+#[derive(Debug, Clone, PartialEq, Eq, SimpleObject)]
+pub struct FeeEstimate {
+    /// The requested percentile.
+    percentile: u8,
+
+    /// The given percentile (nearest-rank) of the paid fees within the window in SPECK (atomic
+    /// unit of DUST) as decimal string; null if there are no regular transactions in the window.
+    fee: Option<String>,
+
+    /// The height of the first block of the window.
+    from_height: u32,
+
+    /// The height of the last block of the window, i.e. the latest block.
+    to_height: u32,
+
+    /// The number of regular transactions in the window.
+    transaction_count: u64,
+}
+
+impl FeeEstimate {
+    pub fn new(recent_fees: domain::RecentFees, percentile: u8) -> Self {
+        let domain::RecentFees {
+            from_height,
+            to_height,
+            fees,
+        } = recent_fees;
+
+        Self {
+            percentile,
+            fee: fee_percentile(&fees, percentile).map(|fee| fee.to_string()),
+            from_height,
+            to_height,
+            transaction_count: fees.len() as u64,
+        }
+    }
+}
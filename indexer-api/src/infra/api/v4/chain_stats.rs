@@ -0,0 +1,113 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::domain;
+use async_graphql::{Enum, SimpleObject};
+
+/// The granularity of chain statistics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
+pub enum ChainStatsGranularity {
+    /// Per block.
+    Block,
+
+    /// Per UTC day.
+    Day,
+}
+
+impl From<ChainStatsGranularity> for domain::ChainStatsGranularity {
+    fn from(granularity: ChainStatsGranularity) -> Self {
+        match granularity {
+            ChainStatsGranularity::Block => Self::Block,
+            ChainStatsGranularity::Day => Self::Day,
+        }
+    }
+}
+
+/// Chain statistics of a block or a UTC day.
+#[derive(Debug, Clone, PartialEq, Eq, SimpleObject)]
+pub struct ChainStats {
+    /// The block timestamp or the start of the day, UNIX timestamp in milliseconds.
+    timestamp: u64,
+
+    /// The block height; null for days.
+    block_height: Option<u32>,
+
+    /// The number of blocks, i.e. 1 for a block.
+    block_count: u64,
+
+    /// The number of regular transactions.
+    regular_transaction_count: u64,
+
+    /// The number of system transactions.
+    system_transaction_count: u64,
+
+    /// The number of contract deploys.
+    contract_deploy_count: u64,
+
+    /// The number of contract calls.
+    contract_call_count: u64,
+
+    /// The number of contract updates.
+    contract_update_count: u64,
+
+    /// The number of created unshielded UTXOs.
+    unshielded_utxos_created: u64,
+
+    /// The number of spent unshielded UTXOs.
+    unshielded_utxos_spent: u64,
+
+    /// The number of zswap ledger events.
+    zswap_ledger_event_count: u64,
+
+    /// The number of DUST ledger events.
+    dust_ledger_event_count: u64,
+
+    /// The number of distinct unshielded addresses owning UTXOs created or spent; null for blocks.
+    active_address_count: Option<u64>,
+}
+
+impl From<domain::ChainStats> for ChainStats {
+    fn from(value: domain::ChainStats) -> Self {
+        let domain::ChainStats {
+            timestamp,
+            block_height,
+            block_count,
+            regular_transaction_count,
+            system_transaction_count,
+            contract_deploy_count,
+            contract_call_count,
+            contract_update_count,
+            unshielded_utxos_created,
+            unshielded_utxos_spent,
+            zswap_ledger_event_count,
+            dust_ledger_event_count,
+            active_address_count,
+        } = value;
+
+        Self {
+            timestamp,
+            block_height,
+            block_count,
+            regular_transaction_count,
+            system_transaction_count,
+            contract_deploy_count,
+            contract_call_count,
+            contract_update_count,
+            unshielded_utxos_created,
+            unshielded_utxos_spent,
+            zswap_ledger_event_count,
+            dust_ledger_event_count,
+            active_address_count,
+        }
+    }
+}
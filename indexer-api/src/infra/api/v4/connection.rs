@@ -0,0 +1,208 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Relay-compliant connections (`edges`, `pageInfo`, opaque cursors) for paginated list queries,
+//! built from keyset pages keyed by the monotonic database IDs (or another unique key).
+
+use crate::{
+    domain::storage::Page,
+    infra::api::{ApiResult, OptionExt},
+};
+use async_graphql::{
+    OutputType,
+    connection::{Connection, CursorType, Edge, OpaqueCursor},
+};
+use serde::{Serialize, de::DeserializeOwned};
+
+/// Number of edges of a page if neither `first` nor `last` is given.
+const DEFAULT_PAGE_SIZE: u32 = 100;
+
+/// Maximum number of edges of a page; larger `first` or `last` values are capped.
+const MAX_PAGE_SIZE: u32 = 500;
+
+/// Opaque cursor of an edge, encoding the key of its node.
+pub type Cursor<K = u64> = OpaqueCursor<K>;
+
+/// Validated Relay connection arguments with decoded cursors.
+#[derive(Debug)]
+pub struct ConnectionArgs<K = u64> {
+    after: Option<K>,
+    before: Option<K>,
+    size: u32,
+    backward: bool,
+}
+
+impl<K> ConnectionArgs<K>
+where
+    K: Clone + Serialize + DeserializeOwned + Send + Sync,
+{
+    /// Validate the given Relay connection arguments: at most one of `first` and `last`, which
+    /// must not be negative, and cursors previously handed out for the same query.
+    pub fn new(
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+    ) -> ApiResult<Self> {
+        (first.is_none() || last.is_none())
+            .then_some(())
+            .some_or_client_error(|| "first and last must not be given both")?;
+
+        let (size, backward) = match (first, last) {
+            (_, Some(last)) => (page_size(last, "last")?, true),
+            (Some(first), _) => (page_size(first, "first")?, false),
+            (None, None) => (DEFAULT_PAGE_SIZE, false),
+        };
+
+        let after = after
+            .map(|after| decode_cursor(&after, "after"))
+            .transpose()?;
+        let before = before
+            .map(|before| decode_cursor(&before, "before"))
+            .transpose()?;
+
+        Ok(Self {
+            after,
+            before,
+            size,
+            backward,
+        })
+    }
+
+    /// The page to fetch from storage; one item larger than requested to tell whether there are
+    /// more items beyond it.
+    pub fn page(&self) -> Page<K> {
+        Page {
+            after: self.after.clone(),
+            before: self.before.clone(),
+            limit: self.size + 1,
+            backward: self.backward,
+        }
+    }
+
+    /// Create a connection from the items fetched for [ConnectionArgs::page], in ascending key
+    /// order, using the given functions to get the key of an item and to convert it into a node.
+    pub fn into_connection<T, N>(
+        self,
+        mut items: Vec<T>,
+        key: impl Fn(&T) -> K,
+        into_node: impl Fn(T) -> N,
+    ) -> Connection<Cursor<K>, N>
+    where
+        N: OutputType,
+    {
+        let size = self.size as usize;
+        let more = items.len() > size;
+
+        // The surplus item is the one with the highest key when paginating forward and the one with
+        // the lowest key when paginating backward. Like the Relay specification permits, the
+        // opposite direction is only reported to have more items if there is a cursor for it.
+        let (has_previous_page, has_next_page) = if self.backward {
+            if more {
+                items.drain(..items.len() - size);
+            }
+            (more, self.before.is_some())
+        } else {
+            items.truncate(size);
+            (self.after.is_some(), more)
+        };
+
+        let mut connection = Connection::new(has_previous_page, has_next_page);
+        connection.edges.extend(
+            items
+                .into_iter()
+                .map(|item| Edge::new(OpaqueCursor(key(&item)), into_node(item))),
+        );
+
+        connection
+    }
+}
+
+fn page_size(size: i32, name: &str) -> ApiResult<u32> {
+    u32::try_from(size)
+        .ok()
+        .some_or_client_error(|| format!("{name} must not be negative"))
+        .map(|size| size.min(MAX_PAGE_SIZE))
+}
+
+fn decode_cursor<K>(cursor: &str, name: &str) -> ApiResult<K>
+where
+    K: Serialize + DeserializeOwned + Send + Sync,
+{
+    Cursor::<K>::decode_cursor(cursor)
+        .ok()
+        .some_or_client_error(|| format!("invalid {name} cursor"))
+        .map(|cursor| cursor.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cursor(id: u64) -> Option<String> {
+        Some(Cursor::<u64>(id).encode_cursor())
+    }
+
+    #[test]
+    fn test_connection_args() {
+        let args = ConnectionArgs::<u64>::new(None, None, None, None).unwrap();
+        assert_eq!(args.page().limit, DEFAULT_PAGE_SIZE + 1);
+        assert!(!args.page().backward);
+
+        let args = ConnectionArgs::<u64>::new(Some(1_000), cursor(7), None, None).unwrap();
+        assert_eq!(args.page().limit, MAX_PAGE_SIZE + 1);
+        assert_eq!(args.page().after, Some(7));
+
+        assert!(ConnectionArgs::<u64>::new(Some(1), None, Some(1), None).is_err());
+        assert!(ConnectionArgs::<u64>::new(Some(-1), None, None, None).is_err());
+        assert!(ConnectionArgs::<u64>::new(None, Some("foo".to_string()), None, None).is_err());
+    }
+
+    #[test]
+    fn test_into_connection() {
+        // Forward: the surplus item with the highest key is dropped.
+        let args = ConnectionArgs::<u64>::new(Some(2), cursor(1), None, None).unwrap();
+        let connection = args.into_connection(vec![2, 3, 4], |id| *id, |id| id);
+        assert_eq!(
+            connection
+                .edges
+                .iter()
+                .map(|edge| edge.node)
+                .collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+        assert!(connection.has_previous_page);
+        assert!(connection.has_next_page);
+
+        // Backward: the surplus item with the lowest key is dropped.
+        let args = ConnectionArgs::<u64>::new(None, None, Some(2), None).unwrap();
+        let connection = args.into_connection(vec![2, 3, 4], |id| *id, |id| id);
+        assert_eq!(
+            connection
+                .edges
+                .iter()
+                .map(|edge| edge.node)
+                .collect::<Vec<_>>(),
+            vec![3, 4]
+        );
+        assert!(connection.has_previous_page);
+        assert!(!connection.has_next_page);
+
+        // Last page.
+        let args = ConnectionArgs::<u64>::new(Some(2), None, None, None).unwrap();
+        let connection = args.into_connection(vec![1], |id| *id, |id| id);
+        assert_eq!(connection.edges.len(), 1);
+        assert!(!connection.has_previous_page);
+        assert!(!connection.has_next_page);
+    }
+}
@@ -19,16 +19,23 @@ use crate::{
     domain::{self, storage::Storage},
     infra::api::{
         ApiResult, ContextExt, OptionExt, ResultExt,
-        v4::{HexEncodable, HexEncoded, contract_action::ContractAction, directives::beta},
+        v4::{
+            HexEncodable, HexEncoded,
+            connection::{ConnectionArgs, Cursor},
+            contract_abi::{get_decoded_state, get_state_field},
+            contract_action::ContractAction,
+            directives::beta,
+        },
     },
 };
-use async_graphql::{ComplexObject, Context, Enum, SimpleObject};
+use async_graphql::{ComplexObject, Context, Enum, Json, SimpleObject, connection::Connection};
 use indexer_common::domain::{
     ContractMaintenanceAuthority as DomainContractMaintenanceAuthority,
     ContractMaintenanceVerifyingKey as DomainContractMaintenanceVerifyingKey,
     SerializedContractAddress, SerializedContractState, VerifyingKeyKind as DomainVerifyingKeyKind,
     ledger::ContractState,
 };
+use serde_json::Value;
 use std::marker::PhantomData;
 
 /// Default number of recent actions returned by `Contract.actions` when no `limit` is given.
@@ -114,6 +121,27 @@ where
         Ok(authority.into())
     }
 
+    /// The state as of the queried block decoded into JSON with the ABI registered for this
+    /// contract; null if none has been registered.
+    #[graphql(directive = beta::apply())]
+    async fn decoded_state(&self, cx: &Context<'_>) -> ApiResult<Option<Json<Value>>> {
+        get_decoded_state::<S>(&self.raw_address, &self.raw_state, self.transaction_id, cx).await
+    }
+
+    /// The value at the given dot-separated path, e.g. `balances.alice`, in the decoded state;
+    /// null if there is no such value or no ABI has been registered for this contract.
+    #[graphql(directive = beta::apply())]
+    async fn state_field(&self, cx: &Context<'_>, path: String) -> ApiResult<Option<Json<Value>>> {
+        get_state_field::<S>(
+            &self.raw_address,
+            &self.raw_state,
+            self.transaction_id,
+            &path,
+            cx,
+        )
+        .await
+    }
+
     /// Recent contract actions for this contract, newest first, optionally filtered by type;
     /// `limit` defaults to 100 and is capped at 500. Use the `contractActions` subscription to
     /// enumerate all actions.
@@ -143,6 +171,33 @@ where
 
         Ok(actions.into_iter().map(Into::into).collect())
     }
+
+    /// Contract actions for this contract, oldest first, optionally filtered by type, as a Relay
+    /// connection; pages hold 100 actions unless `first` or `last` is given, which are capped at
+    /// 500. Use `last` to get the most recent actions.
+    #[graphql(directive = beta::apply())]
+    async fn actions_connection(
+        &self,
+        cx: &Context<'_>,
+        r#type: Option<ContractActionType>,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+    ) -> ApiResult<Connection<Cursor, ContractAction<S>>> {
+        let args = ConnectionArgs::new(first, after, last, before)?;
+        let variant = r#type.map(ContractActionType::variant_name);
+
+        let actions = cx
+            .get_storage::<S>()
+            .get_contract_actions_page_by_address(&self.raw_address, variant, &args.page())
+            .await
+            .map_err_into_server_error(|| {
+                format!("get contract actions page for address {}", self.raw_address)
+            })?;
+
+        Ok(args.into_connection(actions, |action| action.id, Into::into))
+    }
 }
 
 /// Contract action variant, used to filter `Contract.actions`.
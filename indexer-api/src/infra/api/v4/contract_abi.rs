@@ -0,0 +1,85 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    domain::{state_field, storage::Storage},
+    infra::api::{ApiResult, ContextExt, ResultExt, v4::contract_action::get_contract_state},
+};
+use async_graphql::{Context, Json};
+use indexer_common::domain::{
+    SerializedContractAddress, SerializedContractState, ledger::ContractStateValue,
+};
+use serde_json::Value;
+
+/// Get the given serialized state of the contract with the given address, created by the
+/// transaction with the given ID, decoded with the ABI registered for that contract, if any.
+pub(super) async fn get_decoded_state<S>(
+    address: &SerializedContractAddress,
+    state: &SerializedContractState,
+    transaction_id: u64,
+    cx: &Context<'_>,
+) -> ApiResult<Option<Json<Value>>>
+where
+    S: Storage,
+{
+    let abi = cx
+        .get_storage::<S>()
+        .get_contract_abi(address)
+        .await
+        .map_err_into_server_error(|| format!("get contract ABI for address {address}"))?;
+    let Some(abi) = abi else {
+        return Ok(None);
+    };
+
+    let data = get_state_data::<S>(state, transaction_id, cx).await?;
+    let state = abi.decode(&data).map_err_into_client_error(|| {
+        format!("decode contract state with ABI registered for address {address}")
+    })?;
+
+    Ok(Some(Json(state)))
+}
+
+/// Like [get_decoded_state], but only get the value at the given dot-separated path.
+pub(super) async fn get_state_field<S>(
+    address: &SerializedContractAddress,
+    state: &SerializedContractState,
+    transaction_id: u64,
+    path: &str,
+    cx: &Context<'_>,
+) -> ApiResult<Option<Json<Value>>>
+where
+    S: Storage,
+{
+    let state = get_decoded_state::<S>(address, state, transaction_id, cx).await?;
+    let value = state.and_then(|Json(state)| state_field(&state, path).cloned().map(Json));
+
+    Ok(value)
+}
+
+/// Get the data of the given serialized contract state, created by the transaction with the given
+/// ID.
+pub(super) async fn get_state_data<S>(
+    state: &SerializedContractState,
+    transaction_id: u64,
+    cx: &Context<'_>,
+) -> ApiResult<ContractStateValue>
+where
+    S: Storage,
+{
+    let data = get_contract_state::<S>(state, transaction_id, cx)
+        .await?
+        .data()
+        .map_err_into_server_error(|| "get contract state data")?;
+
+    Ok(data)
+}
@@ -18,16 +18,21 @@ use crate::{
         v4::{
             HexEncodable, HexEncoded,
             block::BlockOffset,
+            contract_abi::{get_decoded_state, get_state_field},
             contract_event::ContractEvent,
+            contract_state_diff::{ContractStateDiff, get_state_diff},
             directives::beta,
             transaction::{Transaction, TransactionOffset},
             unshielded::ContractBalance,
         },
     },
 };
-use async_graphql::{ComplexObject, Context, Interface, OneofObject, SimpleObject};
+use async_graphql::{ComplexObject, Context, Interface, Json, OneofObject, SimpleObject};
 use derive_more::Debug;
-use indexer_common::domain::{ContractAttributes, SerializedContractAddress};
+use indexer_common::domain::{
+    ContractAttributes, SerializedContractAddress, SerializedContractState, ledger::ContractState,
+};
+use serde_json::Value;
 use std::marker::PhantomData;
 
 /// A contract action.
@@ -38,7 +43,18 @@ use std::marker::PhantomData;
     field(name = "state", ty = "&HexEncoded"),
     field(name = "zswap_state", ty = "&HexEncoded"),
     field(name = "transaction", ty = "ApiResult<Transaction<S>>"),
-    field(name = "unshielded_balances", ty = "ApiResult<Vec<ContractBalance>>")
+    field(name = "unshielded_balances", ty = "ApiResult<Vec<ContractBalance>>"),
+    field(name = "decoded_state", ty = "ApiResult<Option<Json<Value>>>"),
+    field(
+        name = "state_field",
+        ty = "ApiResult<Option<Json<Value>>>",
+        arg(name = "path", ty = "String")
+    ),
+    field(
+        name = "state_diff",
+        ty = "ApiResult<Option<ContractStateDiff>>",
+        arg(name = "against", ty = "Option<ContractActionOffset>")
+    )
 )]
 pub enum ContractAction<S: Storage> {
     /// A contract deployment.
@@ -73,6 +89,8 @@ where
                 zswap_state: zswap_state.hex_encode(),
                 transaction_id,
                 contract_action_id: id,
+                raw_address: address,
+                raw_state: state,
                 _s: PhantomData,
             }),
 
@@ -84,6 +102,7 @@ where
                 transaction_id,
                 contract_action_id: id,
                 raw_address: address,
+                raw_state: state,
                 _s: PhantomData,
             }),
 
@@ -93,6 +112,8 @@ where
                 zswap_state: zswap_state.hex_encode(),
                 transaction_id,
                 contract_action_id: id,
+                raw_address: address,
+                raw_state: state,
                 _s: PhantomData,
             }),
         }
@@ -121,6 +142,13 @@ where
     #[graphql(skip)]
     contract_action_id: u64,
 
+    #[graphql(skip)]
+    raw_address: SerializedContractAddress,
+
+    #[debug(skip)]
+    #[graphql(skip)]
+    raw_state: SerializedContractState,
+
     #[graphql(skip)]
     _s: PhantomData<S>,
 }
@@ -150,6 +178,44 @@ where
 
         Ok(balances.into_iter().map(Into::into).collect())
     }
+
+    /// The state decoded into JSON with the ABI registered for this contract; null if none has
+    /// been registered.
+    async fn decoded_state(&self, cx: &Context<'_>) -> ApiResult<Option<Json<Value>>> {
+        get_decoded_state::<S>(&self.raw_address, &self.raw_state, self.transaction_id, cx).await
+    }
+
+    /// The value at the given dot-separated path, e.g. `balances.alice`, in the decoded state;
+    /// null if there is no such value or no ABI has been registered for this contract.
+    async fn state_field(&self, cx: &Context<'_>, path: String) -> ApiResult<Option<Json<Value>>> {
+        get_state_field::<S>(
+            &self.raw_address,
+            &self.raw_state,
+            self.transaction_id,
+            &path,
+            cx,
+        )
+        .await
+    }
+
+    /// The differences of the state to the one of the contract action for the same address at the
+    /// given offset or, by default, of the preceding contract action for the same address; null if
+    /// there is no such contract action.
+    async fn state_diff(
+        &self,
+        cx: &Context<'_>,
+        against: Option<ContractActionOffset>,
+    ) -> ApiResult<Option<ContractStateDiff>> {
+        get_state_diff::<S>(
+            &self.raw_address,
+            self.contract_action_id,
+            &self.raw_state,
+            self.transaction_id,
+            against,
+            cx,
+        )
+        .await
+    }
 }
 
 /// A contract call.
@@ -180,6 +246,10 @@ where
     #[graphql(skip)]
     raw_address: SerializedContractAddress,
 
+    #[debug(skip)]
+    #[graphql(skip)]
+    raw_state: SerializedContractState,
+
     #[graphql(skip)]
     _s: PhantomData<S>,
 }
@@ -240,6 +310,44 @@ where
         Ok(balances.into_iter().map(Into::into).collect())
     }
 
+    /// The state decoded into JSON with the ABI registered for this contract; null if none has
+    /// been registered.
+    async fn decoded_state(&self, cx: &Context<'_>) -> ApiResult<Option<Json<Value>>> {
+        get_decoded_state::<S>(&self.raw_address, &self.raw_state, self.transaction_id, cx).await
+    }
+
+    /// The value at the given dot-separated path, e.g. `balances.alice`, in the decoded state;
+    /// null if there is no such value or no ABI has been registered for this contract.
+    async fn state_field(&self, cx: &Context<'_>, path: String) -> ApiResult<Option<Json<Value>>> {
+        get_state_field::<S>(
+            &self.raw_address,
+            &self.raw_state,
+            self.transaction_id,
+            &path,
+            cx,
+        )
+        .await
+    }
+
+    /// The differences of the state to the one of the contract action for the same address at the
+    /// given offset or, by default, of the preceding contract action for the same address; null if
+    /// there is no such contract action.
+    async fn state_diff(
+        &self,
+        cx: &Context<'_>,
+        against: Option<ContractActionOffset>,
+    ) -> ApiResult<Option<ContractStateDiff>> {
+        get_state_diff::<S>(
+            &self.raw_address,
+            self.contract_action_id,
+            &self.raw_state,
+            self.transaction_id,
+            against,
+            cx,
+        )
+        .await
+    }
+
     /// Contract events emitted by this contract call.
     ///
     /// Only `ContractCall` exposes this field — `ContractDeploy` and
@@ -297,6 +405,13 @@ where
     #[graphql(skip)]
     contract_action_id: u64,
 
+    #[graphql(skip)]
+    raw_address: SerializedContractAddress,
+
+    #[debug(skip)]
+    #[graphql(skip)]
+    raw_state: SerializedContractState,
+
     #[graphql(skip)]
     _s: PhantomData<S>,
 }
@@ -326,6 +441,44 @@ where
 
         Ok(balances.into_iter().map(Into::into).collect())
     }
+
+    /// The state decoded into JSON with the ABI registered for this contract; null if none has
+    /// been registered.
+    async fn decoded_state(&self, cx: &Context<'_>) -> ApiResult<Option<Json<Value>>> {
+        get_decoded_state::<S>(&self.raw_address, &self.raw_state, self.transaction_id, cx).await
+    }
+
+    /// The value at the given dot-separated path, e.g. `balances.alice`, in the decoded state;
+    /// null if there is no such value or no ABI has been registered for this contract.
+    async fn state_field(&self, cx: &Context<'_>, path: String) -> ApiResult<Option<Json<Value>>> {
+        get_state_field::<S>(
+            &self.raw_address,
+            &self.raw_state,
+            self.transaction_id,
+            &path,
+            cx,
+        )
+        .await
+    }
+
+    /// The differences of the state to the one of the contract action for the same address at the
+    /// given offset or, by default, of the preceding contract action for the same address; null if
+    /// there is no such contract action.
+    async fn state_diff(
+        &self,
+        cx: &Context<'_>,
+        against: Option<ContractActionOffset>,
+    ) -> ApiResult<Option<ContractStateDiff>> {
+        get_state_diff::<S>(
+            &self.raw_address,
+            self.contract_action_id,
+            &self.raw_state,
+            self.transaction_id,
+            against,
+            cx,
+        )
+        .await
+    }
 }
 
 /// Either a block offset or a transaction offset.
@@ -351,3 +504,90 @@ where
 
     Ok(transaction.into())
 }
+
+/// Get the latest contract action for the given address at the given offset.
+pub(super) async fn get_contract_action_by_offset<S>(
+    address: &SerializedContractAddress,
+    offset: ContractActionOffset,
+    cx: &Context<'_>,
+) -> ApiResult<Option<domain::ContractAction>>
+where
+    S: Storage,
+{
+    let storage = cx.get_storage::<S>();
+
+    let contract_action = match offset {
+        ContractActionOffset::BlockOffset(BlockOffset::Hash(hash)) => {
+            let hash = hash
+                .hex_decode()
+                .map_err_into_client_error(|| "invalid offset")?;
+
+            storage
+                .get_contract_action_by_address_and_block_hash(address, hash)
+                .await
+                .map_err_into_server_error(|| {
+                    format!("get contract action by address {address} and block hash {hash}")
+                })?
+        }
+
+        ContractActionOffset::BlockOffset(BlockOffset::Height(height)) => storage
+            .get_contract_action_by_address_and_block_height(address, height)
+            .await
+            .map_err_into_server_error(|| {
+                format!("get contract action by address {address} and block height {height}")
+            })?,
+
+        ContractActionOffset::TransactionOffset(TransactionOffset::Hash(hash)) => {
+            let hash = hash
+                .hex_decode()
+                .map_err_into_client_error(|| "invalid offset")?;
+
+            storage
+                .get_contract_action_by_address_and_transaction_hash(address, hash)
+                .await
+                .map_err_into_server_error(|| {
+                    format!("get contract action by address {address} and transaction hash {hash}")
+                })?
+        }
+
+        ContractActionOffset::TransactionOffset(TransactionOffset::Identifier(identifier)) => {
+            let identifier = identifier
+                .hex_decode()
+                .map_err_into_client_error(|| "invalid identifier")?;
+
+            storage
+                .get_contract_action_by_address_and_transaction_identifier(address, &identifier)
+                .await
+                .map_err_into_server_error(|| {
+                    format!(
+                        "get contract action by address {address} and transaction identifier \
+                         {identifier}"
+                    )
+                })?
+        }
+    };
+
+    Ok(contract_action)
+}
+
+/// Deserialize the given serialized contract state, created by the transaction with the given ID.
+pub(super) async fn get_contract_state<S>(
+    state: &SerializedContractState,
+    transaction_id: u64,
+    cx: &Context<'_>,
+) -> ApiResult<ContractState>
+where
+    S: Storage,
+{
+    let protocol_version = cx
+        .get_storage::<S>()
+        .get_protocol_version_by_transaction_id(transaction_id)
+        .await
+        .map_err_into_server_error(|| {
+            format!("get protocol version for transaction id {transaction_id}")
+        })?
+        .some_or_server_error(|| format!("no transaction with id {transaction_id}"))?;
+
+    ContractState::deserialize(state, protocol_version.ledger_version())
+        .map_err_into_server_error(|| "deserialize contract state")
+}
@@ -1090,7 +1090,7 @@ impl ContractEventFilter {
 
 impl ContractEventType {
     /// The `LEDGER_EVENT_VARIANT` value matching this type.
-    fn variant_name(self) -> &'static str {
+    pub(super) fn variant_name(self) -> &'static str {
         match self {
             Self::ShieldedSpend => "ShieldedSpend",
             Self::ShieldedReceive => "ShieldedReceive",
@@ -0,0 +1,251 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    domain::{self, storage::Storage},
+    infra::api::{
+        ApiResult, ContextExt, ResultExt,
+        v4::{
+            HexEncodable, HexEncoded,
+            contract::ContractMaintenanceAuthority,
+            contract_action::{
+                ContractActionOffset, get_contract_action_by_offset, get_contract_state,
+            },
+        },
+    },
+};
+use async_graphql::{Context, Enum, SimpleObject};
+use indexer_common::domain::{SerializedContractAddress, SerializedContractState};
+
+/// The differences between the states of two contract actions for the same address.
+#[derive(Debug, Clone, SimpleObject)]
+pub struct ContractStateDiff {
+    /// The changed state values, in state order.
+    changes: Vec<ContractStateChange>,
+
+    /// The changed unshielded token balances.
+    balance_deltas: Vec<ContractBalanceDelta>,
+
+    /// The maintenance authority before and after, if changed.
+    maintenance_authority_change: Option<ContractMaintenanceAuthorityChange>,
+}
+
+impl From<domain::ContractStateDiff> for ContractStateDiff {
+    fn from(diff: domain::ContractStateDiff) -> Self {
+        let domain::ContractStateDiff {
+            changes,
+            balance_deltas,
+            maintenance_authority_change,
+        } = diff;
+
+        Self {
+            changes: changes.into_iter().map(Into::into).collect(),
+            balance_deltas: balance_deltas.into_iter().map(Into::into).collect(),
+            maintenance_authority_change: maintenance_authority_change.map(|(before, after)| {
+                ContractMaintenanceAuthorityChange {
+                    before: before.into(),
+                    after: after.into(),
+                }
+            }),
+        }
+    }
+}
+
+/// A changed state value.
+#[derive(Debug, Clone, SimpleObject)]
+pub struct ContractStateChange {
+    /// The kind of this change.
+    kind: ContractStateChangeKind,
+
+    /// The path from the root of the contract state to the changed state value.
+    path: Vec<ContractStatePathSegment>,
+
+    /// The hex-encoded atoms of a changed cell before the change or the hex-encoded serialized
+    /// root of a changed Merkle tree, if any; empty for other kinds of changes.
+    before: Vec<HexEncoded>,
+
+    /// The hex-encoded atoms of a changed cell after the change or the hex-encoded serialized root
+    /// of a changed Merkle tree, if any; empty for other kinds of changes.
+    after: Vec<HexEncoded>,
+}
+
+impl From<domain::ContractStateChange> for ContractStateChange {
+    fn from(change: domain::ContractStateChange) -> Self {
+        let domain::ContractStateChange { path, kind } = change;
+
+        let (kind, before, after) = match kind {
+            domain::ContractStateChangeKind::CellChanged { before, after } => (
+                ContractStateChangeKind::CellChanged,
+                hex_encode_atoms(before),
+                hex_encode_atoms(after),
+            ),
+
+            domain::ContractStateChangeKind::Inserted => {
+                (ContractStateChangeKind::Inserted, vec![], vec![])
+            }
+
+            domain::ContractStateChangeKind::Removed => {
+                (ContractStateChangeKind::Removed, vec![], vec![])
+            }
+
+            domain::ContractStateChangeKind::MerkleTreeChanged { before, after } => (
+                ContractStateChangeKind::MerkleTreeChanged,
+                before.into_iter().map(|root| root.hex_encode()).collect(),
+                after.into_iter().map(|root| root.hex_encode()).collect(),
+            ),
+
+            domain::ContractStateChangeKind::Replaced => {
+                (ContractStateChangeKind::Replaced, vec![], vec![])
+            }
+        };
+
+        Self {
+            kind,
+            path: path.into_iter().map(Into::into).collect(),
+            before,
+            after,
+        }
+    }
+}
+
+/// The kind of a contract state change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
+pub enum ContractStateChangeKind {
+    /// The value of a cell has changed.
+    CellChanged,
+
+    /// A map entry or array element has been inserted; the path ends with its key or index.
+    Inserted,
+
+    /// A map entry or array element has been removed; the path ends with its key or index.
+    Removed,
+
+    /// The root of a Merkle tree has changed, i.e. entries have been inserted or removed.
+    MerkleTreeChanged,
+
+    /// A state value has been replaced with one of another kind.
+    Replaced,
+}
+
+/// A segment of the path to a state value: either an array index or a map key.
+#[derive(Debug, Clone, SimpleObject)]
+pub struct ContractStatePathSegment {
+    /// The index into an array.
+    index: Option<u32>,
+
+    /// The hex-encoded atoms of a map key.
+    key: Option<Vec<HexEncoded>>,
+}
+
+impl From<domain::ContractStatePathSegment> for ContractStatePathSegment {
+    fn from(segment: domain::ContractStatePathSegment) -> Self {
+        match segment {
+            domain::ContractStatePathSegment::Index(index) => Self {
+                index: Some(index),
+                key: None,
+            },
+
+            domain::ContractStatePathSegment::Key(key) => Self {
+                index: None,
+                key: Some(hex_encode_atoms(key)),
+            },
+        }
+    }
+}
+
+/// The changed balance of an unshielded token type.
+#[derive(Debug, Clone, SimpleObject)]
+pub struct ContractBalanceDelta {
+    /// The hex-encoded token type.
+    token_type: HexEncoded,
+
+    /// The amount before the change as string to support u128, zero if there was no balance.
+    before: String,
+
+    /// The amount after the change as string to support u128, zero if there is no balance.
+    after: String,
+
+    /// The signed difference as string, e.g. `-42` or `+42`.
+    delta: String,
+}
+
+impl From<domain::ContractBalanceDelta> for ContractBalanceDelta {
+    fn from(delta: domain::ContractBalanceDelta) -> Self {
+        let domain::ContractBalanceDelta {
+            token_type,
+            before,
+            after,
+        } = delta;
+
+        let delta = if after >= before {
+            format!("+{}", after - before)
+        } else {
+            format!("-{}", before - after)
+        };
+
+        Self {
+            token_type: token_type.hex_encode(),
+            before: before.to_string(),
+            after: after.to_string(),
+            delta,
+        }
+    }
+}
+
+/// A changed maintenance authority.
+#[derive(Debug, Clone, SimpleObject)]
+pub struct ContractMaintenanceAuthorityChange {
+    before: ContractMaintenanceAuthority,
+    after: ContractMaintenanceAuthority,
+}
+
+/// Get the differences from the state of the contract action for the given address at the given
+/// offset or, if no offset is given, of the preceding contract action for the given address to the
+/// given state of the contract action with the given ID; `None` if there is no such action.
+pub(super) async fn get_state_diff<S>(
+    address: &SerializedContractAddress,
+    contract_action_id: u64,
+    state: &SerializedContractState,
+    transaction_id: u64,
+    against: Option<ContractActionOffset>,
+    cx: &Context<'_>,
+) -> ApiResult<Option<ContractStateDiff>>
+where
+    S: Storage,
+{
+    let against = match against {
+        Some(offset) => get_contract_action_by_offset::<S>(address, offset, cx).await?,
+
+        None => cx
+            .get_storage::<S>()
+            .get_previous_contract_action_by_address(address, contract_action_id)
+            .await
+            .map_err_into_server_error(|| {
+                format!("get previous contract action by address {address}")
+            })?,
+    };
+    let Some(against) = against else {
+        return Ok(None);
+    };
+
+    let before = get_contract_state::<S>(&against.state, against.transaction_id, cx).await?;
+    let after = get_contract_state::<S>(state, transaction_id, cx).await?;
+    let diff = domain::ContractStateDiff::new(&before, &after)
+        .map_err_into_server_error(|| "diff contract states")?;
+
+    Ok(Some(diff.into()))
+}
+
+fn hex_encode_atoms(atoms: Vec<Vec<u8>>) -> Vec<HexEncoded> {
+    atoms.into_iter().map(|atom| atom.hex_encode()).collect()
+}
@@ -12,32 +12,59 @@
 // limitations under the License.
 
 use crate::{
-    domain::storage::Storage,
+    domain::{
+        ContractAbi, Node, SubmitTransactionOutcome, WatchListKey, WebhookKey,
+        contract_abi_registration_message, is_public_address, storage::Storage,
+    },
     infra::api::{
         ApiResult, ContextExt, OptionExt, ResultExt,
-        v4::{HexEncodable, HexEncoded, decode_session_id, viewing_key::ViewingKey},
+        v4::{
+            HexEncodable, HexEncoded,
+            contract_action::get_contract_state,
+            decode_session_id,
+            unshielded::UnshieldedAddress,
+            viewing_key::ViewingKey,
+            watch_list::try_into_domain_addresses,
+            webhook::{RegisteredWebhook, WebhookFilter},
+        },
     },
 };
 use async_graphql::{Context, InputObject, Object, scalar};
 use fastrace::trace;
-use log::debug;
+use indexer_common::{
+    domain::{ByteVec, Publisher, WalletConnected, WalletDisconnected, ledger},
+    error::StdErrorExt,
+};
+use log::{debug, warn};
+use reqwest::Url;
+use secrecy::ExposeSecret;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::marker::PhantomData;
+use tokio::net::lookup_host;
 
-pub struct Mutation<S> {
+pub struct Mutation<S, P, N> {
     _s: PhantomData<S>,
+    _p: PhantomData<P>,
+    _n: PhantomData<N>,
 }
 
-impl<S> Default for Mutation<S> {
+impl<S, P, N> Default for Mutation<S, P, N> {
     fn default() -> Self {
-        Self { _s: PhantomData }
+        Self {
+            _s: PhantomData,
+            _p: PhantomData,
+            _n: PhantomData,
+        }
     }
 }
 
 #[Object]
-impl<S> Mutation<S>
+impl<S, P, N> Mutation<S, P, N>
 where
     S: Storage,
+    P: Publisher,
+    N: Node,
 {
     /// Connect the wallet with the given viewing key and return a session ID.
     #[trace]
@@ -75,6 +102,16 @@ where
 
         debug!(wallet_id:%; "wallet connected");
 
+        // The wallet-indexer also polls the active wallets, hence failing to publish only delays
+        // indexing this wallet and must not fail connecting it.
+        if let Err(error) = cx
+            .get_publisher::<P>()
+            .publish(&WalletConnected { wallet_id })
+            .await
+        {
+            warn!(wallet_id:%, error = error.as_chain(); "cannot publish WalletConnected event");
+        }
+
         Ok(session_id.hex_encode())
     }
 
@@ -99,6 +136,360 @@ where
 
         debug!(wallet_id:%; "wallet disconnected");
 
+        if let Err(error) = cx
+            .get_publisher::<P>()
+            .publish(&WalletDisconnected { wallet_id })
+            .await
+        {
+            warn!(wallet_id:%, error = error.as_chain(); "cannot publish WalletDisconnected event");
+        }
+
+        Ok(Unit)
+    }
+
+    /// Submit the given hex-encoded serialized transaction to the node and return its hex-encoded
+    /// hash. The transaction is validated by deserializing it for the protocol version of the
+    /// latest block. Use the `transactionStatus` subscription to follow its status.
+    #[trace]
+    async fn submit_transaction(&self, cx: &Context<'_>, raw: HexEncoded) -> ApiResult<HexEncoded> {
+        let node = cx
+            .get_node::<N>()
+            .some_or_client_error(|| "transaction submission not enabled")?;
+
+        cx.get_subscription_quotas()
+            .try_submit_transaction()
+            .map_err_into_client_error(|| "transaction submission limit exceeded")?;
+
+        let raw = raw
+            .hex_decode::<ByteVec>()
+            .map_err_into_client_error(|| "invalid transaction")?;
+
+        let ledger_version = cx
+            .get_storage::<S>()
+            .get_latest_block()
+            .await
+            .map_err_into_server_error(|| "get latest block")?
+            .some_or_server_error(|| "no latest block")?
+            .protocol_version
+            .ledger_version();
+
+        let hash = ledger::Transaction::deserialize(&raw, ledger_version)
+            .map_err_into_client_error(|| {
+                format!("invalid transaction for ledger {ledger_version}")
+            })?
+            .hash();
+
+        let outcome = node
+            .submit_transaction(&raw)
+            .await
+            .map_err_into_server_error(|| format!("submit transaction {hash}"))?;
+
+        if let SubmitTransactionOutcome::Rejected { reason } = outcome {
+            debug!(hash:%, reason:%; "transaction rejected");
+            return None.some_or_client_error(|| format!("transaction rejected by node: {reason}"));
+        }
+
+        debug!(hash:%; "transaction submitted");
+
+        Ok(hash.hex_encode())
+    }
+
+    /// Create a watch list with the given name and unshielded addresses and return its hex-encoded
+    /// key, which is needed to subscribe to, change or delete it.
+    #[trace(properties = { "name": "{name}" })]
+    async fn create_watch_list(
+        &self,
+        cx: &Context<'_>,
+        name: String,
+        addresses: Vec<UnshieldedAddress>,
+    ) -> ApiResult<HexEncoded> {
+        let addresses = try_into_domain_addresses(&addresses, cx.get_network_id())
+            .map_err_into_client_error(|| "invalid unshielded address")?;
+
+        let max_watched_addresses = cx
+            .get_subscription_config()
+            .unshielded_transactions
+            .max_watched_addresses;
+        (addresses.len() <= max_watched_addresses)
+            .then_some(())
+            .some_or_client_error(|| {
+                format!("a watch list must not have more than {max_watched_addresses} addresses")
+            })?;
+
+        let key = cx
+            .get_storage::<S>()
+            .create_watch_list(&name, &addresses)
+            .await
+            .map_err_into_server_error(|| "create watch list")?;
+
+        debug!(name:%, address_count = addresses.len(); "watch list created");
+
+        Ok(key.hex_encode())
+    }
+
+    /// Add the given unshielded addresses to the watch list with the given key; already watched
+    /// ones are ignored.
+    #[trace]
+    async fn add_to_watch_list(
+        &self,
+        cx: &Context<'_>,
+        key: HexEncoded,
+        addresses: Vec<UnshieldedAddress>,
+    ) -> ApiResult<Unit> {
+        let key = key
+            .hex_decode::<WatchListKey>()
+            .map_err_into_client_error(|| "invalid watch list key")?;
+        let mut addresses = try_into_domain_addresses(&addresses, cx.get_network_id())
+            .map_err_into_client_error(|| "invalid unshielded address")?;
+
+        let storage = cx.get_storage::<S>();
+
+        let watch_list = storage
+            .get_watch_list(key)
+            .await
+            .map_err_into_server_error(|| "get watch list")?
+            .some_or_client_error(|| "unknown watch list")?;
+
+        addresses.retain(|address| !watch_list.addresses.contains(address));
+        let max_watched_addresses = cx
+            .get_subscription_config()
+            .unshielded_transactions
+            .max_watched_addresses;
+        (watch_list.addresses.len() + addresses.len() <= max_watched_addresses)
+            .then_some(())
+            .some_or_client_error(|| {
+                format!("a watch list must not have more than {max_watched_addresses} addresses")
+            })?;
+
+        storage
+            .add_watch_list_addresses(key, &addresses)
+            .await
+            .map_err_into_server_error(|| "add watch list addresses")?;
+
+        Ok(Unit)
+    }
+
+    /// Remove the given unshielded addresses from the watch list with the given key.
+    #[trace]
+    async fn remove_from_watch_list(
+        &self,
+        cx: &Context<'_>,
+        key: HexEncoded,
+        addresses: Vec<UnshieldedAddress>,
+    ) -> ApiResult<Unit> {
+        let key = key
+            .hex_decode::<WatchListKey>()
+            .map_err_into_client_error(|| "invalid watch list key")?;
+        let addresses = try_into_domain_addresses(&addresses, cx.get_network_id())
+            .map_err_into_client_error(|| "invalid unshielded address")?;
+
+        let storage = cx.get_storage::<S>();
+
+        storage
+            .get_watch_list(key)
+            .await
+            .map_err_into_server_error(|| "get watch list")?
+            .some_or_client_error(|| "unknown watch list")?;
+
+        storage
+            .remove_watch_list_addresses(key, &addresses)
+            .await
+            .map_err_into_server_error(|| "remove watch list addresses")?;
+
+        Ok(Unit)
+    }
+
+    /// Delete the watch list with the given key.
+    #[trace]
+    async fn delete_watch_list(&self, cx: &Context<'_>, key: HexEncoded) -> ApiResult<Unit> {
+        let key = key
+            .hex_decode::<WatchListKey>()
+            .map_err_into_client_error(|| "invalid watch list key")?;
+
+        cx.get_storage::<S>()
+            .delete_watch_list(key)
+            .await
+            .map_err_into_server_error(|| "delete watch list")?;
+
+        Ok(Unit)
+    }
+
+    /// Register a webhook to which the indexed events matching the given filter are POSTed as
+    /// JSON; returns its key and the secret for verifying the payload signatures.
+    #[trace(properties = { "url": "{url}" })]
+    async fn register_webhook(
+        &self,
+        cx: &Context<'_>,
+        url: String,
+        filter: Option<WebhookFilter>,
+    ) -> ApiResult<RegisteredWebhook> {
+        let webhook_url = Url::parse(&url)
+            .ok()
+            .filter(|url| matches!(url.scheme(), "http" | "https"))
+            .some_or_client_error(|| "invalid webhook URL, expected an HTTP(S) URL")?;
+        let filter = filter
+            .unwrap_or_default()
+            .try_into_domain(cx.get_network_id())
+            .map_err_into_client_error(|| "invalid webhook filter")?;
+
+        let webhooks_config = cx.get_webhooks_config();
+
+        let host = webhook_url
+            .host_str()
+            .some_or_client_error(|| "invalid webhook URL, expected a host")?;
+        (webhooks_config.allowed_hosts.is_empty()
+            || webhooks_config
+                .allowed_hosts
+                .iter()
+                .any(|allowed_host| allowed_host.eq_ignore_ascii_case(host)))
+        .then_some(())
+        .some_or_client_error(|| format!("webhook host {host} is not allowed"))?;
+
+        // Checked here to reject obviously internal targets early; as a host name may be
+        // re-pointed after registration, the webhook sender checks the addresses again.
+        if !webhooks_config.allow_private_addresses {
+            let host = host.trim_start_matches('[').trim_end_matches(']');
+            let port = webhook_url.port_or_known_default().unwrap_or_default();
+            let addresses = lookup_host((host, port))
+                .await
+                .map_err_into_client_error(|| format!("cannot resolve webhook host {host}"))?
+                .collect::<Vec<_>>();
+            addresses
+                .iter()
+                .all(|address| is_public_address(address.ip()))
+                .then_some(())
+                .some_or_client_error(|| {
+                    format!("webhook host {host} must only resolve to public addresses")
+                })?;
+        }
+
+        let storage = cx.get_storage::<S>();
+
+        let max_webhooks = webhooks_config.max_webhooks;
+        let webhook_count = storage
+            .get_webhook_count()
+            .await
+            .map_err_into_server_error(|| "get webhook count")?;
+        (webhook_count < max_webhooks)
+            .then_some(())
+            .some_or_client_error(|| {
+                format!("there must not be more than {max_webhooks} registered webhooks")
+            })?;
+
+        let registered_webhook = storage
+            .register_webhook(&url, &filter)
+            .await
+            .map_err_into_server_error(|| "register webhook")?;
+
+        debug!(url:%; "webhook registered");
+
+        Ok(registered_webhook.into())
+    }
+
+    /// Delete the webhook with the given key together with its deliveries.
+    #[trace]
+    async fn delete_webhook(&self, cx: &Context<'_>, key: HexEncoded) -> ApiResult<Unit> {
+        let key = key
+            .hex_decode::<WebhookKey>()
+            .map_err_into_client_error(|| "invalid webhook key")?;
+
+        cx.get_storage::<S>()
+            .delete_webhook(key)
+            .await
+            .map_err_into_server_error(|| "delete webhook")?;
+
+        Ok(Unit)
+    }
+
+    /// Register the given ABI, i.e. the ledger layout as compiled by Compact and given as JSON
+    /// text, for the deployed contract with the given address, so that its state can be queried
+    /// decoded; replaces a previously registered ABI. The ABI must decode the latest state of the
+    /// contract. Registering must be authorized either by signatures of the maintenance authority
+    /// of the contract or by the operator token.
+    #[trace]
+    async fn register_contract_abi(
+        &self,
+        cx: &Context<'_>,
+        address: HexEncoded,
+        abi: String,
+        signatures: Option<Vec<MaintenanceSignature>>,
+        operator_token: Option<String>,
+    ) -> ApiResult<Unit> {
+        let address = address
+            .hex_decode()
+            .map_err_into_client_error(|| "invalid address")?;
+        let contract_abi = serde_json::from_str::<ContractAbi>(&abi)
+            .map_err_into_client_error(|| "invalid ABI")?;
+
+        let storage = cx.get_storage::<S>();
+
+        let contract_action = storage
+            .get_latest_contract_action_by_address(&address)
+            .await
+            .map_err_into_server_error(|| {
+                format!("get latest contract action by address {address}")
+            })?
+            .some_or_client_error(|| format!("no contract deployed at address {address}"))?;
+        let contract_state =
+            get_contract_state::<S>(&contract_action.state, contract_action.transaction_id, cx)
+                .await?;
+
+        let data = contract_state
+            .data()
+            .map_err_into_server_error(|| "get contract state data")?;
+        contract_abi
+            .decode(&data)
+            .map_err_into_client_error(|| "ABI does not match the contract state")?;
+
+        match operator_token {
+            Some(operator_token) => {
+                // Compare hashes to not leak the configured token via timing.
+                cx.get_contract_abis_config()
+                    .operator_token
+                    .as_ref()
+                    .filter(|expected| {
+                        Sha256::digest(expected.expose_secret()) == Sha256::digest(&operator_token)
+                    })
+                    .some_or_client_error(|| "invalid operator token")?;
+            }
+
+            None => {
+                let signatures = signatures
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|MaintenanceSignature { index, signature }| {
+                        signature
+                            .hex_decode::<ByteVec>()
+                            .map(|signature| (index, signature))
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err_into_client_error(|| "invalid maintenance signature")?;
+                let counter = contract_state
+                    .maintenance_authority()
+                    .map_err_into_server_error(|| "get contract maintenance authority")?
+                    .counter;
+                let message = contract_abi_registration_message(address.as_ref(), counter, &abi);
+
+                contract_state
+                    .is_signed_by_maintenance_authority(&message, &signatures)
+                    .map_err_into_client_error(|| "invalid maintenance signature")?
+                    .then_some(())
+                    .some_or_client_error(|| {
+                        format!(
+                            "ABI must be signed by the maintenance authority of the contract at \
+                             address {address}"
+                        )
+                    })?;
+            }
+        }
+
+        storage
+            .register_contract_abi(&address, &contract_abi)
+            .await
+            .map_err_into_server_error(|| "register contract ABI")?;
+
+        debug!(address:%; "contract ABI registered");
+
         Ok(Unit)
     }
 }
@@ -110,6 +501,16 @@ pub struct ConnectOptions {
     start_index: Option<i64>,
 }
 
+/// A signature of a member of the maintenance authority committee of a contract.
+#[derive(Debug, Clone, InputObject)]
+pub struct MaintenanceSignature {
+    /// Index of the signing member in the committee.
+    index: u32,
+
+    /// The tagged-serialized signature.
+    signature: HexEncoded,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Unit;
 
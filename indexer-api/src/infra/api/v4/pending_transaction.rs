@@ -0,0 +1,61 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    domain,
+    infra::api::v4::{HexEncodable, HexEncoded},
+};
+use async_graphql::SimpleObject;
+use derive_more::Debug;
+
+/// A transaction pending in the node's transaction pool, i.e. submitted but not yet included in a
+/// block.
+#[derive(Debug, Clone, SimpleObject)]
+pub struct PendingTransaction {
+    /// The hex-encoded transaction hash.
+    pub hash: HexEncoded,
+
+    /// The protocol version.
+    pub protocol_version: u32,
+
+    /// The hex-encoded serialized transaction content.
+    #[debug(skip)]
+    pub raw: HexEncoded,
+
+    /// The UNIX timestamp (milliseconds) when first seen in the node's transaction pool.
+    pub first_seen: u64,
+
+    /// The UNIX timestamp (milliseconds) when last seen in the node's transaction pool.
+    pub last_seen: u64,
+}
+
+impl From<domain::PendingTransaction> for PendingTransaction {
+    fn from(pending_transaction: domain::PendingTransaction) -> Self {
+        let domain::PendingTransaction {
+            hash,
+            protocol_version,
+            raw,
+            first_seen,
+            last_seen,
+            ..
+        } = pending_transaction;
+
+        Self {
+            hash: hash.hex_encode(),
+            protocol_version: protocol_version.into(),
+            raw: raw.hex_encode(),
+            first_seen,
+            last_seen,
+        }
+    }
+}
@@ -13,7 +13,7 @@
 
 use crate::{
     domain::{
-        LedgerStateCacheError,
+        LedgerStateCacheError, WatchListKey, WebhookKey,
         bridge::TreasuryReason,
         storage::{Storage, bridge::BridgeEventFilter},
     },
@@ -22,34 +22,58 @@ use crate::{
         v4::{
             CardanoNetworkId, CardanoRewardAddress, HexEncoded,
             block::{Block, BlockOffset},
+            block_stats::{BlockStats, FeeEstimate},
             bridge::{
                 BridgeBalance, BridgeEvent, BridgeEventVariant, BridgePoolSummary,
                 BridgeTreasuryReason,
             },
+            chain_stats::{ChainStats, ChainStatsGranularity},
+            connection::{ConnectionArgs, Cursor},
             contract::Contract,
-            contract_action::{ContractAction, ContractActionOffset},
+            contract_action::{
+                ContractAction, ContractActionOffset, get_contract_action_by_offset,
+            },
             contract_event::{ContractEvent, ContractEventFilter},
+            decode_session_id,
             directives::beta,
             dust::DustGenerationStatus,
             dust_generations::DustGenerations,
             merkle_tree_collapsed_update::MerkleTreeCollapsedUpdate,
+            pending_transaction::PendingTransaction,
+            search::{DustAddressSummary, SearchResult, SearchTerm, UnshieldedAddressSummary},
             spo::{
                 CommitteeMember, EpochInfo, EpochPerf, FirstValidEpoch, PoolMetadata,
                 PresenceEvent, RegisteredStat, RegisteredTotals, Spo, SpoComposite, SpoIdentity,
                 StakeShare,
             },
             system_parameters::{DParameterChange, TermsAndConditionsChange},
-            transaction::{Transaction, TransactionOffset},
+            token::{Token, TokenHolder},
+            transaction::{Transaction, TransactionFilter, TransactionOffset},
+            unshielded::{
+                UnshieldedAddress as ApiUnshieldedAddress, UnshieldedBalance, UnshieldedUtxo,
+            },
+            wallet::{WalletStatus, get_wallet_status},
+            watch_list::WatchList,
+            webhook::{WebhookDelivery, WebhookDeliveryStatus},
         },
     },
 };
-use async_graphql::{Context, Object};
+use async_graphql::{Context, Object, connection::Connection};
 use fastrace::trace;
-use indexer_common::domain::{LedgerVersion, UnshieldedAddress, ledger};
+use indexer_common::domain::{BlockHash, LedgerVersion, UnshieldedAddress, ledger};
 use std::marker::PhantomData;
 
 const DEFAULT_PERFORMANCE_LIMIT: i64 = 20;
 
+/// Maximum length in bytes of a search term.
+const MAX_SEARCH_TERM_LEN: usize = 256;
+
+/// Maximum number of SPOs returned when searching by name or ticker.
+const MAX_SPO_SEARCH_RESULTS: i64 = 20;
+
+/// Number of latest blocks whose paid fees are used for fee estimates.
+const FEE_ESTIMATE_WINDOW: u32 = 100;
+
 /// GraphQL queries.
 pub struct Query<S> {
     _s: PhantomData<S>,
@@ -101,6 +125,273 @@ where
         Ok(block.map(Into::into))
     }
 
+    /// Find blocks with heights in the given inclusive range, ordered by height; `limit` defaults
+    /// to 100 and is capped at 500.
+    #[trace(properties = {
+        "from_height": "{from_height}",
+        "to_height": "{to_height}",
+        "limit": "{limit:?}"
+    })]
+    async fn blocks(
+        &self,
+        cx: &Context<'_>,
+        from_height: u32,
+        to_height: u32,
+        limit: Option<i32>,
+    ) -> ApiResult<Vec<Block<S>>> {
+        let limit = limit.unwrap_or(100).clamp(1, 500) as u32;
+
+        let blocks = cx
+            .get_storage::<S>()
+            .get_blocks_by_height_range(from_height, to_height, limit)
+            .await
+            .map_err_into_server_error(|| {
+                format!("get blocks by height range {from_height}..={to_height}")
+            })?;
+
+        Ok(blocks.into_iter().map(Into::into).collect())
+    }
+
+    /// Find the fee statistics of the blocks with heights in the given inclusive range, ordered by
+    /// height; `limit` defaults to 100 and is capped at 500.
+    #[trace(properties = {
+        "from_height": "{from_height}",
+        "to_height": "{to_height}",
+        "limit": "{limit:?}"
+    })]
+    async fn fee_history(
+        &self,
+        cx: &Context<'_>,
+        from_height: u32,
+        to_height: u32,
+        limit: Option<i32>,
+    ) -> ApiResult<Vec<BlockStats>> {
+        let limit = limit.unwrap_or(100).clamp(1, 500) as u32;
+
+        let stats = cx
+            .get_storage::<S>()
+            .get_block_stats_by_height_range(from_height, to_height, limit)
+            .await
+            .map_err_into_server_error(|| {
+                format!("get block stats by height range {from_height}..={to_height}")
+            })?;
+
+        Ok(stats.into_iter().map(Into::into).collect())
+    }
+
+    /// Estimate the fees for a transaction as the given percentile (0 to 100) of the paid fees
+    /// within the latest 100 blocks; null if there are no blocks yet.
+    #[trace(properties = { "percentile": "{percentile}" })]
+    async fn fee_estimate(
+        &self,
+        cx: &Context<'_>,
+        percentile: u8,
+    ) -> ApiResult<Option<FeeEstimate>> {
+        (percentile <= 100)
+            .then_some(())
+            .some_or_client_error(|| "percentile must not be greater than 100")?;
+
+        let recent_fees = cx
+            .get_storage::<S>()
+            .get_recent_fees(FEE_ESTIMATE_WINDOW)
+            .await
+            .map_err_into_server_error(|| "get recent fees")?;
+
+        Ok(recent_fees.map(|recent_fees| FeeEstimate::new(recent_fees, percentile)))
+    }
+
+    /// Find the chain statistics of the given granularity within the given inclusive range of UNIX
+    /// timestamps in milliseconds, ordered by timestamp: per block with a timestamp within the
+    /// range or per UTC day containing any of these; `limit` defaults to 100 and is capped at 500.
+    #[trace(properties = {
+        "granularity": "{granularity:?}",
+        "from": "{from}",
+        "to": "{to}",
+        "limit": "{limit:?}"
+    })]
+    async fn chain_stats(
+        &self,
+        cx: &Context<'_>,
+        granularity: ChainStatsGranularity,
+        from: u64,
+        to: u64,
+        limit: Option<i32>,
+    ) -> ApiResult<Vec<ChainStats>> {
+        let limit = limit.unwrap_or(100).clamp(1, 500) as u32;
+
+        let stats = cx
+            .get_storage::<S>()
+            .get_chain_stats(granularity.into(), from, to, limit)
+            .await
+            .map_err_into_server_error(|| {
+                format!("get chain stats per {granularity:?} for {from}..={to}")
+            })?;
+
+        Ok(stats.into_iter().map(Into::into).collect())
+    }
+
+    /// Find the latest block with a timestamp not after the given UNIX timestamp in milliseconds,
+    /// like `Block.timestamp`; null if there is no such block.
+    #[trace(properties = { "timestamp": "{timestamp}" })]
+    async fn block_at(&self, cx: &Context<'_>, timestamp: u64) -> ApiResult<Option<Block<S>>> {
+        let block = cx
+            .get_storage::<S>()
+            .get_block_by_timestamp(timestamp)
+            .await
+            .map_err_into_server_error(|| format!("get block by timestamp {timestamp}"))?;
+
+        Ok(block.map(Into::into))
+    }
+
+    /// Search for blocks, transactions, contracts, addresses and SPOs matching the given term,
+    /// which can be a block height, a hex-encoded block hash, transaction hash, transaction
+    /// identifier, contract address or SPO pool ID, a Bech32m-encoded unshielded or DUST address,
+    /// a Bech32-encoded Cardano reward address or else an SPO name or ticker.
+    #[trace(properties = { "term": "{term}" })]
+    async fn search(&self, cx: &Context<'_>, term: String) -> ApiResult<Vec<SearchResult<S>>> {
+        (!term.trim().is_empty())
+            .then_some(())
+            .some_or_client_error(|| "search term must not be empty")?;
+        (term.len() <= MAX_SEARCH_TERM_LEN)
+            .then_some(())
+            .some_or_client_error(|| {
+                format!("search term must not be longer than {MAX_SEARCH_TERM_LEN} bytes")
+            })?;
+
+        let storage = cx.get_storage::<S>();
+        let network_id = cx.get_network_id();
+        let mut results = vec![];
+
+        match SearchTerm::parse(&term) {
+            SearchTerm::Height(height) => {
+                let block = storage
+                    .get_block_by_height(height)
+                    .await
+                    .map_err_into_server_error(|| format!("get block by height {height}"))?;
+                results.extend(block.map(Into::into));
+            }
+
+            SearchTerm::Bytes(bytes) => {
+                // Block and transaction hashes have the same length.
+                let mut transactions = vec![];
+                if let Ok(hash) = BlockHash::try_from(bytes.clone()) {
+                    let block = cx
+                        .get_block_by_hash_loader::<S>()
+                        .load_one(hash)
+                        .await
+                        .map_err_into_server_error(|| format!("get block by hash {hash}"))?;
+                    results.extend(block.map(Into::into));
+
+                    transactions = storage
+                        .get_transactions_by_hash(hash)
+                        .await
+                        .map_err_into_server_error(|| format!("get transactions by hash {hash}"))?;
+                }
+
+                let transactions_by_identifier = storage
+                    .get_transactions_by_identifier(&bytes)
+                    .await
+                    .map_err_into_server_error(|| {
+                        format!("get transactions by identifier {bytes}")
+                    })?;
+                for transaction in transactions_by_identifier {
+                    if !transactions.iter().any(|t| t.id() == transaction.id()) {
+                        transactions.push(transaction);
+                    }
+                }
+                results.extend(transactions.into_iter().map(Into::into));
+
+                let contract_action = storage
+                    .get_latest_contract_action_by_address(&bytes)
+                    .await
+                    .map_err_into_server_error(|| {
+                        format!("get latest contract action by address {bytes}")
+                    })?;
+                results.extend(contract_action.map(Into::into));
+
+                // SPO pool IDs are Blake2b-224 hashes of the pool operator key.
+                if bytes.len() == 28 {
+                    let spo = storage
+                        .get_spo_by_pool_id(&const_hex::encode(&bytes))
+                        .await
+                        .map_err_into_server_error(|| "get SPO by pool ID")?;
+                    results.extend(spo.map(Into::into));
+                }
+            }
+
+            SearchTerm::UnshieldedAddress(address) => {
+                let domain_address = address
+                    .try_into_domain(network_id)
+                    .map_err_into_client_error(|| "invalid unshielded address")?;
+
+                let balances = storage
+                    .get_unshielded_balances_by_address(domain_address, None)
+                    .await
+                    .map_err_into_server_error(|| {
+                        format!("get unshielded balances for {domain_address}")
+                    })?
+                    .into_iter()
+                    .map(Into::into)
+                    .collect();
+
+                results.push(SearchResult::UnshieldedAddress(UnshieldedAddressSummary {
+                    address,
+                    balances,
+                }));
+            }
+
+            SearchTerm::DustAddress(address) => {
+                let dust_address = address
+                    .try_into_domain(network_id)
+                    .map_err_into_client_error(|| "invalid DUST address")?;
+
+                let cardano_reward_addresses = storage
+                    .get_cardano_reward_addresses_by_dust_address(&dust_address)
+                    .await
+                    .map_err_into_server_error(|| {
+                        format!("get Cardano reward addresses for DUST address {dust_address}")
+                    })?;
+                let generation_status = storage
+                    .get_dust_generation_status(&cardano_reward_addresses, LedgerVersion::LATEST)
+                    .await
+                    .map_err_into_server_error(|| "get DUST generation status")?
+                    .into_iter()
+                    .map(|status| (status, network_id).into())
+                    .collect();
+
+                results.push(SearchResult::DustAddress(DustAddressSummary {
+                    address,
+                    generation_status,
+                }));
+            }
+
+            SearchTerm::CardanoRewardAddress(address) => {
+                let cardano_reward_address = address
+                    .decode_for_network(CardanoNetworkId::from(network_id))
+                    .map_err_into_client_error(|| "invalid Cardano reward address")?;
+
+                let generation_status = storage
+                    .get_dust_generation_status(&[cardano_reward_address], LedgerVersion::LATEST)
+                    .await
+                    .map_err_into_server_error(|| "get DUST generation status")?;
+
+                results.extend(generation_status.into_iter().map(|status| {
+                    SearchResult::CardanoRewardAddress(Box::new((status, network_id).into()))
+                }));
+            }
+
+            SearchTerm::Text(text) => {
+                let spos = storage
+                    .get_spo_list(MAX_SPO_SEARCH_RESULTS, 0, Some(text.as_str()))
+                    .await
+                    .map_err_into_server_error(|| "get SPO list")?;
+                results.extend(spos.into_iter().map(Into::into));
+            }
+        }
+
+        Ok(results)
+    }
+
     /// Get a Merkle tree collapsed update for the given zswap state index range.
     #[trace(properties = { "start_index": "{start_index}", "end_index": "{end_index}" })]
     async fn zswap_merkle_tree_collapsed_update(
@@ -176,6 +467,282 @@ where
         }
     }
 
+    /// List all transactions, ordered by ID, as a Relay connection; pages hold 100 transactions
+    /// unless `first` or `last` is given, which are capped at 500.
+    #[trace(properties = { "first": "{first:?}", "last": "{last:?}" })]
+    async fn transactions_connection(
+        &self,
+        cx: &Context<'_>,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+    ) -> ApiResult<Connection<Cursor, Transaction<S>>> {
+        let args = ConnectionArgs::new(first, after, last, before)?;
+
+        let transactions = cx
+            .get_storage::<S>()
+            .get_transactions_page(&args.page())
+            .await
+            .map_err_into_server_error(|| "get transactions page")?;
+
+        Ok(args.into_connection(transactions, |transaction| transaction.id(), Into::into))
+    }
+
+    /// Search transactions matching the given filter, ordered by ID, as a Relay connection; pages
+    /// hold 100 transactions unless `first` or `last` is given, which are capped at 500.
+    #[trace(properties = { "filter": "{filter:?}", "first": "{first:?}", "last": "{last:?}" })]
+    async fn search_transactions(
+        &self,
+        cx: &Context<'_>,
+        filter: TransactionFilter,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+    ) -> ApiResult<Connection<Cursor, Transaction<S>>> {
+        let args = ConnectionArgs::new(first, after, last, before)?;
+        let filter = filter
+            .try_into_domain(cx.get_network_id())
+            .map_err_into_client_error(|| "invalid transaction filter")?;
+
+        let transactions = cx
+            .get_storage::<S>()
+            .get_transactions_page_by_filter(&filter, &args.page())
+            .await
+            .map_err_into_server_error(|| "get transactions page by filter")?;
+
+        Ok(args.into_connection(transactions, |transaction| transaction.id(), Into::into))
+    }
+
+    /// Find the transactions pending in the node's transaction pool, i.e. submitted but not yet
+    /// included in a block, ordered by when first seen; optionally only the ones touching the given
+    /// contract address and/or unshielded address.
+    #[trace(properties = {
+        "contract_address": "{contract_address:?}",
+        "unshielded_address": "{unshielded_address:?}"
+    })]
+    async fn pending_transactions(
+        &self,
+        cx: &Context<'_>,
+        contract_address: Option<HexEncoded>,
+        unshielded_address: Option<ApiUnshieldedAddress>,
+    ) -> ApiResult<Vec<PendingTransaction>> {
+        let storage = cx.get_storage::<S>();
+
+        let contract_address = contract_address
+            .map(|address| address.hex_decode())
+            .transpose()
+            .map_err_into_client_error(|| "invalid contract address")?;
+        let unshielded_address = unshielded_address
+            .map(|address| address.try_into_domain(cx.get_network_id()))
+            .transpose()
+            .map_err_into_client_error(|| "invalid unshielded address")?;
+
+        let pending_transactions = storage
+            .get_pending_transactions(contract_address.as_ref(), unshielded_address)
+            .await
+            .map_err_into_server_error(|| "get pending transactions")?
+            .into_iter()
+            .map(Into::into)
+            .collect();
+
+        Ok(pending_transactions)
+    }
+
+    /// Find the unspent unshielded UTXOs of the given address as of the given block or, if
+    /// omitted, the latest block, ordered by creation.
+    #[trace(properties = { "address": "{address:?}", "at_block": "{at_block:?}" })]
+    async fn unshielded_utxos(
+        &self,
+        cx: &Context<'_>,
+        address: ApiUnshieldedAddress,
+        at_block: Option<BlockOffset>,
+    ) -> ApiResult<Vec<UnshieldedUtxo<S>>> {
+        let network_id = cx.get_network_id();
+
+        let address = address
+            .try_into_domain(network_id)
+            .map_err_into_client_error(|| "invalid unshielded address")?;
+        let height = block_height::<S>(cx, at_block).await?;
+
+        let utxos = cx
+            .get_storage::<S>()
+            .get_unspent_unshielded_utxos_by_address(address, height)
+            .await
+            .map_err_into_server_error(|| format!("get unspent unshielded UTXOs for {address}"))?
+            .into_iter()
+            .map(|utxo| UnshieldedUtxo::from((utxo, network_id)))
+            .collect();
+
+        Ok(utxos)
+    }
+
+    /// Find the balances per token type of the given unshielded address, i.e. the sums of the
+    /// values of its unspent UTXOs, as of the given block or, if omitted, the latest block.
+    #[trace(properties = { "address": "{address:?}", "at_block": "{at_block:?}" })]
+    async fn unshielded_balances(
+        &self,
+        cx: &Context<'_>,
+        address: ApiUnshieldedAddress,
+        at_block: Option<BlockOffset>,
+    ) -> ApiResult<Vec<UnshieldedBalance>> {
+        let address = address
+            .try_into_domain(cx.get_network_id())
+            .map_err_into_client_error(|| "invalid unshielded address")?;
+        let height = block_height::<S>(cx, at_block).await?;
+
+        let balances = cx
+            .get_storage::<S>()
+            .get_unshielded_balances_by_address(address, height)
+            .await
+            .map_err_into_server_error(|| format!("get unshielded balances for {address}"))?
+            .into_iter()
+            .map(Into::into)
+            .collect();
+
+        Ok(balances)
+    }
+
+    /// Find the token for the given hex-encoded token type in the token registry.
+    #[trace(properties = { "token_type": "{token_type:?}" })]
+    async fn token(&self, cx: &Context<'_>, token_type: HexEncoded) -> ApiResult<Option<Token>> {
+        let token_type = token_type
+            .hex_decode()
+            .map_err_into_client_error(|| "invalid token type")?;
+
+        let token = cx
+            .get_storage::<S>()
+            .get_token(token_type)
+            .await
+            .map_err_into_server_error(|| format!("get token for token type {token_type}"))?;
+
+        Ok(token.map(Into::into))
+    }
+
+    /// List the tokens of the token registry, ordered by when first seen, as a Relay connection;
+    /// pages hold 100 tokens unless `first` or `last` is given, which are capped at 500.
+    #[trace(properties = { "first": "{first:?}", "last": "{last:?}" })]
+    async fn tokens(
+        &self,
+        cx: &Context<'_>,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+    ) -> ApiResult<Connection<Cursor, Token>> {
+        let args = ConnectionArgs::new(first, after, last, before)?;
+
+        let tokens = cx
+            .get_storage::<S>()
+            .get_tokens_page(&args.page())
+            .await
+            .map_err_into_server_error(|| "get tokens page")?;
+
+        Ok(args.into_connection(tokens, |token| token.id, Into::into))
+    }
+
+    /// Find the holders of the given hex-encoded token type, ordered by descending balance;
+    /// `limit` defaults to 100 and is capped at 500.
+    #[trace(properties = { "token_type": "{token_type:?}", "limit": "{limit:?}" })]
+    async fn token_holders(
+        &self,
+        cx: &Context<'_>,
+        token_type: HexEncoded,
+        limit: Option<i32>,
+    ) -> ApiResult<Vec<TokenHolder>> {
+        let network_id = cx.get_network_id();
+
+        let token_type = token_type
+            .hex_decode()
+            .map_err_into_client_error(|| "invalid token type")?;
+        let limit = limit.unwrap_or(100).clamp(1, 500) as u32;
+
+        let holders = cx
+            .get_storage::<S>()
+            .get_token_holders(token_type, limit)
+            .await
+            .map_err_into_server_error(|| format!("get token holders for token type {token_type}"))?
+            .into_iter()
+            .map(|holder| TokenHolder::from((holder, network_id)))
+            .collect();
+
+        Ok(holders)
+    }
+
+    /// Find the watch list with the given hex-encoded key.
+    #[trace]
+    async fn watch_list(&self, cx: &Context<'_>, key: HexEncoded) -> ApiResult<Option<WatchList>> {
+        let key = key
+            .hex_decode::<WatchListKey>()
+            .map_err_into_client_error(|| "invalid watch list key")?;
+
+        let watch_list = cx
+            .get_storage::<S>()
+            .get_watch_list(key)
+            .await
+            .map_err_into_server_error(|| "get watch list")?
+            .map(|watch_list| WatchList::from((watch_list, cx.get_network_id())));
+
+        Ok(watch_list)
+    }
+
+    /// Get the sync status of the wallet for the given session ID, e.g. to show sync progress
+    /// without subscribing to shielded transactions.
+    #[trace]
+    async fn wallet_status(
+        &self,
+        cx: &Context<'_>,
+        session_id: HexEncoded,
+    ) -> ApiResult<WalletStatus> {
+        let session_id =
+            decode_session_id(session_id).map_err_into_client_error(|| "invalid session ID")?;
+
+        let wallet_id = cx
+            .get_storage::<S>()
+            .resolve_session_id(session_id)
+            .await
+            .map_err_into_server_error(|| "resolve session ID")?
+            .some_or_client_error(|| "unknown or expired session ID")?;
+
+        get_wallet_status::<S>(wallet_id, cx).await
+    }
+
+    /// Find the most recent deliveries of the webhook with the given hex-encoded key, newest first,
+    /// optionally only the ones with the given status; `limit` defaults to 100 and is capped at
+    /// 500.
+    #[trace(properties = { "status": "{status:?}", "limit": "{limit:?}" })]
+    async fn webhook_deliveries(
+        &self,
+        cx: &Context<'_>,
+        key: HexEncoded,
+        status: Option<WebhookDeliveryStatus>,
+        limit: Option<i32>,
+    ) -> ApiResult<Vec<WebhookDelivery>> {
+        let key = key
+            .hex_decode::<WebhookKey>()
+            .map_err_into_client_error(|| "invalid webhook key")?;
+        let limit = limit.unwrap_or(100).clamp(1, 500) as u32;
+
+        let storage = cx.get_storage::<S>();
+
+        let id = storage
+            .get_webhook_id(key)
+            .await
+            .map_err_into_server_error(|| "get webhook ID")?
+            .some_or_client_error(|| "unknown webhook")?;
+
+        let deliveries = storage
+            .get_webhook_deliveries(id, status.map(Into::into), limit)
+            .await
+            .map_err_into_server_error(|| "get webhook deliveries")?
+            .into_iter()
+            .map(Into::into)
+            .collect();
+
+        Ok(deliveries)
+    }
+
     /// Find a contract action for the given address and optional offset.
     #[trace(properties = { "address": "{address}", "offset": "{offset:?}" })]
     async fn contract_action(
@@ -191,56 +758,7 @@ where
             .map_err_into_client_error(|| "invalid address")?;
 
         let contract_action = match offset {
-            Some(ContractActionOffset::BlockOffset(BlockOffset::Hash(hash))) => {
-                let hash = hash
-                    .hex_decode()
-                    .map_err_into_client_error(|| "invalid offset")?;
-
-                storage
-                    .get_contract_action_by_address_and_block_hash(address, hash)
-                    .await
-                    .map_err_into_server_error(|| {
-                        format!("get contract action by address {address} and block hash {hash}")
-                    })?
-            }
-
-            Some(ContractActionOffset::BlockOffset(BlockOffset::Height(height))) => storage
-                .get_contract_action_by_address_and_block_height(address, height)
-                .await
-                .map_err_into_server_error(|| {
-                    format!("get contract action by address {address} and block height {height}")
-                })?,
-
-            Some(ContractActionOffset::TransactionOffset(TransactionOffset::Hash(hash))) => {
-                let hash = hash
-                    .hex_decode()
-                    .map_err_into_client_error(|| "invalid offset")?;
-
-                storage
-                    .get_contract_action_by_address_and_transaction_hash(address, hash)
-                    .await
-                    .map_err_into_server_error(|| {
-                        format!(
-                            "get contract action by address {address} and transaction hash {hash}"
-                        )
-                    })?
-            }
-
-            Some(ContractActionOffset::TransactionOffset(TransactionOffset::Identifier(
-                identifier,
-            ))) => {
-                let identifier = identifier
-                    .hex_decode()
-                    .map_err_into_client_error(|| "invalid identifier")?;
-
-                storage
-                    .get_contract_action_by_address_and_transaction_identifier(
-                        address,
-                        &identifier,
-                    )
-                    .await
-                    .map_err_into_server_error(|| format!("get contract action by address {address} and transaction identifier {identifier}"))?
-            }
+            Some(offset) => get_contract_action_by_offset::<S>(address, offset, cx).await?,
 
             None => storage
                 .get_latest_contract_action_by_address(address)
@@ -462,6 +980,41 @@ where
             .map_err_into_server_error(|| "convert contract event row to GraphQL type")
     }
 
+    /// List contract events matching the filter, ordered by ID, as a Relay connection; pages hold
+    /// 100 events unless `first` or `last` is given, which are capped at 500.
+    #[graphql(directive = beta::apply())]
+    #[trace(properties = { "first": "{first:?}", "last": "{last:?}" })]
+    async fn contract_events_connection(
+        &self,
+        cx: &Context<'_>,
+        filter: ContractEventFilter,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+    ) -> ApiResult<Connection<Cursor, ContractEvent<S>>> {
+        let args = ConnectionArgs::new(first, after, last, before)?;
+
+        let filter = filter
+            .into_domain()
+            .map_err(|error| ApiError::client("invalid contract event filter", error))?;
+
+        let contract_events = cx
+            .get_storage::<S>()
+            .get_contract_events_page(&filter, &args.page())
+            .await
+            .map_err_into_server_error(|| "get contract events page")?
+            .into_iter()
+            .map(|row| {
+                let id = row.id;
+                ContractEvent::try_from(row).map(|event| (id, event))
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err_into_server_error(|| "convert contract event row to GraphQL type")?;
+
+        Ok(args.into_connection(contract_events, |(id, _)| *id, |(_, event)| event))
+    }
+
     /// Get the full history of D-parameter changes for governance auditability.
     #[trace]
     async fn d_parameter_history(&self, cx: &Context<'_>) -> ApiResult<Vec<DParameterChange>> {
@@ -632,6 +1185,33 @@ where
         Ok(spos.into_iter().map(Into::into).collect())
     }
 
+    /// List SPOs with optional search, ordered by pool ID, as a Relay connection; pages hold 100
+    /// SPOs unless `first` or `last` is given, which are capped at 500.
+    #[trace]
+    async fn spo_list_connection(
+        &self,
+        cx: &Context<'_>,
+        search: Option<String>,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+    ) -> ApiResult<Connection<Cursor<String>, Spo>> {
+        let args = ConnectionArgs::new(first, after, last, before)?;
+        let search_ref = search
+            .as_deref()
+            .map(str::trim)
+            .filter(|search| !search.is_empty());
+
+        let spos = cx
+            .get_storage::<S>()
+            .get_spo_list_page(search_ref, &args.page())
+            .await
+            .map_err_into_server_error(|| "get SPO list page")?;
+
+        Ok(args.into_connection(spos, |spo| spo.pool_id_hex.clone(), Into::into))
+    }
+
     /// Get composite SPO data (identity + metadata + performance).
     #[trace]
     async fn spo_composite_by_pool_id(
@@ -913,6 +1493,45 @@ where
         Ok(events.into_iter().map(Into::into).collect())
     }
 
+    /// List c2m-bridge events with optional filters, ordered by ID, as a Relay connection; pages
+    /// hold 100 events unless `first` or `last` is given, which are capped at 500.
+    #[trace]
+    #[allow(clippy::too_many_arguments)]
+    #[graphql(directive = beta::apply())]
+    async fn bridge_events_connection(
+        &self,
+        cx: &Context<'_>,
+        recipient: Option<HexEncoded>,
+        variant: Option<BridgeEventVariant>,
+        block_height_from: Option<u64>,
+        block_height_to: Option<u64>,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+    ) -> ApiResult<Connection<Cursor, BridgeEvent>> {
+        let args = ConnectionArgs::new(first, after, last, before)?;
+        let recipient = recipient
+            .map(|h| h.hex_decode::<UnshieldedAddress>())
+            .transpose()
+            .map_err_into_client_error(|| "invalid recipient address")?;
+
+        let filter = BridgeEventFilter {
+            variants: variant.map(Into::into).into_iter().collect(),
+            recipient,
+            block_height_from,
+            block_height_to,
+            id_from: None,
+        };
+        let events = cx
+            .get_storage::<S>()
+            .get_bridge_events_page(&filter, &args.page())
+            .await
+            .map_err_into_server_error(|| "get bridge events page")?;
+
+        Ok(args.into_connection(events, |event| event.id, Into::into))
+    }
+
     /// Get the c2m-bridge balance summary (deposited, claimed, balance) for an address.
     #[trace]
     #[graphql(directive = beta::apply())]
@@ -1061,6 +1680,33 @@ where
     }
 }
 
+/// Resolve the given optional block offset into a block height; `None` stands for the latest block.
+async fn block_height<S>(cx: &Context<'_>, offset: Option<BlockOffset>) -> ApiResult<Option<u32>>
+where
+    S: Storage,
+{
+    match offset {
+        Some(BlockOffset::Hash(hash)) => {
+            let hash = hash
+                .hex_decode()
+                .map_err_into_client_error(|| "invalid block hash")?;
+
+            let block = cx
+                .get_block_by_hash_loader::<S>()
+                .load_one(hash)
+                .await
+                .map_err_into_server_error(|| format!("get block by hash {hash}"))?
+                .some_or_client_error(|| format!("unknown block hash {hash}"))?;
+
+            Ok(Some(block.height))
+        }
+
+        Some(BlockOffset::Height(height)) => Ok(Some(height)),
+
+        None => Ok(None),
+    }
+}
+
 /// Normalize hex string by stripping 0x prefix and lowercasing.
 fn normalize_hex(input: &str) -> String {
     let s = input
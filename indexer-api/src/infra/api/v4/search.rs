@@ -0,0 +1,222 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    domain::{self, spo::Spo as DomainSpo, storage::Storage},
+    infra::api::v4::{
+        AddressType, CardanoRewardAddress,
+        block::Block,
+        contract::Contract,
+        dust::{DustAddress, DustGenerationStatus},
+        spo::Spo,
+        transaction::Transaction,
+        unshielded::{UnshieldedAddress, UnshieldedBalance},
+    },
+};
+use async_graphql::{SimpleObject, Union};
+use indexer_common::domain::ByteVec;
+
+/// A result of the search query.
+#[derive(Union)]
+pub enum SearchResult<S: Storage> {
+    /// A block matching a block hash or height.
+    Block(Box<Block<S>>),
+
+    /// A transaction matching a transaction hash or identifier.
+    Transaction(Box<TransactionSearchResult<S>>),
+
+    /// A contract matching a contract address.
+    Contract(Box<Contract<S>>),
+
+    /// A summary of an unshielded address.
+    UnshieldedAddress(UnshieldedAddressSummary),
+
+    /// A summary of a DUST address.
+    DustAddress(DustAddressSummary),
+
+    /// The DUST generation status of a Cardano reward address.
+    CardanoRewardAddress(Box<DustGenerationStatus>),
+
+    /// An SPO matching a pool ID, name or ticker.
+    Spo(Box<Spo>),
+}
+
+impl<S> From<domain::Block> for SearchResult<S>
+where
+    S: Storage,
+{
+    fn from(block: domain::Block) -> Self {
+        Self::Block(Box::new(block.into()))
+    }
+}
+
+impl<S> From<domain::Transaction> for SearchResult<S>
+where
+    S: Storage,
+{
+    fn from(transaction: domain::Transaction) -> Self {
+        Self::Transaction(Box::new(TransactionSearchResult {
+            transaction: transaction.into(),
+        }))
+    }
+}
+
+impl<S> From<domain::ContractAction> for SearchResult<S>
+where
+    S: Storage,
+{
+    fn from(contract_action: domain::ContractAction) -> Self {
+        Self::Contract(Box::new(contract_action.into()))
+    }
+}
+
+impl<S> From<DomainSpo> for SearchResult<S>
+where
+    S: Storage,
+{
+    fn from(spo: DomainSpo) -> Self {
+        Self::Spo(Box::new(spo.into()))
+    }
+}
+
+/// A transaction matching a transaction hash or identifier.
+#[derive(SimpleObject)]
+pub struct TransactionSearchResult<S>
+where
+    S: Storage,
+{
+    /// The matching transaction.
+    pub transaction: Transaction<S>,
+}
+
+/// A summary of an unshielded address.
+#[derive(SimpleObject)]
+pub struct UnshieldedAddressSummary {
+    /// The Bech32m-encoded unshielded address.
+    pub address: UnshieldedAddress,
+
+    /// The balances per token type of the unspent UTXOs of this address.
+    pub balances: Vec<UnshieldedBalance>,
+}
+
+/// A summary of a DUST address.
+#[derive(SimpleObject)]
+pub struct DustAddressSummary {
+    /// The Bech32m-encoded DUST address.
+    pub address: DustAddress,
+
+    /// The DUST generation status of each Cardano reward address currently registered for this
+    /// DUST address.
+    pub generation_status: Vec<DustGenerationStatus>,
+}
+
+/// A search term, classified by its format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchTerm {
+    /// A block height.
+    Height(u32),
+
+    /// Hex-encoded bytes, i.e. a block or transaction hash, a transaction identifier, a contract
+    /// address or an SPO pool ID; an optional `0x` prefix is ignored.
+    Bytes(ByteVec),
+
+    /// A Bech32m-encoded unshielded address.
+    UnshieldedAddress(UnshieldedAddress),
+
+    /// A Bech32m-encoded DUST address.
+    DustAddress(DustAddress),
+
+    /// A Bech32-encoded Cardano reward address.
+    CardanoRewardAddress(CardanoRewardAddress),
+
+    /// Anything else, used to search SPOs by name or ticker.
+    Text(String),
+}
+
+impl SearchTerm {
+    /// Classify the given search term; addresses are only classified by their prefix, i.e. not yet
+    /// validated.
+    pub fn parse(term: &str) -> Self {
+        let term = term.trim();
+
+        if term.bytes().all(|b| b.is_ascii_digit())
+            && let Ok(height) = term.parse()
+        {
+            return Self::Height(height);
+        }
+
+        let hex = term
+            .strip_prefix("0x")
+            .or_else(|| term.strip_prefix("0X"))
+            .unwrap_or(term);
+        if !hex.is_empty()
+            && let Ok(bytes) = const_hex::decode(hex)
+        {
+            return Self::Bytes(bytes.into());
+        }
+
+        if term.starts_with(AddressType::Unshielded.hrp_prefix()) {
+            return Self::UnshieldedAddress(UnshieldedAddress(term.to_owned()));
+        }
+
+        if term.starts_with(AddressType::Dust.hrp_prefix()) {
+            return Self::DustAddress(DustAddress(term.to_owned()));
+        }
+
+        if (term.starts_with("stake1") || term.starts_with("stake_test1"))
+            && let Ok(address) = CardanoRewardAddress::try_from(term)
+        {
+            return Self::CardanoRewardAddress(address);
+        }
+
+        Self::Text(term.to_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::infra::api::v4::{
+        AddressType, encode_address, search::SearchTerm, unshielded::UnshieldedAddress,
+    };
+    use indexer_common::domain::ByteVec;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(SearchTerm::parse(" 42 "), SearchTerm::Height(42));
+
+        let hash = "0x".to_string() + &"ab".repeat(32);
+        assert_eq!(
+            SearchTerm::parse(&hash),
+            SearchTerm::Bytes(ByteVec::from(vec![0xab; 32]))
+        );
+
+        // Too large for a height, but an even number of hex digits.
+        assert_eq!(
+            SearchTerm::parse("123456789012"),
+            SearchTerm::Bytes(ByteVec::from(vec![0x12, 0x34, 0x56, 0x78, 0x90, 0x12]))
+        );
+
+        let network_id = "undeployed".try_into().unwrap();
+        let address = encode_address([1; 32], AddressType::Unshielded, &network_id);
+        assert_eq!(
+            SearchTerm::parse(&address),
+            SearchTerm::UnshieldedAddress(UnshieldedAddress(address))
+        );
+
+        assert_eq!(
+            SearchTerm::parse("stakefish"),
+            SearchTerm::Text("stakefish".to_string())
+        );
+        assert_eq!(SearchTerm::parse(""), SearchTerm::Text("".to_string()));
+    }
+}
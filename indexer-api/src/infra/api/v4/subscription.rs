@@ -18,10 +18,14 @@ mod contract_event;
 mod dust_generations;
 mod dust_ledger_events;
 mod dust_nullifier_transactions;
+mod pending_transactions;
 mod polling;
 mod shielded;
 mod shielded_nullifier_transactions;
+mod transaction_status;
 mod unshielded;
+mod wallet_status;
+mod watched_unshielded;
 mod zswap_ledger_events;
 
 use crate::{
@@ -32,9 +36,12 @@ use crate::{
         dust_generations::DustGenerationsSubscription,
         dust_ledger_events::DustLedgerEventsSubscription,
         dust_nullifier_transactions::DustNullifierTransactionsSubscription,
+        pending_transactions::PendingTransactionsSubscription,
         shielded::ShieldedTransactionsSubscription,
         shielded_nullifier_transactions::ShieldedNullifierTransactionsSubscription,
-        unshielded::UnshieldedTransactionsSubscription,
+        transaction_status::TransactionStatusSubscription,
+        unshielded::UnshieldedTransactionsSubscription, wallet_status::WalletStatusSubscription,
+        watched_unshielded::WatchedUnshieldedTransactionsSubscription,
         zswap_ledger_events::ZswapLedgerEventsSubscription,
     },
 };
@@ -50,9 +57,13 @@ pub struct Subscription<S, B>(
     DustGenerationsSubscription<S, B>,
     DustLedgerEventsSubscription<S, B>,
     DustNullifierTransactionsSubscription<S, B>,
+    PendingTransactionsSubscription<S, B>,
     ShieldedNullifierTransactionsSubscription<S, B>,
     ShieldedTransactionsSubscription<S, B>,
+    TransactionStatusSubscription<S, B>,
     UnshieldedTransactionsSubscription<S, B>,
+    WalletStatusSubscription<S, B>,
+    WatchedUnshieldedTransactionsSubscription<S, B>,
     ZswapLedgerEventsSubscription<S, B>,
 )
 where
@@ -73,9 +84,13 @@ where
             DustGenerationsSubscription::default(),
             DustLedgerEventsSubscription::default(),
             DustNullifierTransactionsSubscription::default(),
+            PendingTransactionsSubscription::default(),
             ShieldedNullifierTransactionsSubscription::default(),
             ShieldedTransactionsSubscription::default(),
+            TransactionStatusSubscription::default(),
             UnshieldedTransactionsSubscription::default(),
+            WalletStatusSubscription::default(),
+            WatchedUnshieldedTransactionsSubscription::default(),
             ZswapLedgerEventsSubscription::default(),
         )
     }
@@ -24,10 +24,10 @@ use crate::{
 use async_graphql::{Context, Subscription};
 use async_stream::try_stream;
 use fastrace::{Span, future::FutureExt, prelude::SpanContext};
-use futures::{Stream, TryStreamExt};
-use indexer_common::domain::{BlockIndexed, Subscriber};
+use futures::{Stream, TryStreamExt, stream};
+use indexer_common::domain::{BlockHash, BlockIndexed, BlocksFinalized, Subscriber};
 use log::{debug, warn};
-use std::{marker::PhantomData, pin::pin};
+use std::{collections::VecDeque, marker::PhantomData, pin::pin};
 
 pub struct BlockSubscription<S, B> {
     _s: PhantomData<S>,
@@ -50,11 +50,14 @@ where
     B: Subscriber,
 {
     /// Subscribe to blocks starting at the given offset or at the latest block if the offset is
-    /// omitted.
+    /// omitted. Not yet finalized blocks are only delivered if `includeUnfinalized` is true; then a
+    /// block with a height not greater than the one of a previously delivered block signals a
+    /// reorg and replaces the previously delivered blocks from its height on.
     async fn blocks<'a>(
         &self,
         cx: &'a Context<'a>,
         offset: Option<BlockOffset>,
+        include_unfinalized: Option<bool>,
     ) -> Result<impl Stream<Item = ApiResult<Block<S>>> + use<'a, S, B>, ApiError> {
         let quota_guard = cx
             .get_subscription_quotas()
@@ -65,54 +68,110 @@ where
         let subscriber = cx.get_subscriber::<B>();
         let batch_size = cx.get_subscription_config().blocks.batch_size;
 
-        let block_indexed_stream = subscriber.subscribe::<BlockIndexed>();
+        // Finalizing already indexed blocks makes these deliverable unless including unfinalized
+        // ones, hence finalizations must be considered just like indexed blocks.
+        let block_events = stream::select(
+            subscriber.subscribe::<BlockIndexed>().map_ok(|_| ()),
+            subscriber.subscribe::<BlocksFinalized>().map_ok(|_| ()),
+        );
         let mut height = resolve_height::<S>(offset, cx).await?;
+        let include_unfinalized = include_unfinalized.unwrap_or_default();
 
         let blocks = try_stream! {
             let _hold = quota_guard;
 
+            // Delivered blocks which were not yet finalized, oldest first.
+            let mut unfinalized_blocks = VecDeque::new();
+
             // Stream existing blocks.
             debug!(height; "streaming existing blocks");
 
-            let blocks = storage.get_blocks(height, batch_size);
+            let blocks = storage.get_blocks(height, batch_size, include_unfinalized);
             let mut blocks = pin!(blocks);
             while let Some(block) = get_next_block(&mut blocks)
                 .await
                 .map_err_into_server_error(|| format!("get next block at height {height}"))?
             {
                 height = block.height + 1;
+                track_unfinalized_block(&block, &mut unfinalized_blocks);
                 yield block.into();
             }
 
             // Stream live blocks.
             debug!(height; "streaming live blocks");
-            let mut block_indexed_stream = pin!(block_indexed_stream);
-            while block_indexed_stream
+            let mut block_events = pin!(block_events);
+            while block_events
                 .try_next()
                 .await
-                .map_err_into_server_error(|| "get next BlockIndexed event")?
+                .map_err_into_server_error(|| "get next BlockIndexed or BlocksFinalized event")?
                 .is_some()
             {
+                if let Some(fork_height) = get_fork_height(storage, &mut unfinalized_blocks)
+                    .await
+                    .map_err_into_server_error(|| "get fork height of unfinalized blocks")?
+                {
+                    debug!(fork_height, height; "reorg of unfinalized blocks");
+                    height = fork_height;
+                }
+
                 debug!(height; "streaming next blocks");
 
-                let blocks = storage.get_blocks(height, batch_size);
+                let blocks = storage.get_blocks(height, batch_size, include_unfinalized);
                 let mut blocks = pin!(blocks);
                 while let Some(block) = get_next_block(&mut blocks)
                     .await
                     .map_err_into_server_error(|| format!("get next block at height {height}"))?
                 {
                     height = block.height + 1;
+                    track_unfinalized_block(&block, &mut unfinalized_blocks);
                     yield block.into();
                 }
             }
 
-            warn!("stream of BlockIndexed events completed unexpectedly");
+            warn!("stream of BlockIndexed or BlocksFinalized events completed unexpectedly");
         };
 
         Ok(blocks)
     }
 }
 
+/// Keep track of the delivered unfinalized blocks; these are forgotten once a finalized block has
+/// been delivered.
+fn track_unfinalized_block(
+    block: &domain::Block,
+    unfinalized_blocks: &mut VecDeque<(u32, BlockHash)>,
+) {
+    if block.finalized {
+        unfinalized_blocks.clear();
+    } else {
+        unfinalized_blocks.push_back((block.height, block.hash));
+    }
+}
+
+/// Determine the height of the oldest delivered unfinalized block which has been replaced by a
+/// reorg, forgetting it and all newer ones.
+async fn get_fork_height<S>(
+    storage: &S,
+    unfinalized_blocks: &mut VecDeque<(u32, BlockHash)>,
+) -> Result<Option<u32>, sqlx::Error>
+where
+    S: Storage,
+{
+    for (index, &(height, hash)) in unfinalized_blocks.iter().enumerate() {
+        let stored_hash = storage
+            .get_block_by_height(height)
+            .await?
+            .map(|block| block.hash);
+
+        if stored_hash != Some(hash) {
+            unfinalized_blocks.truncate(index);
+            return Ok(Some(height));
+        }
+    }
+
+    Ok(None)
+}
+
 async fn get_next_block<E>(
     blocks: &mut (impl Stream<Item = Result<domain::Block, E>> + Unpin),
 ) -> Result<Option<domain::Block>, E> {
@@ -0,0 +1,128 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    domain::storage::Storage,
+    infra::api::{
+        ApiError, ApiResult, ContextExt, ResultExt,
+        v4::{HexEncoded, pending_transaction::PendingTransaction, unshielded::UnshieldedAddress},
+    },
+};
+use async_graphql::{Context, Subscription};
+use async_stream::try_stream;
+use futures::{Stream, TryStreamExt};
+use indexer_common::domain::{PendingTransactionsUpdated, Subscriber};
+use log::{debug, warn};
+use std::{collections::HashSet, marker::PhantomData, pin::pin};
+
+pub struct PendingTransactionsSubscription<S, B> {
+    _s: PhantomData<S>,
+    _b: PhantomData<B>,
+}
+
+impl<S, B> Default for PendingTransactionsSubscription<S, B> {
+    fn default() -> Self {
+        Self {
+            _s: PhantomData,
+            _b: PhantomData,
+        }
+    }
+}
+
+#[Subscription]
+impl<S, B> PendingTransactionsSubscription<S, B>
+where
+    S: Storage,
+    B: Subscriber,
+{
+    /// Subscribe to the transactions pending in the node's transaction pool, optionally only the
+    /// ones touching the given contract address and/or unshielded address. The currently pending
+    /// transactions are delivered immediately, further ones as soon as they are seen.
+    async fn pending_transactions<'a>(
+        &self,
+        cx: &'a Context<'a>,
+        contract_address: Option<HexEncoded>,
+        unshielded_address: Option<UnshieldedAddress>,
+    ) -> Result<impl Stream<Item = ApiResult<PendingTransaction>> + use<'a, S, B>, ApiError> {
+        let quota_guard = cx
+            .get_subscription_quotas()
+            .try_acquire(cx.get_per_connection_counter(), None)
+            .map_err_into_client_error(|| "subscription limit exceeded")?;
+
+        let contract_address = contract_address
+            .map(|address| address.hex_decode())
+            .transpose()
+            .map_err_into_client_error(|| "invalid contract address")?;
+        let unshielded_address = unshielded_address
+            .map(|address| address.try_into_domain(cx.get_network_id()))
+            .transpose()
+            .map_err_into_client_error(|| "invalid unshielded address")?;
+
+        let storage = cx.get_storage::<S>();
+        let subscriber = cx.get_subscriber::<B>();
+
+        let pending_transactions_updated_stream =
+            subscriber.subscribe::<PendingTransactionsUpdated>();
+
+        let pending_transactions = try_stream! {
+            let _hold = quota_guard;
+
+            let mut pending_transactions_updated_stream = pin!(pending_transactions_updated_stream);
+
+            // Hashes of the delivered pending transactions which are still pending; IDs cannot be
+            // used to find new ones, because SQLite may reuse the IDs of deleted rows.
+            let mut delivered = HashSet::new();
+
+            'deliver: loop {
+                let pending_transactions = storage
+                    .get_pending_transactions(contract_address.as_ref(), unshielded_address)
+                    .await
+                    .map_err_into_server_error(|| "get pending transactions")?;
+
+                let mut still_pending = HashSet::with_capacity(pending_transactions.len());
+                for pending_transaction in pending_transactions {
+                    still_pending.insert(pending_transaction.hash);
+
+                    if !delivered.contains(&pending_transaction.hash) {
+                        debug!(hash:% = pending_transaction.hash; "delivering pending transaction");
+                        yield PendingTransaction::from(pending_transaction);
+                    }
+                }
+                delivered = still_pending;
+
+                // Only newly saved pending transactions are to be delivered.
+                loop {
+                    let pending_transactions_updated = pending_transactions_updated_stream
+                        .try_next()
+                        .await
+                        .map_err_into_server_error(|| "get next PendingTransactionsUpdated event")?;
+
+                    match pending_transactions_updated {
+                        Some(PendingTransactionsUpdated { saved, .. }) if saved > 0 => break,
+
+                        Some(_) => continue,
+
+                        None => {
+                            warn!(
+                                "stream of PendingTransactionsUpdated events completed unexpectedly"
+                            );
+                            break 'deliver;
+                        }
+                    }
+                }
+            }
+        };
+
+        Ok(pending_transactions)
+    }
+}
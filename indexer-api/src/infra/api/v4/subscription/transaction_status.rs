@@ -0,0 +1,198 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    domain::{Transaction, storage::Storage},
+    infra::api::{
+        ApiError, ApiResult, ContextExt, ResultExt,
+        v4::{HexEncodable, HexEncoded},
+    },
+};
+use async_graphql::{Context, Enum, SimpleObject, Subscription};
+use async_stream::try_stream;
+use futures::{Stream, TryStreamExt, stream};
+use indexer_common::domain::{
+    BlockIndexed, BlocksFinalized, Subscriber, TransactionHash, TransactionResult,
+};
+use log::{debug, warn};
+use std::{marker::PhantomData, pin::pin};
+
+/// The status of a transaction, e.g. a submitted one.
+#[derive(Debug, Clone, PartialEq, Eq, SimpleObject)]
+pub struct TransactionStatusUpdate {
+    /// The status of the transaction.
+    status: TransactionStatus,
+
+    /// The hex-encoded hash of the block including the transaction, unless pending.
+    block_hash: Option<HexEncoded>,
+
+    /// The height of the block including the transaction, unless pending.
+    block_height: Option<u32>,
+}
+
+/// The status of a transaction: pending, included in a not yet finalized block, finalized or
+/// failed, the latter two being final.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
+pub enum TransactionStatus {
+    Pending,
+    Included,
+    Finalized,
+    Failed,
+}
+
+pub struct TransactionStatusSubscription<S, B> {
+    _s: PhantomData<S>,
+    _b: PhantomData<B>,
+}
+
+impl<S, B> Default for TransactionStatusSubscription<S, B> {
+    fn default() -> Self {
+        Self {
+            _s: PhantomData,
+            _b: PhantomData,
+        }
+    }
+}
+
+#[Subscription]
+impl<S, B> TransactionStatusSubscription<S, B>
+where
+    S: Storage,
+    B: Subscriber,
+{
+    /// Subscribe to the status of the transaction with the given hex-encoded hash. The current
+    /// status is delivered immediately, any changes as blocks get indexed; a transaction included
+    /// in a block which gets replaced by a reorg becomes pending again. The subscription completes
+    /// once the transaction has been finalized or failed in a finalized block.
+    async fn transaction_status<'a>(
+        &self,
+        cx: &'a Context<'a>,
+        hash: HexEncoded,
+    ) -> Result<impl Stream<Item = ApiResult<TransactionStatusUpdate>> + use<'a, S, B>, ApiError>
+    {
+        let quota_guard = cx
+            .get_subscription_quotas()
+            .try_acquire(cx.get_per_connection_counter(), None)
+            .map_err_into_client_error(|| "subscription limit exceeded")?;
+
+        let hash = hash
+            .hex_decode()
+            .map_err_into_client_error(|| "invalid transaction hash")?;
+
+        let storage = cx.get_storage::<S>();
+        let subscriber = cx.get_subscriber::<B>();
+
+        // Transactions become finalized by indexing blocks as well as by finalizing already
+        // indexed ones.
+        let block_events = stream::select(
+            subscriber.subscribe::<BlockIndexed>().map_ok(|_| ()),
+            subscriber.subscribe::<BlocksFinalized>().map_ok(|_| ()),
+        );
+
+        let updates = try_stream! {
+            let _hold = quota_guard;
+
+            let mut block_events = pin!(block_events);
+            let mut last_update = None;
+
+            loop {
+                let update = get_transaction_status_update(storage, hash)
+                    .await
+                    .map_err_into_server_error(|| format!("get status of transaction {hash}"))?;
+
+                if last_update.as_ref() != Some(&update) {
+                    debug!(hash:%, update:?; "transaction status changed");
+
+                    last_update = Some(update.clone());
+                    let done = matches!(
+                        update.status,
+                        TransactionStatus::Finalized | TransactionStatus::Failed
+                    );
+                    yield update;
+
+                    if done {
+                        break;
+                    }
+                }
+
+                let block_event = block_events
+                    .try_next()
+                    .await
+                    .map_err_into_server_error(|| {
+                        "get next BlockIndexed or BlocksFinalized event"
+                    })?;
+                if block_event.is_none() {
+                    warn!(
+                        "stream of BlockIndexed or BlocksFinalized events completed unexpectedly"
+                    );
+                    break;
+                }
+            }
+        };
+
+        Ok(updates)
+    }
+}
+
+async fn get_transaction_status_update<S>(
+    storage: &S,
+    hash: TransactionHash,
+) -> Result<TransactionStatusUpdate, sqlx::Error>
+where
+    S: Storage,
+{
+    let pending = TransactionStatusUpdate {
+        status: TransactionStatus::Pending,
+        block_hash: None,
+        block_height: None,
+    };
+
+    // Transactions are ordered descendingly by ID, hence the first one is the latest.
+    let Some(transaction) = storage
+        .get_transactions_by_hash(hash)
+        .await?
+        .into_iter()
+        .next()
+    else {
+        return Ok(pending);
+    };
+
+    let (block_hash, failed) = match transaction {
+        Transaction::Regular(transaction) => (
+            transaction.block_hash,
+            transaction.transaction_result == TransactionResult::Failure,
+        ),
+        Transaction::System(transaction) => (transaction.block_hash, false),
+    };
+
+    let Some(block) = storage
+        .get_blocks_by_hashes(&[block_hash])
+        .await?
+        .into_iter()
+        .next()
+    else {
+        return Ok(pending);
+    };
+
+    let status = match (block.finalized, failed) {
+        (false, _) => TransactionStatus::Included,
+        (true, false) => TransactionStatus::Finalized,
+        (true, true) => TransactionStatus::Failed,
+    };
+
+    Ok(TransactionStatusUpdate {
+        status,
+        block_hash: Some(block.hash.hex_encode()),
+        block_height: Some(block.height),
+    })
+}
@@ -47,18 +47,18 @@ enum UnshieldedTransactionsEvent<S: Storage> {
 
 /// A transaction that created and/or spent UTXOs alongside these and other information.
 #[derive(Debug, SimpleObject)]
-struct UnshieldedTransaction<S>
+pub(super) struct UnshieldedTransaction<S>
 where
     S: Storage,
 {
     /// The transaction that created and/or spent UTXOs.
-    transaction: Transaction<S>,
+    pub(super) transaction: Transaction<S>,
 
     /// UTXOs created in the above transaction, possibly empty.
-    created_utxos: Vec<UnshieldedUtxo<S>>,
+    pub(super) created_utxos: Vec<UnshieldedUtxo<S>>,
 
     /// UTXOs spent in the above transaction, possibly empty.
-    spent_utxos: Vec<UnshieldedUtxo<S>>,
+    pub(super) spent_utxos: Vec<UnshieldedUtxo<S>>,
 }
 
 /// Information about the unshielded indexing progress.
@@ -217,7 +217,7 @@ where
 }
 
 #[trace(properties = { "transaction_id": "{transaction_id}", "address": "{address:?}" })]
-async fn make_unshielded_transaction<S>(
+pub(super) async fn make_unshielded_transaction<S>(
     transaction_id: &mut u64,
     storage: &S,
     address: indexer_common::domain::UnshieldedAddress,
@@ -307,7 +307,7 @@ where
     }
 }
 
-async fn get_next_transaction<E>(
+pub(super) async fn get_next_transaction<E>(
     transactions: &mut (impl Stream<Item = Result<domain::Transaction, E>> + Unpin),
 ) -> Result<Option<domain::Transaction>, E> {
     transactions
@@ -0,0 +1,115 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    domain::storage::Storage,
+    infra::api::{
+        ApiError, ApiResult, ContextExt, OptionExt, ResultExt,
+        v4::{
+            HexEncoded, decode_session_id,
+            wallet::{WalletStatus, get_wallet_status},
+        },
+    },
+};
+use async_graphql::{Context, Subscription};
+use async_stream::try_stream;
+use drop_stream::DropStreamExt;
+use futures::{Stream, TryStreamExt, stream};
+use indexer_common::domain::{BlockIndexed, Subscriber, WalletIndexed};
+use log::{debug, warn};
+use std::{future::ready, marker::PhantomData, pin::pin};
+
+pub struct WalletStatusSubscription<S, B> {
+    _s: PhantomData<S>,
+    _b: PhantomData<B>,
+}
+
+impl<S, B> Default for WalletStatusSubscription<S, B> {
+    fn default() -> Self {
+        Self {
+            _s: PhantomData,
+            _b: PhantomData,
+        }
+    }
+}
+
+#[Subscription]
+impl<S, B> WalletStatusSubscription<S, B>
+where
+    S: Storage,
+    B: Subscriber,
+{
+    /// Subscribe to the sync status of the wallet for the given session ID. The current status is
+    /// delivered immediately, any changes as blocks get indexed or the wallet gets indexed further.
+    async fn wallet_status<'a>(
+        &self,
+        cx: &'a Context<'a>,
+        session_id: HexEncoded,
+    ) -> Result<impl Stream<Item = ApiResult<WalletStatus>> + use<'a, S, B>, ApiError> {
+        let session_id =
+            decode_session_id(session_id).map_err_into_client_error(|| "invalid session ID")?;
+
+        let quota_guard = cx
+            .get_subscription_quotas()
+            .try_acquire(cx.get_per_connection_counter(), Some(session_id))
+            .map_err_into_client_error(|| "subscription limit exceeded")?;
+
+        let wallet_id = cx
+            .get_storage::<S>()
+            .resolve_session_id(session_id)
+            .await
+            .map_err_into_server_error(|| "resolve session ID")?
+            .some_or_client_error(|| "unknown or expired session ID")?;
+
+        let subscriber = cx.get_subscriber::<B>();
+        let wallet_indexed_events = subscriber
+            .subscribe::<WalletIndexed>()
+            .try_filter(move |wallet_indexed| ready(wallet_indexed.wallet_id == wallet_id));
+        let events = stream::select(
+            subscriber.subscribe::<BlockIndexed>().map_ok(|_| ()),
+            wallet_indexed_events.map_ok(|_| ()),
+        );
+
+        let statuses = try_stream! {
+            let mut events = pin!(events);
+            let mut last_status = None;
+
+            loop {
+                let status = get_wallet_status::<S>(wallet_id, cx).await?;
+
+                if last_status.as_ref() != Some(&status) {
+                    debug!(wallet_id:%, status:?; "wallet status changed");
+
+                    last_status = Some(status.clone());
+                    yield status;
+                }
+
+                let event = events
+                    .try_next()
+                    .await
+                    .map_err_into_server_error(|| "get next BlockIndexed or WalletIndexed event")?;
+                if event.is_none() {
+                    warn!("stream of BlockIndexed or WalletIndexed events completed unexpectedly");
+                    break;
+                }
+            }
+        };
+
+        let statuses = statuses.on_drop(move || {
+            drop(quota_guard);
+            debug!(wallet_id:%; "wallet status subscription ended");
+        });
+
+        Ok(statuses)
+    }
+}
@@ -0,0 +1,296 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::unshielded::{UnshieldedTransaction, get_next_transaction, make_unshielded_transaction};
+use crate::{
+    domain::{WatchListKey, storage::Storage},
+    infra::api::{
+        ApiError, ApiResult, ContextExt, OptionExt, ResultExt,
+        v4::{
+            AddressType, HexEncoded, encode_address,
+            transaction::Transaction,
+            unshielded::{UnshieldedAddress, UnshieldedUtxo},
+            watch_list::try_into_domain_addresses,
+        },
+    },
+};
+use async_graphql::{Context, InputObject, SimpleObject, Subscription};
+use async_stream::try_stream;
+use derive_more::Debug;
+use drop_stream::DropStreamExt;
+use futures::{Stream, TryStreamExt};
+use indexer_common::domain::{NetworkId, Subscriber, UnshieldedUtxoIndexed};
+use log::{debug, warn};
+use std::{
+    collections::{HashMap, HashSet},
+    future::ready,
+    marker::PhantomData,
+    pin::pin,
+};
+
+/// A transaction that created and/or spent UTXOs of a watched address alongside these and other
+/// information.
+#[derive(Debug, SimpleObject)]
+struct WatchedUnshieldedTransaction<S>
+where
+    S: Storage,
+{
+    /// The watched address whose UTXOs were created and/or spent. To resume, use the ID of the
+    /// below transaction plus one as cursor for this address.
+    address: UnshieldedAddress,
+
+    /// The transaction that created and/or spent UTXOs.
+    transaction: Transaction<S>,
+
+    /// UTXOs of the watched address created in the above transaction, possibly empty.
+    created_utxos: Vec<UnshieldedUtxo<S>>,
+
+    /// UTXOs of the watched address spent in the above transaction, possibly empty.
+    spent_utxos: Vec<UnshieldedUtxo<S>>,
+}
+
+impl<S> WatchedUnshieldedTransaction<S>
+where
+    S: Storage,
+{
+    fn new(
+        address: indexer_common::domain::UnshieldedAddress,
+        unshielded_transaction: UnshieldedTransaction<S>,
+        network_id: &NetworkId,
+    ) -> Self {
+        let UnshieldedTransaction {
+            transaction,
+            created_utxos,
+            spent_utxos,
+        } = unshielded_transaction;
+
+        Self {
+            address: encode_address(address, AddressType::Unshielded, network_id).into(),
+            transaction,
+            created_utxos,
+            spent_utxos,
+        }
+    }
+}
+
+/// The transaction ID from which to resume streaming events for a watched address.
+#[derive(Debug, InputObject)]
+struct WatchedAddressCursor {
+    /// The watched address.
+    address: UnshieldedAddress,
+
+    /// The transaction ID to start at (inclusive).
+    transaction_id: u64,
+}
+
+pub struct WatchedUnshieldedTransactionsSubscription<S, B> {
+    _s: PhantomData<S>,
+    _b: PhantomData<B>,
+}
+
+impl<S, B> Default for WatchedUnshieldedTransactionsSubscription<S, B> {
+    fn default() -> Self {
+        Self {
+            _s: PhantomData,
+            _b: PhantomData,
+        }
+    }
+}
+
+#[Subscription]
+impl<S, B> WatchedUnshieldedTransactionsSubscription<S, B>
+where
+    S: Storage,
+    B: Subscriber,
+{
+    /// Subscribe unshielded transaction events for the given addresses and/or the addresses of the
+    /// watch list with the given hex-encoded key, multiplexed over one stream. Events for an
+    /// address start at its transaction ID in the given cursors or zero if omitted. The addresses
+    /// of the watch list are read when subscribing, i.e. later changes need a new subscription.
+    async fn watched_unshielded_transactions<'a>(
+        &self,
+        cx: &'a Context<'a>,
+        addresses: Option<Vec<UnshieldedAddress>>,
+        watch_list_key: Option<HexEncoded>,
+        cursors: Option<Vec<WatchedAddressCursor>>,
+    ) -> Result<
+        impl Stream<Item = ApiResult<WatchedUnshieldedTransaction<S>>> + use<'a, S, B>,
+        ApiError,
+    > {
+        let network_id = cx.get_network_id();
+
+        let mut addresses = try_into_domain_addresses(&addresses.unwrap_or_default(), network_id)
+            .map_err_into_client_error(|| "invalid address")?;
+
+        if let Some(key) = watch_list_key {
+            let key = key
+                .hex_decode::<WatchListKey>()
+                .map_err_into_client_error(|| "invalid watch list key")?;
+
+            let watch_list = cx
+                .get_storage::<S>()
+                .get_watch_list(key)
+                .await
+                .map_err_into_server_error(|| "get watch list")?
+                .some_or_client_error(|| "unknown watch list")?;
+
+            addresses.extend(watch_list.addresses);
+            addresses.sort_by_key(|address| address.0);
+            addresses.dedup();
+        }
+
+        (!addresses.is_empty())
+            .then_some(())
+            .some_or_client_error(|| "neither addresses nor watch list key given")?;
+        let max_watched_addresses = cx
+            .get_subscription_config()
+            .unshielded_transactions
+            .max_watched_addresses;
+        (addresses.len() <= max_watched_addresses)
+            .then_some(())
+            .some_or_client_error(|| {
+                format!("must not watch more than {max_watched_addresses} addresses")
+            })?;
+
+        let mut transaction_ids = addresses
+            .iter()
+            .map(|address| (*address, 0))
+            .collect::<HashMap<_, _>>();
+        for cursor in cursors.unwrap_or_default() {
+            let address = cursor
+                .address
+                .try_into_domain(network_id)
+                .map_err_into_client_error(|| "invalid cursor address")?;
+            if let Some(transaction_id) = transaction_ids.get_mut(&address) {
+                *transaction_id = cursor.transaction_id;
+            }
+        }
+
+        // All watched addresses share a single subscription slot.
+        let quota_guard = cx
+            .get_subscription_quotas()
+            .try_acquire(cx.get_per_connection_counter(), None)
+            .map_err_into_client_error(|| "subscription limit exceeded")?;
+
+        let events = make_watched_unshielded_transactions::<S, B>(cx, addresses, transaction_ids)
+            .on_drop(move || {
+                drop(quota_guard);
+            });
+
+        Ok(events)
+    }
+}
+
+fn make_watched_unshielded_transactions<'a, S, B>(
+    cx: &'a Context<'a>,
+    addresses: Vec<indexer_common::domain::UnshieldedAddress>,
+    mut transaction_ids: HashMap<indexer_common::domain::UnshieldedAddress, u64>,
+) -> impl Stream<Item = ApiResult<WatchedUnshieldedTransaction<S>>> + use<'a, S, B>
+where
+    S: Storage,
+    B: Subscriber,
+{
+    let network_id = cx.get_network_id();
+    let storage = cx.get_storage::<S>();
+    let subscriber = cx.get_subscriber::<B>();
+    let batch_size = cx
+        .get_subscription_config()
+        .unshielded_transactions
+        .batch_size;
+
+    let watched = addresses.iter().copied().collect::<HashSet<_>>();
+    let utxo_indexed_events = subscriber
+        .subscribe::<UnshieldedUtxoIndexed>()
+        .try_filter(move |event| ready(watched.contains(&event.address)));
+
+    try_stream! {
+        // Stream UTXO events for existing transactions, one watched address after the other.
+        debug!(address_count = addresses.len(); "streaming events for existing transactions");
+
+        for address in addresses {
+            let transaction_id = transaction_ids.entry(address).or_default();
+
+            let transactions = storage.get_transactions_by_unshielded_address(
+                address,
+                *transaction_id,
+                batch_size,
+            );
+            let mut transactions = pin!(transactions);
+            while let Some(transaction) = get_next_transaction(&mut transactions)
+                .await
+                .map_err_into_server_error(|| {
+                    format!("get next transaction for address {address}")
+                })?
+            {
+                if let Some(unshielded_transaction) = make_unshielded_transaction(
+                    transaction_id,
+                    storage,
+                    address,
+                    transaction,
+                    network_id,
+                )
+                .await?
+                {
+                    yield WatchedUnshieldedTransaction::new(
+                        address,
+                        unshielded_transaction,
+                        network_id,
+                    );
+                }
+            }
+        }
+
+        // Stream UTXO events for live transactions of the watched address of each event.
+        debug!("streaming events for live transactions");
+        let mut utxo_indexed_events = pin!(utxo_indexed_events);
+        while let Some(UnshieldedUtxoIndexed { address }) = utxo_indexed_events
+            .try_next()
+            .await
+            .map_err_into_server_error(|| "get next UnshieldedUtxoIndexed event")?
+        {
+            let transaction_id = transaction_ids.entry(address).or_default();
+
+            let transactions = storage.get_transactions_by_unshielded_address(
+                address,
+                *transaction_id,
+                batch_size,
+            );
+            let mut transactions = pin!(transactions);
+            while let Some(transaction) =
+                get_next_transaction(&mut transactions)
+                    .await
+                    .map_err_into_server_error(|| {
+                        format!("get next transaction for address {address}")
+                    })?
+            {
+                if let Some(unshielded_transaction) = make_unshielded_transaction(
+                    transaction_id,
+                    storage,
+                    address,
+                    transaction,
+                    network_id,
+                )
+                .await?
+                {
+                    yield WatchedUnshieldedTransaction::new(
+                        address,
+                        unshielded_transaction,
+                        network_id,
+                    );
+                }
+            }
+        }
+
+        warn!("stream of UnshieldedUtxoIndexed events completed unexpectedly");
+    }
+}
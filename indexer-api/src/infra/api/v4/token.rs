@@ -0,0 +1,89 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    domain,
+    infra::api::v4::{
+        AddressType, HexEncodable, HexEncoded, encode_address, unshielded::UnshieldedAddress,
+    },
+};
+use async_graphql::SimpleObject;
+use indexer_common::domain::NetworkId;
+
+/// An unshielded token type from the token registry.
+#[derive(Debug, Clone, SimpleObject)]
+pub struct Token {
+    /// The hex-encoded token type.
+    pub token_type: HexEncoded,
+
+    /// The height of the block in which this token type was first seen.
+    pub first_seen_block_height: u32,
+
+    /// The hex-encoded address of the contract that minted this token type, if minted by a
+    /// contract.
+    pub contract_address: Option<HexEncoded>,
+
+    /// The hex-encoded domain separator used by the minting contract, if minted by a contract.
+    pub domain_sep: Option<HexEncoded>,
+
+    /// The circulating supply as string to support u128, i.e. the sum of the values of the
+    /// unspent unshielded UTXOs of this token type and the balances held by contracts.
+    pub circulating_supply: String,
+
+    /// The number of unshielded addresses holding a non-zero balance of this token type.
+    pub holder_count: u64,
+}
+
+impl From<domain::Token> for Token {
+    fn from(token: domain::Token) -> Self {
+        let domain::Token {
+            token_type,
+            first_seen_block_height,
+            contract_address,
+            domain_sep,
+            circulating_supply,
+            holder_count,
+            ..
+        } = token;
+
+        Self {
+            token_type: token_type.hex_encode(),
+            first_seen_block_height,
+            contract_address: contract_address.map(|address| address.hex_encode()),
+            domain_sep: domain_sep.map(|domain_sep| domain_sep.hex_encode()),
+            circulating_supply: circulating_supply.to_string(),
+            holder_count,
+        }
+    }
+}
+
+/// A balance of a token type held by an unshielded address.
+#[derive(Debug, Clone, SimpleObject)]
+pub struct TokenHolder {
+    /// The Bech32m-encoded unshielded address holding the balance.
+    pub owner: UnshieldedAddress,
+
+    /// The balance as string to support u128.
+    pub balance: String,
+}
+
+impl From<(domain::TokenHolder, &NetworkId)> for TokenHolder {
+    fn from((holder, network_id): (domain::TokenHolder, &NetworkId)) -> Self {
+        let domain::TokenHolder { owner, balance } = holder;
+
+        Self {
+            owner: encode_address(owner, AddressType::Unshielded, network_id).into(),
+            balance: balance.to_string(),
+        }
+    }
+}
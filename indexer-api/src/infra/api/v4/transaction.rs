@@ -16,19 +16,24 @@ use crate::{
     infra::api::{
         ApiResult, ContextExt, OptionExt, ResultExt,
         v4::{
-            HexEncodable, HexEncoded,
+            HexDecodeError, HexEncodable, HexEncoded,
             block::Block,
             contract_action::ContractAction,
             directives::beta,
             ledger_events::{DustLedgerEvent, ZswapLedgerEvent},
-            unshielded::UnshieldedUtxo,
+            unshielded::{UnshieldedAddress, UnshieldedAddressFormatError, UnshieldedUtxo},
         },
     },
 };
-use async_graphql::{ComplexObject, Context, Enum, Interface, OneofObject, SimpleObject};
+use async_graphql::{
+    ComplexObject, Context, Enum, InputObject, Interface, OneofObject, SimpleObject,
+};
 use derive_more::Debug;
-use indexer_common::domain::{BlockHash, LedgerEventGrouping};
-use std::marker::PhantomData;
+use indexer_common::domain::{
+    BlockHash, LedgerEventGrouping, NetworkId, SerializedContractAddress, TokenType,
+};
+use std::{marker::PhantomData, num::ParseIntError};
+use thiserror::Error;
 
 /// A Midnight transaction.
 #[derive(Debug, Clone, Interface)]
@@ -486,6 +491,138 @@ pub enum TransactionOffset {
     Identifier(HexEncoded),
 }
 
+/// Filter for transactions; all given fields must match and bounds are inclusive. Result status and
+/// paid fees only match regular transactions.
+#[derive(Debug, Clone, Default, InputObject)]
+pub struct TransactionFilter {
+    /// The lowest block height.
+    pub from_height: Option<u32>,
+
+    /// The highest block height.
+    pub to_height: Option<u32>,
+
+    /// The earliest block timestamp, a UNIX timestamp in milliseconds like `Block.timestamp`.
+    pub from_timestamp: Option<u64>,
+
+    /// The latest block timestamp, a UNIX timestamp in milliseconds like `Block.timestamp`.
+    pub to_timestamp: Option<u64>,
+
+    /// The transaction variant.
+    pub variant: Option<TransactionVariant>,
+
+    /// The status of the transaction result.
+    pub result_status: Option<TransactionResultStatus>,
+
+    /// The minimum paid fees in SPECK as decimal string.
+    pub min_paid_fees: Option<String>,
+
+    /// The maximum paid fees in SPECK as decimal string.
+    pub max_paid_fees: Option<String>,
+
+    /// The hex-encoded address of a contract with an action in the transaction.
+    pub contract_address: Option<HexEncoded>,
+
+    /// An unshielded address owning UTXOs created or spent by the transaction.
+    pub unshielded_address: Option<UnshieldedAddress>,
+
+    /// The hex-encoded token type of UTXOs created or spent by the transaction; together with
+    /// `unshieldedAddress` only UTXOs of that address count.
+    pub token_type: Option<HexEncoded>,
+}
+
+impl TransactionFilter {
+    /// Convert into the domain filter, validating the given fields.
+    pub fn try_into_domain(
+        self,
+        network_id: &NetworkId,
+    ) -> Result<domain::storage::transaction::TransactionFilter, TransactionFilterError> {
+        let Self {
+            from_height,
+            to_height,
+            from_timestamp,
+            to_timestamp,
+            variant,
+            result_status,
+            min_paid_fees,
+            max_paid_fees,
+            contract_address,
+            unshielded_address,
+            token_type,
+        } = self;
+
+        let paid_fees_min = min_paid_fees
+            .map(|fees| fees.parse::<u128>())
+            .transpose()
+            .map_err(TransactionFilterError::InvalidMinPaidFees)?;
+        let paid_fees_max = max_paid_fees
+            .map(|fees| fees.parse::<u128>())
+            .transpose()
+            .map_err(TransactionFilterError::InvalidMaxPaidFees)?;
+
+        let contract_address = contract_address
+            .map(|address| address.hex_decode::<SerializedContractAddress>())
+            .transpose()
+            .map_err(TransactionFilterError::InvalidContractAddress)?;
+
+        let unshielded_address = unshielded_address
+            .map(|address| address.try_into_domain(network_id))
+            .transpose()?;
+
+        let token_type = token_type
+            .map(|token_type| token_type.hex_decode::<TokenType>())
+            .transpose()
+            .map_err(TransactionFilterError::InvalidTokenType)?;
+
+        Ok(domain::storage::transaction::TransactionFilter {
+            block_height_from: from_height,
+            block_height_to: to_height,
+            timestamp_from: from_timestamp,
+            timestamp_to: to_timestamp,
+            variant: variant.map(Into::into),
+            result_status: result_status.map(Into::into),
+            paid_fees_min,
+            paid_fees_max,
+            contract_address,
+            unshielded_address,
+            token_type,
+        })
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum TransactionFilterError {
+    #[error("invalid minPaidFees")]
+    InvalidMinPaidFees(#[source] ParseIntError),
+
+    #[error("invalid maxPaidFees")]
+    InvalidMaxPaidFees(#[source] ParseIntError),
+
+    #[error("invalid contractAddress")]
+    InvalidContractAddress(#[source] HexDecodeError),
+
+    #[error("invalid unshieldedAddress")]
+    InvalidUnshieldedAddress(#[from] UnshieldedAddressFormatError),
+
+    #[error("invalid tokenType")]
+    InvalidTokenType(#[source] HexDecodeError),
+}
+
+/// The variant of a transaction: regular or system.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
+pub enum TransactionVariant {
+    Regular,
+    System,
+}
+
+impl From<TransactionVariant> for indexer_common::domain::TransactionVariant {
+    fn from(variant: TransactionVariant) -> Self {
+        match variant {
+            TransactionVariant::Regular => Self::Regular,
+            TransactionVariant::System => Self::System,
+        }
+    }
+}
+
 /// The result of applying a transaction to the ledger state. In case of a partial success (status),
 /// there will be segments.
 #[derive(Debug, Clone, SimpleObject)]
@@ -502,6 +639,16 @@ pub enum TransactionResultStatus {
     Failure,
 }
 
+impl From<TransactionResultStatus> for domain::storage::transaction::TransactionResultStatus {
+    fn from(status: TransactionResultStatus) -> Self {
+        match status {
+            TransactionResultStatus::Success => Self::Success,
+            TransactionResultStatus::PartialSuccess => Self::PartialSuccess,
+            TransactionResultStatus::Failure => Self::Failure,
+        }
+    }
+}
+
 /// One of many segments for a partially successful transaction result showing success for some
 /// segment.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, SimpleObject)]
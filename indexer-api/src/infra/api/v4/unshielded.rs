@@ -206,3 +206,24 @@ impl From<domain::ContractBalance> for ContractBalance {
         }
     }
 }
+
+/// Represents a token balance held by an unshielded address, i.e. the sum of the values of its
+/// unspent UTXOs of a token type.
+#[derive(Debug, Clone, PartialEq, Eq, SimpleObject)]
+pub struct UnshieldedBalance {
+    /// Hex-encoded token type identifier.
+    pub token_type: HexEncoded,
+
+    /// Balance amount as string to support larger integer values (up to 16 bytes).
+    pub amount: String,
+}
+
+impl From<domain::UnshieldedBalance> for UnshieldedBalance {
+    fn from(balance: domain::UnshieldedBalance) -> Self {
+        let domain::UnshieldedBalance { token_type, amount } = balance;
+        Self {
+            token_type: token_type.hex_encode(),
+            amount: amount.to_string(),
+        }
+    }
+}
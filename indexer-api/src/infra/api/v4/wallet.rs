@@ -0,0 +1,109 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    domain::{self, storage::Storage},
+    infra::api::{ApiResult, ContextExt, OptionExt, ResultExt},
+};
+use async_graphql::{Context, SimpleObject};
+use uuid::Uuid;
+
+/// The sync status of a wallet, i.e. how far its relevant transactions have been indexed.
+#[derive(Debug, Clone, PartialEq, SimpleObject)]
+pub struct WalletStatus {
+    /// The transaction ID from which on relevant transactions are wanted.
+    wanted_start_index: u64,
+
+    /// The lowest transaction ID checked for relevance; above the wanted start index while
+    /// transactions before are still being scanned.
+    first_indexed_transaction_id: u64,
+
+    /// The highest transaction ID checked for relevance.
+    last_indexed_transaction_id: u64,
+
+    /// The highest regular transaction ID of the chain, shared across queries for a few seconds;
+    /// null if there are none yet.
+    highest_transaction_id: Option<u64>,
+
+    /// The number of relevant transactions indexed so far.
+    relevant_transaction_count: u64,
+
+    /// The UNIX timestamp in milliseconds at which the wallet was last active.
+    last_active: u64,
+
+    /// Whether all transactions up to the highest one have been checked for relevance.
+    synced: bool,
+
+    /// The measured rate in transaction IDs per second at which transactions are checked for
+    /// relevance; null until measured.
+    scan_rate: Option<f64>,
+
+    /// The estimated number of seconds until synced based on the scan rate; zero if synced and null
+    /// until the scan rate has been measured.
+    eta_seconds: Option<u64>,
+}
+
+impl From<domain::WalletStatus> for WalletStatus {
+    fn from(status: domain::WalletStatus) -> Self {
+        let synced = status.remaining_transaction_ids() == 0;
+        let eta_seconds = status.eta_seconds();
+
+        let domain::WalletStatus {
+            wanted_start_index,
+            first_indexed_transaction_id,
+            last_indexed_transaction_id,
+            highest_transaction_id,
+            relevant_transaction_count,
+            last_active,
+            scan_rate,
+        } = status;
+
+        Self {
+            wanted_start_index,
+            first_indexed_transaction_id,
+            last_indexed_transaction_id,
+            highest_transaction_id,
+            relevant_transaction_count,
+            last_active,
+            synced,
+            scan_rate,
+            eta_seconds,
+        }
+    }
+}
+
+/// Get the sync status of the wallet with the given ID.
+pub async fn get_wallet_status<S>(wallet_id: Uuid, cx: &Context<'_>) -> ApiResult<WalletStatus>
+where
+    S: Storage,
+{
+    let storage = cx.get_storage::<S>();
+
+    let highest_transaction_id = cx
+        .get_progress_cache()
+        .highest_transaction_id(async {
+            storage
+                .get_highest_regular_transaction_id()
+                .await
+                .map_err_into_server_error(|| "get highest regular transaction ID")
+        })
+        .await?;
+
+    let status = storage
+        .get_wallet_status(wallet_id, highest_transaction_id)
+        .await
+        .map_err_into_server_error(|| format!("get wallet status for wallet ID {wallet_id}"))?
+        .some_or_client_error(|| "unknown or expired session ID")?;
+
+    Ok(status.into())
+}
@@ -0,0 +1,61 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    domain,
+    infra::api::v4::{
+        AddressType, encode_address,
+        unshielded::{UnshieldedAddress, UnshieldedAddressFormatError},
+    },
+};
+use async_graphql::SimpleObject;
+use indexer_common::domain::NetworkId;
+
+/// A named server-side list of watched unshielded addresses.
+#[derive(Debug, Clone, SimpleObject)]
+pub struct WatchList {
+    /// The name of this watch list.
+    pub name: String,
+
+    /// The Bech32m-encoded watched addresses.
+    pub addresses: Vec<UnshieldedAddress>,
+}
+
+impl From<(domain::WatchList, &NetworkId)> for WatchList {
+    fn from((watch_list, network_id): (domain::WatchList, &NetworkId)) -> Self {
+        let domain::WatchList { name, addresses } = watch_list;
+
+        let addresses = addresses
+            .into_iter()
+            .map(|address| encode_address(address, AddressType::Unshielded, network_id).into())
+            .collect();
+
+        Self { name, addresses }
+    }
+}
+
+/// Convert the given API addresses into domain addresses, ordered bytewise and without duplicates.
+pub fn try_into_domain_addresses(
+    addresses: &[UnshieldedAddress],
+    network_id: &NetworkId,
+) -> Result<Vec<indexer_common::domain::UnshieldedAddress>, UnshieldedAddressFormatError> {
+    let mut addresses = addresses
+        .iter()
+        .map(|address| address.try_into_domain(network_id))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    addresses.sort_by_key(|address| address.0);
+    addresses.dedup();
+
+    Ok(addresses)
+}
@@ -0,0 +1,228 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    domain,
+    infra::api::v4::{
+        HexDecodeError, HexEncodable, HexEncoded,
+        contract_event::ContractEventType,
+        unshielded::{UnshieldedAddress, UnshieldedAddressFormatError},
+    },
+};
+use async_graphql::{Enum, InputObject, SimpleObject};
+use indexer_common::domain::{
+    ByteVec, NetworkId, SerializedContractAddress,
+    bridge::{BridgeRecipient, BridgeRecipientError},
+};
+use thiserror::Error;
+
+/// Filter selecting the events delivered to a webhook. Each given field subscribes to one kind of
+/// event; without any field, each indexed block is delivered.
+#[derive(Debug, Clone, Default, InputObject)]
+pub struct WebhookFilter {
+    /// Deliver the contract actions of the contract with this hex-encoded address or, together
+    /// with `contractEventType`, its contract events of that type.
+    pub contract_address: Option<HexEncoded>,
+
+    /// Deliver the contract events of this type; requires `contractAddress`.
+    pub contract_event_type: Option<ContractEventType>,
+
+    /// Deliver the transactions creating or spending unshielded UTXOs of this address.
+    pub unshielded_address: Option<UnshieldedAddress>,
+
+    /// Deliver the bridge events for this hex-encoded recipient.
+    pub bridge_recipient: Option<HexEncoded>,
+}
+
+impl WebhookFilter {
+    /// Convert into the domain filter, validating the given fields.
+    pub fn try_into_domain(
+        self,
+        network_id: &NetworkId,
+    ) -> Result<domain::WebhookFilter, WebhookFilterError> {
+        let Self {
+            contract_address,
+            contract_event_type,
+            unshielded_address,
+            bridge_recipient,
+        } = self;
+
+        if contract_event_type.is_some() && contract_address.is_none() {
+            return Err(WebhookFilterError::ContractEventTypeWithoutContractAddress);
+        }
+
+        let contract_address = contract_address
+            .map(|address| {
+                address
+                    .hex_decode::<SerializedContractAddress>()
+                    .map_err(WebhookFilterError::InvalidContractAddress)
+            })
+            .transpose()?;
+
+        let unshielded_address = unshielded_address
+            .map(|address| address.try_into_domain(network_id))
+            .transpose()?;
+
+        let bridge_recipient = bridge_recipient
+            .map(|recipient| {
+                let recipient = recipient
+                    .hex_decode::<ByteVec>()
+                    .map_err(WebhookFilterError::InvalidBridgeRecipient)?;
+                Ok::<_, WebhookFilterError>(BridgeRecipient::new(recipient.0)?)
+            })
+            .transpose()?;
+
+        Ok(domain::WebhookFilter {
+            contract_address,
+            contract_event_type: contract_event_type.map(ContractEventType::variant_name),
+            unshielded_address,
+            bridge_recipient,
+        })
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum WebhookFilterError {
+    #[error("contractEventType requires contractAddress")]
+    ContractEventTypeWithoutContractAddress,
+
+    #[error("invalid contractAddress")]
+    InvalidContractAddress(#[source] HexDecodeError),
+
+    #[error("invalid unshieldedAddress")]
+    InvalidUnshieldedAddress(#[from] UnshieldedAddressFormatError),
+
+    #[error("invalid bridgeRecipient")]
+    InvalidBridgeRecipient(#[source] HexDecodeError),
+
+    #[error("invalid bridgeRecipient")]
+    BridgeRecipientLength(#[from] BridgeRecipientError),
+}
+
+/// A registered webhook.
+#[derive(Debug, Clone, SimpleObject)]
+pub struct RegisteredWebhook {
+    /// The hex-encoded key identifying this webhook, needed to query its deliveries or delete it.
+    pub key: HexEncoded,
+
+    /// The hex-encoded secret for verifying the `X-Midnight-Indexer-Signature` header, i.e. the
+    /// HMAC-SHA256 over `"{timestamp}.{body}"` with the timestamp from the
+    /// `X-Midnight-Indexer-Timestamp` header. Only returned on registration.
+    pub secret: HexEncoded,
+}
+
+impl From<(domain::WebhookKey, domain::WebhookSecret)> for RegisteredWebhook {
+    fn from((key, secret): (domain::WebhookKey, domain::WebhookSecret)) -> Self {
+        Self {
+            key: key.hex_encode(),
+            secret: secret.expose_secret().hex_encode(),
+        }
+    }
+}
+
+/// The status of a webhook delivery.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
+pub enum WebhookDeliveryStatus {
+    /// Not yet delivered, to be attempted (again).
+    Pending,
+
+    /// Delivered, i.e. the endpoint responded with a 2xx status.
+    Delivered,
+
+    /// Given up after exhausting the attempts.
+    Dead,
+}
+
+impl From<domain::WebhookDeliveryStatus> for WebhookDeliveryStatus {
+    fn from(status: domain::WebhookDeliveryStatus) -> Self {
+        match status {
+            domain::WebhookDeliveryStatus::Pending => Self::Pending,
+            domain::WebhookDeliveryStatus::Delivered => Self::Delivered,
+            domain::WebhookDeliveryStatus::Dead => Self::Dead,
+        }
+    }
+}
+
+impl From<WebhookDeliveryStatus> for domain::WebhookDeliveryStatus {
+    fn from(status: WebhookDeliveryStatus) -> Self {
+        match status {
+            WebhookDeliveryStatus::Pending => Self::Pending,
+            WebhookDeliveryStatus::Delivered => Self::Delivered,
+            WebhookDeliveryStatus::Dead => Self::Dead,
+        }
+    }
+}
+
+/// A delivery of an indexed event to a webhook.
+#[derive(Debug, Clone, SimpleObject)]
+pub struct WebhookDelivery {
+    /// The delivery ID, also sent in the `X-Midnight-Indexer-Delivery` header.
+    pub id: u64,
+
+    /// The event type, also sent in the `X-Midnight-Indexer-Event` header.
+    pub event_type: String,
+
+    /// The JSON payload.
+    pub payload: String,
+
+    pub status: WebhookDeliveryStatus,
+
+    /// The number of attempts so far.
+    pub attempts: u32,
+
+    /// The HTTP status of the last response, if any.
+    pub last_response_status: Option<u64>,
+
+    /// The error of the last failed attempt, if any.
+    pub last_error: Option<String>,
+
+    /// The UNIX timestamp in milliseconds at which this delivery was enqueued.
+    pub created_at: u64,
+
+    /// The UNIX timestamp in milliseconds of the next attempt of a pending delivery.
+    pub next_attempt_at: u64,
+
+    /// The UNIX timestamp in milliseconds at which this delivery succeeded.
+    pub delivered_at: Option<u64>,
+}
+
+impl From<domain::WebhookDelivery> for WebhookDelivery {
+    fn from(delivery: domain::WebhookDelivery) -> Self {
+        let domain::WebhookDelivery {
+            id,
+            event_type,
+            payload,
+            status,
+            attempts,
+            next_attempt_at,
+            last_response_status,
+            last_error,
+            created_at,
+            delivered_at,
+            ..
+        } = delivery;
+
+        Self {
+            id,
+            event_type,
+            payload,
+            status: status.into(),
+            attempts,
+            last_response_status,
+            last_error,
+            created_at,
+            next_attempt_at,
+            delivered_at,
+        }
+    }
+}
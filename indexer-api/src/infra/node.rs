@@ -0,0 +1,207 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::domain::{Node, SubmitTransactionOutcome};
+use http::{
+    HeaderMap,
+    header::{InvalidHeaderValue, USER_AGENT},
+};
+use indexer_common::error::BoxError;
+use log::debug;
+use serde::Deserialize;
+use std::{error::Error as StdError, iter, ops::RangeInclusive, time::Duration};
+use subxt::{
+    OnlineClient, SubstrateConfig,
+    dynamic::{self, Value},
+    rpcs::{
+        self,
+        client::{ReconnectingRpcClient, reconnecting_rpc_client::ExponentialBackoff},
+    },
+};
+use thiserror::Error;
+
+/// JSON-RPC error codes of the Substrate transaction pool for rejected transactions, from 1010
+/// (invalid transaction) to 1016 (immediately dropped, e.g. because the pool is full).
+const POOL_REJECTION_CODES: RangeInclusive<i32> = 1010..=1016;
+
+/// A [Node] implementation based on subxt, submitting transactions as unsigned
+/// `Midnight::send_mn_transaction` extrinsics.
+#[derive(Clone)]
+pub struct SubxtNode {
+    online_client: OnlineClient<SubstrateConfig>,
+}
+
+impl SubxtNode {
+    /// Create a new [SubxtNode] with the given [Config].
+    pub async fn new(config: Config) -> Result<Self, Error> {
+        let Config {
+            url,
+            reconnect_max_delay,
+            reconnect_max_attempts,
+        } = config;
+
+        let retry_policy = ExponentialBackoff::from_millis(10)
+            .max_delay(reconnect_max_delay)
+            .take(reconnect_max_attempts);
+        let user_agent = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION")).parse()?;
+        let headers = HeaderMap::from_iter([(USER_AGENT, user_agent)]);
+        let rpc_client = ReconnectingRpcClient::builder()
+            .set_headers(headers)
+            .retry_policy(retry_policy)
+            .build(&url)
+            .await
+            .map_err(|error| Error::RpcClient(error.into()))?;
+
+        let online_client = OnlineClient::<SubstrateConfig>::from_rpc_client(rpc_client).await?;
+
+        Ok(Self { online_client })
+    }
+}
+
+impl Node for SubxtNode {
+    type Error = SubmitTransactionError;
+
+    async fn submit_transaction(
+        &self,
+        transaction: &[u8],
+    ) -> Result<SubmitTransactionOutcome, Self::Error> {
+        let payload = dynamic::tx(
+            "Midnight",
+            "send_mn_transaction",
+            vec![Value::from_bytes(transaction)],
+        );
+
+        let result = self
+            .online_client
+            .tx()
+            .create_unsigned(&payload)
+            .map_err(|error| SubmitTransactionError::CreateExtrinsic(error.into()))?
+            .submit()
+            .await;
+
+        match result {
+            Ok(extrinsic_hash) => {
+                debug!(extrinsic_hash:?; "transaction submitted");
+                Ok(SubmitTransactionOutcome::Submitted)
+            }
+
+            Err(error) => match rejection_reason(&error) {
+                Some(reason) => {
+                    debug!(reason:%; "transaction rejected");
+                    Ok(SubmitTransactionOutcome::Rejected { reason })
+                }
+
+                None => Err(SubmitTransactionError::SubmitExtrinsic(error.into())),
+            },
+        }
+    }
+}
+
+/// Find a transaction pool rejection in the source chain of the given error and return its
+/// message, followed by the data, if any, which carries the actual validity error.
+fn rejection_reason(error: &(dyn StdError + 'static)) -> Option<String> {
+    iter::successors(Some(error), |error| error.source()).find_map(|error| {
+        match error.downcast_ref::<rpcs::Error>() {
+            Some(rpcs::Error::User(error)) if POOL_REJECTION_CODES.contains(&error.code) => {
+                let reason = match &error.data {
+                    Some(data) => {
+                        let data = serde_json::from_str::<String>(data.get())
+                            .unwrap_or_else(|_| data.get().to_owned());
+                        format!("{}: {data}", error.message)
+                    }
+
+                    None => error.message.to_owned(),
+                };
+
+                Some(reason)
+            }
+
+            _ => None,
+        }
+    })
+}
+
+/// Config for the node connection used to submit transactions.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Config {
+    pub url: String,
+
+    #[serde(with = "humantime_serde")]
+    pub reconnect_max_delay: Duration,
+
+    pub reconnect_max_attempts: usize,
+}
+
+/// Error possibly returned by [SubxtNode::new].
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("cannot create reconnecting subxt RPC client")]
+    RpcClient(#[source] BoxError),
+
+    #[error("cannot create subxt online client")]
+    OnlineClient(#[from] subxt::error::OnlineClientError),
+
+    #[error("cannot create HTTP header")]
+    InvalidHeaderValue(#[from] InvalidHeaderValue),
+}
+
+/// Error possibly returned by [SubxtNode::submit_transaction].
+#[derive(Debug, Error)]
+pub enum SubmitTransactionError {
+    #[error("cannot create extrinsic")]
+    CreateExtrinsic(#[source] BoxError),
+
+    #[error("cannot submit extrinsic")]
+    SubmitExtrinsic(#[source] BoxError),
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::infra::node::{SubmitTransactionError, rejection_reason};
+    use serde_json::value::RawValue;
+    use subxt::rpcs::{self, UserError};
+
+    #[test]
+    fn test_rejection_reason() {
+        let invalid = rpcs::Error::User(UserError {
+            code: 1010,
+            message: "Invalid Transaction".to_string(),
+            data: Some(RawValue::from_string(r#""Custom error: 5""#.to_string()).unwrap()),
+        });
+        let error = SubmitTransactionError::SubmitExtrinsic(invalid.into());
+        assert_eq!(
+            rejection_reason(&error).as_deref(),
+            Some("Invalid Transaction: Custom error: 5")
+        );
+
+        let already_imported = rpcs::Error::User(UserError {
+            code: 1013,
+            message: "Transaction Already Imported".to_string(),
+            data: None,
+        });
+        assert_eq!(
+            rejection_reason(&already_imported).as_deref(),
+            Some("Transaction Already Imported")
+        );
+
+        let method_not_found = rpcs::Error::User(UserError {
+            code: -32601,
+            message: "Method not found".to_string(),
+            data: None,
+        });
+        assert_eq!(rejection_reason(&method_not_found), None);
+
+        let disconnected = rpcs::Error::DisconnectedWillReconnect("closed".to_string());
+        assert_eq!(rejection_reason(&disconnected), None);
+    }
+}
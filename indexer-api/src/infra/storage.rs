@@ -12,28 +12,41 @@
 // limitations under the License.
 
 mod block;
+mod block_stats;
 mod bridge;
+mod chain_stats;
+mod contract_abi;
 mod contract_action;
 mod contract_event;
 mod dust;
 mod dust_generations;
 mod ledger_events;
 mod ledger_state;
+mod pending_transaction;
 mod shielded_nullifiers;
 mod spo;
 mod system_parameters;
+mod token;
 mod transaction;
 mod unshielded;
 mod wallet;
+mod watch_list;
+mod webhook;
 
-use crate::domain;
-use chacha20poly1305::ChaCha20Poly1305;
+use crate::domain::{self, storage::Page};
+use indexer_common::cipher::Keyring;
+use sqlx::{Encode, QueryBuilder, Type};
+
+#[cfg(feature = "cloud")]
+type Db = sqlx::Postgres;
+#[cfg(feature = "standalone")]
+type Db = sqlx::Sqlite;
 
 /// Unified storage implementation for PostgreSQL (cloud) and SQLite (standalone). Uses Cargo
 /// features to select the appropriate database backend at build time.
 #[derive(Clone)]
 pub struct Storage {
-    cipher: ChaCha20Poly1305,
+    keyring: Keyring,
 
     #[cfg(feature = "cloud")]
     pool: indexer_common::infra::pool::postgres::PostgresPool,
@@ -45,19 +58,629 @@ pub struct Storage {
 impl Storage {
     #[cfg(feature = "cloud")]
     pub fn new(
-        cipher: ChaCha20Poly1305,
+        keyring: Keyring,
         pool: indexer_common::infra::pool::postgres::PostgresPool,
     ) -> Self {
-        Self { cipher, pool }
+        Self { keyring, pool }
     }
 
     #[cfg(feature = "standalone")]
-    pub fn new(
-        cipher: ChaCha20Poly1305,
-        pool: indexer_common::infra::pool::sqlite::SqlitePool,
-    ) -> Self {
-        Self { cipher, pool }
+    pub fn new(keyring: Keyring, pool: indexer_common::infra::pool::sqlite::SqlitePool) -> Self {
+        Self { keyring, pool }
     }
 }
 
 impl domain::storage::Storage for Storage {}
+
+/// Append the bounds, ordering and limit of the given page on the given key column to a query
+/// which already has a WHERE clause.
+fn push_page<'a, K>(query_builder: &mut QueryBuilder<'a, Db>, column: &str, page: Page<K>)
+where
+    K: 'a + Encode<'a, Db> + Type<Db>,
+{
+    if let Some(after) = page.after {
+        query_builder
+            .push(format!(" AND {column} > "))
+            .push_bind(after);
+    }
+    if let Some(before) = page.before {
+        query_builder
+            .push(format!(" AND {column} < "))
+            .push_bind(before);
+    }
+
+    let direction = if page.backward { "DESC" } else { "ASC" };
+    query_builder
+        .push(format!(" ORDER BY {column} {direction} LIMIT "))
+        .push_bind(page.limit as i64);
+}
+
+/// Bring items fetched for a page, i.e. with the ordering appended by [push_page], into
+/// ascending key order.
+fn into_ascending<K, T>(page: &Page<K>, mut items: Vec<T>) -> Vec<T> {
+    if page.backward {
+        items.reverse();
+    }
+    items
+}
+
+#[cfg(all(test, feature = "standalone"))]
+mod tests {
+    use crate::{
+        domain::storage::{
+            Page,
+            block::BlockStorage,
+            contract_action::ContractActionStorage,
+            transaction::{TransactionFilter, TransactionResultStatus, TransactionStorage},
+            unshielded::UnshieldedUtxoStorage,
+            watch_list::WatchListStorage,
+        },
+        infra::storage::Storage,
+    };
+    use chacha20poly1305::{ChaCha20Poly1305, Key, KeyInit};
+    use futures::TryStreamExt;
+    use indexer_common::{
+        cipher::Keyring,
+        domain::{ByteArray, TransactionResult, TransactionVariant},
+        infra::{
+            migrations,
+            pool::sqlite::{Config, SqlitePool},
+            sqlx::U128BeBytes,
+        },
+    };
+    use indoc::indoc;
+    use sqlx::types::Json;
+    use std::{error::Error as StdError, num::NonZeroU32};
+
+    async fn new_storage() -> Result<(Storage, SqlitePool), Box<dyn StdError>> {
+        let pool = SqlitePool::new(Config::default()).await?;
+        migrations::sqlite::run(&pool).await?;
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(&[0u8; 32]));
+        Ok((Storage::new(Keyring::new(0, cipher), pool.clone()), pool))
+    }
+
+    /// Seed the block at the given height with the given timestamp; its ID is the height plus one.
+    async fn seed_block(pool: &SqlitePool, height: u8, timestamp: i64) -> Result<i64, sqlx::Error> {
+        let query = indoc! {"
+            INSERT INTO blocks (
+                id, hash, height, protocol_version, parent_hash, author,
+                timestamp, zswap_merkle_tree_root, ledger_parameters, ledger_state_key
+            )
+            VALUES ($1, $2, $3, 1000000, $4, NULL, $5, X'00', X'00', X'00')
+        "};
+
+        let id = height as i64 + 1;
+        sqlx::query(query)
+            .bind(id)
+            .bind([height + 1; 32].as_slice())
+            .bind(height as i64)
+            .bind([height; 32].as_slice())
+            .bind(timestamp)
+            .execute(&**pool)
+            .await?;
+
+        Ok(id)
+    }
+
+    /// Seed a regular or system transaction with the given ID and hash `[id; 32]`.
+    async fn seed_transaction(
+        pool: &SqlitePool,
+        id: u8,
+        block_id: i64,
+        variant: &str,
+    ) -> Result<(), sqlx::Error> {
+        let query = indoc! {"
+            INSERT INTO transactions (id, block_id, variant, hash, protocol_version, raw)
+            VALUES ($1, $2, $3, $4, 1000000, X'00')
+        "};
+
+        sqlx::query(query)
+            .bind(id as i64)
+            .bind(block_id)
+            .bind(variant)
+            .bind([id; 32].as_slice())
+            .execute(&**pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Seed a regular transaction with the given ID and hash `[id; 32]` with the given result and
+    /// paid fees.
+    async fn seed_regular_transaction(
+        pool: &SqlitePool,
+        id: u8,
+        block_id: i64,
+        transaction_result: TransactionResult,
+        paid_fees: u128,
+    ) -> Result<(), sqlx::Error> {
+        seed_transaction(pool, id, block_id, "Regular").await?;
+
+        let query = indoc! {"
+            INSERT INTO regular_transactions (
+                id, transaction_result, zswap_merkle_tree_root,
+                zswap_start_index, zswap_end_index,
+                dust_commitment_start_index, dust_commitment_end_index,
+                dust_generation_start_index, dust_generation_end_index,
+                paid_fees, estimated_fees
+            )
+            VALUES ($1, $2, X'00', 0, 0, 0, 0, 0, 0, $3, $3)
+        "};
+
+        sqlx::query(query)
+            .bind(id as i64)
+            .bind(Json(transaction_result))
+            .bind(U128BeBytes::from(paid_fees))
+            .execute(&**pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Seed an unshielded UTXO with intent hash `[n; 32]`, owned by `[owner; 32]`, of token type
+    /// `[token_type; 32]`.
+    async fn seed_utxo(
+        pool: &SqlitePool,
+        n: u8,
+        owner: u8,
+        token_type: u8,
+        value: u128,
+        creating_transaction_id: u8,
+        spending_transaction_id: Option<u8>,
+    ) -> Result<(), sqlx::Error> {
+        let query = indoc! {"
+            INSERT INTO unshielded_utxos (
+                creating_transaction_id, spending_transaction_id, owner, token_type, value,
+                intent_hash, output_index, ctime, initial_nonce, registered_for_dust_generation
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, 0, NULL, $6, 0)
+        "};
+
+        sqlx::query(query)
+            .bind(creating_transaction_id as i64)
+            .bind(spending_transaction_id.map(|id| id as i64))
+            .bind([owner; 32].as_slice())
+            .bind([token_type; 32].as_slice())
+            .bind(U128BeBytes::from(value))
+            .bind([n; 32].as_slice())
+            .execute(&**pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Seed a call of the contract at `[address; 32]` by the transaction with the given ID.
+    async fn seed_contract_call(
+        pool: &SqlitePool,
+        transaction_id: u8,
+        address: u8,
+    ) -> Result<(), sqlx::Error> {
+        let query = indoc! {r#"
+            INSERT INTO contract_actions (
+                transaction_id, variant, address, state, zswap_state, attributes
+            )
+            VALUES ($1, 'Call', $2, X'00', X'00', '{"Call":{"entry_point":"go"}}')
+        "#};
+
+        sqlx::query(query)
+            .bind(transaction_id as i64)
+            .bind([address; 32].as_slice())
+            .execute(&**pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Height of the block at the given timestamp.
+    async fn get_height_at(storage: &Storage, timestamp: u64) -> Result<Option<u32>, sqlx::Error> {
+        let block = storage.get_block_by_timestamp(timestamp).await?;
+        Ok(block.map(|block| block.height))
+    }
+
+    #[tokio::test]
+    async fn get_block_by_timestamp() -> Result<(), Box<dyn StdError>> {
+        let (storage, pool) = new_storage().await?;
+        seed_block(&pool, 0, 6_000).await?;
+        seed_block(&pool, 1, 12_000).await?;
+        seed_block(&pool, 2, 18_000).await?;
+        seed_block(&pool, 3, 18_000).await?;
+
+        // An exact match is the block at that timestamp, not the one before.
+        assert_eq!(get_height_at(&storage, 12_000).await?, Some(1));
+        assert_eq!(get_height_at(&storage, 11_999).await?, Some(0));
+        assert_eq!(get_height_at(&storage, 12_001).await?, Some(1));
+
+        // Blocks with equal timestamps resolve to the highest one.
+        assert_eq!(get_height_at(&storage, 18_000).await?, Some(3));
+        assert_eq!(get_height_at(&storage, u32::MAX as u64).await?, Some(3));
+
+        // Nothing before the first block.
+        assert_eq!(get_height_at(&storage, 5_999).await?, None);
+
+        Ok(())
+    }
+
+    /// Values of the unspent UTXOs of the given owner at the given height.
+    async fn get_unspent_values_at(
+        storage: &Storage,
+        owner: u8,
+        height: Option<u32>,
+    ) -> Result<Vec<u128>, sqlx::Error> {
+        let utxos = storage
+            .get_unspent_unshielded_utxos_by_address(ByteArray([owner; 32]), height)
+            .await?;
+        Ok(utxos.into_iter().map(|utxo| utxo.value).collect())
+    }
+
+    #[tokio::test]
+    async fn get_unspent_unshielded_utxos_at_height() -> Result<(), Box<dyn StdError>> {
+        let (storage, pool) = new_storage().await?;
+        for height in 0..=2 {
+            let block_id = seed_block(&pool, height, height as i64 * 6_000).await?;
+            seed_transaction(&pool, height + 1, block_id, "Regular").await?;
+        }
+
+        // Owner 1 receives 100 at height 0, spends it at height 1 receiving 60 in return and
+        // receives 40 at height 2; owner 2 receives 7 at height 0.
+        seed_utxo(&pool, 1, 1, 0xaa, 100, 1, Some(2)).await?;
+        seed_utxo(&pool, 2, 1, 0xaa, 60, 2, None).await?;
+        seed_utxo(&pool, 3, 1, 0xaa, 40, 3, None).await?;
+        seed_utxo(&pool, 4, 2, 0xaa, 7, 1, None).await?;
+
+        assert_eq!(
+            get_unspent_values_at(&storage, 1, Some(0)).await?,
+            vec![100]
+        );
+
+        // A UTXO spent exactly at the given height is no longer unspent at that height.
+        assert_eq!(get_unspent_values_at(&storage, 1, Some(1)).await?, vec![60]);
+
+        assert_eq!(
+            get_unspent_values_at(&storage, 1, Some(2)).await?,
+            vec![60, 40]
+        );
+        assert_eq!(
+            get_unspent_values_at(&storage, 1, None).await?,
+            vec![60, 40]
+        );
+        assert_eq!(get_unspent_values_at(&storage, 2, Some(1)).await?, vec![7]);
+
+        let balances = storage
+            .get_unshielded_balances_by_address(ByteArray([1; 32]), Some(1))
+            .await?
+            .into_iter()
+            .map(|balance| (balance.token_type, balance.amount))
+            .collect::<Vec<_>>();
+        assert_eq!(balances, vec![(ByteArray([0xaa; 32]), 60)]);
+
+        Ok(())
+    }
+
+    /// IDs of the transactions creating or spending UTXOs of the given owner from the given
+    /// transaction ID on.
+    async fn get_transaction_ids_for(
+        storage: &Storage,
+        owner: u8,
+        transaction_id: u64,
+    ) -> Result<Vec<u64>, sqlx::Error> {
+        storage
+            .get_transactions_by_unshielded_address(
+                ByteArray([owner; 32]),
+                transaction_id,
+                NonZeroU32::new(10).unwrap(),
+            )
+            .map_ok(|transaction| transaction.id())
+            .try_collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn watch_list_multiplexes_addresses() -> Result<(), Box<dyn StdError>> {
+        let (storage, pool) = new_storage().await?;
+        let block_id = seed_block(&pool, 0, 0).await?;
+        for id in 1..=3 {
+            seed_regular_transaction(&pool, id, block_id, TransactionResult::Success, 0).await?;
+        }
+
+        // Address 2 receives in transaction 1 and sends to address 3 in transaction 2; address 1
+        // receives in transaction 3.
+        seed_utxo(&pool, 1, 2, 0xaa, 100, 1, Some(2)).await?;
+        seed_utxo(&pool, 2, 3, 0xaa, 100, 2, None).await?;
+        seed_utxo(&pool, 3, 1, 0xaa, 100, 3, None).await?;
+
+        // Duplicate addresses are ignored and removed ones are no longer watched.
+        let address = |n: u8| ByteArray([n; 32]);
+        let key = storage
+            .create_watch_list("watched", &[address(1), address(2), address(2)])
+            .await?;
+        storage
+            .add_watch_list_addresses(key, &[address(2), address(3)])
+            .await?;
+        storage
+            .remove_watch_list_addresses(key, &[address(1)])
+            .await?;
+
+        let watch_list = storage.get_watch_list(key).await?.unwrap();
+        assert_eq!(watch_list.name, "watched");
+        assert_eq!(watch_list.addresses, vec![address(2), address(3)]);
+
+        // Each watched address is streamed from its own cursor, a transaction touching several
+        // watched addresses for each of them.
+        assert_eq!(get_transaction_ids_for(&storage, 2, 0).await?, vec![1, 2]);
+        assert_eq!(get_transaction_ids_for(&storage, 2, 2).await?, vec![2]);
+        assert_eq!(get_transaction_ids_for(&storage, 3, 0).await?, vec![2]);
+        assert_eq!(get_transaction_ids_for(&storage, 3, 3).await?, vec![]);
+
+        storage.delete_watch_list(key).await?;
+        assert_eq!(storage.get_watch_list(key).await?, None);
+
+        Ok(())
+    }
+
+    /// IDs of the transactions on the given page matching the given filter.
+    async fn search_transaction_ids(
+        storage: &Storage,
+        filter: TransactionFilter,
+        page: Page,
+    ) -> Result<Vec<u64>, sqlx::Error> {
+        let transactions = storage
+            .get_transactions_page_by_filter(&filter, &page)
+            .await?;
+        Ok(transactions
+            .iter()
+            .map(|transaction| transaction.id())
+            .collect())
+    }
+
+    #[tokio::test]
+    async fn get_transactions_page_by_filter() -> Result<(), Box<dyn StdError>> {
+        let (storage, pool) = new_storage().await?;
+        let block_ids = [
+            seed_block(&pool, 0, 0).await?,
+            seed_block(&pool, 1, 6_000).await?,
+            seed_block(&pool, 2, 12_000).await?,
+        ];
+
+        let partial_success = TransactionResult::PartialSuccess(vec![(1, true), (2, false)]);
+        seed_regular_transaction(&pool, 1, block_ids[0], TransactionResult::Success, 10).await?;
+        seed_regular_transaction(&pool, 2, block_ids[1], partial_success, 20).await?;
+        seed_transaction(&pool, 3, block_ids[1], "System").await?;
+        seed_regular_transaction(&pool, 4, block_ids[2], TransactionResult::Failure, 30).await?;
+
+        // Transaction 2 calls a contract; owner 1 receives token A in transaction 1 and sends it
+        // in transaction 4 which gives token B to owner 2.
+        seed_contract_call(&pool, 2, 0xc1).await?;
+        seed_utxo(&pool, 1, 1, 0xaa, 100, 1, Some(4)).await?;
+        seed_utxo(&pool, 2, 2, 0xbb, 100, 4, None).await?;
+
+        let page = Page {
+            after: None,
+            before: None,
+            limit: 10,
+            backward: false,
+        };
+        let cases = [
+            (TransactionFilter::default(), vec![1, 2, 3, 4]),
+            (
+                TransactionFilter {
+                    block_height_from: Some(1),
+                    block_height_to: Some(1),
+                    ..Default::default()
+                },
+                vec![2, 3],
+            ),
+            (
+                TransactionFilter {
+                    timestamp_from: Some(6_000),
+                    ..Default::default()
+                },
+                vec![2, 3, 4],
+            ),
+            (
+                TransactionFilter {
+                    timestamp_to: Some(5_999),
+                    ..Default::default()
+                },
+                vec![1],
+            ),
+            (
+                TransactionFilter {
+                    variant: Some(TransactionVariant::System),
+                    ..Default::default()
+                },
+                vec![3],
+            ),
+            (
+                TransactionFilter {
+                    result_status: Some(TransactionResultStatus::Success),
+                    ..Default::default()
+                },
+                vec![1],
+            ),
+            (
+                TransactionFilter {
+                    result_status: Some(TransactionResultStatus::PartialSuccess),
+                    ..Default::default()
+                },
+                vec![2],
+            ),
+            (
+                TransactionFilter {
+                    result_status: Some(TransactionResultStatus::Failure),
+                    ..Default::default()
+                },
+                vec![4],
+            ),
+            (
+                TransactionFilter {
+                    paid_fees_min: Some(15),
+                    paid_fees_max: Some(30),
+                    ..Default::default()
+                },
+                vec![2, 4],
+            ),
+            (
+                TransactionFilter {
+                    contract_address: Some(vec![0xc1; 32].into()),
+                    ..Default::default()
+                },
+                vec![2],
+            ),
+            (
+                TransactionFilter {
+                    unshielded_address: Some(ByteArray([1; 32])),
+                    ..Default::default()
+                },
+                vec![1, 4],
+            ),
+            (
+                TransactionFilter {
+                    token_type: Some(ByteArray([0xbb; 32])),
+                    ..Default::default()
+                },
+                vec![4],
+            ),
+            // Address and token type must match the same UTXO.
+            (
+                TransactionFilter {
+                    unshielded_address: Some(ByteArray([1; 32])),
+                    token_type: Some(ByteArray([0xbb; 32])),
+                    ..Default::default()
+                },
+                vec![],
+            ),
+            (
+                TransactionFilter {
+                    block_height_from: Some(1),
+                    variant: Some(TransactionVariant::Regular),
+                    ..Default::default()
+                },
+                vec![2, 4],
+            ),
+        ];
+        for (filter, expected_ids) in cases {
+            let ids = search_transaction_ids(&storage, filter.clone(), page.clone()).await?;
+            assert_eq!(ids, expected_ids, "{filter:?}");
+        }
+
+        // Pages of matching transactions in both directions, each in ascending order.
+        let filter = TransactionFilter {
+            variant: Some(TransactionVariant::Regular),
+            ..Default::default()
+        };
+        let page = Page {
+            after: Some(1),
+            limit: 1,
+            ..page
+        };
+        let ids = search_transaction_ids(&storage, filter.clone(), page.clone()).await?;
+        assert_eq!(ids, vec![2]);
+        let page = Page {
+            after: None,
+            limit: 2,
+            backward: true,
+            ..page
+        };
+        let ids = search_transaction_ids(&storage, filter, page).await?;
+        assert_eq!(ids, vec![2, 4]);
+
+        Ok(())
+    }
+
+    /// A keyset page with the given bounds and limit.
+    fn page(after: Option<u64>, before: Option<u64>, limit: u32, backward: bool) -> Page {
+        Page {
+            after,
+            before,
+            limit,
+            backward,
+        }
+    }
+
+    #[tokio::test]
+    async fn get_transactions_page() -> Result<(), Box<dyn StdError>> {
+        let (storage, pool) = new_storage().await?;
+        let block_id = seed_block(&pool, 0, 0).await?;
+        for id in 1..=5 {
+            seed_regular_transaction(&pool, id, block_id, TransactionResult::Success, 0).await?;
+        }
+
+        let cases = [
+            // Forward paging with `after`, the last page ending exactly at the last transaction,
+            // followed by an empty final page.
+            (page(None, None, 2, false), vec![1, 2]),
+            (page(Some(2), None, 2, false), vec![3, 4]),
+            (page(Some(3), None, 2, false), vec![4, 5]),
+            (page(Some(4), None, 2, false), vec![5]),
+            (page(Some(5), None, 2, false), vec![]),
+            (page(None, None, 5, false), vec![1, 2, 3, 4, 5]),
+            // Backward paging with `before` and `last`, in ascending order nevertheless.
+            (page(None, None, 2, true), vec![4, 5]),
+            (page(None, Some(4), 2, true), vec![2, 3]),
+            (page(None, Some(3), 2, true), vec![1, 2]),
+            (page(None, Some(2), 2, true), vec![1]),
+            (page(None, Some(1), 2, true), vec![]),
+            // Both bounds.
+            (page(Some(1), Some(5), 10, false), vec![2, 3, 4]),
+            (page(Some(1), Some(5), 2, false), vec![2, 3]),
+            (page(Some(1), Some(5), 2, true), vec![3, 4]),
+            (page(Some(2), Some(3), 2, false), vec![]),
+        ];
+        for (page, expected_ids) in cases {
+            let ids = storage
+                .get_transactions_page(&page)
+                .await?
+                .iter()
+                .map(|transaction| transaction.id())
+                .collect::<Vec<_>>();
+            assert_eq!(ids, expected_ids, "{page:?}");
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn get_contract_actions_page_by_address() -> Result<(), Box<dyn StdError>> {
+        let (storage, pool) = new_storage().await?;
+        let block_id = seed_block(&pool, 0, 0).await?;
+        for id in 1..=5 {
+            seed_regular_transaction(&pool, id, block_id, TransactionResult::Success, 0).await?;
+        }
+
+        // The contract actions with IDs 1, 2, 4 and 5 are the ones of contract 0xc1.
+        for (transaction_id, address) in [(1, 0xc1), (2, 0xc1), (3, 0xc2), (4, 0xc1), (5, 0xc1)] {
+            seed_contract_call(&pool, transaction_id, address).await?;
+        }
+
+        let cases = [
+            // Forward paging with `after`, skipping the contract action of the other contract,
+            // the last page ending exactly at the last contract action, followed by an empty
+            // final page.
+            (None, page(None, None, 2, false), vec![1, 2]),
+            (None, page(Some(2), None, 2, false), vec![4, 5]),
+            (None, page(Some(4), None, 2, false), vec![5]),
+            (None, page(Some(5), None, 2, false), vec![]),
+            // Backward paging with `before` and `last`, in ascending order nevertheless.
+            (None, page(None, None, 3, true), vec![2, 4, 5]),
+            (None, page(None, Some(4), 3, true), vec![1, 2]),
+            (None, page(None, Some(2), 3, true), vec![1]),
+            (None, page(None, Some(1), 3, true), vec![]),
+            // Both bounds.
+            (None, page(Some(1), Some(5), 10, false), vec![2, 4]),
+            // Variant filter.
+            (Some("Call"), page(None, None, 10, false), vec![1, 2, 4, 5]),
+            (Some("Deploy"), page(None, None, 10, false), vec![]),
+        ];
+        for (variant, page, expected_ids) in cases {
+            let ids = storage
+                .get_contract_actions_page_by_address(&vec![0xc1; 32].into(), variant, &page)
+                .await?
+                .iter()
+                .map(|contract_action| contract_action.id)
+                .collect::<Vec<_>>();
+            assert_eq!(ids, expected_ids, "{variant:?} {page:?}");
+        }
+
+        Ok(())
+    }
+}
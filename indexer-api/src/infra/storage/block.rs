@@ -40,7 +40,8 @@ impl BlockStorage for Storage {
                 dust_commitment_end_index,
                 dust_generation_end_index,
                 dust_commitment_merkle_tree_root,
-                dust_generation_merkle_tree_root
+                dust_generation_merkle_tree_root,
+                finalized
             FROM blocks
             ORDER BY height DESC
             LIMIT 1
@@ -73,7 +74,8 @@ impl BlockStorage for Storage {
                 dust_commitment_end_index,
                 dust_generation_end_index,
                 dust_commitment_merkle_tree_root,
-                dust_generation_merkle_tree_root
+                dust_generation_merkle_tree_root,
+                finalized
             FROM blocks
             WHERE height = $1
             LIMIT 1
@@ -85,14 +87,89 @@ impl BlockStorage for Storage {
             .await
     }
 
+    #[trace(properties = {
+        "from_height": "{from_height}",
+        "to_height": "{to_height}",
+        "limit": "{limit}"
+    })]
+    async fn get_blocks_by_height_range(
+        &self,
+        from_height: u32,
+        to_height: u32,
+        limit: u32,
+    ) -> Result<Vec<Block>, sqlx::Error> {
+        let query = indoc! {"
+            SELECT
+                id,
+                hash,
+                height,
+                protocol_version,
+                parent_hash,
+                author,
+                timestamp,
+                zswap_merkle_tree_root,
+                ledger_parameters,
+                zswap_end_index,
+                dust_commitment_end_index,
+                dust_generation_end_index,
+                dust_commitment_merkle_tree_root,
+                dust_generation_merkle_tree_root,
+                finalized
+            FROM blocks
+            WHERE height >= $1
+            AND height <= $2
+            ORDER BY height
+            LIMIT $3
+        "};
+
+        sqlx::query_as(query)
+            .bind(from_height as i64)
+            .bind(to_height as i64)
+            .bind(limit as i64)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    #[trace(properties = { "timestamp": "{timestamp}" })]
+    async fn get_block_by_timestamp(&self, timestamp: u64) -> Result<Option<Block>, sqlx::Error> {
+        let query = indoc! {"
+            SELECT
+                id,
+                hash,
+                height,
+                protocol_version,
+                parent_hash,
+                author,
+                timestamp,
+                zswap_merkle_tree_root,
+                ledger_parameters,
+                zswap_end_index,
+                dust_commitment_end_index,
+                dust_generation_end_index,
+                dust_commitment_merkle_tree_root,
+                dust_generation_merkle_tree_root,
+                finalized
+            FROM blocks
+            WHERE timestamp <= $1
+            ORDER BY timestamp DESC, height DESC
+            LIMIT 1
+        "};
+
+        sqlx::query_as(query)
+            .bind(timestamp as i64)
+            .fetch_optional(&*self.pool)
+            .await
+    }
+
     fn get_blocks(
         &self,
         mut height: u32,
         batch_size: NonZeroU32,
+        include_unfinalized: bool,
     ) -> impl Stream<Item = Result<Block, sqlx::Error>> {
         let chunks = try_stream! {
             loop {
-                let blocks = self.get_blocks(height, batch_size).await?;
+                let blocks = self.get_blocks(height, batch_size, include_unfinalized).await?;
 
                 match blocks.last() {
                     Some(block) => height = block.height + 1,
@@ -127,7 +204,8 @@ impl Storage {
                 dust_commitment_end_index,
                 dust_generation_end_index,
                 dust_commitment_merkle_tree_root,
-                dust_generation_merkle_tree_root
+                dust_generation_merkle_tree_root,
+                finalized
             FROM blocks
             WHERE hash = ANY($1)
         "};
@@ -157,7 +235,8 @@ impl Storage {
                 dust_commitment_end_index,
                 dust_generation_end_index,
                 dust_commitment_merkle_tree_root,
-                dust_generation_merkle_tree_root
+                dust_generation_merkle_tree_root,
+                finalized
             FROM blocks
             WHERE hash IN (
         "};
@@ -172,11 +251,16 @@ impl Storage {
         query.build_query_as().fetch_all(&*self.pool).await
     }
 
-    #[trace(properties = { "height": "{height}", "batch_size": "{batch_size}" })]
+    #[trace(properties = {
+        "height": "{height}",
+        "batch_size": "{batch_size}",
+        "include_unfinalized": "{include_unfinalized}"
+    })]
     async fn get_blocks(
         &self,
         height: u32,
         batch_size: NonZeroU32,
+        include_unfinalized: bool,
     ) -> Result<Vec<Block>, sqlx::Error> {
         let query = indoc! {"
             SELECT
@@ -193,9 +277,11 @@ impl Storage {
                 dust_commitment_end_index,
                 dust_generation_end_index,
                 dust_commitment_merkle_tree_root,
-                dust_generation_merkle_tree_root
+                dust_generation_merkle_tree_root,
+                finalized
             FROM blocks
             WHERE height >= $1
+            AND (finalized OR $3)
             ORDER BY height
             LIMIT $2
         "};
@@ -203,6 +289,7 @@ impl Storage {
         sqlx::query_as(query)
             .bind(height as i64)
             .bind(batch_size.get() as i64)
+            .bind(include_unfinalized)
             .fetch_all(&*self.pool)
             .await
     }
@@ -0,0 +1,122 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    domain::{BlockStats, RecentFees, storage::block_stats::BlockStatsStorage},
+    infra::storage::Storage,
+};
+use fastrace::trace;
+use indexer_common::infra::sqlx::U128BeBytes;
+use indoc::indoc;
+
+impl BlockStatsStorage for Storage {
+    #[trace(properties = { "block_id": "{block_id}" })]
+    async fn get_block_stats(&self, block_id: u64) -> Result<Option<BlockStats>, sqlx::Error> {
+        let query = indoc! {"
+            SELECT
+                block_height,
+                transaction_count,
+                transaction_bytes,
+                fee_sum,
+                fee_min,
+                fee_median,
+                fee_p90
+            FROM block_stats
+            WHERE block_id = $1
+        "};
+
+        sqlx::query_as(query)
+            .bind(block_id as i64)
+            .fetch_optional(&*self.pool)
+            .await
+    }
+
+    #[trace(properties = {
+        "from_height": "{from_height}",
+        "to_height": "{to_height}",
+        "limit": "{limit}"
+    })]
+    async fn get_block_stats_by_height_range(
+        &self,
+        from_height: u32,
+        to_height: u32,
+        limit: u32,
+    ) -> Result<Vec<BlockStats>, sqlx::Error> {
+        let query = indoc! {"
+            SELECT
+                block_height,
+                transaction_count,
+                transaction_bytes,
+                fee_sum,
+                fee_min,
+                fee_median,
+                fee_p90
+            FROM block_stats
+            WHERE block_height >= $1
+            AND block_height <= $2
+            ORDER BY block_height
+            LIMIT $3
+        "};
+
+        sqlx::query_as(query)
+            .bind(from_height as i64)
+            .bind(to_height as i64)
+            .bind(limit as i64)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    #[trace(properties = { "block_count": "{block_count}" })]
+    async fn get_recent_fees(&self, block_count: u32) -> Result<Option<RecentFees>, sqlx::Error> {
+        let query = indoc! {"
+            SELECT MAX(height)
+            FROM blocks
+        "};
+
+        let to_height = sqlx::query_scalar::<_, Option<i64>>(query)
+            .fetch_one(&*self.pool)
+            .await?;
+        let Some(to_height) = to_height else {
+            return Ok(None);
+        };
+        let to_height = to_height as u32;
+        let from_height = (to_height + 1).saturating_sub(block_count.max(1));
+
+        // Paid fees are fixed-size big-endian integers, hence ordering by their bytes is numeric.
+        let query = indoc! {"
+            SELECT regular_transactions.paid_fees
+            FROM regular_transactions
+            INNER JOIN transactions ON transactions.id = regular_transactions.id
+            INNER JOIN blocks ON blocks.id = transactions.block_id
+            WHERE blocks.height >= $1
+            AND blocks.height <= $2
+            AND regular_transactions.paid_fees IS NOT NULL
+            ORDER BY regular_transactions.paid_fees
+        "};
+
+        let fees = sqlx::query_scalar::<_, U128BeBytes>(query)
+            .bind(from_height as i64)
+            .bind(to_height as i64)
+            .fetch_all(&*self.pool)
+            .await?
+            .into_iter()
+            .map(u128::from)
+            .collect();
+
+        Ok(Some(RecentFees {
+            from_height,
+            to_height,
+            fees,
+        }))
+    }
+}
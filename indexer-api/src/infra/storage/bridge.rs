@@ -16,9 +16,12 @@ use crate::{
         bridge::{
             BridgeBalance, BridgeEvent, BridgePoolSummary, BridgeTreasuryAggregate, TreasuryReason,
         },
-        storage::bridge::{BridgeEventFilter, BridgeStorage},
+        storage::{
+            Page,
+            bridge::{BridgeEventFilter, BridgeStorage},
+        },
     },
-    infra::storage::Storage,
+    infra::storage::{Storage, into_ascending, push_page},
 };
 use fastrace::trace;
 use indexer_common::{
@@ -145,6 +148,27 @@ impl BridgeStorage for Storage {
         rows.iter().map(map_event_row).collect()
     }
 
+    #[trace]
+    async fn get_bridge_events_page(
+        &self,
+        filter: &BridgeEventFilter,
+        page: &Page,
+    ) -> Result<Vec<BridgeEvent>, sqlx::Error> {
+        let mut builder: QueryBuilder<'_, Db> = QueryBuilder::new(SELECT_EVENT_FRAGMENT);
+        if !push_filter(&mut builder, filter) {
+            builder.push(" WHERE TRUE");
+        }
+        push_page(&mut builder, "bpe.id", page.map_keys(|id| *id as i64));
+
+        let rows = builder.build().fetch_all(&*self.pool).await?;
+        let events = rows
+            .iter()
+            .map(map_event_row)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(into_ascending(page, events))
+    }
+
     #[trace]
     async fn get_bridge_balance(
         &self,
@@ -0,0 +1,99 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    domain::{ChainStats, ChainStatsGranularity, storage::chain_stats::ChainStatsStorage},
+    infra::storage::Storage,
+};
+use fastrace::trace;
+use indoc::indoc;
+
+/// Milliseconds per day, the daily rollups are keyed by the start of their UTC day.
+const DAY_MILLIS: u64 = 24 * 60 * 60 * 1_000;
+
+impl ChainStatsStorage for Storage {
+    #[trace(properties = {
+        "granularity": "{granularity:?}",
+        "from": "{from}",
+        "to": "{to}",
+        "limit": "{limit}"
+    })]
+    async fn get_chain_stats(
+        &self,
+        granularity: ChainStatsGranularity,
+        from: u64,
+        to: u64,
+        limit: u32,
+    ) -> Result<Vec<ChainStats>, sqlx::Error> {
+        let (query, from) = match granularity {
+            ChainStatsGranularity::Block => {
+                let query = indoc! {"
+                    SELECT
+                        timestamp,
+                        block_height,
+                        CAST(1 AS BIGINT) AS block_count,
+                        regular_transaction_count,
+                        system_transaction_count,
+                        contract_deploy_count,
+                        contract_call_count,
+                        contract_update_count,
+                        unshielded_utxos_created,
+                        unshielded_utxos_spent,
+                        zswap_ledger_event_count,
+                        dust_ledger_event_count,
+                        CAST(NULL AS BIGINT) AS active_address_count
+                    FROM block_rollups
+                    WHERE timestamp >= $1
+                    AND timestamp <= $2
+                    ORDER BY block_height
+                    LIMIT $3
+                "};
+
+                (query, from)
+            }
+
+            ChainStatsGranularity::Day => {
+                let query = indoc! {"
+                    SELECT
+                        day AS timestamp,
+                        CAST(NULL AS BIGINT) AS block_height,
+                        block_count,
+                        regular_transaction_count,
+                        system_transaction_count,
+                        contract_deploy_count,
+                        contract_call_count,
+                        contract_update_count,
+                        unshielded_utxos_created,
+                        unshielded_utxos_spent,
+                        zswap_ledger_event_count,
+                        dust_ledger_event_count,
+                        active_address_count
+                    FROM daily_rollups
+                    WHERE day >= $1
+                    AND day <= $2
+                    ORDER BY day
+                    LIMIT $3
+                "};
+
+                (query, from - from % DAY_MILLIS)
+            }
+        };
+
+        sqlx::query_as(query)
+            .bind(from as i64)
+            .bind(to as i64)
+            .bind(limit as i64)
+            .fetch_all(&*self.pool)
+            .await
+    }
+}
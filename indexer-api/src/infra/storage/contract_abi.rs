@@ -0,0 +1,67 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    domain::{ContractAbi, storage::contract_abi::ContractAbiStorage},
+    infra::storage::Storage,
+};
+use fastrace::trace;
+use indexer_common::domain::SerializedContractAddress;
+use indoc::indoc;
+use sqlx::types::{Json, time::OffsetDateTime};
+
+impl ContractAbiStorage for Storage {
+    #[trace]
+    async fn register_contract_abi(
+        &self,
+        address: &SerializedContractAddress,
+        abi: &ContractAbi,
+    ) -> Result<(), sqlx::Error> {
+        let query = indoc! {"
+            INSERT INTO contract_abis (address, abi, created_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (address)
+            DO UPDATE SET
+                abi = $2,
+                created_at = $3
+        "};
+
+        sqlx::query(query)
+            .bind(address)
+            .bind(Json(abi))
+            .bind(OffsetDateTime::now_utc())
+            .execute(&*self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    #[trace]
+    async fn get_contract_abi(
+        &self,
+        address: &SerializedContractAddress,
+    ) -> Result<Option<ContractAbi>, sqlx::Error> {
+        let query = indoc! {"
+            SELECT abi
+            FROM contract_abis
+            WHERE address = $1
+        "};
+
+        let abi = sqlx::query_scalar::<_, Json<ContractAbi>>(query)
+            .bind(address)
+            .fetch_optional(&*self.pool)
+            .await?;
+
+        Ok(abi.map(|Json(abi)| abi))
+    }
+}
@@ -12,8 +12,11 @@
 // limitations under the License.
 
 use crate::{
-    domain::{ContractAction, storage::contract_action::ContractActionStorage},
-    infra::storage::Storage,
+    domain::{
+        ContractAction,
+        storage::{Page, contract_action::ContractActionStorage},
+    },
+    infra::storage::{Storage, into_ascending, push_page},
 };
 use async_stream::try_stream;
 use fastrace::trace;
@@ -86,6 +89,34 @@ impl ContractActionStorage for Storage {
             .await
     }
 
+    #[trace(properties = { "address": "{address}", "id": "{id}" })]
+    async fn get_previous_contract_action_by_address(
+        &self,
+        address: &SerializedContractAddress,
+        id: u64,
+    ) -> Result<Option<ContractAction>, sqlx::Error> {
+        let query = indoc! {"
+            SELECT
+                contract_actions.id,
+                address,
+                state,
+                attributes,
+                zswap_state,
+                transaction_id
+            FROM contract_actions
+            WHERE address = $1
+            AND id < $2
+            ORDER BY id DESC
+            LIMIT 1
+        "};
+
+        sqlx::query_as(query)
+            .bind(address)
+            .bind(id as i64)
+            .fetch_optional(&*self.pool)
+            .await
+    }
+
     #[trace(properties = { "address": "{address}", "hash": "{hash}" })]
     async fn get_contract_action_by_address_and_block_hash(
         &self,
@@ -274,6 +305,50 @@ impl ContractActionStorage for Storage {
             .await
     }
 
+    #[trace(properties = { "address": "{address}", "variant": "{variant:?}", "page": "{page:?}" })]
+    async fn get_contract_actions_page_by_address(
+        &self,
+        address: &SerializedContractAddress,
+        variant: Option<&str>,
+        page: &Page,
+    ) -> Result<Vec<ContractAction>, sqlx::Error> {
+        let mut query_builder = sqlx::QueryBuilder::new(indoc! {"
+            SELECT
+                contract_actions.id,
+                address,
+                state,
+                attributes,
+                zswap_state,
+                transaction_id
+            FROM contract_actions
+            WHERE address =
+        "});
+        query_builder.push_bind(address.as_ref());
+
+        if let Some(variant) = variant {
+            // The variant column is a Postgres enum (cast to text to compare) and a SQLite TEXT.
+            #[cfg(feature = "cloud")]
+            query_builder
+                .push(" AND variant::text = ")
+                .push_bind(variant);
+            #[cfg(feature = "standalone")]
+            query_builder.push(" AND variant = ").push_bind(variant);
+        }
+
+        push_page(
+            &mut query_builder,
+            "contract_actions.id",
+            page.map_keys(|id| *id as i64),
+        );
+
+        let contract_actions = query_builder
+            .build_query_as::<ContractAction>()
+            .fetch_all(&*self.pool)
+            .await?;
+
+        Ok(into_ascending(page, contract_actions))
+    }
+
     #[trace(properties = { "address": "{address}", "hash": "{hash}" })]
     async fn get_contract_action_by_address_and_transaction_hash(
         &self,
@@ -18,9 +18,12 @@
 use crate::{
     domain::{
         ContractEventRow,
-        storage::contract_event::{ContractEventFilter, ContractEventStorage},
+        storage::{
+            Page,
+            contract_event::{ContractEventFilter, ContractEventStorage},
+        },
     },
-    infra::storage::Storage,
+    infra::storage::{Storage, into_ascending, push_page},
 };
 use async_stream::try_stream;
 use fastrace::trace;
@@ -58,6 +61,27 @@ impl ContractEventStorage for Storage {
             .await
     }
 
+    #[trace(properties = { "page": "{page:?}" })]
+    async fn get_contract_events_page(
+        &self,
+        filter: &ContractEventFilter,
+        page: &Page,
+    ) -> Result<Vec<ContractEventRow>, sqlx::Error> {
+        let mut query_builder = base_query_builder(filter);
+        push_page(
+            &mut query_builder,
+            "ledger_events.id",
+            page.map_keys(|id| *id as i64),
+        );
+
+        let rows = query_builder
+            .build_query_as::<ContractEventRow>()
+            .fetch_all(&*self.pool)
+            .await?;
+
+        Ok(into_ascending(page, rows))
+    }
+
     async fn get_contract_events_from_id(
         &self,
         filter: &ContractEventFilter,
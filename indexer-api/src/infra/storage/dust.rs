@@ -17,7 +17,10 @@ use crate::{
 };
 use fastrace::trace;
 use indexer_common::{
-    domain::{ByteVec, CardanoRewardAddress, LedgerVersion, TimestampMs, TimestampSecs, ledger},
+    domain::{
+        ByteVec, CardanoRewardAddress, DustPublicKey, LedgerVersion, TimestampMs, TimestampSecs,
+        ledger,
+    },
     infra::sqlx::U128BeBytes,
 };
 use indoc::indoc;
@@ -132,4 +135,23 @@ impl DustStorage for Storage {
 
         Ok(statuses)
     }
+
+    #[trace]
+    async fn get_cardano_reward_addresses_by_dust_address(
+        &self,
+        dust_address: &DustPublicKey,
+    ) -> Result<Vec<CardanoRewardAddress>, sqlx::Error> {
+        let query = indoc! {"
+            SELECT DISTINCT cardano_stake_key
+            FROM cnight_registrations
+            WHERE dust_address = $1
+            AND removed_at IS NULL
+            ORDER BY cardano_stake_key
+        "};
+
+        sqlx::query_scalar::<_, CardanoRewardAddress>(query)
+            .bind(dust_address.as_ref())
+            .fetch_all(&*self.pool)
+            .await
+    }
 }
@@ -0,0 +1,79 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    domain::{PendingTransaction, storage::pending_transaction::PendingTransactionStorage},
+    infra::storage::Storage,
+};
+use fastrace::trace;
+use indexer_common::domain::{SerializedContractAddress, UnshieldedAddress};
+use indoc::indoc;
+
+impl PendingTransactionStorage for Storage {
+    #[trace(properties = {
+        "contract_address": "{contract_address:?}",
+        "unshielded_address": "{unshielded_address:?}"
+    })]
+    async fn get_pending_transactions(
+        &self,
+        contract_address: Option<&SerializedContractAddress>,
+        unshielded_address: Option<UnshieldedAddress>,
+    ) -> Result<Vec<PendingTransaction>, sqlx::Error> {
+        let mut query_builder = sqlx::QueryBuilder::new(indoc! {"
+            SELECT
+                id,
+                hash,
+                protocol_version,
+                raw,
+                first_seen,
+                last_seen
+            FROM pending_transactions
+            WHERE TRUE
+        "});
+
+        if let Some(contract_address) = contract_address {
+            query_builder
+                .push(indoc! {"
+                    AND EXISTS (
+                        SELECT 1
+                        FROM pending_transaction_addresses
+                        WHERE pending_transaction_id = pending_transactions.id
+                        AND variant = 'Contract'
+                        AND address =
+                "})
+                .push_bind(contract_address.as_ref())
+                .push(")");
+        }
+
+        if let Some(unshielded_address) = &unshielded_address {
+            query_builder
+                .push(indoc! {"
+                    AND EXISTS (
+                        SELECT 1
+                        FROM pending_transaction_addresses
+                        WHERE pending_transaction_id = pending_transactions.id
+                        AND variant = 'Unshielded'
+                        AND address =
+                "})
+                .push_bind(unshielded_address.as_ref())
+                .push(")");
+        }
+
+        query_builder.push(" ORDER BY first_seen, id");
+
+        query_builder
+            .build_query_as::<PendingTransaction>()
+            .fetch_all(&*self.pool)
+            .await
+    }
+}
@@ -17,12 +17,13 @@ use crate::{
             CommitteeMember, EpochInfo, EpochPerf, FirstValidEpoch, PoolMetadata, PresenceEvent,
             RegisteredStat, RegisteredTotals, Spo, SpoComposite, SpoIdentity, StakeShare,
         },
-        storage::spo::SpoStorage,
+        storage::{Page, spo::SpoStorage},
     },
-    infra::storage::Storage,
+    infra::storage::{Storage, into_ascending, push_page},
 };
 use fastrace::trace;
 use indoc::indoc;
+use sqlx::QueryBuilder;
 
 impl SpoStorage for Storage {
     #[trace]
@@ -403,6 +404,110 @@ impl SpoStorage for Storage {
             .collect())
     }
 
+    #[trace]
+    async fn get_spo_list_page(
+        &self,
+        search: Option<&str>,
+        page: &Page<String>,
+    ) -> Result<Vec<Spo>, sqlx::Error> {
+        let mut query_builder = QueryBuilder::new(indoc! {"
+            SELECT s.pool_id AS pool_id_hex,
+                   'UNKNOWN' AS validator_class,
+                   si.sidechain_pubkey AS sidechain_pubkey_hex,
+                   si.aura_pubkey AS aura_pubkey_hex,
+                   pm.name, pm.ticker, pm.homepage_url, pm.url AS logo_url
+            FROM spo_stake_snapshot s
+            LEFT JOIN spo_identity si ON si.pool_id = s.pool_id
+            LEFT JOIN pool_metadata_cache pm ON pm.pool_id = s.pool_id
+            WHERE TRUE
+        "});
+
+        if let Some(s) = search {
+            let s_like = format!("%{s}%");
+            let s_hex = normalize_hex(s).unwrap_or_else(|| s.to_ascii_lowercase());
+            let s_hex_like = format!("%{s_hex}%");
+
+            #[cfg(feature = "cloud")]
+            query_builder
+                .push(" AND (pm.name ILIKE ")
+                .push_bind(s_like.clone())
+                .push(" OR pm.ticker ILIKE ")
+                .push_bind(s_like.clone())
+                .push(" OR pm.homepage_url ILIKE ")
+                .push_bind(s_like)
+                .push(" OR s.pool_id ILIKE ")
+                .push_bind(s_hex_like.clone())
+                .push(" OR si.sidechain_pubkey ILIKE ")
+                .push_bind(s_hex_like.clone())
+                .push(" OR si.aura_pubkey ILIKE ")
+                .push_bind(s_hex_like.clone())
+                .push(" OR si.mainchain_pubkey ILIKE ")
+                .push_bind(s_hex_like)
+                .push(")");
+
+            #[cfg(feature = "standalone")]
+            query_builder
+                .push(" AND (LOWER(pm.name) LIKE LOWER(")
+                .push_bind(s_like.clone())
+                .push(") OR LOWER(pm.ticker) LIKE LOWER(")
+                .push_bind(s_like.clone())
+                .push(") OR LOWER(pm.homepage_url) LIKE LOWER(")
+                .push_bind(s_like)
+                .push(") OR LOWER(s.pool_id) LIKE LOWER(")
+                .push_bind(s_hex_like.clone())
+                .push(") OR LOWER(si.sidechain_pubkey) LIKE LOWER(")
+                .push_bind(s_hex_like.clone())
+                .push(") OR LOWER(si.aura_pubkey) LIKE LOWER(")
+                .push_bind(s_hex_like.clone())
+                .push(") OR LOWER(si.mainchain_pubkey) LIKE LOWER(")
+                .push_bind(s_hex_like)
+                .push("))");
+        }
+
+        push_page(&mut query_builder, "s.pool_id", page.clone());
+
+        let rows = query_builder
+            .build_query_as::<(
+                String,
+                String,
+                String,
+                Option<String>,
+                Option<String>,
+                Option<String>,
+                Option<String>,
+                Option<String>,
+            )>()
+            .fetch_all(&*self.pool)
+            .await?;
+
+        let spos = rows
+            .into_iter()
+            .map(
+                |(
+                    pool_id_hex,
+                    validator_class,
+                    sidechain_pubkey_hex,
+                    aura_pubkey_hex,
+                    name,
+                    ticker,
+                    homepage_url,
+                    logo_url,
+                )| Spo {
+                    pool_id_hex,
+                    validator_class,
+                    sidechain_pubkey_hex,
+                    aura_pubkey_hex,
+                    name,
+                    ticker,
+                    homepage_url,
+                    logo_url,
+                },
+            )
+            .collect();
+
+        Ok(into_ascending(page, spos))
+    }
+
     #[trace]
     async fn get_spo_composite_by_pool_id(
         &self,
@@ -0,0 +1,95 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    domain::{
+        Token, TokenHolder,
+        storage::{Page, token::TokenStorage},
+    },
+    infra::storage::{Db, Storage, into_ascending, push_page},
+};
+use fastrace::trace;
+use indexer_common::domain::TokenType;
+use indoc::indoc;
+use sqlx::QueryBuilder;
+
+impl TokenStorage for Storage {
+    #[trace(properties = { "token_type": "{token_type}" })]
+    async fn get_token(&self, token_type: TokenType) -> Result<Option<Token>, sqlx::Error> {
+        let query = indoc! {"
+            SELECT
+                id,
+                token_type,
+                first_seen_block_height,
+                contract_address,
+                domain_sep,
+                circulating_supply,
+                holder_count
+            FROM tokens
+            WHERE token_type = $1
+        "};
+
+        sqlx::query_as::<_, Token>(query)
+            .bind(token_type.as_ref())
+            .fetch_optional(&*self.pool)
+            .await
+    }
+
+    #[trace(properties = { "page": "{page:?}" })]
+    async fn get_tokens_page(&self, page: &Page) -> Result<Vec<Token>, sqlx::Error> {
+        let mut query_builder = QueryBuilder::<Db>::new(indoc! {"
+            SELECT
+                id,
+                token_type,
+                first_seen_block_height,
+                contract_address,
+                domain_sep,
+                circulating_supply,
+                holder_count
+            FROM tokens
+            WHERE TRUE
+        "});
+        push_page(&mut query_builder, "id", page.map_keys(|id| *id as i64));
+
+        let tokens = query_builder
+            .build_query_as::<Token>()
+            .fetch_all(&*self.pool)
+            .await?;
+
+        Ok(into_ascending(page, tokens))
+    }
+
+    #[trace(properties = { "token_type": "{token_type}", "limit": "{limit}" })]
+    async fn get_token_holders(
+        &self,
+        token_type: TokenType,
+        limit: u32,
+    ) -> Result<Vec<TokenHolder>, sqlx::Error> {
+        // Balances are fixed-size big-endian bytes, hence ordered bytewise like numerically.
+        let query = indoc! {"
+            SELECT
+                owner,
+                balance
+            FROM token_holders
+            WHERE token_type = $1
+            ORDER BY balance DESC, owner
+            LIMIT $2
+        "};
+
+        sqlx::query_as::<_, TokenHolder>(query)
+            .bind(token_type.as_ref())
+            .bind(limit as i64)
+            .fetch_all(&*self.pool)
+            .await
+    }
+}
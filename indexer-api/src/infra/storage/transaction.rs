@@ -13,17 +13,22 @@
 
 use crate::{
     domain::{
-        RegularTransaction, SystemTransaction, Transaction, bridge::BridgeClaim,
-        storage::transaction::TransactionStorage,
+        RegularTransaction, SystemTransaction, Transaction,
+        bridge::BridgeClaim,
+        storage::{
+            Page,
+            transaction::{TransactionFilter, TransactionResultStatus, TransactionStorage},
+        },
     },
-    infra::storage::Storage,
+    infra::storage::{Storage, push_page},
 };
 use async_stream::try_stream;
 use fastrace::trace;
 use futures::{Stream, StreamExt, TryStreamExt};
 use indexer_common::{
     domain::{
-        SerializedTransactionIdentifier, TransactionHash, TransactionVariant, UnshieldedAddress,
+        SerializedTransactionIdentifier, TransactionHash, TransactionResult, TransactionVariant,
+        UnshieldedAddress,
     },
     infra::sqlx::U128BeBytes,
     stream::flatten_chunks,
@@ -31,7 +36,10 @@ use indexer_common::{
 use indoc::indoc;
 #[cfg(feature = "standalone")]
 use sqlx::Sqlite;
-use sqlx::{FromRow, QueryBuilder, Row, types::Uuid};
+use sqlx::{
+    FromRow, QueryBuilder, Row,
+    types::{Json, Uuid},
+};
 use std::{collections::HashMap, num::NonZeroU32};
 
 #[cfg(feature = "cloud")]
@@ -50,6 +58,105 @@ fn make_bridge_claim(row: &<Db as sqlx::Database>::Row) -> Result<BridgeClaim, s
     })
 }
 
+/// Append the conditions of the given filter to a query selecting from `transactions` joined with
+/// `blocks` and left-joined with `regular_transactions`, which already has a WHERE clause.
+fn push_transaction_filter(query_builder: &mut QueryBuilder<'_, Db>, filter: &TransactionFilter) {
+    if let Some(from) = filter.block_height_from {
+        query_builder
+            .push(" AND blocks.height >= ")
+            .push_bind(from as i64);
+    }
+    if let Some(to) = filter.block_height_to {
+        query_builder
+            .push(" AND blocks.height <= ")
+            .push_bind(to as i64);
+    }
+    if let Some(from) = filter.timestamp_from {
+        query_builder
+            .push(" AND blocks.timestamp >= ")
+            .push_bind(from as i64);
+    }
+    if let Some(to) = filter.timestamp_to {
+        query_builder
+            .push(" AND blocks.timestamp <= ")
+            .push_bind(to as i64);
+    }
+
+    if let Some(variant) = filter.variant {
+        query_builder
+            .push(" AND transactions.variant = ")
+            .push_bind(variant);
+    }
+
+    match filter.result_status {
+        Some(TransactionResultStatus::Success) => {
+            query_builder
+                .push(" AND regular_transactions.transaction_result = ")
+                .push_bind(Json(TransactionResult::Success));
+        }
+
+        // Partial successes carry their segments, hence match on the variant name only.
+        Some(TransactionResultStatus::PartialSuccess) => {
+            #[cfg(feature = "cloud")]
+            query_builder.push(" AND regular_transactions.transaction_result ? 'PartialSuccess'");
+            #[cfg(feature = "standalone")]
+            query_builder
+                .push(" AND regular_transactions.transaction_result LIKE '{\"PartialSuccess\":%'");
+        }
+
+        Some(TransactionResultStatus::Failure) => {
+            query_builder
+                .push(" AND regular_transactions.transaction_result = ")
+                .push_bind(Json(TransactionResult::Failure));
+        }
+
+        None => {}
+    }
+
+    // Big-endian encoded fees of the same length compare like the numbers they encode.
+    if let Some(min) = filter.paid_fees_min {
+        query_builder
+            .push(" AND regular_transactions.paid_fees >= ")
+            .push_bind(U128BeBytes::from(min));
+    }
+    if let Some(max) = filter.paid_fees_max {
+        query_builder
+            .push(" AND regular_transactions.paid_fees <= ")
+            .push_bind(U128BeBytes::from(max));
+    }
+
+    if let Some(address) = &filter.contract_address {
+        query_builder
+            .push(" AND transactions.id IN (")
+            .push("SELECT transaction_id FROM contract_actions WHERE address = ")
+            .push_bind(address.as_ref().to_vec())
+            .push(")");
+    }
+
+    if filter.unshielded_address.is_some() || filter.token_type.is_some() {
+        let push_utxo_condition = |query_builder: &mut QueryBuilder<'_, Db>| {
+            query_builder.push(" WHERE TRUE");
+            if let Some(address) = &filter.unshielded_address {
+                query_builder
+                    .push(" AND owner = ")
+                    .push_bind(address.as_ref().to_vec());
+            }
+            if let Some(token_type) = &filter.token_type {
+                query_builder
+                    .push(" AND token_type = ")
+                    .push_bind(token_type.as_ref().to_vec());
+            }
+        };
+
+        query_builder
+            .push(" AND transactions.id IN (SELECT creating_transaction_id FROM unshielded_utxos");
+        push_utxo_condition(query_builder);
+        query_builder.push(" UNION SELECT spending_transaction_id FROM unshielded_utxos");
+        push_utxo_condition(query_builder);
+        query_builder.push(")");
+    }
+}
+
 impl Storage {
     /// Attach bridge-claim payloads to any regular transactions that are CardanoBridge claims, by
     /// looking them up in `bridge_claims` in a single batched query. Non-claim transactions are
@@ -243,6 +350,59 @@ impl TransactionStorage for Storage {
         Ok(transactions)
     }
 
+    #[trace(properties = { "page": "{page:?}" })]
+    async fn get_transactions_page(&self, page: &Page) -> Result<Vec<Transaction>, sqlx::Error> {
+        let mut query_builder = QueryBuilder::<Db>::new("SELECT id FROM transactions WHERE TRUE");
+        push_page(&mut query_builder, "id", page.map_keys(|id| *id as i64));
+
+        let ids = query_builder
+            .build_query_scalar::<i64>()
+            .fetch_all(&*self.pool)
+            .await?
+            .into_iter()
+            .map(|id| id as u64)
+            .collect::<Vec<_>>();
+
+        let mut transactions = self.get_transactions_by_ids(&ids).await?;
+        transactions.sort_by_key(|transaction| transaction.id());
+
+        Ok(transactions)
+    }
+
+    #[trace(properties = { "filter": "{filter:?}", "page": "{page:?}" })]
+    async fn get_transactions_page_by_filter(
+        &self,
+        filter: &TransactionFilter,
+        page: &Page,
+    ) -> Result<Vec<Transaction>, sqlx::Error> {
+        let mut query_builder = QueryBuilder::<Db>::new(indoc! {"
+            SELECT transactions.id
+            FROM transactions
+            INNER JOIN blocks ON blocks.id = transactions.block_id
+            LEFT JOIN regular_transactions ON regular_transactions.id = transactions.id
+            WHERE TRUE
+        "});
+        push_transaction_filter(&mut query_builder, filter);
+        push_page(
+            &mut query_builder,
+            "transactions.id",
+            page.map_keys(|id| *id as i64),
+        );
+
+        let ids = query_builder
+            .build_query_scalar::<i64>()
+            .fetch_all(&*self.pool)
+            .await?
+            .into_iter()
+            .map(|id| id as u64)
+            .collect::<Vec<_>>();
+
+        let mut transactions = self.get_transactions_by_ids(&ids).await?;
+        transactions.sort_by_key(|transaction| transaction.id());
+
+        Ok(transactions)
+    }
+
     async fn get_transactions_by_block_ids(
         &self,
         ids: &[u64],
@@ -549,6 +709,21 @@ impl TransactionStorage for Storage {
         Ok(id.map(|id| id as u64))
     }
 
+    #[trace]
+    async fn get_highest_regular_transaction_id(&self) -> Result<Option<u64>, sqlx::Error> {
+        let query = indoc! {"
+            SELECT MAX(id)
+            FROM transactions
+            WHERE variant = 'Regular'
+        "};
+
+        let (id,) = sqlx::query_as::<_, (Option<i64>,)>(query)
+            .fetch_one(&*self.pool)
+            .await?;
+
+        Ok(id.map(|id| id as u64))
+    }
+
     #[allow(clippy::type_complexity)]
     #[trace(properties = { "wallet_id": "{wallet_id}" })]
     async fn get_highest_zswap_end_indices(
@@ -12,12 +12,19 @@
 // limitations under the License.
 
 use crate::{
-    domain::{UnshieldedUtxo, storage::unshielded::UnshieldedUtxoStorage},
+    domain::{UnshieldedBalance, UnshieldedUtxo, storage::unshielded::UnshieldedUtxoStorage},
     infra::storage::Storage,
 };
 use fastrace::trace;
 use indexer_common::domain::UnshieldedAddress;
 use indoc::indoc;
+use sqlx::QueryBuilder;
+use std::collections::BTreeMap;
+
+#[cfg(feature = "cloud")]
+type Db = sqlx::Postgres;
+#[cfg(feature = "standalone")]
+type Db = sqlx::Sqlite;
 
 impl UnshieldedUtxoStorage for Storage {
     #[trace(properties = { "address": "{address}" })]
@@ -51,6 +58,85 @@ impl UnshieldedUtxoStorage for Storage {
         Ok(utxos)
     }
 
+    #[trace(properties = { "address": "{address}", "height": "{height:?}" })]
+    async fn get_unspent_unshielded_utxos_by_address(
+        &self,
+        address: UnshieldedAddress,
+        height: Option<u32>,
+    ) -> Result<Vec<UnshieldedUtxo>, sqlx::Error> {
+        let mut query_builder = QueryBuilder::<Db>::new(indoc! {"
+            SELECT
+                unshielded_utxos.id,
+                unshielded_utxos.creating_transaction_id,
+                unshielded_utxos.spending_transaction_id,
+                unshielded_utxos.owner,
+                unshielded_utxos.token_type,
+                unshielded_utxos.value,
+                unshielded_utxos.intent_hash,
+                unshielded_utxos.output_index,
+                unshielded_utxos.ctime,
+                unshielded_utxos.initial_nonce,
+                unshielded_utxos.registered_for_dust_generation
+            FROM unshielded_utxos
+            INNER JOIN transactions AS creating_transactions
+                ON creating_transactions.id = unshielded_utxos.creating_transaction_id
+            INNER JOIN blocks AS creating_blocks
+                ON creating_blocks.id = creating_transactions.block_id
+            LEFT JOIN transactions AS spending_transactions
+                ON spending_transactions.id = unshielded_utxos.spending_transaction_id
+            LEFT JOIN blocks AS spending_blocks
+                ON spending_blocks.id = spending_transactions.block_id
+            WHERE unshielded_utxos.owner =
+        "});
+        query_builder.push_bind(address.as_ref());
+
+        match height {
+            // Created at or before the block and not yet spent at that point.
+            Some(height) => {
+                query_builder
+                    .push(" AND creating_blocks.height <= ")
+                    .push_bind(height as i64)
+                    .push(" AND (spending_blocks.height IS NULL OR spending_blocks.height > ")
+                    .push_bind(height as i64)
+                    .push(")");
+            }
+
+            None => {
+                query_builder.push(" AND unshielded_utxos.spending_transaction_id IS NULL");
+            }
+        }
+
+        query_builder.push(" ORDER BY unshielded_utxos.id");
+
+        query_builder.build_query_as().fetch_all(&*self.pool).await
+    }
+
+    #[trace(properties = { "address": "{address}", "height": "{height:?}" })]
+    async fn get_unshielded_balances_by_address(
+        &self,
+        address: UnshieldedAddress,
+        height: Option<u32>,
+    ) -> Result<Vec<UnshieldedBalance>, sqlx::Error> {
+        // Values are stored as big-endian bytes which cannot be summed up in SQL.
+        let balances = self
+            .get_unspent_unshielded_utxos_by_address(address, height)
+            .await?
+            .into_iter()
+            .fold(BTreeMap::<[u8; 32], u128>::new(), |mut balances, utxo| {
+                let amount = balances.entry(utxo.token_type.0).or_default();
+                *amount = amount.saturating_add(utxo.value);
+                balances
+            })
+            .into_iter()
+            .map(|(token_type, amount)| UnshieldedBalance {
+                token_type: token_type.into(),
+                amount,
+            })
+            .collect();
+
+        Ok(balances)
+    }
+
     #[trace(properties = { "transaction_id": "{transaction_id}" })]
     async fn get_unshielded_utxos_created_by_transaction(
         &self,
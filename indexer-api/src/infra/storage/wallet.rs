@@ -11,13 +11,19 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{domain::storage::wallet::WalletStorage, infra::storage::Storage};
+use crate::{
+    domain::{WalletStatus, storage::wallet::WalletStorage},
+    infra::storage::Storage,
+};
 use chacha20poly1305::aead::{OsRng, rand_core::RngCore};
 use fastrace::trace;
 use futures::TryFutureExt;
 use indexer_common::domain::{SessionId, ViewingKey};
 use indoc::indoc;
-use sqlx::types::{Uuid, time::OffsetDateTime};
+use sqlx::{
+    prelude::FromRow,
+    types::{Uuid, time::OffsetDateTime},
+};
 
 impl WalletStorage for Storage {
     #[trace]
@@ -29,8 +35,12 @@ impl WalletStorage for Storage {
         let id = Uuid::now_v7();
         let viewing_key_hash = viewing_key.hash();
         let session_id = generate_session_id();
+        let data_key = self
+            .keyring
+            .generate_data_key(id.as_bytes())
+            .map_err(|error| sqlx::Error::Encode(error.into()))?;
         let viewing_key = viewing_key
-            .encrypt(id, &self.cipher)
+            .encrypt(id, &data_key.cipher)
             .map_err(|error| sqlx::Error::Encode(error.into()))?;
         let start_index: i64 = start_index
             .unwrap_or(0)
@@ -42,13 +52,15 @@ impl WalletStorage for Storage {
                 id,
                 viewing_key_hash,
                 viewing_key,
+                encryption_key_id,
+                data_key,
                 wanted_start_index,
                 first_indexed_transaction_id,
                 last_indexed_transaction_id,
                 last_active,
                 session_id
             )
-            VALUES ($1, $2, $3, $4, $4, $4, $5, $6)
+            VALUES ($1, $2, $3, $7, $8, $4, $4, $4, $5, $6)
             ON CONFLICT (viewing_key_hash)
             DO UPDATE SET
                 last_active = $5,
@@ -66,6 +78,8 @@ impl WalletStorage for Storage {
             .bind(start_index)
             .bind(OffsetDateTime::now_utc())
             .bind(session_id.as_ref())
+            .bind(data_key.key_id as i64)
+            .bind(&data_key.wrapped)
             .execute(&*self.pool)
             .await?;
 
@@ -125,6 +139,74 @@ impl WalletStorage for Storage {
 
         result
     }
+
+    #[trace(properties = { "wallet_id": "{wallet_id}" })]
+    async fn get_wallet_status(
+        &self,
+        wallet_id: Uuid,
+        highest_transaction_id: Option<u64>,
+    ) -> Result<Option<WalletStatus>, sqlx::Error> {
+        let query = indoc! {"
+            SELECT
+                wanted_start_index,
+                first_indexed_transaction_id,
+                last_indexed_transaction_id,
+                relevant_transaction_count,
+                last_active,
+                scan_rate
+            FROM wallets
+            WHERE id = $1
+        "};
+
+        let row = sqlx::query_as::<_, WalletStatusRow>(query)
+            .bind(wallet_id)
+            .fetch_optional(&*self.pool)
+            .await?;
+
+        Ok(row.map(|row| row.into_wallet_status(highest_transaction_id)))
+    }
+}
+
+#[derive(Debug, FromRow)]
+struct WalletStatusRow {
+    #[sqlx(try_from = "i64")]
+    wanted_start_index: u64,
+
+    #[sqlx(try_from = "i64")]
+    first_indexed_transaction_id: u64,
+
+    #[sqlx(try_from = "i64")]
+    last_indexed_transaction_id: u64,
+
+    #[sqlx(try_from = "i64")]
+    relevant_transaction_count: u64,
+
+    last_active: OffsetDateTime,
+
+    scan_rate: Option<f64>,
+}
+
+impl WalletStatusRow {
+    fn into_wallet_status(self, highest_transaction_id: Option<u64>) -> WalletStatus {
+        let WalletStatusRow {
+            wanted_start_index,
+            first_indexed_transaction_id,
+            last_indexed_transaction_id,
+            relevant_transaction_count,
+            last_active,
+            scan_rate,
+        } = self;
+
+        WalletStatus {
+            wanted_start_index,
+            first_indexed_transaction_id,
+            last_indexed_transaction_id,
+            highest_transaction_id,
+            relevant_transaction_count,
+            last_active: (last_active.unix_timestamp_nanos() / 1_000_000) as u64,
+            scan_rate,
+        }
+    }
 }
 
 fn generate_session_id() -> SessionId {
@@ -0,0 +1,186 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    domain::{WatchList, WatchListKey, storage::watch_list::WatchListStorage},
+    infra::storage::Storage,
+};
+use chacha20poly1305::aead::{OsRng, rand_core::RngCore};
+use fastrace::trace;
+use indexer_common::domain::UnshieldedAddress;
+use indoc::indoc;
+use sqlx::{QueryBuilder, types::time::OffsetDateTime};
+
+impl WatchListStorage for Storage {
+    #[trace(properties = { "name": "{name}" })]
+    async fn create_watch_list(
+        &self,
+        name: &str,
+        addresses: &[UnshieldedAddress],
+    ) -> Result<WatchListKey, sqlx::Error> {
+        let key = generate_watch_list_key();
+
+        let mut tx = self.pool.begin().await?;
+
+        let query = indoc! {"
+            INSERT INTO watch_lists (key, name, created_at)
+            VALUES ($1, $2, $3)
+            RETURNING id
+        "};
+        let (id,) = sqlx::query_as::<_, (i64,)>(query)
+            .bind(key.as_ref())
+            .bind(name)
+            .bind(OffsetDateTime::now_utc())
+            .fetch_one(&mut *tx)
+            .await?;
+
+        if !addresses.is_empty() {
+            QueryBuilder::new("INSERT INTO watch_list_addresses (watch_list_id, address)")
+                .push_values(addresses, |mut q, address| {
+                    q.push_bind(id).push_bind(address.as_ref());
+                })
+                .push(" ON CONFLICT (watch_list_id, address) DO NOTHING")
+                .build()
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(key)
+    }
+
+    #[trace]
+    async fn get_watch_list(&self, key: WatchListKey) -> Result<Option<WatchList>, sqlx::Error> {
+        let query = indoc! {"
+            SELECT id, name
+            FROM watch_lists
+            WHERE key = $1
+        "};
+        let Some((id, name)) = sqlx::query_as::<_, (i64, String)>(query)
+            .bind(key.as_ref())
+            .fetch_optional(&*self.pool)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let query = indoc! {"
+            SELECT address
+            FROM watch_list_addresses
+            WHERE watch_list_id = $1
+            ORDER BY address
+        "};
+        let addresses = sqlx::query_scalar::<_, UnshieldedAddress>(query)
+            .bind(id)
+            .fetch_all(&*self.pool)
+            .await?;
+
+        Ok(Some(WatchList { name, addresses }))
+    }
+
+    #[trace]
+    async fn add_watch_list_addresses(
+        &self,
+        key: WatchListKey,
+        addresses: &[UnshieldedAddress],
+    ) -> Result<(), sqlx::Error> {
+        if addresses.is_empty() {
+            return Ok(());
+        }
+
+        let query = indoc! {"
+            SELECT id
+            FROM watch_lists
+            WHERE key = $1
+        "};
+        let Some(id) = sqlx::query_scalar::<_, i64>(query)
+            .bind(key.as_ref())
+            .fetch_optional(&*self.pool)
+            .await?
+        else {
+            return Ok(());
+        };
+
+        QueryBuilder::new("INSERT INTO watch_list_addresses (watch_list_id, address)")
+            .push_values(addresses, |mut q, address| {
+                q.push_bind(id).push_bind(address.as_ref());
+            })
+            .push(" ON CONFLICT (watch_list_id, address) DO NOTHING")
+            .build()
+            .execute(&*self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    #[trace]
+    async fn remove_watch_list_addresses(
+        &self,
+        key: WatchListKey,
+        addresses: &[UnshieldedAddress],
+    ) -> Result<(), sqlx::Error> {
+        if addresses.is_empty() {
+            return Ok(());
+        }
+
+        let query = indoc! {"
+            DELETE FROM watch_list_addresses
+            WHERE watch_list_id = (SELECT id FROM watch_lists WHERE key =
+        "};
+        let mut query_builder = QueryBuilder::new(query);
+        query_builder
+            .push_bind(key.as_ref())
+            .push(") AND address IN (");
+        let mut separated = query_builder.separated(", ");
+        for address in addresses {
+            separated.push_bind(address.as_ref());
+        }
+        query_builder.push(")").build().execute(&*self.pool).await?;
+
+        Ok(())
+    }
+
+    #[trace]
+    async fn delete_watch_list(&self, key: WatchListKey) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let query = indoc! {"
+            DELETE FROM watch_list_addresses
+            WHERE watch_list_id = (SELECT id FROM watch_lists WHERE key = $1)
+        "};
+        sqlx::query(query)
+            .bind(key.as_ref())
+            .execute(&mut *tx)
+            .await?;
+
+        let query = indoc! {"
+            DELETE FROM watch_lists
+            WHERE key = $1
+        "};
+        sqlx::query(query)
+            .bind(key.as_ref())
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+fn generate_watch_list_key() -> WatchListKey {
+    let mut key = [0u8; 32];
+    OsRng.fill_bytes(&mut key);
+    key.into()
+}
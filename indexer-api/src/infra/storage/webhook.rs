@@ -0,0 +1,469 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    domain::{
+        CONTRACT_EVENT_VARIANTS, NewWebhookDelivery, Webhook, WebhookDelivery,
+        WebhookDeliveryStatus, WebhookFilter, WebhookKey, WebhookSecret,
+        storage::webhook::WebhookStorage,
+    },
+    infra::storage::Storage,
+};
+use chacha20poly1305::aead::{OsRng, rand_core::RngCore};
+use fastrace::trace;
+use indexer_common::{
+    cipher::KeyId,
+    domain::{ByteVec, UnshieldedAddress, bridge::BridgeRecipient},
+};
+use indoc::indoc;
+use sqlx::{QueryBuilder, Row, types::time::OffsetDateTime};
+
+#[cfg(feature = "cloud")]
+type Db = sqlx::Postgres;
+#[cfg(feature = "standalone")]
+type Db = sqlx::Sqlite;
+
+/// Maximum number of deliveries inserted with a single statement, keeping the bound parameters
+/// well below the SQLite limit.
+const ENQUEUE_CHUNK_SIZE: usize = 1_000;
+
+const DELIVERY_COLUMNS: &str = "\
+    id, webhook_id, event_key, event_type, payload, status, attempts, next_attempt_at, \
+    last_response_status, last_error, created_at, delivered_at";
+
+impl WebhookStorage for Storage {
+    #[trace(properties = { "url": "{url}" })]
+    async fn register_webhook(
+        &self,
+        url: &str,
+        filter: &WebhookFilter,
+    ) -> Result<(WebhookKey, WebhookSecret), sqlx::Error> {
+        let key = generate_webhook_key();
+        let secret = WebhookSecret::generate();
+        let data_key = self
+            .keyring
+            .generate_data_key(key.as_ref())
+            .map_err(|error| sqlx::Error::Encode(error.into()))?;
+        let encrypted_secret = secret
+            .encrypt(&key, &data_key.cipher)
+            .map_err(|error| sqlx::Error::Encode(error.into()))?;
+
+        let WebhookFilter {
+            contract_address,
+            contract_event_type,
+            unshielded_address,
+            bridge_recipient,
+        } = filter;
+
+        // Only transactions indexed after registering are delivered.
+        let query = indoc! {"
+            INSERT INTO webhooks (
+                key,
+                url,
+                secret,
+                contract_address,
+                contract_event_type,
+                unshielded_address,
+                bridge_recipient,
+                transaction_id,
+                created_at,
+                encryption_key_id,
+                data_key
+            )
+            VALUES (
+                $1, $2, $3, $4, $5, $6, $7,
+                (SELECT COALESCE(MAX(id), 0) + 1 FROM transactions),
+                $8, $9, $10
+            )
+        "};
+
+        sqlx::query(query)
+            .bind(key.as_ref())
+            .bind(url)
+            .bind(&encrypted_secret)
+            .bind(contract_address.as_ref().map(|address| address.as_ref()))
+            .bind(*contract_event_type)
+            .bind(unshielded_address.as_ref().map(|address| address.as_ref()))
+            .bind(
+                bridge_recipient
+                    .as_ref()
+                    .map(|recipient| recipient.as_bytes()),
+            )
+            .bind(OffsetDateTime::now_utc())
+            .bind(data_key.key_id as i64)
+            .bind(&data_key.wrapped)
+            .execute(&*self.pool)
+            .await?;
+
+        Ok((key, secret))
+    }
+
+    #[trace]
+    async fn get_webhook_id(&self, key: WebhookKey) -> Result<Option<u64>, sqlx::Error> {
+        let query = indoc! {"
+            SELECT id
+            FROM webhooks
+            WHERE key = $1
+        "};
+
+        sqlx::query_scalar::<_, i64>(query)
+            .bind(key.as_ref())
+            .fetch_optional(&*self.pool)
+            .await
+            .map(|id| id.map(|id| id as u64))
+    }
+
+    #[trace]
+    async fn get_webhooks(&self) -> Result<Vec<Webhook>, sqlx::Error> {
+        let query = indoc! {"
+            SELECT
+                id,
+                key,
+                url,
+                secret,
+                contract_address,
+                contract_event_type,
+                unshielded_address,
+                bridge_recipient,
+                transaction_id,
+                encryption_key_id,
+                data_key
+            FROM webhooks
+            ORDER BY id
+        "};
+
+        sqlx::query(query)
+            .fetch_all(&*self.pool)
+            .await?
+            .iter()
+            .map(|row| self.map_webhook_row(row))
+            .collect()
+    }
+
+    #[trace]
+    async fn get_webhook_count(&self) -> Result<u64, sqlx::Error> {
+        let query = indoc! {"
+            SELECT COUNT(*)
+            FROM webhooks
+        "};
+
+        sqlx::query_scalar::<_, i64>(query)
+            .fetch_one(&*self.pool)
+            .await
+            .map(|count| count as u64)
+    }
+
+    #[trace]
+    async fn delete_webhook(&self, key: WebhookKey) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let query = indoc! {"
+            DELETE FROM webhook_dead_letters
+            WHERE webhook_id = (SELECT id FROM webhooks WHERE key = $1)
+        "};
+        sqlx::query(query)
+            .bind(key.as_ref())
+            .execute(&mut *tx)
+            .await?;
+
+        let query = indoc! {"
+            DELETE FROM webhook_deliveries
+            WHERE webhook_id = (SELECT id FROM webhooks WHERE key = $1)
+        "};
+        sqlx::query(query)
+            .bind(key.as_ref())
+            .execute(&mut *tx)
+            .await?;
+
+        let query = indoc! {"
+            DELETE FROM webhooks
+            WHERE key = $1
+        "};
+        sqlx::query(query)
+            .bind(key.as_ref())
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    #[trace]
+    async fn enqueue_webhook_deliveries(
+        &self,
+        deliveries: &[NewWebhookDelivery],
+        transaction_ids: &[(u64, u64)],
+        now: u64,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        for deliveries in deliveries.chunks(ENQUEUE_CHUNK_SIZE) {
+            let query = indoc! {"
+                INSERT INTO webhook_deliveries (
+                    webhook_id,
+                    event_key,
+                    event_type,
+                    payload,
+                    status,
+                    attempts,
+                    next_attempt_at,
+                    created_at
+                )
+            "};
+
+            QueryBuilder::new(query)
+                .push_values(deliveries, |mut q, delivery| {
+                    q.push_bind(delivery.webhook_id as i64)
+                        .push_bind(&delivery.event_key)
+                        .push_bind(delivery.event_type)
+                        .push_bind(&delivery.payload)
+                        .push_bind(WebhookDeliveryStatus::Pending)
+                        .push_bind(0_i64)
+                        .push_bind(now as i64)
+                        .push_bind(now as i64);
+                })
+                .push(" ON CONFLICT (webhook_id, event_key) DO NOTHING")
+                .build()
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        for (id, transaction_id) in transaction_ids {
+            let query = indoc! {"
+                UPDATE webhooks
+                SET transaction_id = $2
+                WHERE id = $1
+                AND transaction_id < $2
+            "};
+
+            sqlx::query(query)
+                .bind(*id as i64)
+                .bind(*transaction_id as i64)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    #[trace(properties = { "limit": "{limit}" })]
+    async fn claim_webhook_deliveries(
+        &self,
+        now: u64,
+        lease_until: u64,
+        limit: u32,
+    ) -> Result<Vec<WebhookDelivery>, sqlx::Error> {
+        // Skipping locked rows lets concurrent replicas claim disjoint deliveries.
+        #[cfg(feature = "cloud")]
+        let lock = "FOR UPDATE SKIP LOCKED";
+        #[cfg(feature = "standalone")]
+        let lock = "";
+
+        let query = format!(
+            "UPDATE webhook_deliveries \
+             SET attempts = attempts + 1, next_attempt_at = $2 \
+             WHERE id IN ( \
+                 SELECT id \
+                 FROM webhook_deliveries \
+                 WHERE status = 'Pending' \
+                 AND next_attempt_at <= $1 \
+                 ORDER BY next_attempt_at, id \
+                 LIMIT $3 \
+                 {lock} \
+             ) \
+             RETURNING {DELIVERY_COLUMNS}"
+        );
+
+        let mut deliveries = sqlx::query_as::<_, WebhookDelivery>(&query)
+            .bind(now as i64)
+            .bind(lease_until as i64)
+            .bind(limit as i64)
+            .fetch_all(&*self.pool)
+            .await?;
+        deliveries.sort_by_key(|delivery| delivery.id);
+
+        Ok(deliveries)
+    }
+
+    #[trace(properties = { "id": "{id}" })]
+    async fn complete_webhook_delivery(
+        &self,
+        id: u64,
+        response_status: u16,
+        now: u64,
+    ) -> Result<(), sqlx::Error> {
+        let query = indoc! {"
+            UPDATE webhook_deliveries
+            SET
+                status = $2,
+                last_response_status = $3,
+                last_error = NULL,
+                delivered_at = $4
+            WHERE id = $1
+        "};
+
+        sqlx::query(query)
+            .bind(id as i64)
+            .bind(WebhookDeliveryStatus::Delivered)
+            .bind(response_status as i64)
+            .bind(now as i64)
+            .execute(&*self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    #[trace(properties = { "id": "{id}" })]
+    async fn fail_webhook_delivery(
+        &self,
+        id: u64,
+        response_status: Option<u16>,
+        error: &str,
+        next_attempt_at: Option<u64>,
+        now: u64,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let status = match next_attempt_at {
+            Some(_) => WebhookDeliveryStatus::Pending,
+            None => WebhookDeliveryStatus::Dead,
+        };
+
+        let query = indoc! {"
+            UPDATE webhook_deliveries
+            SET
+                status = $2,
+                last_response_status = $3,
+                last_error = $4,
+                next_attempt_at = COALESCE($5, next_attempt_at)
+            WHERE id = $1
+        "};
+        sqlx::query(query)
+            .bind(id as i64)
+            .bind(status)
+            .bind(response_status.map(i64::from))
+            .bind(error)
+            .bind(next_attempt_at.map(|next_attempt_at| next_attempt_at as i64))
+            .execute(&mut *tx)
+            .await?;
+
+        if status == WebhookDeliveryStatus::Dead {
+            let query = indoc! {"
+                INSERT INTO webhook_dead_letters (delivery_id, webhook_id, failed_at)
+                SELECT id, webhook_id, $2
+                FROM webhook_deliveries
+                WHERE id = $1
+                ON CONFLICT (delivery_id) DO NOTHING
+            "};
+            sqlx::query(query)
+                .bind(id as i64)
+                .bind(now as i64)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    #[trace(properties = { "id": "{id}", "limit": "{limit}" })]
+    async fn get_webhook_deliveries(
+        &self,
+        id: u64,
+        status: Option<WebhookDeliveryStatus>,
+        limit: u32,
+    ) -> Result<Vec<WebhookDelivery>, sqlx::Error> {
+        let mut query = QueryBuilder::<Db>::new(format!(
+            "SELECT {DELIVERY_COLUMNS} FROM webhook_deliveries WHERE webhook_id = "
+        ));
+        query.push_bind(id as i64);
+        if let Some(status) = status {
+            query.push(" AND status = ").push_bind(status);
+        }
+        query
+            .push(" ORDER BY id DESC LIMIT ")
+            .push_bind(limit as i64);
+
+        query
+            .build_query_as::<WebhookDelivery>()
+            .fetch_all(&*self.pool)
+            .await
+    }
+}
+
+impl Storage {
+    fn map_webhook_row(&self, row: &<Db as sqlx::Database>::Row) -> Result<Webhook, sqlx::Error> {
+        let id: i64 = row.try_get(0)?;
+        let key: Vec<u8> = row.try_get(1)?;
+        let url: String = row.try_get(2)?;
+        let secret: Vec<u8> = row.try_get(3)?;
+        let contract_address: Option<Vec<u8>> = row.try_get(4)?;
+        let contract_event_type: Option<String> = row.try_get(5)?;
+        let unshielded_address: Option<Vec<u8>> = row.try_get(6)?;
+        let bridge_recipient: Option<Vec<u8>> = row.try_get(7)?;
+        let transaction_id: i64 = row.try_get(8)?;
+        let encryption_key_id: i64 = row.try_get(9)?;
+        let data_key: Option<Vec<u8>> = row.try_get(10)?;
+
+        let key = WebhookKey::try_from(key).map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+        let encryption_key_id =
+            KeyId::try_from(encryption_key_id).map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+        let cipher = self
+            .keyring
+            .data_key_cipher(encryption_key_id, data_key.as_deref(), key.as_ref())
+            .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+        let secret = WebhookSecret::decrypt(secret, &key, &cipher)
+            .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+        let contract_event_type = contract_event_type
+            .map(|contract_event_type| {
+                CONTRACT_EVENT_VARIANTS
+                    .into_iter()
+                    .find(|variant| *variant == contract_event_type)
+                    .ok_or_else(|| {
+                        sqlx::Error::Decode(
+                            format!("unknown contract event type {contract_event_type}").into(),
+                        )
+                    })
+            })
+            .transpose()?;
+        let unshielded_address = unshielded_address
+            .map(|b| UnshieldedAddress::try_from(b).map_err(|e| sqlx::Error::Decode(Box::new(e))))
+            .transpose()?;
+        let bridge_recipient = bridge_recipient
+            .map(|b| BridgeRecipient::new(b).map_err(|e| sqlx::Error::Decode(Box::new(e))))
+            .transpose()?;
+
+        Ok(Webhook {
+            id: id as u64,
+            url,
+            secret,
+            filter: WebhookFilter {
+                contract_address: contract_address.map(ByteVec::from),
+                contract_event_type,
+                unshielded_address,
+                bridge_recipient,
+            },
+            transaction_id: transaction_id as u64,
+        })
+    }
+}
+
+fn generate_webhook_key() -> WebhookKey {
+    let mut key = [0u8; 32];
+    OsRng.fill_bytes(&mut key);
+    key.into()
+}
@@ -0,0 +1,240 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::domain::{WebhookRequest, WebhookSender, is_public_address};
+use reqwest::{
+    Client,
+    dns::{Addrs, Name, Resolve, Resolving},
+    header::CONTENT_TYPE,
+    redirect::Policy,
+};
+use std::{io, net::IpAddr, sync::Arc, time::Duration};
+use thiserror::Error;
+
+/// A [WebhookSender] implementation based on reqwest. Redirects are not followed, i.e. a webhook
+/// must be registered with its final URL.
+///
+/// Unless private addresses are allowed, requests are only sent to public addresses: IP literal
+/// hosts are checked before sending and resolved host names are filtered by a custom resolver, so
+/// a host name re-pointed to an internal address after registration cannot be used to reach it.
+#[derive(Clone)]
+pub struct HttpWebhookSender {
+    client: Client,
+    allow_private_addresses: bool,
+}
+
+impl HttpWebhookSender {
+    /// Create a new [HttpWebhookSender].
+    pub fn new(allow_private_addresses: bool) -> Result<Self, reqwest::Error> {
+        let builder = Client::builder()
+            .user_agent(concat!(
+                env!("CARGO_PKG_NAME"),
+                "/",
+                env!("CARGO_PKG_VERSION")
+            ))
+            .redirect(Policy::none());
+        let builder = if allow_private_addresses {
+            builder
+        } else {
+            builder.dns_resolver(Arc::new(PublicAddressResolver))
+        };
+        let client = builder.build()?;
+
+        Ok(Self {
+            client,
+            allow_private_addresses,
+        })
+    }
+}
+
+impl WebhookSender for HttpWebhookSender {
+    type Error = HttpWebhookSenderError;
+
+    async fn send(
+        &self,
+        request: WebhookRequest<'_>,
+        timeout: Duration,
+    ) -> Result<u16, Self::Error> {
+        let WebhookRequest { url, headers, body } = request;
+
+        let request = headers
+            .into_iter()
+            .fold(
+                self.client
+                    .post(url)
+                    .timeout(timeout)
+                    .header(CONTENT_TYPE, "application/json"),
+                |request, (name, value)| request.header(name, value),
+            )
+            .body(body.to_owned())
+            .build()?;
+
+        // IP literal hosts are not resolved, hence not covered by the resolver.
+        if !self.allow_private_addresses {
+            let address = request
+                .url()
+                .host_str()
+                .map(|host| host.trim_start_matches('[').trim_end_matches(']'))
+                .and_then(|host| host.parse::<IpAddr>().ok());
+            if let Some(address) = address
+                && !is_public_address(address)
+            {
+                return Err(HttpWebhookSenderError::NonPublicAddress(address));
+            }
+        }
+
+        let response = self.client.execute(request).await?;
+
+        Ok(response.status().as_u16())
+    }
+}
+
+/// Error possibly returned by [HttpWebhookSender::send].
+#[derive(Debug, Error)]
+pub enum HttpWebhookSenderError {
+    #[error("webhook URL targets non-public address {0}")]
+    NonPublicAddress(IpAddr),
+
+    #[error(transparent)]
+    Reqwest(#[from] reqwest::Error),
+}
+
+/// Resolver only yielding public addresses, failing if a host name resolves to none.
+struct PublicAddressResolver;
+
+impl Resolve for PublicAddressResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let host = name.as_str();
+            let addresses = tokio::net::lookup_host((host, 0))
+                .await?
+                .filter(|address| is_public_address(address.ip()))
+                .collect::<Vec<_>>();
+
+            if addresses.is_empty() {
+                let error = io::Error::other(format!("{host} resolves to no public address"));
+                return Err(error.into());
+            }
+
+            Ok(Box::new(addresses.into_iter()) as Addrs)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        domain::{WebhookRequest, WebhookSender},
+        infra::webhook::{HttpWebhookSender, HttpWebhookSenderError},
+    };
+    use axum::{
+        Router,
+        http::{HeaderMap, StatusCode},
+        routing::post,
+    };
+    use std::{
+        sync::{
+            Arc,
+            atomic::{AtomicUsize, Ordering},
+        },
+        time::Duration,
+    };
+    use tokio::{net::TcpListener, sync::mpsc};
+
+    #[tokio::test]
+    async fn test_send() -> anyhow::Result<()> {
+        // Local stand-in for a webhook endpoint, answering with 204 once and 500 afterwards.
+        let (received_tx, mut received_rx) = mpsc::unbounded_channel();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let app = Router::new().route(
+            "/hook",
+            post(move |headers: HeaderMap, body: String| {
+                let received_tx = received_tx.clone();
+                let first = calls.fetch_add(1, Ordering::Relaxed) == 0;
+                async move {
+                    let signature = headers
+                        .get("x-signature")
+                        .and_then(|value| value.to_str().ok())
+                        .map(ToOwned::to_owned);
+                    let content_type = headers
+                        .get("content-type")
+                        .and_then(|value| value.to_str().ok())
+                        .map(ToOwned::to_owned);
+                    received_tx
+                        .send((signature, content_type, body))
+                        .expect("receiver is alive");
+                    if first {
+                        StatusCode::NO_CONTENT
+                    } else {
+                        StatusCode::INTERNAL_SERVER_ERROR
+                    }
+                }
+            }),
+        );
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("http://{}/hook", listener.local_addr()?);
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let sender = HttpWebhookSender::new(true)?;
+        let request = WebhookRequest {
+            url: &url,
+            headers: vec![("x-signature", "sha256=00".to_string())],
+            body: r#"{"type":"Block"}"#,
+        };
+
+        let status = sender.send(request.clone(), Duration::from_secs(5)).await?;
+        assert_eq!(status, 204);
+        let (signature, content_type, body) = received_rx.recv().await.expect("request received");
+        assert_eq!(signature.as_deref(), Some("sha256=00"));
+        assert_eq!(content_type.as_deref(), Some("application/json"));
+        assert_eq!(body, r#"{"type":"Block"}"#);
+
+        let status = sender.send(request, Duration::from_secs(5)).await?;
+        assert_eq!(status, 500);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_send_non_public_address() -> anyhow::Result<()> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let port = listener.local_addr()?.port();
+        tokio::spawn(async move { axum::serve(listener, Router::new()).await });
+
+        let sender = HttpWebhookSender::new(false)?;
+
+        let url = format!("http://127.0.0.1:{port}/hook");
+        let request = WebhookRequest {
+            url: &url,
+            headers: vec![],
+            body: "{}",
+        };
+        let result = sender.send(request, Duration::from_secs(5)).await;
+        assert!(matches!(
+            result,
+            Err(HttpWebhookSenderError::NonPublicAddress(address)) if address.is_loopback()
+        ));
+
+        // Host names resolving to loopback addresses only are rejected by the resolver.
+        let url = format!("http://localhost:{port}/hook");
+        let request = WebhookRequest {
+            url: &url,
+            headers: vec![],
+            body: "{}",
+        };
+        let result = sender.send(request, Duration::from_secs(5)).await;
+        assert!(matches!(result, Err(HttpWebhookSenderError::Reqwest(_))));
+
+        Ok(())
+    }
+}
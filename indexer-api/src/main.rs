@@ -44,7 +44,7 @@ fn run() -> anyhow::Result<()> {
         infra::{self, api::AxumApi},
     };
     use indexer_common::{
-        cipher::make_cipher,
+        cipher::{KeyProvider, LocalKeyProvider},
         config::ConfigExt,
         infra::{ledger_db, migrations, pool, pub_sub},
         telemetry,
@@ -73,7 +73,8 @@ fn run() -> anyhow::Result<()> {
         ledger_db_config,
         pub_sub_config,
         api_config,
-        secret,
+        submission_node_config,
+        cipher_config,
     } = infra_config;
 
     let runtime = Builder::new_multi_thread()
@@ -95,16 +96,42 @@ fn run() -> anyhow::Result<()> {
                 .context("run Postgres migrations")?;
         }
 
-        let cipher = make_cipher(secret).context("make cipher")?;
-        let storage = infra::storage::Storage::new(cipher, pool.clone());
+        let keyring = LocalKeyProvider::new(cipher_config)
+            .keyring()
+            .await
+            .context("make keyring")?;
+        let storage = infra::storage::Storage::new(keyring, pool.clone());
 
         ledger_db::init(ledger_db_config, pool);
 
+        let publisher = pub_sub::nats::publisher::NatsPublisher::new(pub_sub_config.clone())
+            .await
+            .context("create NatsPublisher")?;
         let subscriber = pub_sub::nats::subscriber::NatsSubscriber::new(pub_sub_config).await?;
 
-        let api = AxumApi::new(api_config, storage, subscriber.clone());
-
-        application::run(application_config, api, subscriber).await
+        let node = match submission_node_config {
+            Some(config) => Some(
+                infra::node::SubxtNode::new(config)
+                    .await
+                    .context("create SubxtNode for transaction submission")?,
+            ),
+            None => None,
+        };
+
+        let webhook_sender = infra::webhook::HttpWebhookSender::new(
+            api_config.webhooks_config.allow_private_addresses,
+        )
+        .context("create HttpWebhookSender")?;
+
+        let api = AxumApi::new(
+            api_config,
+            storage.clone(),
+            subscriber.clone(),
+            publisher,
+            node,
+        );
+
+        application::run(application_config, api, storage, subscriber, webhook_sender).await
     });
 
     // The implicit runtime drop hangs indefinitely when spawned tasks are inside
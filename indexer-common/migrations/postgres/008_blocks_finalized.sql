@@ -0,0 +1,14 @@
+-- Finality flag for blocks.
+--
+-- By default the chain-indexer only follows finalized blocks, hence the column
+-- defaults to TRUE and existing rows need no backfill. With the optional
+-- `follow_best_blocks` mode the chain-indexer also indexes the best (not yet
+-- finalized) blocks, saves them with `finalized = FALSE`, flips them once the
+-- node reports them finalized and deletes them again if they are abandoned by
+-- a reorg.
+
+--------------------------------------------------------------------------------
+-- blocks
+--------------------------------------------------------------------------------
+ALTER TABLE blocks ADD COLUMN finalized BOOLEAN NOT NULL DEFAULT TRUE;
+CREATE INDEX ON blocks (finalized, height);
@@ -0,0 +1,36 @@
+-- Pending transactions from the node's transaction pool.
+--
+-- With the optional `pending_transactions` task the chain-indexer polls the
+-- node's pending extrinsics and saves the contained Midnight transactions
+-- together with the contract and unshielded addresses they touch. Rows are
+-- short-lived: they are pruned once the transaction is included in a block,
+-- i.e. appears in `transactions`, or once it has not been seen in the pool for
+-- the configured TTL. These tables are not part of snapshots.
+
+--------------------------------------------------------------------------------
+-- types
+--------------------------------------------------------------------------------
+CREATE TYPE PENDING_TRANSACTION_ADDRESS_VARIANT AS ENUM('Contract', 'Unshielded');
+--------------------------------------------------------------------------------
+-- pending_transactions
+--------------------------------------------------------------------------------
+CREATE TABLE pending_transactions (
+  id BIGSERIAL PRIMARY KEY,
+  hash BYTEA NOT NULL UNIQUE,
+  protocol_version BIGINT NOT NULL,
+  raw BYTEA NOT NULL,
+  first_seen BIGINT NOT NULL,
+  last_seen BIGINT NOT NULL
+);
+CREATE INDEX ON pending_transactions (last_seen);
+--------------------------------------------------------------------------------
+-- pending_transaction_addresses
+--------------------------------------------------------------------------------
+CREATE TABLE pending_transaction_addresses (
+  id BIGSERIAL PRIMARY KEY,
+  pending_transaction_id BIGINT NOT NULL REFERENCES pending_transactions (id),
+  variant PENDING_TRANSACTION_ADDRESS_VARIANT NOT NULL,
+  address BYTEA NOT NULL
+);
+CREATE INDEX ON pending_transaction_addresses (pending_transaction_id);
+CREATE INDEX ON pending_transaction_addresses (variant, address);
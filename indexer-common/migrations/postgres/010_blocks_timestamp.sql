@@ -0,0 +1,7 @@
+-- Index for mapping wall-clock times to blocks, i.e. finding the latest block
+-- with a timestamp not after a given one (`blockAt` query of the indexer-api).
+
+--------------------------------------------------------------------------------
+-- blocks
+--------------------------------------------------------------------------------
+CREATE INDEX ON blocks (timestamp, height);
@@ -0,0 +1,133 @@
+-- Token registry maintained by the chain-indexer.
+--
+-- `tokens` holds one row per unshielded token type seen on chain, either as
+-- the type of an unshielded UTXO or as the type of a contract unshielded mint.
+-- The circulating supply is the sum of the values of the unspent unshielded
+-- UTXOs of a token type plus the balances of that token type held by
+-- contracts as of their latest contract action, hence it accounts for tokens
+-- minted into or burned from contract balances.
+-- `token_holders` holds the current balance per owner over unspent UTXOs;
+-- owners whose balance drops to zero are removed. Balances and supplies
+-- are fixed-size 16-byte big-endian unsigned integers, hence ordering them
+-- bytewise orders them numerically.
+--
+-- Both tables are updated with every saved block and on rolling back best
+-- blocks. On existing databases they are backfilled from the already indexed
+-- unshielded UTXOs, contract unshielded mints and contract balances.
+
+--------------------------------------------------------------------------------
+-- tokens
+--------------------------------------------------------------------------------
+CREATE TABLE tokens (
+  id BIGSERIAL PRIMARY KEY,
+  token_type BYTEA NOT NULL UNIQUE,
+  first_seen_block_height BIGINT NOT NULL,
+  contract_address BYTEA,
+  domain_sep BYTEA,
+  circulating_supply BYTEA NOT NULL,
+  holder_count BIGINT NOT NULL
+);
+CREATE INDEX ON tokens (first_seen_block_height);
+--------------------------------------------------------------------------------
+-- token_holders
+--------------------------------------------------------------------------------
+CREATE TABLE token_holders (
+  id BIGSERIAL PRIMARY KEY,
+  token_type BYTEA NOT NULL,
+  owner BYTEA NOT NULL,
+  balance BYTEA NOT NULL,
+  UNIQUE (token_type, owner)
+);
+CREATE INDEX ON token_holders (token_type, balance DESC);
+--------------------------------------------------------------------------------
+-- backfill
+--------------------------------------------------------------------------------
+CREATE FUNCTION pg_temp.u128_to_numeric (bytes BYTEA) RETURNS NUMERIC AS $$
+  SELECT sum(get_byte(bytes, i)::NUMERIC * 256::NUMERIC ^ (15 - i))
+  FROM generate_series(0, 15) AS i
+$$ LANGUAGE sql IMMUTABLE;
+
+CREATE FUNCTION pg_temp.numeric_to_u128 (n NUMERIC) RETURNS BYTEA AS $$
+DECLARE
+  bytes BYTEA := '\x00000000000000000000000000000000';
+BEGIN
+  FOR i IN REVERSE 15..0 LOOP
+    bytes := set_byte(bytes, i, mod(n, 256)::INT);
+    n := div(n, 256);
+  END LOOP;
+  RETURN bytes;
+END
+$$ LANGUAGE plpgsql IMMUTABLE;
+
+INSERT INTO token_holders (token_type, owner, balance)
+SELECT token_type, owner, pg_temp.numeric_to_u128(sum(pg_temp.u128_to_numeric(value)))
+FROM unshielded_utxos
+WHERE spending_transaction_id IS NULL
+GROUP BY token_type, owner
+HAVING sum(pg_temp.u128_to_numeric(value)) > 0;
+
+WITH unshielded_mints AS (
+  SELECT
+    decode(ledger_events.attributes -> 'ContractUnshieldedMint' ->> 'token_type', 'hex') AS token_type,
+    decode(ledger_events.attributes -> 'ContractUnshieldedMint' ->> 'domain_sep', 'hex') AS domain_sep,
+    ledger_events.contract_address,
+    blocks.height,
+    ledger_events.id
+  FROM ledger_events
+  INNER JOIN transactions ON transactions.id = ledger_events.transaction_id
+  INNER JOIN blocks ON blocks.id = transactions.block_id
+  WHERE ledger_events.variant = 'UnshieldedMint'
+),
+first_seen AS (
+  SELECT token_type, min(height) AS height
+  FROM (
+    SELECT unshielded_utxos.token_type, blocks.height
+    FROM unshielded_utxos
+    INNER JOIN transactions ON transactions.id = unshielded_utxos.creating_transaction_id
+    INNER JOIN blocks ON blocks.id = transactions.block_id
+    UNION ALL
+    SELECT token_type, height
+    FROM unshielded_mints
+  ) AS token_types
+  GROUP BY token_type
+),
+first_mints AS (
+  SELECT DISTINCT ON (token_type) token_type, contract_address, domain_sep
+  FROM unshielded_mints
+  WHERE contract_address IS NOT NULL
+  ORDER BY token_type, id
+),
+holders AS (
+  SELECT token_type, sum(pg_temp.u128_to_numeric(balance)) AS supply, count(*) AS holder_count
+  FROM token_holders
+  GROUP BY token_type
+),
+contract_supplies AS (
+  SELECT token_type, sum(pg_temp.u128_to_numeric(amount)) AS supply
+  FROM contract_balances
+  WHERE contract_action_id IN (
+    SELECT max(id)
+    FROM contract_actions
+    GROUP BY address
+  )
+  GROUP BY token_type
+)
+INSERT INTO tokens (
+  token_type,
+  first_seen_block_height,
+  contract_address,
+  domain_sep,
+  circulating_supply,
+  holder_count
+)
+SELECT
+  first_seen.token_type,
+  first_seen.height,
+  first_mints.contract_address,
+  first_mints.domain_sep,
+  pg_temp.numeric_to_u128(COALESCE(holders.supply, 0) + COALESCE(contract_supplies.supply, 0)),
+  COALESCE(holders.holder_count, 0)
+FROM first_seen
+LEFT JOIN first_mints ON first_mints.token_type = first_seen.token_type
+LEFT JOIN holders ON holders.token_type = first_seen.token_type
+LEFT JOIN contract_supplies ON contract_supplies.token_type = first_seen.token_type;
@@ -0,0 +1,26 @@
+-- Named watch lists of unshielded addresses.
+--
+-- Clients watching many unshielded addresses, e.g. the deposit addresses of an
+-- exchange, can store them server-side and subscribe to the unshielded
+-- transactions of all of them with a single subscription. A watch list is
+-- identified by a random key returned when created. These tables are filled by
+-- the indexer-api and are not part of snapshots.
+
+--------------------------------------------------------------------------------
+-- watch_lists
+--------------------------------------------------------------------------------
+CREATE TABLE watch_lists (
+  id BIGSERIAL PRIMARY KEY,
+  key BYTEA NOT NULL UNIQUE,
+  name TEXT NOT NULL,
+  created_at TIMESTAMPTZ NOT NULL
+);
+--------------------------------------------------------------------------------
+-- watch_list_addresses
+--------------------------------------------------------------------------------
+CREATE TABLE watch_list_addresses (
+  id BIGSERIAL PRIMARY KEY,
+  watch_list_id BIGINT NOT NULL REFERENCES watch_lists (id),
+  address BYTEA NOT NULL,
+  UNIQUE (watch_list_id, address)
+);
@@ -0,0 +1,63 @@
+-- Webhooks notified about indexed events.
+--
+-- Clients register an HTTP endpoint together with a filter and get matching
+-- events POSTed as JSON, signed with HMAC-SHA256 using a per-webhook secret
+-- which is encrypted at rest. Every indexer-api replica enqueues deliveries for
+-- the events it is notified about, deduplicated by (webhook_id, event_key), and
+-- whichever replica claims a due delivery first sends it. Failed attempts are
+-- retried with exponential backoff; once the attempts are exhausted a delivery
+-- is marked 'Dead' and recorded in webhook_dead_letters. Timestamps are in
+-- milliseconds since the UNIX epoch. These tables are filled by the indexer-api
+-- and are not part of snapshots.
+
+--------------------------------------------------------------------------------
+-- types
+--------------------------------------------------------------------------------
+CREATE TYPE WEBHOOK_DELIVERY_STATUS AS ENUM('Pending', 'Delivered', 'Dead');
+--------------------------------------------------------------------------------
+-- webhooks
+--------------------------------------------------------------------------------
+CREATE TABLE webhooks (
+  id BIGSERIAL PRIMARY KEY,
+  key BYTEA NOT NULL UNIQUE,
+  url TEXT NOT NULL,
+  secret BYTEA NOT NULL,
+  contract_address BYTEA,
+  contract_event_type TEXT,
+  unshielded_address BYTEA,
+  bridge_recipient BYTEA,
+  -- Next transaction to look at for unshielded_address.
+  transaction_id BIGINT NOT NULL,
+  created_at TIMESTAMPTZ NOT NULL
+);
+--------------------------------------------------------------------------------
+-- webhook_deliveries
+--------------------------------------------------------------------------------
+CREATE TABLE webhook_deliveries (
+  id BIGSERIAL PRIMARY KEY,
+  webhook_id BIGINT NOT NULL REFERENCES webhooks (id),
+  event_key TEXT NOT NULL,
+  event_type TEXT NOT NULL,
+  -- The JSON payload, verbatim as signed and sent.
+  payload TEXT NOT NULL,
+  status WEBHOOK_DELIVERY_STATUS NOT NULL,
+  attempts BIGINT NOT NULL,
+  next_attempt_at BIGINT NOT NULL,
+  last_response_status BIGINT,
+  last_error TEXT,
+  created_at BIGINT NOT NULL,
+  delivered_at BIGINT,
+  UNIQUE (webhook_id, event_key)
+);
+CREATE INDEX ON webhook_deliveries (status, next_attempt_at);
+CREATE INDEX ON webhook_deliveries (webhook_id, id);
+--------------------------------------------------------------------------------
+-- webhook_dead_letters
+--------------------------------------------------------------------------------
+CREATE TABLE webhook_dead_letters (
+  id BIGSERIAL PRIMARY KEY,
+  delivery_id BIGINT NOT NULL UNIQUE REFERENCES webhook_deliveries (id),
+  webhook_id BIGINT NOT NULL REFERENCES webhooks (id),
+  failed_at BIGINT NOT NULL
+);
+CREATE INDEX ON webhook_dead_letters (webhook_id);
@@ -0,0 +1,15 @@
+-- Contract ABIs registered for decoding contract states.
+--
+-- The maintenance authority of a contract, or an operator, can upload the ledger
+-- layout of the contract as compiled by Compact, so that the indexer-api can
+-- serve its state decoded into JSON. Registering again replaces the ABI. This
+-- table is filled by the indexer-api and is not part of snapshots.
+
+--------------------------------------------------------------------------------
+-- contract_abis
+--------------------------------------------------------------------------------
+CREATE TABLE contract_abis (
+  address BYTEA PRIMARY KEY,
+  abi JSONB NOT NULL,
+  created_at TIMESTAMPTZ NOT NULL
+);
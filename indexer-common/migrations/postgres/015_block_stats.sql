@@ -0,0 +1,88 @@
+-- Fee statistics per block maintained by the chain-indexer.
+--
+-- The fees are the paid fees of the regular transactions of a block, system
+-- transactions do not pay fees; the minimum, median and 90th percentile (both
+-- nearest-rank) are NULL for blocks without regular transactions. The
+-- transaction count and bytes cover all transactions. Fees are fixed-size
+-- 16-byte big-endian unsigned integers like the paid fees of regular
+-- transactions.
+--
+-- Rows are saved with their blocks and deleted on rolling back best blocks. On
+-- existing databases they are backfilled from the already indexed blocks and
+-- transactions.
+
+--------------------------------------------------------------------------------
+-- block_stats
+--------------------------------------------------------------------------------
+CREATE TABLE block_stats (
+  id BIGSERIAL PRIMARY KEY,
+  block_id BIGINT NOT NULL UNIQUE REFERENCES blocks (id),
+  block_height BIGINT NOT NULL,
+  transaction_count BIGINT NOT NULL,
+  transaction_bytes BIGINT NOT NULL,
+  fee_sum BYTEA NOT NULL,
+  fee_min BYTEA,
+  fee_median BYTEA,
+  fee_p90 BYTEA
+);
+CREATE INDEX ON block_stats (block_height);
+--------------------------------------------------------------------------------
+-- backfill
+--------------------------------------------------------------------------------
+CREATE OR REPLACE FUNCTION pg_temp.u128_to_numeric (bytes BYTEA) RETURNS NUMERIC AS $$
+  SELECT sum(get_byte(bytes, i)::NUMERIC * 256::NUMERIC ^ (15 - i))
+  FROM generate_series(0, 15) AS i
+$$ LANGUAGE sql IMMUTABLE;
+
+CREATE OR REPLACE FUNCTION pg_temp.numeric_to_u128 (n NUMERIC) RETURNS BYTEA AS $$
+DECLARE
+  bytes BYTEA := '\x00000000000000000000000000000000';
+BEGIN
+  FOR i IN REVERSE 15..0 LOOP
+    bytes := set_byte(bytes, i, mod(n, 256)::INT);
+    n := div(n, 256);
+  END LOOP;
+  RETURN bytes;
+END
+$$ LANGUAGE plpgsql IMMUTABLE;
+
+WITH transaction_stats AS (
+  SELECT block_id, count(*) AS transaction_count, sum(octet_length(raw)) AS transaction_bytes
+  FROM transactions
+  GROUP BY block_id
+),
+fee_stats AS (
+  SELECT
+    transactions.block_id,
+    pg_temp.numeric_to_u128(sum(pg_temp.u128_to_numeric(fees.fee))) AS fee_sum,
+    array_agg(fees.fee ORDER BY fees.fee) AS sorted_fees,
+    count(*) AS fee_count
+  FROM (
+    SELECT id, COALESCE(paid_fees, '\x00000000000000000000000000000000') AS fee
+    FROM regular_transactions
+  ) AS fees
+  INNER JOIN transactions ON transactions.id = fees.id
+  GROUP BY transactions.block_id
+)
+INSERT INTO block_stats (
+  block_id,
+  block_height,
+  transaction_count,
+  transaction_bytes,
+  fee_sum,
+  fee_min,
+  fee_median,
+  fee_p90
+)
+SELECT
+  blocks.id,
+  blocks.height,
+  COALESCE(transaction_stats.transaction_count, 0),
+  COALESCE(transaction_stats.transaction_bytes, 0),
+  COALESCE(fee_stats.fee_sum, '\x00000000000000000000000000000000'),
+  fee_stats.sorted_fees[1],
+  fee_stats.sorted_fees[greatest((50 * fee_stats.fee_count + 99) / 100, 1)],
+  fee_stats.sorted_fees[greatest((90 * fee_stats.fee_count + 99) / 100, 1)]
+FROM blocks
+LEFT JOIN transaction_stats ON transaction_stats.block_id = blocks.id
+LEFT JOIN fee_stats ON fee_stats.block_id = blocks.id;
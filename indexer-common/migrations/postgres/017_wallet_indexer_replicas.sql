@@ -0,0 +1,13 @@
+-- Wallet-indexer replicas registered via heartbeats to shard the wallets among
+-- them: each wallet is owned by one of the live replicas, determined by
+-- rendezvous hashing. Replicas whose heartbeats have expired are deleted by the
+-- remaining ones, which thereby take over their wallets.
+
+--------------------------------------------------------------------------------
+-- wallet_indexer_replicas
+--------------------------------------------------------------------------------
+CREATE TABLE wallet_indexer_replicas (
+  id UUID PRIMARY KEY,
+  last_heartbeat TIMESTAMPTZ NOT NULL
+);
+CREATE INDEX ON wallet_indexer_replicas (last_heartbeat);
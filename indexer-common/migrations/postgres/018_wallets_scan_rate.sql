@@ -0,0 +1,9 @@
+-- Scan rate of wallets, i.e. the transaction IDs per second at which the
+-- wallet-indexer has most recently scanned a full transaction batch for a
+-- wallet, which means while catching up. The indexer-api uses it to estimate
+-- the remaining time until a wallet is synced. NULL until measured.
+
+--------------------------------------------------------------------------------
+-- wallets
+--------------------------------------------------------------------------------
+ALTER TABLE wallets ADD COLUMN scan_rate DOUBLE PRECISION;
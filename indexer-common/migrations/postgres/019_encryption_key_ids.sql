@@ -0,0 +1,22 @@
+-- Envelope encryption of the sensitive data, allowing to rotate the secret key:
+-- the data is encrypted with its own random data key, which is wrapped, i.e.
+-- encrypted, with the secret key with the stored ID. After a rotation, the
+-- wallet-indexer re-wraps the data keys with the current secret key. Data
+-- without data key has been encrypted with the secret key directly, before
+-- envelope encryption was introduced, and is re-encrypted with a new data key
+-- by the wallet-indexer. 0 is the ID of the secret key used before key IDs
+-- existed.
+
+--------------------------------------------------------------------------------
+-- wallets
+--------------------------------------------------------------------------------
+ALTER TABLE wallets ADD COLUMN encryption_key_id BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE wallets ADD COLUMN data_key BYTEA;
+CREATE INDEX ON wallets (encryption_key_id);
+
+--------------------------------------------------------------------------------
+-- webhooks
+--------------------------------------------------------------------------------
+ALTER TABLE webhooks ADD COLUMN encryption_key_id BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE webhooks ADD COLUMN data_key BYTEA;
+CREATE INDEX ON webhooks (encryption_key_id);
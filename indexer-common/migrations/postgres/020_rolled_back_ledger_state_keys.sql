@@ -0,0 +1,13 @@
+-- Ledger state keys of rolled back blocks, saved together with deleting the
+-- blocks and deleted by the chain-indexer once it has lowered the gc root
+-- counts of their ledger states in the ledger DB. Keys left over from a
+-- rollback interrupted in between are handled at the next startup.
+
+--------------------------------------------------------------------------------
+-- rolled_back_ledger_state_keys
+--------------------------------------------------------------------------------
+CREATE TABLE rolled_back_ledger_state_keys (
+  id BIGSERIAL PRIMARY KEY,
+  protocol_version BIGINT NOT NULL,
+  ledger_state_key BYTEA NOT NULL
+);
@@ -0,0 +1,16 @@
+-- Number of relevant transactions of wallets, maintained by the wallet-indexer
+-- when saving relevant transactions and by the chain-indexer when rolling them
+-- back. The indexer-api reads it for the wallet status instead of counting the
+-- relevant transactions on every poll.
+
+--------------------------------------------------------------------------------
+-- wallets
+--------------------------------------------------------------------------------
+ALTER TABLE wallets ADD COLUMN relevant_transaction_count BIGINT NOT NULL DEFAULT 0;
+
+UPDATE wallets
+SET relevant_transaction_count = (
+  SELECT COUNT(*)
+  FROM relevant_transactions
+  WHERE relevant_transactions.wallet_id = wallets.id
+);
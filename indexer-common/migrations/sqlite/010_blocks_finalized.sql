@@ -0,0 +1,8 @@
+-- Finality flag for blocks. See the matching postgres/008_blocks_finalized.sql
+-- for full context.
+
+--------------------------------------------------------------------------------
+-- blocks
+--------------------------------------------------------------------------------
+ALTER TABLE blocks ADD COLUMN finalized BOOLEAN NOT NULL DEFAULT TRUE;
+CREATE INDEX blocks_finalized_height_idx ON blocks (finalized, height);
@@ -0,0 +1,26 @@
+-- Pending transactions from the node's transaction pool. See the matching
+-- postgres/009_pending_transactions.sql for full context.
+
+--------------------------------------------------------------------------------
+-- pending_transactions
+--------------------------------------------------------------------------------
+CREATE TABLE pending_transactions (
+  id INTEGER PRIMARY KEY,
+  hash BLOB NOT NULL UNIQUE,
+  protocol_version INTEGER NOT NULL,
+  raw BLOB NOT NULL,
+  first_seen INTEGER NOT NULL,
+  last_seen INTEGER NOT NULL
+);
+CREATE INDEX pending_transactions_last_seen_idx ON pending_transactions (last_seen);
+--------------------------------------------------------------------------------
+-- pending_transaction_addresses
+--------------------------------------------------------------------------------
+CREATE TABLE pending_transaction_addresses (
+  id INTEGER PRIMARY KEY,
+  pending_transaction_id INTEGER NOT NULL REFERENCES pending_transactions (id),
+  variant TEXT CHECK (variant IN ('Contract', 'Unshielded')) NOT NULL,
+  address BLOB NOT NULL
+);
+CREATE INDEX pending_transaction_addresses_pending_transaction_id_idx ON pending_transaction_addresses (pending_transaction_id);
+CREATE INDEX pending_transaction_addresses_variant_address_idx ON pending_transaction_addresses (variant, address);
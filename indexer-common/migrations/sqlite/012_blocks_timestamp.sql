@@ -0,0 +1,7 @@
+-- Index for timestamp-based block lookup. See the matching
+-- postgres/010_blocks_timestamp.sql for full context.
+
+--------------------------------------------------------------------------------
+-- blocks
+--------------------------------------------------------------------------------
+CREATE INDEX blocks_timestamp_height_idx ON blocks (timestamp, height);
@@ -0,0 +1,181 @@
+-- Token registry maintained by the chain-indexer. See the matching
+-- postgres/011_tokens.sql for full context.
+
+--------------------------------------------------------------------------------
+-- tokens
+--------------------------------------------------------------------------------
+CREATE TABLE tokens (
+  id INTEGER PRIMARY KEY,
+  token_type BLOB NOT NULL UNIQUE,
+  first_seen_block_height INTEGER NOT NULL,
+  contract_address BLOB,
+  domain_sep BLOB,
+  circulating_supply BLOB NOT NULL,
+  holder_count INTEGER NOT NULL
+);
+CREATE INDEX tokens_first_seen_block_height_idx ON tokens (first_seen_block_height);
+--------------------------------------------------------------------------------
+-- token_holders
+--------------------------------------------------------------------------------
+CREATE TABLE token_holders (
+  id INTEGER PRIMARY KEY,
+  token_type BLOB NOT NULL,
+  owner BLOB NOT NULL,
+  balance BLOB NOT NULL,
+  UNIQUE (token_type, owner)
+);
+CREATE INDEX token_holders_token_type_balance_idx ON token_holders (token_type, balance DESC);
+--------------------------------------------------------------------------------
+-- backfill
+--
+-- Sums of 16-byte big-endian values are computed over four 32-bit limbs, which
+-- are parsed from hex as JSON5 hexadecimal integers, propagating the carries
+-- from the least significant limb.
+--------------------------------------------------------------------------------
+CREATE TEMP VIEW unspent_limbs AS
+SELECT
+  token_type,
+  owner,
+  json_extract('0x' || substr(hex(value), 1, 8), '$') AS limb_0,
+  json_extract('0x' || substr(hex(value), 9, 8), '$') AS limb_1,
+  json_extract('0x' || substr(hex(value), 17, 8), '$') AS limb_2,
+  json_extract('0x' || substr(hex(value), 25, 8), '$') AS limb_3
+FROM unshielded_utxos
+WHERE spending_transaction_id IS NULL;
+
+CREATE TEMP VIEW contract_limbs AS
+SELECT
+  token_type,
+  json_extract('0x' || substr(hex(amount), 1, 8), '$') AS limb_0,
+  json_extract('0x' || substr(hex(amount), 9, 8), '$') AS limb_1,
+  json_extract('0x' || substr(hex(amount), 17, 8), '$') AS limb_2,
+  json_extract('0x' || substr(hex(amount), 25, 8), '$') AS limb_3
+FROM contract_balances
+WHERE contract_action_id IN (
+  SELECT max(id)
+  FROM contract_actions
+  GROUP BY address
+);
+
+CREATE TEMP TABLE limb_sums (
+  token_type BLOB NOT NULL,
+  owner BLOB,
+  limb_0 INTEGER NOT NULL,
+  limb_1 INTEGER NOT NULL,
+  limb_2 INTEGER NOT NULL,
+  limb_3 INTEGER NOT NULL
+);
+
+INSERT INTO limb_sums
+SELECT token_type, owner, sum(limb_0), sum(limb_1), sum(limb_2), sum(limb_3)
+FROM unspent_limbs
+GROUP BY token_type, owner
+HAVING sum(limb_0) + sum(limb_1) + sum(limb_2) + sum(limb_3) > 0;
+
+INSERT INTO limb_sums
+SELECT token_type, NULL, sum(limb_0), sum(limb_1), sum(limb_2), sum(limb_3)
+FROM (
+  SELECT token_type, limb_0, limb_1, limb_2, limb_3
+  FROM unspent_limbs
+  UNION ALL
+  SELECT token_type, limb_0, limb_1, limb_2, limb_3
+  FROM contract_limbs
+)
+GROUP BY token_type;
+
+CREATE TEMP VIEW limb_carries AS
+SELECT
+  token_type,
+  owner,
+  limb_0,
+  limb_1,
+  limb_2,
+  limb_3,
+  (limb_2 + (limb_3 >> 32)) >> 32 AS carry_1
+FROM limb_sums;
+
+CREATE TEMP VIEW u128_sums AS
+SELECT
+  token_type,
+  owner,
+  unhex(
+    printf(
+      '%08X%08X%08X%08X',
+      (limb_0 + ((limb_1 + carry_1) >> 32)) & 0xFFFFFFFF,
+      (limb_1 + carry_1) & 0xFFFFFFFF,
+      (limb_2 + (limb_3 >> 32)) & 0xFFFFFFFF,
+      limb_3 & 0xFFFFFFFF
+    )
+  ) AS value
+FROM limb_carries;
+
+INSERT INTO token_holders (token_type, owner, balance)
+SELECT token_type, owner, value
+FROM u128_sums
+WHERE owner IS NOT NULL;
+
+WITH unshielded_mints AS (
+  SELECT
+    unhex(ledger_events.attributes ->> '$.ContractUnshieldedMint.token_type') AS token_type,
+    unhex(ledger_events.attributes ->> '$.ContractUnshieldedMint.domain_sep') AS domain_sep,
+    ledger_events.contract_address,
+    blocks.height,
+    ledger_events.id
+  FROM ledger_events
+  INNER JOIN transactions ON transactions.id = ledger_events.transaction_id
+  INNER JOIN blocks ON blocks.id = transactions.block_id
+  WHERE ledger_events.variant = 'UnshieldedMint'
+),
+first_seen AS (
+  SELECT token_type, min(height) AS height
+  FROM (
+    SELECT unshielded_utxos.token_type, blocks.height
+    FROM unshielded_utxos
+    INNER JOIN transactions ON transactions.id = unshielded_utxos.creating_transaction_id
+    INNER JOIN blocks ON blocks.id = transactions.block_id
+    UNION ALL
+    SELECT token_type, height
+    FROM unshielded_mints
+  )
+  GROUP BY token_type
+),
+first_mints AS (
+  SELECT token_type, contract_address, domain_sep
+  FROM unshielded_mints
+  WHERE id IN (
+    SELECT min(id)
+    FROM unshielded_mints
+    WHERE contract_address IS NOT NULL
+    GROUP BY token_type
+  )
+),
+holder_counts AS (
+  SELECT token_type, count(*) AS holder_count
+  FROM token_holders
+  GROUP BY token_type
+)
+INSERT INTO tokens (
+  token_type,
+  first_seen_block_height,
+  contract_address,
+  domain_sep,
+  circulating_supply,
+  holder_count
+)
+SELECT
+  first_seen.token_type,
+  first_seen.height,
+  first_mints.contract_address,
+  first_mints.domain_sep,
+  COALESCE(u128_sums.value, zeroblob(16)),
+  COALESCE(holder_counts.holder_count, 0)
+FROM first_seen
+LEFT JOIN first_mints ON first_mints.token_type = first_seen.token_type
+LEFT JOIN u128_sums ON u128_sums.token_type = first_seen.token_type AND u128_sums.owner IS NULL
+LEFT JOIN holder_counts ON holder_counts.token_type = first_seen.token_type;
+
+DROP VIEW u128_sums;
+DROP VIEW limb_carries;
+DROP TABLE limb_sums;
+DROP VIEW contract_limbs;
+DROP VIEW unspent_limbs;
@@ -0,0 +1,21 @@
+-- Named watch lists of unshielded addresses. See the matching
+-- postgres/012_watch_lists.sql for full context.
+
+--------------------------------------------------------------------------------
+-- watch_lists
+--------------------------------------------------------------------------------
+CREATE TABLE watch_lists (
+  id INTEGER PRIMARY KEY,
+  key BLOB NOT NULL UNIQUE,
+  name TEXT NOT NULL,
+  created_at INTEGER NOT NULL
+);
+--------------------------------------------------------------------------------
+-- watch_list_addresses
+--------------------------------------------------------------------------------
+CREATE TABLE watch_list_addresses (
+  id INTEGER PRIMARY KEY,
+  watch_list_id INTEGER NOT NULL REFERENCES watch_lists (id),
+  address BLOB NOT NULL,
+  UNIQUE (watch_list_id, address)
+);
@@ -0,0 +1,48 @@
+-- Webhooks notified about indexed events. See the matching
+-- postgres/013_webhooks.sql for full context.
+
+--------------------------------------------------------------------------------
+-- webhooks
+--------------------------------------------------------------------------------
+CREATE TABLE webhooks (
+  id INTEGER PRIMARY KEY,
+  key BLOB NOT NULL UNIQUE,
+  url TEXT NOT NULL,
+  secret BLOB NOT NULL,
+  contract_address BLOB,
+  contract_event_type TEXT,
+  unshielded_address BLOB,
+  bridge_recipient BLOB,
+  transaction_id INTEGER NOT NULL,
+  created_at INTEGER NOT NULL
+);
+--------------------------------------------------------------------------------
+-- webhook_deliveries
+--------------------------------------------------------------------------------
+CREATE TABLE webhook_deliveries (
+  id INTEGER PRIMARY KEY,
+  webhook_id INTEGER NOT NULL REFERENCES webhooks (id),
+  event_key TEXT NOT NULL,
+  event_type TEXT NOT NULL,
+  payload TEXT NOT NULL,
+  status TEXT CHECK (status IN ('Pending', 'Delivered', 'Dead')) NOT NULL,
+  attempts INTEGER NOT NULL,
+  next_attempt_at INTEGER NOT NULL,
+  last_response_status INTEGER,
+  last_error TEXT,
+  created_at INTEGER NOT NULL,
+  delivered_at INTEGER,
+  UNIQUE (webhook_id, event_key)
+);
+CREATE INDEX webhook_deliveries_status_next_attempt_at_idx ON webhook_deliveries (status, next_attempt_at);
+CREATE INDEX webhook_deliveries_webhook_id_id_idx ON webhook_deliveries (webhook_id, id);
+--------------------------------------------------------------------------------
+-- webhook_dead_letters
+--------------------------------------------------------------------------------
+CREATE TABLE webhook_dead_letters (
+  id INTEGER PRIMARY KEY,
+  delivery_id INTEGER NOT NULL UNIQUE REFERENCES webhook_deliveries (id),
+  webhook_id INTEGER NOT NULL REFERENCES webhooks (id),
+  failed_at INTEGER NOT NULL
+);
+CREATE INDEX webhook_dead_letters_webhook_id_idx ON webhook_dead_letters (webhook_id);
@@ -0,0 +1,11 @@
+-- Contract ABIs registered for decoding contract states. See the matching
+-- postgres/014_contract_abis.sql for full context.
+
+--------------------------------------------------------------------------------
+-- contract_abis
+--------------------------------------------------------------------------------
+CREATE TABLE contract_abis (
+  address BLOB PRIMARY KEY,
+  abi TEXT NOT NULL,
+  created_at INTEGER NOT NULL
+);
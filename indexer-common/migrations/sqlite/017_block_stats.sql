@@ -0,0 +1,101 @@
+-- Fee statistics per block maintained by the chain-indexer. See the matching
+-- postgres/015_block_stats.sql for full context.
+
+--------------------------------------------------------------------------------
+-- block_stats
+--------------------------------------------------------------------------------
+CREATE TABLE block_stats (
+  id INTEGER PRIMARY KEY,
+  block_id INTEGER NOT NULL UNIQUE REFERENCES blocks (id),
+  block_height INTEGER NOT NULL,
+  transaction_count INTEGER NOT NULL,
+  transaction_bytes INTEGER NOT NULL,
+  fee_sum BLOB NOT NULL,
+  fee_min BLOB,
+  fee_median BLOB,
+  fee_p90 BLOB
+);
+CREATE INDEX block_stats_block_height_idx ON block_stats (block_height);
+--------------------------------------------------------------------------------
+-- backfill
+--
+-- Fee sums are computed over four 32-bit limbs like in 013_tokens.sql.
+--------------------------------------------------------------------------------
+CREATE TEMP VIEW fees AS
+SELECT
+  transactions.block_id,
+  regular_transactions.fee,
+  row_number() OVER (
+    PARTITION BY transactions.block_id
+    ORDER BY regular_transactions.fee
+  ) AS rank,
+  count(*) OVER (PARTITION BY transactions.block_id) AS fee_count
+FROM (
+  SELECT id, COALESCE(paid_fees, zeroblob(16)) AS fee
+  FROM regular_transactions
+) AS regular_transactions
+INNER JOIN transactions ON transactions.id = regular_transactions.id;
+
+CREATE TEMP VIEW fee_limb_sums AS
+SELECT
+  block_id,
+  sum(json_extract('0x' || substr(hex(fee), 1, 8), '$')) AS limb_0,
+  sum(json_extract('0x' || substr(hex(fee), 9, 8), '$')) AS limb_1,
+  sum(json_extract('0x' || substr(hex(fee), 17, 8), '$')) AS limb_2,
+  sum(json_extract('0x' || substr(hex(fee), 25, 8), '$')) AS limb_3,
+  min(fee) AS fee_min,
+  min(fee) FILTER (WHERE rank = max((50 * fee_count + 99) / 100, 1)) AS fee_median,
+  min(fee) FILTER (WHERE rank = max((90 * fee_count + 99) / 100, 1)) AS fee_p90
+FROM fees
+GROUP BY block_id;
+
+CREATE TEMP VIEW fee_stats AS
+SELECT
+  block_id,
+  unhex(
+    printf(
+      '%08X%08X%08X%08X',
+      (limb_0 + ((limb_1 + carry_1) >> 32)) & 0xFFFFFFFF,
+      (limb_1 + carry_1) & 0xFFFFFFFF,
+      (limb_2 + (limb_3 >> 32)) & 0xFFFFFFFF,
+      limb_3 & 0xFFFFFFFF
+    )
+  ) AS fee_sum,
+  fee_min,
+  fee_median,
+  fee_p90
+FROM (
+  SELECT *, (limb_2 + (limb_3 >> 32)) >> 32 AS carry_1
+  FROM fee_limb_sums
+);
+
+INSERT INTO block_stats (
+  block_id,
+  block_height,
+  transaction_count,
+  transaction_bytes,
+  fee_sum,
+  fee_min,
+  fee_median,
+  fee_p90
+)
+SELECT
+  blocks.id,
+  blocks.height,
+  COALESCE(transaction_stats.transaction_count, 0),
+  COALESCE(transaction_stats.transaction_bytes, 0),
+  COALESCE(fee_stats.fee_sum, zeroblob(16)),
+  fee_stats.fee_min,
+  fee_stats.fee_median,
+  fee_stats.fee_p90
+FROM blocks
+LEFT JOIN (
+  SELECT block_id, count(*) AS transaction_count, sum(length(raw)) AS transaction_bytes
+  FROM transactions
+  GROUP BY block_id
+) AS transaction_stats ON transaction_stats.block_id = blocks.id
+LEFT JOIN fee_stats ON fee_stats.block_id = blocks.id;
+
+DROP VIEW fee_stats;
+DROP VIEW fee_limb_sums;
+DROP VIEW fees;
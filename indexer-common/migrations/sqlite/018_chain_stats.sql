@@ -0,0 +1,191 @@
+-- Chain statistics rollups maintained incrementally by the chain-indexer. See
+-- the matching postgres/016_chain_stats.sql for full context.
+
+--------------------------------------------------------------------------------
+-- block_rollups
+--------------------------------------------------------------------------------
+CREATE TABLE block_rollups (
+  id INTEGER PRIMARY KEY,
+  block_id INTEGER NOT NULL UNIQUE REFERENCES blocks (id),
+  block_height INTEGER NOT NULL,
+  timestamp INTEGER NOT NULL,
+  regular_transaction_count INTEGER NOT NULL,
+  system_transaction_count INTEGER NOT NULL,
+  contract_deploy_count INTEGER NOT NULL,
+  contract_call_count INTEGER NOT NULL,
+  contract_update_count INTEGER NOT NULL,
+  unshielded_utxos_created INTEGER NOT NULL,
+  unshielded_utxos_spent INTEGER NOT NULL,
+  zswap_ledger_event_count INTEGER NOT NULL,
+  dust_ledger_event_count INTEGER NOT NULL
+);
+CREATE INDEX block_rollups_timestamp_idx ON block_rollups (timestamp);
+
+--------------------------------------------------------------------------------
+-- daily_rollups
+--------------------------------------------------------------------------------
+CREATE TABLE daily_rollups (
+  id INTEGER PRIMARY KEY,
+  day INTEGER NOT NULL UNIQUE,
+  block_count INTEGER NOT NULL,
+  regular_transaction_count INTEGER NOT NULL,
+  system_transaction_count INTEGER NOT NULL,
+  contract_deploy_count INTEGER NOT NULL,
+  contract_call_count INTEGER NOT NULL,
+  contract_update_count INTEGER NOT NULL,
+  unshielded_utxos_created INTEGER NOT NULL,
+  unshielded_utxos_spent INTEGER NOT NULL,
+  zswap_ledger_event_count INTEGER NOT NULL,
+  dust_ledger_event_count INTEGER NOT NULL,
+  active_address_count INTEGER NOT NULL
+);
+
+--------------------------------------------------------------------------------
+-- daily_active_addresses
+--------------------------------------------------------------------------------
+CREATE TABLE daily_active_addresses (
+  id INTEGER PRIMARY KEY,
+  day INTEGER NOT NULL,
+  address BLOB NOT NULL,
+  block_id INTEGER NOT NULL REFERENCES blocks (id),
+  UNIQUE (day, address)
+);
+CREATE INDEX daily_active_addresses_block_id_idx ON daily_active_addresses (block_id);
+
+--------------------------------------------------------------------------------
+-- backfill
+--------------------------------------------------------------------------------
+INSERT INTO block_rollups (
+  block_id,
+  block_height,
+  timestamp,
+  regular_transaction_count,
+  system_transaction_count,
+  contract_deploy_count,
+  contract_call_count,
+  contract_update_count,
+  unshielded_utxos_created,
+  unshielded_utxos_spent,
+  zswap_ledger_event_count,
+  dust_ledger_event_count
+)
+SELECT
+  blocks.id,
+  blocks.height,
+  blocks.timestamp,
+  COALESCE(transaction_counts.regular_transaction_count, 0),
+  COALESCE(transaction_counts.system_transaction_count, 0),
+  COALESCE(contract_action_counts.contract_deploy_count, 0),
+  COALESCE(contract_action_counts.contract_call_count, 0),
+  COALESCE(contract_action_counts.contract_update_count, 0),
+  COALESCE(created_utxo_counts.unshielded_utxos_created, 0),
+  COALESCE(spent_utxo_counts.unshielded_utxos_spent, 0),
+  COALESCE(ledger_event_counts.zswap_ledger_event_count, 0),
+  COALESCE(ledger_event_counts.dust_ledger_event_count, 0)
+FROM blocks
+LEFT JOIN (
+  SELECT
+    block_id,
+    count(*) FILTER (WHERE variant = 'Regular') AS regular_transaction_count,
+    count(*) FILTER (WHERE variant = 'System') AS system_transaction_count
+  FROM transactions
+  GROUP BY block_id
+) AS transaction_counts ON transaction_counts.block_id = blocks.id
+LEFT JOIN (
+  SELECT
+    transactions.block_id,
+    count(*) FILTER (WHERE contract_actions.variant = 'Deploy') AS contract_deploy_count,
+    count(*) FILTER (WHERE contract_actions.variant = 'Call') AS contract_call_count,
+    count(*) FILTER (WHERE contract_actions.variant = 'Update') AS contract_update_count
+  FROM contract_actions
+  INNER JOIN transactions ON transactions.id = contract_actions.transaction_id
+  GROUP BY transactions.block_id
+) AS contract_action_counts ON contract_action_counts.block_id = blocks.id
+LEFT JOIN (
+  SELECT transactions.block_id, count(*) AS unshielded_utxos_created
+  FROM unshielded_utxos
+  INNER JOIN transactions ON transactions.id = unshielded_utxos.creating_transaction_id
+  GROUP BY transactions.block_id
+) AS created_utxo_counts ON created_utxo_counts.block_id = blocks.id
+LEFT JOIN (
+  SELECT transactions.block_id, count(*) AS unshielded_utxos_spent
+  FROM unshielded_utxos
+  INNER JOIN transactions ON transactions.id = unshielded_utxos.spending_transaction_id
+  GROUP BY transactions.block_id
+) AS spent_utxo_counts ON spent_utxo_counts.block_id = blocks.id
+LEFT JOIN (
+  SELECT
+    transactions.block_id,
+    count(*) FILTER (WHERE ledger_events.grouping = 'Zswap') AS zswap_ledger_event_count,
+    count(*) FILTER (WHERE ledger_events.grouping = 'Dust') AS dust_ledger_event_count
+  FROM ledger_events
+  INNER JOIN transactions ON transactions.id = ledger_events.transaction_id
+  GROUP BY transactions.block_id
+) AS ledger_event_counts ON ledger_event_counts.block_id = blocks.id;
+
+-- The first block of a day in which an address is active is the one with the
+-- lowest ID, because block IDs increase with the height.
+INSERT INTO daily_active_addresses (day, address, block_id)
+SELECT day, address, min(block_id)
+FROM (
+  SELECT
+    blocks.timestamp - blocks.timestamp % 86400000 AS day,
+    unshielded_utxos.owner AS address,
+    blocks.id AS block_id
+  FROM unshielded_utxos
+  INNER JOIN transactions ON transactions.id IN (
+    unshielded_utxos.creating_transaction_id,
+    unshielded_utxos.spending_transaction_id
+  )
+  INNER JOIN blocks ON blocks.id = transactions.block_id
+) AS active_addresses
+GROUP BY day, address;
+
+INSERT INTO daily_rollups (
+  day,
+  block_count,
+  regular_transaction_count,
+  system_transaction_count,
+  contract_deploy_count,
+  contract_call_count,
+  contract_update_count,
+  unshielded_utxos_created,
+  unshielded_utxos_spent,
+  zswap_ledger_event_count,
+  dust_ledger_event_count,
+  active_address_count
+)
+SELECT
+  block_rollup_sums.day,
+  block_rollup_sums.block_count,
+  block_rollup_sums.regular_transaction_count,
+  block_rollup_sums.system_transaction_count,
+  block_rollup_sums.contract_deploy_count,
+  block_rollup_sums.contract_call_count,
+  block_rollup_sums.contract_update_count,
+  block_rollup_sums.unshielded_utxos_created,
+  block_rollup_sums.unshielded_utxos_spent,
+  block_rollup_sums.zswap_ledger_event_count,
+  block_rollup_sums.dust_ledger_event_count,
+  COALESCE(active_address_counts.active_address_count, 0)
+FROM (
+  SELECT
+    timestamp - timestamp % 86400000 AS day,
+    count(*) AS block_count,
+    sum(regular_transaction_count) AS regular_transaction_count,
+    sum(system_transaction_count) AS system_transaction_count,
+    sum(contract_deploy_count) AS contract_deploy_count,
+    sum(contract_call_count) AS contract_call_count,
+    sum(contract_update_count) AS contract_update_count,
+    sum(unshielded_utxos_created) AS unshielded_utxos_created,
+    sum(unshielded_utxos_spent) AS unshielded_utxos_spent,
+    sum(zswap_ledger_event_count) AS zswap_ledger_event_count,
+    sum(dust_ledger_event_count) AS dust_ledger_event_count
+  FROM block_rollups
+  GROUP BY timestamp - timestamp % 86400000
+) AS block_rollup_sums
+LEFT JOIN (
+  SELECT day, count(*) AS active_address_count
+  FROM daily_active_addresses
+  GROUP BY day
+) AS active_address_counts ON active_address_counts.day = block_rollup_sums.day;
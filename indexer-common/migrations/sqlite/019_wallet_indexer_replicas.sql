@@ -0,0 +1,11 @@
+-- Wallet-indexer replicas registered via heartbeats. See the matching
+-- postgres/017_wallet_indexer_replicas.sql for full context.
+
+--------------------------------------------------------------------------------
+-- wallet_indexer_replicas
+--------------------------------------------------------------------------------
+CREATE TABLE wallet_indexer_replicas (
+  id BLOB PRIMARY KEY, -- UUID
+  last_heartbeat INTEGER NOT NULL -- Unix milliseconds
+);
+CREATE INDEX wallet_indexer_replicas_last_heartbeat_idx ON wallet_indexer_replicas (last_heartbeat);
@@ -0,0 +1,7 @@
+-- Scan rate of wallets. See the matching postgres/018_wallets_scan_rate.sql for
+-- full context.
+
+--------------------------------------------------------------------------------
+-- wallets
+--------------------------------------------------------------------------------
+ALTER TABLE wallets ADD COLUMN scan_rate REAL;
@@ -0,0 +1,16 @@
+-- Envelope encryption of the sensitive data. See the matching
+-- postgres/019_encryption_key_ids.sql for full context.
+
+--------------------------------------------------------------------------------
+-- wallets
+--------------------------------------------------------------------------------
+ALTER TABLE wallets ADD COLUMN encryption_key_id INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE wallets ADD COLUMN data_key BLOB;
+CREATE INDEX wallets_encryption_key_id_idx ON wallets (encryption_key_id);
+
+--------------------------------------------------------------------------------
+-- webhooks
+--------------------------------------------------------------------------------
+ALTER TABLE webhooks ADD COLUMN encryption_key_id INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE webhooks ADD COLUMN data_key BLOB;
+CREATE INDEX webhooks_encryption_key_id_idx ON webhooks (encryption_key_id);
@@ -0,0 +1,11 @@
+-- Ledger state keys of rolled back blocks. See the matching
+-- postgres/020_rolled_back_ledger_state_keys.sql for full context.
+
+--------------------------------------------------------------------------------
+-- rolled_back_ledger_state_keys
+--------------------------------------------------------------------------------
+CREATE TABLE rolled_back_ledger_state_keys (
+  id INTEGER PRIMARY KEY,
+  protocol_version INTEGER NOT NULL,
+  ledger_state_key BLOB NOT NULL
+);
@@ -0,0 +1,14 @@
+-- Number of relevant transactions of wallets. See the matching
+-- postgres/021_wallets_relevant_transaction_count.sql for full context.
+
+--------------------------------------------------------------------------------
+-- wallets
+--------------------------------------------------------------------------------
+ALTER TABLE wallets ADD COLUMN relevant_transaction_count INTEGER NOT NULL DEFAULT 0;
+
+UPDATE wallets
+SET relevant_transaction_count = (
+  SELECT COUNT(*)
+  FROM relevant_transactions
+  WHERE relevant_transactions.wallet_id = wallets.id
+);
@@ -11,10 +11,264 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use chacha20poly1305::{ChaCha20Poly1305, Key, KeyInit};
+use chacha20poly1305::{
+    AeadCore, ChaCha20Poly1305, Key, KeyInit,
+    aead::{Aead, OsRng, Payload},
+};
 use secrecy::{ExposeSecret, SecretString};
+use serde::Deserialize;
+use std::{
+    collections::{BTreeMap, HashMap},
+    error::Error as StdError,
+    fmt::{self, Debug},
+};
 use thiserror::Error;
 
+/// Length of the random nonce prefixed to each ciphertext.
+const NONCE_LEN: usize = 12;
+
+/// Length of a data key.
+const DATA_KEY_LEN: usize = 32;
+
+/// ID of a secret key, stored alongside each ciphertext to select the key for decryption.
+pub type KeyId = u32;
+
+/// Configuration of the secret keys to encrypt stored sensitive data, e.g. set via the
+/// `APP__INFRA__SECRET` and `APP__INFRA__RETIRED_SECRETS__<KEY_ID>` environment variables or their
+/// `_FILE` variants.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Config {
+    /// Hex-encoded current secret key, used for encryption and decryption.
+    pub secret: SecretString,
+
+    /// ID of the current secret key; 0 if omitted.
+    #[serde(default)]
+    pub key_id: KeyId,
+
+    /// Hex-encoded retired secret keys by key ID, only used for decryption of data which has not
+    /// yet been re-encrypted with the current secret key.
+    #[serde(default)]
+    pub retired_secrets: HashMap<String, SecretString>,
+}
+
+/// The secret keys to encrypt and decrypt stored sensitive data: one current key used for
+/// encryption and any number of retired keys only used for decryption.
+///
+/// These are key-encryption keys for envelope encryption: data is encrypted with its own random
+/// data key, which is wrapped, i.e. encrypted, with the current key and stored alongside the data
+/// as part of its [Envelope]. Rotating the current key hence only requires re-wrapping the data
+/// keys, not re-encrypting the data.
+#[derive(Clone)]
+pub struct Keyring {
+    current_key_id: KeyId,
+    ciphers: BTreeMap<KeyId, ChaCha20Poly1305>,
+}
+
+impl Keyring {
+    /// Create a keyring with the given current key and no retired keys.
+    pub fn new(current_key_id: KeyId, cipher: ChaCha20Poly1305) -> Self {
+        let ciphers = BTreeMap::from([(current_key_id, cipher)]);
+
+        Self {
+            current_key_id,
+            ciphers,
+        }
+    }
+
+    /// Add the given retired key which must not have the ID of an already contained key.
+    pub fn with_retired_key(
+        mut self,
+        key_id: KeyId,
+        cipher: ChaCha20Poly1305,
+    ) -> Result<Self, Error> {
+        if self.ciphers.insert(key_id, cipher).is_some() {
+            return Err(Error::DuplicateKeyId(key_id));
+        }
+
+        Ok(self)
+    }
+
+    /// The ID of the current key.
+    pub fn current_key_id(&self) -> KeyId {
+        self.current_key_id
+    }
+
+    /// The IDs of all contained keys, i.e. the current and the retired ones, in ascending order.
+    pub fn key_ids(&self) -> impl Iterator<Item = KeyId> + '_ {
+        self.ciphers.keys().copied()
+    }
+
+    /// Generate a random data key, wrapped with the current key and bound to the given associated
+    /// data, e.g. the ID of the row the encrypted data is stored in.
+    pub fn generate_data_key(&self, aad: &[u8]) -> Result<DataKey, Error> {
+        let key = ChaCha20Poly1305::generate_key(&mut OsRng);
+        let wrapped = encrypt(self.current_cipher(), &key, aad)?;
+
+        Ok(DataKey {
+            key_id: self.current_key_id,
+            wrapped,
+            cipher: ChaCha20Poly1305::new(&key),
+        })
+    }
+
+    /// The cipher to decrypt data with, given the ID of the key and the wrapped data key stored
+    /// alongside the data. Without data key, i.e. for data encrypted before envelope encryption
+    /// was introduced, this is the key with the given ID itself.
+    pub fn data_key_cipher(
+        &self,
+        key_id: KeyId,
+        wrapped: Option<&[u8]>,
+        aad: &[u8],
+    ) -> Result<ChaCha20Poly1305, Error> {
+        match wrapped {
+            Some(wrapped) => {
+                let key = self.unwrap_data_key(key_id, wrapped, aad)?;
+                Ok(ChaCha20Poly1305::new(&key))
+            }
+
+            None => self.get(key_id).cloned(),
+        }
+    }
+
+    /// Re-encrypt the given envelope for the current key: its data key is re-wrapped with the
+    /// current key, leaving the ciphertext untouched. Without data key, the data is re-encrypted
+    /// with a newly generated data key.
+    pub fn reencrypt(&self, envelope: Envelope, aad: &[u8]) -> Result<Envelope, Error> {
+        let Envelope {
+            key_id,
+            data_key,
+            ciphertext,
+        } = envelope;
+
+        match data_key {
+            Some(wrapped) => {
+                let key = self.unwrap_data_key(key_id, &wrapped, aad)?;
+                let wrapped = encrypt(self.current_cipher(), &key, aad)?;
+
+                Ok(Envelope {
+                    key_id: self.current_key_id,
+                    data_key: Some(wrapped),
+                    ciphertext,
+                })
+            }
+
+            None => {
+                let plaintext = decrypt(self.get(key_id)?, &ciphertext, aad)?;
+                let DataKey {
+                    key_id,
+                    wrapped,
+                    cipher,
+                } = self.generate_data_key(aad)?;
+                let ciphertext = encrypt(&cipher, &plaintext, aad)?;
+
+                Ok(Envelope {
+                    key_id,
+                    data_key: Some(wrapped),
+                    ciphertext,
+                })
+            }
+        }
+    }
+
+    fn current_cipher(&self) -> &ChaCha20Poly1305 {
+        self.ciphers
+            .get(&self.current_key_id)
+            .expect("keyring contains current key")
+    }
+
+    fn get(&self, key_id: KeyId) -> Result<&ChaCha20Poly1305, Error> {
+        self.ciphers.get(&key_id).ok_or(Error::UnknownKeyId(key_id))
+    }
+
+    fn unwrap_data_key(&self, key_id: KeyId, wrapped: &[u8], aad: &[u8]) -> Result<Key, Error> {
+        let key = decrypt(self.get(key_id)?, wrapped, aad)?;
+        if key.len() != DATA_KEY_LEN {
+            return Err(Error::InvalidDataKeyLen(key.len()));
+        }
+
+        Ok(Key::clone_from_slice(&key))
+    }
+}
+
+impl Debug for Keyring {
+    /// Attention: Do not leak the secrets!
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Keyring")
+            .field("current_key_id", &self.current_key_id)
+            .field("key_ids", &self.ciphers.keys())
+            .finish()
+    }
+}
+
+/// A random data key generated by a [Keyring]: the cipher to encrypt data with and the data key
+/// wrapped with the key with the given ID, to be stored alongside the encrypted data.
+pub struct DataKey {
+    pub key_id: KeyId,
+    pub wrapped: Vec<u8>,
+    pub cipher: ChaCha20Poly1305,
+}
+
+/// Encrypted data as stored: the ciphertext, the data key it has been encrypted with, wrapped with
+/// the key with the given ID, or no data key if it has been encrypted with the latter directly,
+/// i.e. before envelope encryption was introduced.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Envelope {
+    pub key_id: KeyId,
+    pub data_key: Option<Vec<u8>>,
+    pub ciphertext: Vec<u8>,
+}
+
+/// Provider of the [Keyring], allowing to source the secret keys from different places, e.g. the
+/// local configuration or a key management service.
+#[trait_variant::make(Send)]
+pub trait KeyProvider
+where
+    Self: Send + Sync,
+{
+    /// Error type for the [KeyProvider::keyring] method.
+    type Error: StdError + Send + Sync + 'static;
+
+    /// Provide the keyring with the current and retired keys.
+    async fn keyring(&self) -> Result<Keyring, Self::Error>;
+}
+
+/// A [KeyProvider] implementation for the secret keys from the local configuration, i.e. the config
+/// file, environment variables or files named by `APP__X_FILE` environment variables.
+#[derive(Debug, Clone)]
+pub struct LocalKeyProvider(Config);
+
+impl LocalKeyProvider {
+    pub fn new(config: Config) -> Self {
+        Self(config)
+    }
+}
+
+impl KeyProvider for LocalKeyProvider {
+    type Error = Error;
+
+    async fn keyring(&self) -> Result<Keyring, Self::Error> {
+        let Config {
+            secret,
+            key_id,
+            retired_secrets,
+        } = self.0.clone();
+
+        let keyring = Keyring::new(key_id, make_cipher(secret)?);
+
+        retired_secrets
+            .into_iter()
+            .try_fold(keyring, |keyring, (key_id, secret)| {
+                let key_id = key_id
+                    .parse::<KeyId>()
+                    .map_err(|_| Error::InvalidKeyId(key_id))?;
+                let cipher = make_cipher(secret)
+                    .map_err(|error| Error::RetiredSecret(key_id, Box::new(error)))?;
+
+                keyring.with_retired_key(key_id, cipher)
+            })
+    }
+}
+
 /// Make a `ChaCha20Poly1305` cipher from the given hex-encoded secret key. Only the first 32
 /// bytes are considered.
 pub fn make_cipher(secret: SecretString) -> Result<ChaCha20Poly1305, Error> {
@@ -29,7 +283,46 @@ pub fn make_cipher(secret: SecretString) -> Result<ChaCha20Poly1305, Error> {
     Ok(cipher)
 }
 
-/// Error possibly returned by [make_cipher].
+/// Encrypt the given plaintext using ChaCha20Poly1305 AEAD with a random nonce which is prefixed
+/// to the returned ciphertext.
+fn encrypt(
+    cipher: &ChaCha20Poly1305,
+    plaintext: &[u8],
+    aad: &[u8],
+) -> Result<Vec<u8>, chacha20poly1305::Error> {
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+
+    let payload = Payload {
+        msg: plaintext,
+        aad,
+    };
+    let mut ciphertext = cipher.encrypt(&nonce, payload)?;
+
+    let mut nonce_and_ciphertext = nonce.to_vec();
+    nonce_and_ciphertext.append(&mut ciphertext);
+
+    Ok(nonce_and_ciphertext)
+}
+
+/// Decrypt the given nonce-prefixed ciphertext using ChaCha20Poly1305 AEAD.
+fn decrypt(
+    cipher: &ChaCha20Poly1305,
+    nonce_and_ciphertext: &[u8],
+    aad: &[u8],
+) -> Result<Vec<u8>, chacha20poly1305::Error> {
+    if nonce_and_ciphertext.len() < NONCE_LEN {
+        return Err(chacha20poly1305::Error);
+    }
+    let (nonce, ciphertext) = nonce_and_ciphertext.split_at(NONCE_LEN);
+
+    let payload = Payload {
+        msg: ciphertext,
+        aad,
+    };
+    cipher.decrypt(nonce.into(), payload)
+}
+
+/// Error possibly returned by [make_cipher], [Keyring] or [LocalKeyProvider].
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("cannot hex-decode secret")]
@@ -37,4 +330,137 @@ pub enum Error {
 
     #[error("secret must be at least 32 bytes long, but was {0}")]
     InvalidLen(usize),
+
+    #[error("invalid key ID {0}")]
+    InvalidKeyId(String),
+
+    #[error("duplicate key ID {0}")]
+    DuplicateKeyId(KeyId),
+
+    #[error("unknown key ID {0}")]
+    UnknownKeyId(KeyId),
+
+    #[error("cannot encrypt or decrypt")]
+    Aead(#[from] chacha20poly1305::Error),
+
+    #[error("data key must be {DATA_KEY_LEN} bytes long, but was {0}")]
+    InvalidDataKeyLen(usize),
+
+    #[error("invalid retired secret with key ID {0}")]
+    RetiredSecret(KeyId, #[source] Box<Error>),
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cipher::{
+        Config, DataKey, Envelope, Error, KeyProvider, Keyring, LocalKeyProvider, encrypt,
+    };
+    use assert_matches::assert_matches;
+    use chacha20poly1305::{ChaCha20Poly1305, Key, KeyInit};
+    use std::collections::HashMap;
+
+    const SECRET_0: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+    const SECRET_1: &str = "1111111111111111111111111111111111111111111111111111111111111111";
+
+    #[tokio::test]
+    async fn test_local_key_provider() {
+        let config = Config {
+            secret: SECRET_1.into(),
+            key_id: 1,
+            retired_secrets: HashMap::from([("0".to_string(), SECRET_0.into())]),
+        };
+        let keyring = LocalKeyProvider::new(config.clone())
+            .keyring()
+            .await
+            .expect("keyring can be provided");
+        assert_eq!(keyring.current_key_id(), 1);
+        assert_eq!(keyring.key_ids().collect::<Vec<_>>(), vec![0, 1]);
+        assert!(keyring.data_key_cipher(0, None, b"aad").is_ok());
+        assert_matches!(
+            keyring.data_key_cipher(2, None, b"aad"),
+            Err(Error::UnknownKeyId(2))
+        );
+
+        let config = Config {
+            retired_secrets: HashMap::from([("1".to_string(), SECRET_0.into())]),
+            ..config
+        };
+        let keyring = LocalKeyProvider::new(config.clone()).keyring().await;
+        assert_matches!(keyring, Err(Error::DuplicateKeyId(1)));
+
+        let config = Config {
+            retired_secrets: HashMap::from([("zero".to_string(), SECRET_0.into())]),
+            ..config
+        };
+        let keyring = LocalKeyProvider::new(config.clone()).keyring().await;
+        assert_matches!(keyring, Err(Error::InvalidKeyId(key_id)) if key_id == "zero");
+
+        let config = Config {
+            retired_secrets: HashMap::from([("0".to_string(), "00".into())]),
+            ..config
+        };
+        let keyring = LocalKeyProvider::new(config).keyring().await;
+        assert_matches!(keyring, Err(Error::RetiredSecret(0, _)));
+    }
+
+    #[test]
+    fn test_envelope_encryption() {
+        let cipher_0 = ChaCha20Poly1305::new(Key::from_slice(&[0; 32]));
+        let cipher_1 = ChaCha20Poly1305::new(Key::from_slice(&[1; 32]));
+        let keyring_0 = Keyring::new(0, cipher_0.clone());
+        let keyring_1 = Keyring::new(1, cipher_1.clone())
+            .with_retired_key(0, cipher_0.clone())
+            .expect("retired key can be added");
+        let decrypt = |keyring: &Keyring, envelope: &Envelope, aad: &[u8]| {
+            let cipher =
+                keyring.data_key_cipher(envelope.key_id, envelope.data_key.as_deref(), aad)?;
+            super::decrypt(&cipher, &envelope.ciphertext, aad).map_err(Error::from)
+        };
+
+        // Data encrypted with a data key wrapped with key 0.
+        let DataKey {
+            key_id,
+            wrapped,
+            cipher,
+        } = keyring_0
+            .generate_data_key(b"aad")
+            .expect("data key can be generated");
+        let envelope = Envelope {
+            key_id,
+            data_key: Some(wrapped),
+            ciphertext: encrypt(&cipher, b"plaintext", b"aad").expect("data can be encrypted"),
+        };
+        assert_eq!(key_id, 0);
+        assert_matches!(decrypt(&keyring_0, &envelope, b"aad"), Ok(p) if p == b"plaintext");
+        assert_matches!(
+            decrypt(&keyring_0, &envelope, b"other"),
+            Err(Error::Aead(_))
+        );
+
+        // Re-wrapping leaves the ciphertext untouched and drops the need for key 0.
+        let reencrypted = keyring_1
+            .reencrypt(envelope.clone(), b"aad")
+            .expect("envelope can be re-encrypted");
+        assert_eq!(reencrypted.key_id, 1);
+        assert_eq!(reencrypted.ciphertext, envelope.ciphertext);
+        let keyring_1 = Keyring::new(1, cipher_1.clone());
+        assert_matches!(decrypt(&keyring_1, &reencrypted, b"aad"), Ok(p) if p == b"plaintext");
+
+        // Data encrypted directly with key 0, i.e. before envelope encryption.
+        let envelope = Envelope {
+            key_id: 0,
+            data_key: None,
+            ciphertext: encrypt(&cipher_0, b"plaintext", b"aad").expect("data can be encrypted"),
+        };
+        let keyring_1 = keyring_1
+            .with_retired_key(0, cipher_0)
+            .expect("retired key can be added");
+        let reencrypted = keyring_1
+            .reencrypt(envelope, b"aad")
+            .expect("envelope can be re-encrypted");
+        assert_eq!(reencrypted.key_id, 1);
+        assert!(reencrypted.data_key.is_some());
+        let keyring_1 = Keyring::new(1, cipher_1);
+        assert_matches!(decrypt(&keyring_1, &reencrypted, b"aad"), Ok(p) if p == b"plaintext");
+    }
 }
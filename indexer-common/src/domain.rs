@@ -16,11 +16,13 @@ pub mod dust;
 pub mod ledger;
 
 mod bytes;
+mod fees;
 mod protocol_version;
 mod pub_sub;
 mod viewing_key;
 
 pub use bytes::*;
+pub use fees::*;
 pub use protocol_version::*;
 pub use pub_sub::*;
 pub use viewing_key::*;
@@ -212,7 +214,7 @@ pub enum ContractAttributes {
 }
 
 /// Token balance of a contract.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ContractBalance {
     /// Token type identifier.
     pub token_type: TokenType,
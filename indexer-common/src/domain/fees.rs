@@ -0,0 +1,46 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+/// Get the given percentile (0 to 100) of the given fees in ascending order using the nearest-rank
+/// method, i.e. the smallest fee not exceeded by at least the given percentage of the fees; `None`
+/// if there are no fees.
+pub fn fee_percentile(sorted_fees: &[u128], percentile: u8) -> Option<u128> {
+    if sorted_fees.is_empty() {
+        return None;
+    }
+
+    let percentile = percentile.min(100) as usize;
+    let rank = (percentile * sorted_fees.len()).div_ceil(100).max(1);
+
+    Some(sorted_fees[rank - 1])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::fee_percentile;
+
+    #[test]
+    fn test_fee_percentile() {
+        assert_eq!(fee_percentile(&[], 50), None);
+
+        let fees = [10, 20, 30, 40, 50, 60, 70, 80, 90, 100];
+        assert_eq!(fee_percentile(&fees, 0), Some(10));
+        assert_eq!(fee_percentile(&fees, 50), Some(50));
+        assert_eq!(fee_percentile(&fees, 90), Some(90));
+        assert_eq!(fee_percentile(&fees, 91), Some(100));
+        assert_eq!(fee_percentile(&fees, 100), Some(100));
+        assert_eq!(fee_percentile(&fees, 200), Some(100));
+
+        assert_eq!(fee_percentile(&[42], 50), Some(42));
+    }
+}
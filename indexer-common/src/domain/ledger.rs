@@ -108,6 +108,12 @@ pub enum Error {
 
     #[error("unsupported EventDetailsV8 variant: {0}")]
     UnsupportedEventVariant(String),
+
+    #[error("missing arena node {}", const_hex::encode(.0))]
+    MissingArenaNode(ByteVec),
+
+    #[error("ledger DB not initialized")]
+    LedgerDbNotInitialized,
 }
 
 /// Extension methods for `Serializable` implementations.
@@ -12,22 +12,26 @@
 // limitations under the License.
 
 use crate::domain::{
-    ContractBalance, ContractMaintenanceAuthority, ContractMaintenanceVerifyingKey, LedgerVersion,
-    TokenType, VerifyingKeyKind,
-    ledger::{Error, TaggedSerializableExt},
+    ByteVec, ContractBalance, ContractMaintenanceAuthority, ContractMaintenanceVerifyingKey,
+    LedgerVersion, TokenType, VerifyingKeyKind,
+    ledger::{Error, SerializableExt, TaggedSerializableExt},
 };
 use fastrace::trace;
+use midnight_base_crypto_v1::signatures::Signature;
 use midnight_coin_structure_v2::coin::TokenType as MidnightTokenType;
 use midnight_coin_structure_v3::coin::TokenType as MidnightTokenTypeV9;
-use midnight_onchain_runtime_v3::state::ContractState as ContractStateV3;
+use midnight_onchain_runtime_v3::state::{
+    ContractState as ContractStateV3, StateValue as StateValueV3,
+};
 // v8's maintenance authority committee is `Vec<VerifyingKey>` (Schnorr only). v9 generalised it to
 // a `ContractMaintenanceVerifyingKey` enum (Schnorr | ECDSA), re-exported by the v9 runtime.
 use midnight_onchain_runtime_v4::state::{
     ContractMaintenanceVerifyingKey as ContractMaintenanceVerifyingKeyV4,
-    ContractState as ContractStateV4,
+    ContractState as ContractStateV4, StateValue as StateValueV4,
 };
 use midnight_serialize_v1::tagged_deserialize;
-use midnight_storage_core_v1::DefaultDB;
+use midnight_storage_core_v1::{DefaultDB, db::DB};
+use std::collections::BTreeSet;
 
 /// Facade for `ContractState` from `midnight_ledger` across supported (protocol) versions.
 #[derive(Debug, Clone)]
@@ -136,6 +140,14 @@ impl ContractState {
         }
     }
 
+    /// Get the data, i.e. the ledger state, of this contract.
+    pub fn data(&self) -> Result<ContractStateValue, Error> {
+        match self {
+            Self::V3(contract_state) => state_value_v3(&contract_state.data.get()),
+            Self::V4(contract_state) => state_value_v4(&contract_state.data.get()),
+        }
+    }
+
     /// Get the maintenance authority for this contract.
     pub fn maintenance_authority(&self) -> Result<ContractMaintenanceAuthority, Error> {
         match self {
@@ -190,6 +202,161 @@ impl ContractState {
             }
         }
     }
+
+    /// Whether the given signatures over the given message are made by at least as many distinct
+    /// members of the maintenance authority committee of this contract as its threshold requires,
+    /// and at least by one. Each signature is given tagged-serialized together with the index of
+    /// the signing committee member. Only Schnorr committee members are supported.
+    pub fn is_signed_by_maintenance_authority(
+        &self,
+        message: &[u8],
+        signatures: &[(u32, ByteVec)],
+    ) -> Result<bool, Error> {
+        let mut signers = BTreeSet::new();
+
+        for (index, signature) in signatures {
+            let signature = tagged_deserialize::<Signature>(&mut signature.as_ref())
+                .map_err(|error| Error::Deserialize("Signature", error))?;
+
+            let verified = match self {
+                Self::V3(contract_state) => contract_state
+                    .maintenance_authority
+                    .committee
+                    .iter()
+                    .nth(*index as usize)
+                    .is_some_and(|key| key.verify(message, &signature)),
+
+                Self::V4(contract_state) => contract_state
+                    .maintenance_authority
+                    .committee
+                    .iter()
+                    .nth(*index as usize)
+                    .is_some_and(|key| match key {
+                        ContractMaintenanceVerifyingKeyV4::Schnorr(key) => {
+                            key.verify(message, &signature)
+                        }
+                        ContractMaintenanceVerifyingKeyV4::ECDSA(_) => false,
+                    }),
+            };
+
+            if verified {
+                signers.insert(*index);
+            }
+        }
+
+        let threshold = match self {
+            Self::V3(contract_state) => contract_state.maintenance_authority.threshold,
+            Self::V4(contract_state) => contract_state.maintenance_authority.threshold,
+        };
+
+        Ok(signers.len() >= threshold.max(1) as usize)
+    }
+}
+
+/// Version independent tree of the state values making up the data of a contract. Cells and map
+/// keys are represented by the atoms of their aligned values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ContractStateValue {
+    Null,
+    Cell(Vec<Vec<u8>>),
+    Map(Vec<(Vec<Vec<u8>>, ContractStateValue)>),
+    Array(Vec<ContractStateValue>),
+    /// A bounded Merkle tree, represented by its serialized root, if any.
+    MerkleTree {
+        root: Option<ByteVec>,
+    },
+}
+
+fn state_value_v3<D>(state_value: &StateValueV3<D>) -> Result<ContractStateValue, Error>
+where
+    D: DB,
+{
+    let state_value = match state_value {
+        StateValueV3::Null => ContractStateValue::Null,
+
+        StateValueV3::Cell(value) => {
+            ContractStateValue::Cell(value.value.0.iter().map(|atom| atom.0.clone()).collect())
+        }
+
+        StateValueV3::Map(map) => {
+            let entries = map
+                .iter()
+                .map(|entry| {
+                    // Read via deref like the balances, see above.
+                    let (key, value) = &*entry;
+                    let key = key.value.0.iter().map(|atom| atom.0.clone()).collect();
+                    Ok((key, state_value_v3(value)?))
+                })
+                .collect::<Result<_, Error>>()?;
+            ContractStateValue::Map(entries)
+        }
+
+        StateValueV3::Array(values) => {
+            let values = values
+                .iter()
+                .map(|value| state_value_v3(&value))
+                .collect::<Result<_, _>>()?;
+            ContractStateValue::Array(values)
+        }
+
+        StateValueV3::BoundedMerkleTree(tree) => {
+            let root = tree
+                .rehash()
+                .root()
+                .map(|root| root.serialize())
+                .transpose()
+                .map_err(|error| Error::Serialize("MerkleTreeDigestV8", error))?;
+            ContractStateValue::MerkleTree { root }
+        }
+    };
+
+    Ok(state_value)
+}
+
+fn state_value_v4<D>(state_value: &StateValueV4<D>) -> Result<ContractStateValue, Error>
+where
+    D: DB,
+{
+    let state_value = match state_value {
+        StateValueV4::Null => ContractStateValue::Null,
+
+        StateValueV4::Cell(value) => {
+            ContractStateValue::Cell(value.value.0.iter().map(|atom| atom.0.clone()).collect())
+        }
+
+        StateValueV4::Map(map) => {
+            let entries = map
+                .iter()
+                .map(|entry| {
+                    // Read via deref like the balances, see above.
+                    let (key, value) = &*entry;
+                    let key = key.value.0.iter().map(|atom| atom.0.clone()).collect();
+                    Ok((key, state_value_v4(value)?))
+                })
+                .collect::<Result<_, Error>>()?;
+            ContractStateValue::Map(entries)
+        }
+
+        StateValueV4::Array(values) => {
+            let values = values
+                .iter()
+                .map(|value| state_value_v4(&value))
+                .collect::<Result<_, _>>()?;
+            ContractStateValue::Array(values)
+        }
+
+        StateValueV4::BoundedMerkleTree(tree) => {
+            let root = tree
+                .rehash()
+                .root()
+                .map(|root| root.serialize())
+                .transpose()
+                .map_err(|error| Error::Serialize("MerkleTreeDigestV9", error))?;
+            ContractStateValue::MerkleTree { root }
+        }
+    };
+
+    Ok(state_value)
 }
 
 #[cfg(test)]
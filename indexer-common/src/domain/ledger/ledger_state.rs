@@ -25,7 +25,7 @@ use crate::{
             TransactionV9,
         },
     },
-    infra::ledger_db::v1_1,
+    infra::ledger_db::{self, v1_1},
 };
 use fastrace::trace;
 use itertools::Itertools;
@@ -86,12 +86,12 @@ use midnight_onchain_runtime_v4::{
     ops::{LogEventType, VersionedLogItem},
     state::{EntryPointBuf, StateValue},
 };
-use midnight_serialize_v1::{Deserializable, tagged_deserialize};
+use midnight_serialize_v1::{Deserializable, Serializable, tagged_deserialize};
 use midnight_storage_core_v1::{
     Storage,
     arena::{ArenaHash, Sp, TypedArenaKey},
-    backend::StorageBackend,
-    db::DB,
+    backend::{OnDiskObject, StorageBackend},
+    db::{DB, Update},
     storage::default_storage,
 };
 use midnight_transient_crypto_v2::merkle_tree::{
@@ -104,7 +104,8 @@ use midnight_transient_crypto_v3::merkle_tree::{
 use midnight_zswap_v8::ledger::State as ZswapStateV8;
 use midnight_zswap_v9::ledger::State as ZswapStateV9;
 use std::{
-    collections::{BTreeMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashSet},
+    num::NonZeroUsize,
     ops::Deref,
     sync::LazyLock,
 };
@@ -467,6 +468,101 @@ impl LedgerState {
             .map(|wanted| raise_root_counts(&default_storage::<v1_1::LedgerDb>(), &wanted))
     }
 
+    /// Lower the ledger DB's gc root counts of the given rolled back ledger state keys to match
+    /// `window`, the retention window left after the rollback (see [Self::repair_root_counts]).
+    /// Returns the number of unpersists applied.
+    ///
+    /// Unlike unpersisting once per rolled back block, this is idempotent, hence can be repeated
+    /// at startup if the chain-indexer stopped between deleting the rolled back blocks and
+    /// unpersisting their ledger states. Roots also referenced by the window keep the count the
+    /// window implies.
+    pub fn lower_root_counts<'a, 'b>(
+        window: impl IntoIterator<Item = (&'a SerializedLedgerStateKey, LedgerVersion)>,
+        rolled_back: impl IntoIterator<Item = (&'b SerializedLedgerStateKey, LedgerVersion)>,
+    ) -> Result<u32, Error> {
+        let wanted = window_root_counts(window)?;
+        let rolled_back = rolled_back
+            .into_iter()
+            .map(|(key, ledger_version)| Self::arena_root_hash(key, ledger_version))
+            .collect::<Result<BTreeSet<_>, _>>()?;
+
+        Ok(lower_root_counts(
+            &default_storage::<v1_1::LedgerDb>(),
+            &wanted,
+            &rolled_back,
+        ))
+    }
+
+    /// The arena nodes of the given persisted ledger states, i.e. of the DAGs rooted at their
+    /// keys, each one once and in batches of at most `batch_size` nodes, e.g. to export these into
+    /// a snapshot. Reads the ledger DB directly, hence a batch fails with [Error::MissingArenaNode]
+    /// if gc culls one of the ledger states meanwhile.
+    pub fn arena_nodes<'a>(
+        keys: impl IntoIterator<Item = (&'a SerializedLedgerStateKey, LedgerVersion)>,
+        batch_size: NonZeroUsize,
+    ) -> Result<ArenaNodes, Error> {
+        let ledger_db = ledger_db::ledger_db()
+            .ok_or(Error::LedgerDbNotInitialized)?
+            .to_owned();
+
+        let visited = keys
+            .into_iter()
+            .map(|(key, ledger_version)| Self::arena_root_hash(key, ledger_version))
+            .collect::<Result<HashSet<_>, _>>()?;
+        let frontier = visited.iter().cloned().collect();
+
+        Ok(ArenaNodes {
+            ledger_db,
+            batch_size,
+            frontier,
+            visited,
+        })
+    }
+
+    /// Save the given arena nodes, e.g. from [Self::arena_nodes] of another ledger DB. The nodes
+    /// do not become gc roots, hence the ledger states need to be persisted with
+    /// [Self::persist_key] before the next gc pass.
+    pub fn save_arena_nodes(nodes: Vec<ArenaNode>) -> Result<(), Error> {
+        let updates = nodes
+            .into_iter()
+            .map(|ArenaNode { hash, object }| {
+                let hash = ArenaHash::<<v1_1::LedgerDb as DB>::Hasher>::deserialize(
+                    &mut hash.as_slice(),
+                    0,
+                )
+                .map_err(|error| Error::Deserialize("ArenaHash", error))?;
+                let object = OnDiskObject::<<v1_1::LedgerDb as DB>::Hasher>::deserialize(
+                    &mut object.as_slice(),
+                    0,
+                )
+                .map_err(|error| Error::Deserialize("OnDiskObject", error))?;
+                Ok((hash, Update::InsertNode(object)))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        ledger_db::ledger_db()
+            .ok_or(Error::LedgerDbNotInitialized)?
+            .to_owned()
+            .batch_update(updates.into_iter());
+
+        Ok(())
+    }
+
+    /// Persist a ledger state by its serialized key, whose arena nodes have been saved with
+    /// [Self::save_arena_nodes], i.e. raise its gc root count like [Self::persist] does.
+    pub fn persist_key(
+        key: &SerializedLedgerStateKey,
+        ledger_version: LedgerVersion,
+    ) -> Result<(), Error> {
+        let hash = Self::arena_root_hash(key, ledger_version)?;
+        default_storage::<v1_1::LedgerDb>().with_backend(|b| {
+            b.persist(&hash);
+            b.flush_all_changes_to_db();
+        });
+
+        Ok(())
+    }
+
     fn arena_root_hash(
         key: &SerializedLedgerStateKey,
         ledger_version: LedgerVersion,
@@ -2410,6 +2506,62 @@ pub struct RootCountRepair {
     pub strays: usize,
 }
 
+/// An arena node of a ledger state with its raw arena hash and serialized object, see
+/// [LedgerState::arena_nodes].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArenaNode {
+    pub hash: ByteVec,
+    pub object: ByteVec,
+}
+
+/// Iterator over batches of the arena nodes of ledger states, see [LedgerState::arena_nodes].
+#[derive(Debug)]
+pub struct ArenaNodes {
+    ledger_db: v1_1::LedgerDb,
+    batch_size: NonZeroUsize,
+    frontier: Vec<ArenaHash<<v1_1::LedgerDb as DB>::Hasher>>,
+    visited: HashSet<ArenaHash<<v1_1::LedgerDb as DB>::Hasher>>,
+}
+
+impl Iterator for ArenaNodes {
+    type Item = Result<Vec<ArenaNode>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.frontier.is_empty() {
+            return None;
+        }
+
+        let at = self.frontier.len().saturating_sub(self.batch_size.get());
+        let hashes = self.frontier.split_off(at);
+
+        let nodes = self
+            .ledger_db
+            .batch_get_nodes(hashes.into_iter())
+            .into_iter()
+            .map(|(hash, node)| {
+                let node = node.ok_or_else(|| Error::MissingArenaNode(hash.0.to_vec().into()))?;
+
+                for child in node.children.iter().flat_map(|child| child.refs()) {
+                    if self.visited.insert(child.clone()) {
+                        self.frontier.push(child.clone());
+                    }
+                }
+
+                let mut object = Vec::with_capacity(node.serialized_size());
+                Serializable::serialize(&node, &mut object)
+                    .map_err(|error| Error::Serialize("OnDiskObject", error))?;
+
+                Ok(ArenaNode {
+                    hash: hash.0.to_vec().into(),
+                    object: object.into(),
+                })
+            })
+            .collect();
+
+        Some(nodes)
+    }
+}
+
 /// Gc root counts by arena hash. Ordered, so the repair's DB writes happen in the same sequence on
 /// every run.
 type RootCounts = BTreeMap<ArenaHash<<v1_1::LedgerDb as DB>::Hasher>, u32>;
@@ -2472,6 +2624,35 @@ fn raise_root_counts(storage: &Storage<v1_1::LedgerDb>, wanted: &RootCounts) ->
     })
 }
 
+/// Body of [LedgerState::lower_root_counts], with the storage passed in so tests can drive it
+/// without the process-global default.
+fn lower_root_counts(
+    storage: &Storage<v1_1::LedgerDb>,
+    wanted: &RootCounts,
+    rolled_back: &BTreeSet<ArenaHash<<v1_1::LedgerDb as DB>::Hasher>>,
+) -> u32 {
+    storage.with_backend(|backend| {
+        let stored = backend.get_roots();
+        let mut lowered_total = 0;
+
+        for hash in rolled_back {
+            let have = stored.get(hash).copied().unwrap_or_default();
+            let want = wanted.get(hash).copied().unwrap_or_default();
+            let excess = have.saturating_sub(want);
+
+            // Like for raising, repeated unpersists coalesce into one delta.
+            for _ in 0..excess {
+                backend.unpersist(hash);
+            }
+            lowered_total += excess;
+        }
+
+        backend.flush_all_changes_to_db();
+
+        lowered_total
+    })
+}
+
 /// Whether every node of the DAG rooted at `hash` is present in the ledger DB.
 ///
 /// Guards bringing a root back from a stored count of zero. Such a root was garbage as far as gc
@@ -3629,7 +3810,9 @@ mod root_count_repair_tests {
             LedgerVersion,
             ledger::{
                 LedgerState,
-                ledger_state::{RootCountRepair, raise_root_counts, window_root_counts},
+                ledger_state::{
+                    RootCountRepair, lower_root_counts, raise_root_counts, window_root_counts,
+                },
             },
         },
         infra::{
@@ -3648,7 +3831,10 @@ mod root_count_repair_tests {
         db::DB,
         storable::SMALL_OBJECT_LIMIT,
     };
-    use std::{collections::BTreeMap, error::Error as StdError};
+    use std::{
+        collections::{BTreeMap, BTreeSet},
+        error::Error as StdError,
+    };
 
     /// Blocks in the retention window referencing one and the same ledger state root.
     const WINDOW_HITS: u32 = 5;
@@ -3791,6 +3977,30 @@ mod root_count_repair_tests {
         Ok(())
     }
 
+    /// Rolled back roots are lowered to the count the remaining window implies, i.e. unrooted if
+    /// the window does not reference them, and lowering again after an interruption changes
+    /// nothing.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn lowering_is_idempotent() -> Result<(), Box<dyn StdError>> {
+        let (pool, storage) = pool_and_storage().await?;
+        let shared = persisted_root(&storage, 42);
+        let rolled_back = persisted_root(&storage, 43);
+
+        // Three blocks referenced the shared root, two of which were rolled back.
+        set_stored_root_count(&pool, &shared.hash(), 3);
+
+        let wanted = BTreeMap::from([(shared.hash(), 1)]);
+        let rolled_back_roots = BTreeSet::from([shared.hash(), rolled_back.hash()]);
+        assert_eq!(lower_root_counts(&storage, &wanted, &rolled_back_roots), 3);
+        assert_eq!(stored_root_count(&pool, &shared.hash()).await?, Some(1));
+        assert_eq!(stored_root_count(&pool, &rolled_back.hash()).await?, None);
+
+        assert_eq!(lower_root_counts(&storage, &wanted, &rolled_back_roots), 0);
+        assert_eq!(stored_root_count(&pool, &shared.hash()).await?, Some(1));
+
+        Ok(())
+    }
+
     /// The window is a list of per-block keys, so the same key appearing in `n` blocks must tally
     /// to `n` - that tally is what the repair treats as the truth. Goes through a real serialized
     /// ledger state key, exercising the version-dependent `TypedArenaKey` decode.
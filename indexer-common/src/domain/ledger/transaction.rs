@@ -14,16 +14,22 @@
 use crate::{
     domain::{
         ContractAction, ContractAttributes, LedgerVersion, SerializedContractAddress,
-        SerializedContractState, SerializedTransactionIdentifier, TransactionHash, ViewingKey,
+        SerializedContractState, SerializedTransactionIdentifier, TransactionHash,
+        UnshieldedAddress, ViewingKey,
         ledger::{Error, SerializableExt, TransactionV8, TransactionV9},
     },
     infra::ledger_db::v1_1,
 };
 use fastrace::trace;
 use futures::{StreamExt, TryStreamExt};
-use midnight_coin_structure_v2::{coin::Info, contract::ContractAddress};
+use itertools::Itertools;
+use midnight_coin_structure_v2::{
+    coin::{Info, UserAddress},
+    contract::ContractAddress,
+};
 use midnight_coin_structure_v3::{
-    coin::Info as InfoV9, contract::ContractAddress as ContractAddressV9,
+    coin::{Info as InfoV9, UserAddress as UserAddressV9},
+    contract::ContractAddress as ContractAddressV9,
 };
 use midnight_ledger_v8::structure::{
     ContractAction as ContractActionV8, StandardTransaction as StandardTransactionV8,
@@ -246,6 +252,102 @@ impl Transaction {
         }
     }
 
+    /// Get the addresses of the deployed, called or updated contracts; unlike
+    /// [Transaction::contract_actions], this does not need the contract states.
+    pub fn contract_addresses(&self) -> Result<Vec<SerializedContractAddress>, Error> {
+        match self {
+            Self::V8(transaction) => match transaction {
+                TransactionV8::Standard(standard_transaction) => standard_transaction
+                    .actions()
+                    .map(|(_, contract_action)| match contract_action {
+                        ContractActionV8::Deploy(deploy) => {
+                            serialize_contract_address(deploy.address())
+                        }
+                        ContractActionV8::Call(call) => serialize_contract_address(call.address),
+                        ContractActionV8::Maintain(update) => {
+                            serialize_contract_address(update.address)
+                        }
+                    })
+                    .collect(),
+
+                TransactionV8::ClaimRewards(_) => Ok(vec![]),
+            },
+
+            Self::V9(transaction) => match transaction {
+                TransactionV9::Standard(standard_transaction) => standard_transaction
+                    .actions()
+                    .map(|(_, contract_action)| match contract_action {
+                        ContractActionV9::Deploy(deploy) => {
+                            serialize_contract_address_v9(deploy.address())
+                        }
+                        ContractActionV9::Call(call) => serialize_contract_address_v9(call.address),
+                        ContractActionV9::Maintain(update) => {
+                            serialize_contract_address_v9(update.address)
+                        }
+                    })
+                    .collect(),
+
+                TransactionV9::ClaimRewards(_) => Ok(vec![]),
+            },
+        }
+    }
+
+    /// Get the owner addresses of the unshielded UTXOs spent or created, without duplicates;
+    /// unlike applying the transaction, this does not consider whether segments succeed.
+    pub fn unshielded_addresses(&self) -> Vec<UnshieldedAddress> {
+        match self {
+            Self::V8(transaction) => match transaction {
+                TransactionV8::Standard(standard_transaction) => standard_transaction
+                    .intents
+                    .values()
+                    .flat_map(|intent| {
+                        let outputs = intent
+                            .guaranteed_outputs()
+                            .into_iter()
+                            .chain(intent.fallible_outputs())
+                            .map(|output| output.owner.0.0.into());
+                        let inputs = intent
+                            .guaranteed_inputs()
+                            .into_iter()
+                            .chain(intent.fallible_inputs())
+                            .map(|spend| UserAddress::from(spend.owner).0.0.into());
+                        outputs.chain(inputs).collect::<Vec<_>>()
+                    })
+                    .unique()
+                    .collect(),
+
+                TransactionV8::ClaimRewards(claim) => {
+                    vec![UserAddress::from(claim.owner.clone()).0.0.into()]
+                }
+            },
+
+            Self::V9(transaction) => match transaction {
+                TransactionV9::Standard(standard_transaction) => standard_transaction
+                    .intents
+                    .values()
+                    .flat_map(|intent| {
+                        let outputs = intent
+                            .guaranteed_outputs()
+                            .into_iter()
+                            .chain(intent.fallible_outputs())
+                            .map(|output| output.owner.0.0.into());
+                        let inputs = intent
+                            .guaranteed_inputs()
+                            .into_iter()
+                            .chain(intent.fallible_inputs())
+                            .map(|spend| UserAddressV9::from(spend.owner).0.0.into());
+                        outputs.chain(inputs).collect::<Vec<_>>()
+                    })
+                    .unique()
+                    .collect(),
+
+                TransactionV9::ClaimRewards(claim) => {
+                    vec![UserAddressV9::from(claim.owner.clone()).0.0.into()]
+                }
+            },
+        }
+    }
+
     // Check if this transaction belongs to the given viewing key.
     pub fn relevant(&self, viewing_key: ViewingKey) -> bool {
         match self {
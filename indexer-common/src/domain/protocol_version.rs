@@ -15,9 +15,11 @@ use std::num::TryFromIntError;
 
 use derive_more::Display;
 use parity_scale_codec::Decode;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(into = "u32", try_from = "u32")]
 pub enum ProtocolVersion {
     V0_22(u32),
     V1_0(u32),
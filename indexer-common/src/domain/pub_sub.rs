@@ -48,6 +48,13 @@ pub struct BlockIndexed {
 }
 message!(BlockIndexed);
 
+/// Message/event signaling that already indexed blocks up to the given height have been finalized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, From)]
+pub struct BlocksFinalized {
+    pub height: u64,
+}
+message!(BlocksFinalized);
+
 /// Message/event signaling that a wallet has been indexed.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, From)]
 pub struct WalletIndexed {
@@ -55,6 +62,20 @@ pub struct WalletIndexed {
 }
 message!(WalletIndexed);
 
+/// Message/event signaling that a wallet has been connected, i.e. has become active.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, From)]
+pub struct WalletConnected {
+    pub wallet_id: Uuid,
+}
+message!(WalletConnected);
+
+/// Message/event signaling that a wallet has been disconnected, i.e. is no longer active.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, From)]
+pub struct WalletDisconnected {
+    pub wallet_id: Uuid,
+}
+message!(WalletDisconnected);
+
 /// Emitted when a transaction affecting unshielded UTXOs for a concrete address
 /// has been stored in the DB.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -71,6 +92,14 @@ pub struct BridgeEventIndexed {
 }
 message!(BridgeEventIndexed);
 
+/// Message/event signaling that pending transactions have been saved or deleted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, From)]
+pub struct PendingTransactionsUpdated {
+    pub saved: u64,
+    pub deleted: u64,
+}
+message!(PendingTransactionsUpdated);
+
 /// A pub-sub publisher.
 #[trait_variant::make(Send)]
 pub trait Publisher
@@ -103,6 +132,21 @@ where
         T: Message;
 }
 
+/// A [Publisher] implementation that "does nothing".
+#[derive(Debug, Clone, Default)]
+pub struct NoopPublisher;
+
+impl Publisher for NoopPublisher {
+    type Error = Infallible;
+
+    async fn publish<T>(&self, _message: &T) -> Result<(), Self::Error>
+    where
+        T: Message + Send + Sync,
+    {
+        Ok(())
+    }
+}
+
 /// A [Subscriber] implementation that "does nothing".
 #[derive(Debug, Clone, Default)]
 pub struct NoopSubscriber;
@@ -16,12 +16,20 @@
 pub mod v1_1;
 
 use serde::Deserialize;
+#[cfg(any(feature = "cloud", feature = "standalone"))]
+use std::sync::OnceLock;
+
+/// The ledger DB the default storage has been initialized with, for direct node access bypassing
+/// storage-core, e.g. to import snapshots.
+#[cfg(any(feature = "cloud", feature = "standalone"))]
+static LEDGER_DB: OnceLock<v1_1::LedgerDb> = OnceLock::new();
 
 #[cfg(feature = "cloud")]
 pub fn init(config: Config, pool: crate::infra::pool::postgres::PostgresPool) {
     let Config { cache_max_nodes } = config;
 
     let db = v1_1::LedgerDb::new(pool);
+    let _ = LEDGER_DB.set(db.clone());
     let _ = midnight_storage_core_v1::storage::set_default_storage(|| {
         midnight_storage_core_v1::Storage::new(cache_max_nodes, db)
     });
@@ -57,6 +65,7 @@ pub async fn init(config: Config) -> Result<(), Error> {
     migrations::sqlite::run_for_ledger_db(&pool).await?;
 
     let db = v1_1::LedgerDb::new(pool);
+    let _ = LEDGER_DB.set(db.clone());
     let _ = midnight_storage_core_v1::storage::set_default_storage(|| {
         midnight_storage_core_v1::Storage::new(cache_max_nodes, db)
     });
@@ -64,6 +73,12 @@ pub async fn init(config: Config) -> Result<(), Error> {
     Ok(())
 }
 
+/// The ledger DB the default storage has been initialized with, if any.
+#[cfg(any(feature = "cloud", feature = "standalone"))]
+pub fn ledger_db() -> Option<&'static v1_1::LedgerDb> {
+    LEDGER_DB.get()
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct Config {
     /// Maximum number of arena nodes held in the storage-core caches. This is a node *count*, not
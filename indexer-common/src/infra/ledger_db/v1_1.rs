@@ -34,7 +34,7 @@ type SqlxTransaction = sqlx::Transaction<'static, sqlx::Postgres>;
 #[cfg(feature = "standalone")]
 type SqlxTransaction = sqlx::Transaction<'static, sqlx::Sqlite>;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct LedgerDb {
     #[cfg(feature = "cloud")]
     pool: crate::infra::pool::postgres::PostgresPool,
@@ -26,8 +26,10 @@ use tokio::{
 #[derive(Clone)]
 pub struct InMemPubSub {
     block_indexed_sender: Sender<Value>,
+    blocks_finalized_sender: Sender<Value>,
     wallet_indexed_sender: Sender<Value>,
     unshielded_utxo_sender: Sender<Value>,
+    pending_transactions_sender: Sender<Value>,
 }
 
 impl InMemPubSub {
@@ -45,13 +47,17 @@ impl InMemPubSub {
 impl Default for InMemPubSub {
     fn default() -> Self {
         let (block_indexed_sender, block_indexed_receiver) = broadcast::channel(42);
+        let (blocks_finalized_sender, blocks_finalized_receiver) = broadcast::channel(42);
         let (wallet_indexed_sender, wallet_indexed_receiver) = broadcast::channel(42);
         let (unshielded_utxo_sender, unshielded_utxo_receiver) = broadcast::channel(42);
+        let (pending_transactions_sender, pending_transactions_receiver) = broadcast::channel(42);
 
         let pub_sub = InMemPubSub {
             block_indexed_sender,
+            blocks_finalized_sender,
             wallet_indexed_sender,
             unshielded_utxo_sender,
+            pending_transactions_sender,
         };
 
         // Keep one receiver alive per topic for as long as the `InMemPubSub`
@@ -61,8 +67,13 @@ impl Default for InMemPubSub {
         // attached. `RecvError::Lagged` does not invalidate the receiver —
         // `recv` just skips ahead — so we must keep looping, not break.
         spawn_drain("block_indexed_receiver", block_indexed_receiver);
+        spawn_drain("blocks_finalized_receiver", blocks_finalized_receiver);
         spawn_drain("wallet_indexed_receiver", wallet_indexed_receiver);
         spawn_drain("unshielded_utxo_receiver", unshielded_utxo_receiver);
+        spawn_drain(
+            "pending_transactions_receiver",
+            pending_transactions_receiver,
+        );
 
         pub_sub
     }
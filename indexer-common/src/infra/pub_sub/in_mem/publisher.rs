@@ -44,6 +44,10 @@ impl Publisher for InMemPublisher {
                 self.0.block_indexed_sender.send(value)?;
             }
 
+            Topic("BlocksFinalized") => {
+                self.0.blocks_finalized_sender.send(value)?;
+            }
+
             Topic("WalletIndexed") => {
                 self.0.wallet_indexed_sender.send(value)?;
             }
@@ -52,6 +56,10 @@ impl Publisher for InMemPublisher {
                 self.0.unshielded_utxo_sender.send(value)?;
             }
 
+            Topic("PendingTransactionsUpdated") => {
+                self.0.pending_transactions_sender.send(value)?;
+            }
+
             // This must not happen; if it happens, we forgot to add an arm for the topic above!
             _ => panic!("unexpected topic {:?}", T::TOPIC),
         }
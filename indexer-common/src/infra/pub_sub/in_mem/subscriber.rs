@@ -44,6 +44,11 @@ impl Subscriber for InMemSubscriber {
                 BroadcastStream::new(receiver)
             }
 
+            Topic("BlocksFinalized") => {
+                let receiver = self.0.blocks_finalized_sender.subscribe();
+                BroadcastStream::new(receiver)
+            }
+
             Topic("WalletIndexed") => {
                 let receiver = self.0.wallet_indexed_sender.subscribe();
                 BroadcastStream::new(receiver)
@@ -54,6 +59,11 @@ impl Subscriber for InMemSubscriber {
                 BroadcastStream::new(receiver)
             }
 
+            Topic("PendingTransactionsUpdated") => {
+                let receiver = self.0.pending_transactions_sender.subscribe();
+                BroadcastStream::new(receiver)
+            }
+
             // This must not happen; if it happens, we forgot to add an arm for the topic above!
             _ => panic!("unexpected topic {:?}", T::TOPIC),
         };
@@ -50,6 +50,12 @@ pub struct ApplicationConfig {
     pub gc_bound: Duration,
     #[serde(default = "ledger_state_retention_default")]
     pub ledger_state_retention: NonZeroUsize,
+    #[serde(default)]
+    pub follow_best_blocks: bool,
+    #[serde(default)]
+    pub pending_transactions: Option<chain_app::pending_transactions::Config>,
+    #[serde(default)]
+    pub webhooks: Option<api_app::webhooks::Config>,
     #[serde(with = "humantime_serde")]
     pub active_wallets_query_delay: Duration,
     #[serde(with = "humantime_serde")]
@@ -57,6 +63,10 @@ pub struct ApplicationConfig {
     pub transaction_batch_size: NonZeroUsize,
     #[serde(default = "concurrency_limit_default")]
     pub concurrency_limit: NonZeroUsize,
+    #[serde(default)]
+    pub batch_scan: Option<wallet_app::batch_scan::Config>,
+    #[serde(default)]
+    pub reencryption: wallet_app::reencryption::Config,
 }
 
 fn gc_bound_default() -> Duration {
@@ -93,6 +103,8 @@ impl From<ApplicationConfig> for chain_app::Config {
             caught_up_leeway,
             gc_bound,
             ledger_state_retention,
+            follow_best_blocks,
+            pending_transactions,
             ..
         } = config;
 
@@ -103,6 +115,8 @@ impl From<ApplicationConfig> for chain_app::Config {
             caught_up_leeway,
             gc_bound,
             ledger_state_retention,
+            follow_best_blocks,
+            pending_transactions,
         }
     }
 }
@@ -111,6 +125,7 @@ impl From<ApplicationConfig> for api_app::Config {
     fn from(config: ApplicationConfig) -> Self {
         Self {
             network_id: config.network_id,
+            webhooks: config.webhooks,
         }
     }
 }
@@ -122,6 +137,8 @@ impl From<ApplicationConfig> for wallet_app::Config {
             active_wallets_ttl,
             transaction_batch_size,
             concurrency_limit,
+            batch_scan,
+            reencryption,
             ..
         } = config;
 
@@ -130,6 +147,10 @@ impl From<ApplicationConfig> for wallet_app::Config {
             active_wallets_ttl,
             transaction_batch_size,
             concurrency_limit,
+            batch_scan,
+            // A standalone indexer is a single replica, hence there is nothing to shard.
+            sharding: None,
+            reencryption,
         }
     }
 }
@@ -162,7 +183,13 @@ pub struct InfraConfig {
     #[serde(rename = "api")]
     pub api_config: api::Config,
 
-    pub secret: secrecy::SecretString,
+    /// Node to submit transactions to; transaction submission is disabled if omitted.
+    #[serde(rename = "submission_node")]
+    pub submission_node_config: Option<indexer_api::infra::node::Config>,
+
+    /// The current secret key to encrypt stored sensitive data and any retired ones.
+    #[serde(flatten)]
+    pub cipher_config: indexer_common::cipher::Config,
 }
 
 #[derive(Debug, Clone, Deserialize)]
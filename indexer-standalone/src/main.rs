@@ -44,14 +44,22 @@ fn run() -> anyhow::Result<()> {
     use anyhow::Context;
     use chain_indexer::{
         application as chain_app,
-        infra::{storage as chain_storage, subxt_node::SubxtNode},
+        cli::{Cli, Command, SnapshotCommand},
+        infra::{
+            file_node::{FileNode, RecordingNode},
+            storage as chain_storage,
+            subxt_node::SubxtNode,
+        },
     };
+    use clap::Parser;
     use indexer_api::{
         application as api_app,
-        infra::{api::AxumApi, storage as api_storage},
+        infra::{
+            api::AxumApi, node as api_node, storage as api_storage, webhook::HttpWebhookSender,
+        },
     };
     use indexer_common::{
-        cipher::make_cipher,
+        cipher::{KeyProvider, LocalKeyProvider},
         config::ConfigExt,
         infra::{ledger_db, migrations, pool, pub_sub},
         telemetry,
@@ -70,6 +78,12 @@ fn run() -> anyhow::Result<()> {
     };
     use wallet_indexer::{application as wallet_app, infra::storage as wallet_storage};
 
+    let Cli {
+        command,
+        record,
+        replay,
+    } = Cli::parse();
+
     // Load configuration.
     let Config {
         thread_stack_size,
@@ -115,7 +129,8 @@ fn run() -> anyhow::Result<()> {
         node_config,
         spo_node_config,
         api_config,
-        secret,
+        submission_node_config,
+        cipher_config,
     } = infra_config;
 
     let runtime = Builder::new_multi_thread()
@@ -137,7 +152,10 @@ fn run() -> anyhow::Result<()> {
                 .context("run Sqlite migrations")?;
         }
 
-        let cipher = make_cipher(secret).context("make cipher")?;
+        let keyring = LocalKeyProvider::new(cipher_config)
+            .keyring()
+            .await
+            .context("make keyring")?;
 
         let pub_sub = pub_sub::in_mem::InMemPubSub::default();
 
@@ -145,6 +163,42 @@ fn run() -> anyhow::Result<()> {
             .await
             .context("initialize ledger db")?;
 
+        match command {
+            Some(Command::Snapshot(SnapshotCommand::Export { height, file })) => {
+                let storage = chain_storage::Storage::new(pool);
+                return chain_app::snapshot::export(
+                    &application_config.network_id,
+                    height,
+                    application_config.ledger_state_retention,
+                    &file,
+                    &storage,
+                )
+                .await;
+            }
+
+            Some(Command::Snapshot(SnapshotCommand::Import { file })) => {
+                let mut storage = chain_storage::Storage::new(pool);
+                return chain_app::snapshot::import(
+                    &application_config.network_id,
+                    &file,
+                    &mut storage,
+                )
+                .await;
+            }
+
+            Some(Command::Rewind { to_height }) => {
+                let storage = chain_storage::Storage::new(pool);
+                return chain_app::rewind(
+                    to_height,
+                    application_config.ledger_state_retention,
+                    storage,
+                )
+                .await;
+            }
+
+            None => {}
+        }
+
         // Move the node connection setup *inside* each spawned task so a slow
         // or unreachable URL only blocks its own component, not the whole
         // runtime startup. The previous shape `task::spawn({ ... .await? ... })`
@@ -156,12 +210,38 @@ fn run() -> anyhow::Result<()> {
             let publisher = pub_sub.publisher();
             let application_config = application_config.clone();
             task::spawn(async move {
+                let sigterm =
+                    signal(SignalKind::terminate()).expect("SIGTERM handler can be registered");
+
+                if let Some(file) = replay {
+                    let node = FileNode::new(&file).context("create FileNode")?;
+                    return chain_app::run(
+                        application_config.into(),
+                        node,
+                        storage,
+                        publisher,
+                        sigterm,
+                    )
+                    .await;
+                }
+
                 let node = SubxtNode::new(node_config)
                     .await
                     .context("create SubxtNode")?;
-                let sigterm =
-                    signal(SignalKind::terminate()).expect("SIGTERM handler can be registered");
-                chain_app::run(application_config.into(), node, storage, publisher, sigterm).await
+
+                match record {
+                    Some(file) => {
+                        let node =
+                            RecordingNode::new(node, &file).context("create RecordingNode")?;
+                        chain_app::run(application_config.into(), node, storage, publisher, sigterm)
+                            .await
+                    }
+
+                    None => {
+                        chain_app::run(application_config.into(), node, storage, publisher, sigterm)
+                            .await
+                    }
+                }
             })
         };
 
@@ -177,16 +257,44 @@ fn run() -> anyhow::Result<()> {
             })
         };
 
-        let indexer_api = task::spawn({
+        let indexer_api = {
             let subscriber = pub_sub.subscriber();
-            let storage = api_storage::Storage::new(cipher.clone(), pool.clone());
-            let api = AxumApi::new(api_config, storage, subscriber.clone());
+            let publisher = pub_sub.publisher();
+            let storage = api_storage::Storage::new(keyring.clone(), pool.clone());
+            let application_config = application_config.clone();
+            task::spawn(async move {
+                let submission_node = match submission_node_config {
+                    Some(config) => Some(
+                        api_node::SubxtNode::new(config)
+                            .await
+                            .context("create SubxtNode for transaction submission")?,
+                    ),
+                    None => None,
+                };
+                let webhook_sender =
+                    HttpWebhookSender::new(api_config.webhooks_config.allow_private_addresses)
+                        .context("create HttpWebhookSender")?;
+                let api = AxumApi::new(
+                    api_config,
+                    storage.clone(),
+                    subscriber.clone(),
+                    publisher,
+                    submission_node,
+                );
 
-            api_app::run(application_config.clone().into(), api, subscriber)
-        });
+                api_app::run(
+                    application_config.into(),
+                    api,
+                    storage,
+                    subscriber,
+                    webhook_sender,
+                )
+                .await
+            })
+        };
 
         let wallet_indexer = task::spawn({
-            let storage = wallet_storage::Storage::new(cipher, pool);
+            let storage = wallet_storage::Storage::new(keyring, pool);
             let publisher = pub_sub.publisher();
             let subscriber = pub_sub.subscriber();
             let sigterm =
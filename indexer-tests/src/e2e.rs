@@ -21,7 +21,8 @@ use crate::{
         DustGenerationStatusQuery, DustGenerationsQuery, DustGenerationsSubscription,
         DustLedgerEventsSubscription, DustNullifierTransactionsSubscription,
         ShieldedNullifierTransactionsSubscription, ShieldedTransactionsSubscription,
-        TermsAndConditionsHistoryQuery, TransactionsQuery, UnshieldedTransactionsSubscription,
+        SubmitTransactionMutation, TermsAndConditionsHistoryQuery, TransactionRawQuery,
+        TransactionStatusSubscription, TransactionsQuery, UnshieldedTransactionsSubscription,
         ZswapLedgerEventsSubscription, ZswapMerkleTreeCollapsedUpdateQuery, block_query,
         block_subscription::{
             self, BlockSubscriptionBlocks, BlockSubscriptionBlocksTransactions,
@@ -39,6 +40,8 @@ use crate::{
         dust_generation_status_query, dust_generations_query, dust_generations_subscription,
         dust_ledger_events_subscription, dust_nullifier_transactions_subscription,
         shielded_nullifier_transactions_subscription, shielded_transactions_subscription,
+        submit_transaction_mutation, transaction_raw_query,
+        transaction_status_subscription::{self, TransactionStatus},
         transactions_query, unshielded_transactions_subscription, zswap_ledger_events_subscription,
         zswap_merkle_tree_collapsed_update_query,
     },
@@ -130,6 +133,9 @@ pub async fn run(network_id: NetworkId, host: &str, port: u16, secure: bool) ->
     test_zswap_merkle_tree_collapsed_update_query(&indexer_data, &api_client, &api_url)
         .await
         .context("test zswap Merkle tree collapsed update query")?;
+    test_submit_transaction_mutation(&indexer_data, &api_client, &api_url)
+        .await
+        .context("test submit transaction mutation")?;
 
     // Test subscriptions (the block subscription has already been tested above).
     test_contract_actions_subscription(&indexer_data, &ws_api_url)
@@ -156,6 +162,9 @@ pub async fn run(network_id: NetworkId, host: &str, port: u16, secure: bool) ->
     test_dust_nullifier_transactions_subscription(&ws_api_url)
         .await
         .context("test dust nullifier transactions subscription")?;
+    test_transaction_status_subscription(&indexer_data, &ws_api_url)
+        .await
+        .context("test transaction status subscription")?;
 
     println!("Successfully finished e2e testing");
 
@@ -409,6 +418,56 @@ async fn test_disconnect_mutation(
     Ok(())
 }
 
+/// Test the submitTransaction mutation.
+async fn test_submit_transaction_mutation(
+    indexer_data: &IndexerData,
+    api_client: &Client,
+    api_url: &str,
+) -> anyhow::Result<()> {
+    // Invalid transaction.
+    let variables = submit_transaction_mutation::Variables {
+        raw: [42; 1].hex_encode(),
+    };
+    let response = send_query::<SubmitTransactionMutation>(api_client, api_url, variables).await;
+    assert!(response.is_err());
+
+    // Already indexed transaction, which must be rejected by the node with a reason.
+    let transaction = indexer_data
+        .transactions
+        .iter()
+        .find(|transaction| {
+            matches!(
+                transaction.on,
+                BlockSubscriptionBlocksTransactionsOn::RegularTransaction(_)
+            )
+        })
+        .expect("there are regular transactions");
+    let variables = transaction_raw_query::Variables {
+        transaction_offset: transaction_raw_query::TransactionOffset::Hash(
+            transaction.hash.to_owned(),
+        ),
+    };
+    let raw = send_query::<TransactionRawQuery>(api_client, api_url, variables)
+        .await?
+        .transactions
+        .into_iter()
+        .next()
+        .expect("indexed transaction can be queried")
+        .raw;
+    let variables = submit_transaction_mutation::Variables { raw };
+    let error = send_query::<SubmitTransactionMutation>(api_client, api_url, variables)
+        .await
+        .expect_err("already indexed transaction is rejected")
+        .to_string();
+    assert!(
+        error.contains("transaction submission not enabled")
+            || error.contains("transaction rejected by node: "),
+        "unexpected error {error}"
+    );
+
+    Ok(())
+}
+
 /// Test the block query.
 async fn test_block_query(
     indexer_data: &IndexerData,
@@ -1016,6 +1075,81 @@ async fn test_unshielded_transactions_subscription(
     Ok(())
 }
 
+/// Test the transaction status subscription.
+async fn test_transaction_status_subscription(
+    indexer_data: &IndexerData,
+    ws_api_url: &str,
+) -> anyhow::Result<()> {
+    // Indexed transaction. As transaction hashes are not unique, use one which occurs only once.
+    let transaction = indexer_data
+        .transactions
+        .iter()
+        .find(|transaction| {
+            indexer_data
+                .transactions
+                .iter()
+                .filter(|t| t.hash == transaction.hash)
+                .count()
+                == 1
+        })
+        .expect("there are transactions with unique hashes");
+    let block = indexer_data
+        .blocks
+        .iter()
+        .find(|block| block.hash == transaction.block.hash)
+        .expect("transaction block has been collected");
+
+    let variables = transaction_status_subscription::Variables {
+        hash: transaction.hash.to_owned(),
+    };
+    let updates =
+        graphql_ws_client::subscribe::<TransactionStatusSubscription>(ws_api_url, variables)
+            .await
+            .context("subscribe to transaction status")?
+            .take(1)
+            .map_ok(|data| data.transaction_status)
+            .try_collect::<Vec<_>>()
+            .await
+            .context("collect transaction status updates from subscription")?;
+    let [update] = updates.as_slice() else {
+        bail!(
+            "expected one transaction status update, but got {}",
+            updates.len()
+        );
+    };
+    assert!(matches!(
+        update.status,
+        TransactionStatus::INCLUDED | TransactionStatus::FINALIZED | TransactionStatus::FAILED
+    ));
+    assert_eq!(update.block_hash.as_ref(), Some(&block.hash));
+    assert_eq!(update.block_height, Some(block.height));
+
+    // Unknown transaction.
+    let variables = transaction_status_subscription::Variables {
+        hash: [42; 32].hex_encode(),
+    };
+    let updates =
+        graphql_ws_client::subscribe::<TransactionStatusSubscription>(ws_api_url, variables)
+            .await
+            .context("subscribe to transaction status")?
+            .take(1)
+            .map_ok(|data| data.transaction_status)
+            .try_collect::<Vec<_>>()
+            .await
+            .context("collect transaction status updates from subscription")?;
+    let [update] = updates.as_slice() else {
+        bail!(
+            "expected one transaction status update, but got {}",
+            updates.len()
+        );
+    };
+    assert!(matches!(update.status, TransactionStatus::PENDING));
+    assert!(update.block_hash.is_none());
+    assert!(update.block_height.is_none());
+
+    Ok(())
+}
+
 async fn test_zswap_ledger_events_subscription(
     indexer_data: &IndexerData,
     ws_api_url: &str,
@@ -1377,6 +1511,14 @@ mod graphql {
     )]
     pub struct TransactionsQuery;
 
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        schema_path = "../indexer-api/graphql/schema-v4.graphql",
+        query_path = "./e2e.graphql",
+        response_derives = "Debug, Clone, Serialize"
+    )]
+    pub struct TransactionRawQuery;
+
     #[derive(GraphQLQuery)]
     #[graphql(
         schema_path = "../indexer-api/graphql/schema-v4.graphql",
@@ -1409,6 +1551,14 @@ mod graphql {
     )]
     pub struct DisconnectMutation;
 
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        schema_path = "../indexer-api/graphql/schema-v4.graphql",
+        query_path = "./e2e.graphql",
+        response_derives = "Debug, Clone, Serialize"
+    )]
+    pub struct SubmitTransactionMutation;
+
     #[derive(GraphQLQuery)]
     #[graphql(
         schema_path = "../indexer-api/graphql/schema-v4.graphql",
@@ -1473,6 +1623,14 @@ mod graphql {
     )]
     pub struct DustNullifierTransactionsSubscription;
 
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        schema_path = "../indexer-api/graphql/schema-v4.graphql",
+        query_path = "./e2e.graphql",
+        response_derives = "Debug, Clone, Serialize"
+    )]
+    pub struct TransactionStatusSubscription;
+
     #[derive(GraphQLQuery)]
     #[graphql(
         schema_path = "../indexer-api/graphql/schema-v4.graphql",
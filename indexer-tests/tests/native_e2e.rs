@@ -81,7 +81,8 @@ async fn main() -> anyhow::Result<()> {
     println!("Chain Indexer started");
     let mut wallet_indexer = start_wallet_indexer(postgres_port, &nats_url).await?;
     println!("Wallet Indexer started");
-    let (mut indexer_api, api_port) = start_indexer_api(postgres_port, &nats_url).await?;
+    let (mut indexer_api, api_port) =
+        start_indexer_api(postgres_port, &nats_url, &node_handle.node_url).await?;
     println!("Indexer API started");
 
     // Terminate Chain Indexer, then start it again.
@@ -338,7 +339,11 @@ async fn start_wallet_indexer(postgres_port: u16, nats_url: &str) -> anyhow::Res
 }
 
 #[cfg(feature = "cloud")]
-async fn start_indexer_api(postgres_port: u16, nats_url: &str) -> anyhow::Result<(Child, u16)> {
+async fn start_indexer_api(
+    postgres_port: u16,
+    nats_url: &str,
+    node_url: &str,
+) -> anyhow::Result<(Child, u16)> {
     let api_port = find_free_port()?;
 
     Command::new(format!("{}/debug/indexer-api", &*TARGET_DIR))
@@ -354,6 +359,9 @@ async fn start_indexer_api(postgres_port: u16, nats_url: &str) -> anyhow::Result
         .env("APP__INFRA__API__MAX_COMPLEXITY", "600")
         .env("APP__INFRA__PUB_SUB__URL", nats_url)
         .env("APP__INFRA__STORAGE__PORT", postgres_port.to_string())
+        .env("APP__INFRA__SUBMISSION_NODE__URL", node_url)
+        .env("APP__INFRA__SUBMISSION_NODE__RECONNECT_MAX_DELAY", "10s")
+        .env("APP__INFRA__SUBMISSION_NODE__RECONNECT_MAX_ATTEMPTS", "30")
         .env("APP__TELEMETRY__TRACING__ENABLED", "true")
         .spawn()
         .context("spawn indexer-api process")
@@ -386,6 +394,9 @@ fn start_indexer_standalone(node_url: &str) -> anyhow::Result<(Child, u16, TempD
         .env("APP__INFRA__SPO_NODE__BLOCKFROST_ID", "e2e-test-dummy")
         .env("APP__INFRA__STORAGE__CNN_URL", sqlite_file)
         .env("APP__INFRA__LEDGER_DB__CNN_URL", sqlite_ledger_db_file)
+        .env("APP__INFRA__SUBMISSION_NODE__URL", node_url)
+        .env("APP__INFRA__SUBMISSION_NODE__RECONNECT_MAX_DELAY", "10s")
+        .env("APP__INFRA__SUBMISSION_NODE__RECONNECT_MAX_ATTEMPTS", "30")
         .env("APP__TELEMETRY__TRACING__ENABLED", "true")
         .spawn()
         .context("spawn indexer-standalone process")
@@ -11,29 +11,43 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::domain::storage::Storage;
+pub mod batch_scan;
+pub mod reencryption;
+mod schedule;
+pub mod sharding;
+
+use crate::{
+    application::{schedule::WalletSchedule, sharding::Shard},
+    domain::storage::Storage,
+};
 use anyhow::Context;
-use async_stream::try_stream;
-use dashmap::DashMap;
 use fastrace::trace;
-use futures::{Stream, StreamExt, TryStreamExt, future::ok};
-use indexer_common::domain::{BlockIndexed, Publisher, Subscriber, WalletIndexed};
+use futures::{StreamExt, TryStreamExt, future, stream};
+use indexer_common::{
+    domain::{
+        BlockIndexed, Publisher, Subscriber, WalletConnected, WalletDisconnected, WalletIndexed,
+    },
+    error::StdErrorExt,
+};
 use itertools::Itertools;
 use log::{debug, warn};
 use serde::Deserialize;
 use std::{
     num::NonZeroUsize,
+    pin::pin,
     sync::{
         Arc,
         atomic::{AtomicU64, Ordering},
     },
     time::{Duration, Instant},
 };
-use tokio::{select, signal::unix::Signal, sync::Semaphore, task, time::sleep};
+use tokio::{select, signal::unix::Signal, task, time::interval};
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct Config {
+    /// Delay between queries of the active wallet IDs. Wallets are scheduled by pub-sub messages,
+    /// hence these queries only recover from missed messages and expire inactive wallets.
     #[serde(with = "humantime_serde")]
     pub active_wallets_query_delay: Duration,
 
@@ -44,6 +58,21 @@ pub struct Config {
 
     #[serde(default = "concurrency_limit_default")]
     pub concurrency_limit: NonZeroUsize,
+
+    /// Scan each transaction batch once for many wallets instead of once per wallet; disabled if
+    /// omitted.
+    #[serde(default)]
+    pub batch_scan: Option<batch_scan::Config>,
+
+    /// Shard the wallets across the wallet-indexer replicas registered via heartbeats; disabled if
+    /// omitted, i.e. replicas only exclude each other from indexing the same wallet via locks.
+    #[serde(default)]
+    pub sharding: Option<sharding::Config>,
+
+    /// Re-encrypt the viewing keys and webhook secrets for the current secret key, e.g. after a
+    /// key rotation; runs with defaults if omitted.
+    #[serde(default)]
+    pub reencryption: reencryption::Config,
 }
 
 pub async fn run(
@@ -58,6 +87,9 @@ pub async fn run(
         active_wallets_ttl,
         transaction_batch_size,
         concurrency_limit,
+        batch_scan,
+        sharding,
+        reencryption,
     } = config;
 
     // Shared counter for the maximum transaction ID observed in BlockIndexed events. This allows
@@ -65,73 +97,184 @@ pub async fn run(
     // initial value is set to the maximum in case initial events are missed during startup.
     let max_transaction_id = Arc::new(AtomicU64::new(u64::MAX));
 
-    let mut block_indexed_task = task::spawn({
-        let subscriber = subscriber.clone();
+    let schedule = Arc::new(WalletSchedule::default());
+
+    // The wallets owned by this replica, if sharding is enabled; else all wallets are owned.
+    let shard = sharding.map(|_| Arc::new(Shard::new(Uuid::now_v7())));
+
+    // Spawn task to send heartbeats and rebalance the wallets, if sharding is enabled.
+    let mut sharding_task = task::spawn({
+        let storage = storage.clone();
+        let shard = shard.clone();
+
+        async move {
+            match sharding.zip(shard) {
+                Some((config, shard)) => sharding::run(config, shard, storage).await,
+                None => future::pending().await,
+            }
+        }
+    });
+
+    // Spawn task to re-encrypt the viewing keys with the current secret key.
+    let mut reencryption_task = task::spawn({
+        let storage = storage.clone();
+        async move { reencryption::run(reencryption, storage).await }
+    });
+
+    let mut schedule_task = task::spawn({
+        let storage = storage.clone();
         let max_transaction_id = max_transaction_id.clone();
+        let schedule = schedule.clone();
+        let shard = shard.clone();
 
         async move {
-            let block_indexed_stream = subscriber.subscribe::<BlockIndexed>();
-
-            block_indexed_stream
-                .try_for_each(|block_indexed| {
-                    if let Some(id) = block_indexed.max_transaction_id {
-                        let max_id = max_transaction_id.load(Ordering::Acquire);
-
-                        // Above we initially set max_transaction_id to u64::MAX so
-                        // index_wallets_task will always index on startup. This initial value needs
-                        // to be replaced unconditionally with the first received value.
-                        if max_id == u64::MAX || max_id < id {
-                            max_transaction_id.store(id, Ordering::Release);
+            let block_indexed_stream = subscriber
+                .subscribe::<BlockIndexed>()
+                .map_ok(ScheduleEvent::BlockIndexed);
+            let wallet_connected_stream = subscriber
+                .subscribe::<WalletConnected>()
+                .map_ok(ScheduleEvent::WalletConnected);
+            let wallet_disconnected_stream = subscriber
+                .subscribe::<WalletDisconnected>()
+                .map_ok(ScheduleEvent::WalletDisconnected);
+            let mut events = pin!(stream::select(
+                block_indexed_stream,
+                stream::select(wallet_connected_stream, wallet_disconnected_stream),
+            ));
+
+            // Ticks immediately, hence the active wallets are queried on startup.
+            let mut active_wallets_query_interval = interval(active_wallets_query_delay);
+
+            let owns = |wallet_id| shard.as_ref().is_none_or(|shard| shard.owns(wallet_id));
+
+            loop {
+                select! {
+                    event = events.next() => {
+                        let Some(event) = event else {
+                            break;
+                        };
+
+                        match event.context("cannot get next schedule event")? {
+                            ScheduleEvent::BlockIndexed(block_indexed) => {
+                                if let Some(id) = block_indexed.max_transaction_id {
+                                    let max_id = max_transaction_id.load(Ordering::Acquire);
+
+                                    // Above we initially set max_transaction_id to u64::MAX so
+                                    // index_wallets_task will always index on startup. This
+                                    // initial value needs to be replaced unconditionally with the
+                                    // first received value.
+                                    if max_id == u64::MAX || max_id < id {
+                                        max_transaction_id.store(id, Ordering::Release);
+                                    }
+                                }
+
+                                schedule.schedule_all();
+                            }
+
+                            ScheduleEvent::WalletConnected(WalletConnected { wallet_id }) => {
+                                if owns(wallet_id) {
+                                    schedule.connect(wallet_id);
+                                }
+                            }
+
+                            ScheduleEvent::WalletDisconnected(WalletDisconnected { wallet_id }) => {
+                                schedule.disconnect(wallet_id);
+                            }
                         }
                     }
 
-                    ok(())
-                })
-                .await
-                .context("cannot get next BlockIndexed event")?;
+                    _ = active_wallets_query_interval.tick() => {
+                        let wallet_ids = storage
+                            .active_wallet_ids(active_wallets_ttl)
+                            .await
+                            .context("get active wallet IDs")?;
+                        let wallet_ids = wallet_ids.into_iter().filter(|&id| owns(id)).collect();
+                        schedule.reset(wallet_ids);
+                    }
+
+                    // Query the active wallets right away to schedule the owned ones only.
+                    _ = rebalanced(shard.as_deref()) => {
+                        active_wallets_query_interval.reset_immediately();
+                    }
+                }
+            }
 
-            warn!("block_indexed_task completed");
+            warn!("schedule_task completed");
 
             Ok::<(), anyhow::Error>(())
         }
     });
 
     let mut index_wallets_task = {
+        let storage = storage.clone();
+
         task::spawn(async move {
-            // As wallet IDs are cycled (see comment of `active_wallet_ids`), we prevent concurrent
-            // processing of the same wallet by using a semaphore of one (see below) per wallet ID.
-            let worker_by_wallet_id = Arc::new(DashMap::new());
-
-            active_wallet_ids(active_wallets_query_delay, active_wallets_ttl, &storage)
-                .map(|result| result.context("get next active wallet ID"))
-                .try_for_each_concurrent(Some(concurrency_limit.get()), |wallet_id| {
-                    let worker_by_wallet_id = worker_by_wallet_id.clone();
-                    let max_transaction_id = max_transaction_id.clone();
-                    let mut publisher = publisher.clone();
-                    let mut storage = storage.clone();
-
-                    async move {
-                        let permit = worker_by_wallet_id
-                            .entry(wallet_id)
-                            .or_insert_with(|| Arc::new(Semaphore::new(1)))
-                            .clone()
-                            .try_acquire_owned();
-
-                        if permit.is_ok() {
-                            index_wallet(
-                                wallet_id,
+            // The schedule hands out each wallet at most once at a time, hence the same wallet is
+            // never processed concurrently.
+            match batch_scan {
+                Some(batch_scan::Config { max_wallets }) => {
+                    stream::unfold(schedule.clone(), move |schedule| async move {
+                        let wallet_ids = schedule.next_batch(max_wallets).await;
+                        Some((Ok::<_, anyhow::Error>(wallet_ids), schedule))
+                    })
+                    .try_for_each_concurrent(Some(concurrency_limit.get()), |wallet_ids| {
+                        let max_transaction_id = max_transaction_id.clone();
+                        let schedule = schedule.clone();
+                        let mut publisher = publisher.clone();
+                        let mut storage = storage.clone();
+
+                        async move {
+                            let result = batch_scan::index_wallets(
+                                &wallet_ids,
                                 transaction_batch_size,
                                 max_transaction_id,
                                 &mut publisher,
                                 &mut storage,
                             )
-                            .await?;
+                            .await;
+
+                            // Done in any case, else the wallets would never be handed out again.
+                            for wallet_id in wallet_ids {
+                                let more = result
+                                    .as_ref()
+                                    .is_ok_and(|wallet_ids| wallet_ids.contains(&wallet_id));
+                                schedule.done(wallet_id, more);
+                            }
+                            result.map(|_| ())
                         }
+                    })
+                    .await?;
+                }
 
-                        Ok(())
-                    }
-                })
-                .await?;
+                None => {
+                    stream::unfold(schedule.clone(), |schedule| async move {
+                        let wallet_id = schedule.next().await;
+                        Some((Ok::<_, anyhow::Error>(wallet_id), schedule))
+                    })
+                    .try_for_each_concurrent(Some(concurrency_limit.get()), |wallet_id| {
+                        let max_transaction_id = max_transaction_id.clone();
+                        let schedule = schedule.clone();
+                        let mut publisher = publisher.clone();
+                        let mut storage = storage.clone();
+
+                        async move {
+                            let result = index_wallet(
+                                wallet_id,
+                                transaction_batch_size,
+                                max_transaction_id,
+                                &mut publisher,
+                                &mut storage,
+                            )
+                            .await;
+
+                            // Done in any case, else the wallet would never be handed out again.
+                            schedule.done(wallet_id, matches!(result, Ok(true)));
+                            result.map(|_| ())
+                        }
+                    })
+                    .await?;
+                }
+            }
 
             warn!("index_wallets_task completed");
 
@@ -140,10 +283,32 @@ pub async fn run(
     };
 
     select! {
-        result = &mut block_indexed_task => {
+        result = &mut sharding_task => {
             let result = result
-                .context("block_indexed_task")
-                .and_then(|r| r.context("block_indexed_task failed"));
+                .context("sharding_task panicked")
+                .and_then(|r| r.context("sharding_task failed"));
+            reencryption_task.abort();
+            schedule_task.abort();
+            index_wallets_task.abort();
+            result
+        },
+
+        result = &mut reencryption_task => {
+            let result = result
+                .context("reencryption_task panicked")
+                .and_then(|r| r.context("reencryption_task failed"));
+            sharding_task.abort();
+            schedule_task.abort();
+            index_wallets_task.abort();
+            result
+        },
+
+        result = &mut schedule_task => {
+            let result = result
+                .context("schedule_task panicked")
+                .and_then(|r| r.context("schedule_task failed"));
+            sharding_task.abort();
+            reencryption_task.abort();
             index_wallets_task.abort();
             result
         },
@@ -152,58 +317,49 @@ pub async fn run(
             let result = result
                 .context("index_wallets_task panicked")
                 .and_then(|r| r.context("index_wallets_task failed"));
-            block_indexed_task.abort();
+            sharding_task.abort();
+            reencryption_task.abort();
+            schedule_task.abort();
             result
         },
 
         _ = sigterm.recv() => {
             warn!("SIGTERM received");
-            block_indexed_task.abort();
+            sharding_task.abort();
+            reencryption_task.abort();
+            schedule_task.abort();
             index_wallets_task.abort();
+
+            // Let the other replicas take over the wallets of this one right away.
+            if let Some(shard) = shard
+                && let Err(error) = storage.delete_replica(shard.replica_id()).await
+            {
+                warn!(error = error.as_chain(); "cannot delete replica");
+            }
+
             Ok(())
         }
     }
 }
 
-/// Repeatedly query the active wallet IDs with the given delay between repetitions and continuously
-/// stream the ones of the current repetition in a cycle. This only hits the database once per
-/// repetition but keeps the stream "hot" (fast producer), yet means that newly connected wallets
-/// are only indexed once the current repetition ends. Therefore a balance between database load and
-/// wallet latency needs to be found; reasonable values for the delay seem to be between 100ms and
-/// 1000ms.
-fn active_wallet_ids(
-    active_wallets_query_delay: Duration,
-    active_wallets_ttl: Duration,
-    storage: &impl Storage,
-) -> impl Stream<Item = Result<Uuid, sqlx::Error>> + '_ {
-    try_stream! {
-        loop {
-            // Query the current active wallet IDs.
-            let wallet_ids = storage.active_wallet_ids(active_wallets_ttl).await?;
-
-            if wallet_ids.is_empty() {
-                sleep(active_wallets_query_delay).await;
-                continue;
-            }
-
-            let deadline = Instant::now() + active_wallets_query_delay;
-
-            // First we stream all current wallet IDs exactly once, regardless of the deadline.
-            for &wallet_id in &wallet_ids {
-                yield wallet_id
-            }
-
-            // Then we cycle the current wallet IDs until the deadline is reached.
-            for wallet_id in wallet_ids.into_iter().cycle() {
-                if Instant::now() > deadline {
-                    break;
-                }
-                yield wallet_id
-            }
-        }
+/// Wait until the wallets have been rebalanced, if sharding is enabled; else never complete.
+async fn rebalanced(shard: Option<&Shard>) {
+    match shard {
+        Some(shard) => shard.rebalanced().await,
+        None => future::pending().await,
     }
 }
 
+/// Events driving the [WalletSchedule].
+enum ScheduleEvent {
+    BlockIndexed(BlockIndexed),
+    WalletConnected(WalletConnected),
+    WalletDisconnected(WalletDisconnected),
+}
+
+/// Index the next batch of transactions for the wallet with the given ID and return whether there
+/// may be more to index right away, i.e. a full batch has been processed. Nothing is indexed if
+/// the wallet is locked, i.e. being indexed by another wallet-indexer.
 #[trace(properties = { "wallet_id": "{wallet_id}" })]
 async fn index_wallet(
     wallet_id: Uuid,
@@ -211,14 +367,14 @@ async fn index_wallet(
     max_transaction_id: Arc<AtomicU64>,
     publisher: &mut impl Publisher,
     storage: &mut impl Storage,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<bool> {
     let tx = storage
         .acquire_lock(wallet_id)
         .await
         .with_context(|| format!("acquire lock for wallet ID {wallet_id}"))?;
 
     let Some(mut tx) = tx else {
-        return Ok(());
+        return Ok(false);
     };
 
     let wallet = storage
@@ -288,11 +444,12 @@ async fn index_wallet(
             "wallet backward indexed"
         );
 
-        return Ok(());
+        return Ok(true);
     }
 
     // Forward scan: only continue if possibly needed.
     if wallet.last_indexed_transaction_id < max_transaction_id.load(Ordering::Acquire) {
+        let started = Instant::now();
         let from = wallet.last_indexed_transaction_id + 1;
         let transactions = storage
             .get_transactions(from, transaction_batch_size, &mut tx)
@@ -302,8 +459,9 @@ async fn index_wallet(
         let last_indexed_transaction_id = if let Some(transaction) = transactions.last() {
             transaction.id
         } else {
-            return Ok(());
+            return Ok(false);
         };
+        let more = transactions.len() == transaction_batch_size.get();
 
         let relevant_transactions = transactions
             .into_iter()
@@ -318,11 +476,17 @@ async fn index_wallet(
             .filter_map_ok(|(relevant, transaction)| relevant.then_some(transaction))
             .collect::<Result<Vec<_>, _>>()?;
 
+        // Only full batches are measured, because smaller ones at the tip of the chain are bounded
+        // by the block production rather than by scanning.
+        let scan_rate =
+            more.then(|| measure_scan_rate(last_indexed_transaction_id - from + 1, started));
+
         storage
             .save_relevant_transactions(
                 &wallet.viewing_key,
                 &relevant_transactions,
                 last_indexed_transaction_id,
+                scan_rate,
                 &mut tx,
             )
             .await
@@ -345,9 +509,18 @@ async fn index_wallet(
             relevant_transactions_len = relevant_transactions.len();
             "wallet indexed"
         );
+
+        return Ok(more);
     }
 
-    Ok(())
+    Ok(false)
+}
+
+/// Measure the rate in transaction IDs per second at which the given number of transaction IDs
+/// has been scanned since the given instant.
+fn measure_scan_rate(transaction_id_count: u64, started: Instant) -> f64 {
+    let elapsed = started.elapsed().max(Duration::from_millis(1));
+    transaction_id_count as f64 / elapsed.as_secs_f64()
 }
 
 fn concurrency_limit_default() -> NonZeroUsize {
@@ -0,0 +1,180 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    application::{index_wallet, measure_scan_rate},
+    domain::{self, storage::Storage},
+};
+use anyhow::Context;
+use fastrace::trace;
+use indexer_common::domain::{Publisher, WalletIndexed};
+use log::debug;
+use serde::Deserialize;
+use std::{
+    collections::HashSet,
+    num::NonZeroUsize,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Instant,
+};
+use tokio::task;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct Config {
+    /// Maximum number of wallets scanned together.
+    pub max_wallets: NonZeroUsize,
+}
+
+/// Index the next batches of transactions for the wallets with the given IDs and return the IDs of
+/// those for which there may be more to index right away. Wallets locked by another wallet-indexer
+/// are skipped and wallets with a backward gap are indexed one by one. All others are scanned
+/// together, i.e. each transaction batch is fetched and deserialized only once and
+/// trial-decrypted with all their viewing keys, and saved within a single database transaction.
+#[trace]
+pub async fn index_wallets(
+    wallet_ids: &[Uuid],
+    transaction_batch_size: NonZeroUsize,
+    max_transaction_id: Arc<AtomicU64>,
+    publisher: &mut impl Publisher,
+    storage: &mut impl Storage,
+) -> anyhow::Result<HashSet<Uuid>> {
+    let (mut tx, wallet_ids) = storage
+        .acquire_locks(wallet_ids)
+        .await
+        .context("acquire locks for wallets")?;
+
+    let mut backward_wallet_ids = vec![];
+    let mut wallets = vec![];
+    let max_id = max_transaction_id.load(Ordering::Acquire);
+    for wallet_id in wallet_ids {
+        let wallet = storage
+            .get_wallet_by_id(wallet_id, &mut tx)
+            .await
+            .with_context(|| format!("get wallet for wallet ID {wallet_id}"))?;
+
+        if wallet.first_indexed_transaction_id > wallet.wanted_start_index {
+            backward_wallet_ids.push(wallet_id);
+        } else if wallet.last_indexed_transaction_id < max_id {
+            wallets.push((wallet_id, wallet));
+        }
+    }
+
+    // Scan upwards from the lowest last indexed transaction ID: each transaction batch covers the
+    // wallets behind its end and the next batch starts after the first wallet not yet covered.
+    wallets.sort_by_key(|(_, wallet)| wallet.last_indexed_transaction_id);
+    let mut wallets = wallets.as_slice();
+    let mut more_wallet_ids = HashSet::new();
+    let mut indexed_wallet_ids = HashSet::new();
+
+    while let Some((_, wallet)) = wallets.first() {
+        let started = Instant::now();
+        let from = wallet.last_indexed_transaction_id + 1;
+        let transactions = storage
+            .get_transactions(from, transaction_batch_size, &mut tx)
+            .await
+            .context("get transactions")?;
+
+        let Some(last_indexed_transaction_id) = transactions.last().map(|t| t.id) else {
+            break;
+        };
+        let more = transactions.len() == transaction_batch_size.get();
+
+        let covered_len = wallets.partition_point(|(_, wallet)| {
+            wallet.last_indexed_transaction_id < last_indexed_transaction_id
+        });
+        let (covered, rest) = wallets.split_at(covered_len);
+        wallets = rest;
+        let (covered_wallet_ids, covered_wallets): (Vec<_>, Vec<_>) =
+            covered.iter().cloned().unzip();
+
+        // Trial decryption is CPU bound, hence run it outside of the async runtime.
+        let transaction_ids = transactions.iter().map(|t| t.id).collect::<Vec<_>>();
+        let relevant_wallets =
+            task::spawn_blocking(move || domain::relevant_wallets(&transactions, &covered_wallets))
+                .await
+                .context("check transaction relevance for wallets panicked")?
+                .context("check transaction relevance for wallets")?;
+
+        let relevant_transactions = transaction_ids
+            .into_iter()
+            .zip(relevant_wallets)
+            .flat_map(|(transaction_id, indices)| {
+                let covered_wallet_ids = &covered_wallet_ids;
+                indices
+                    .into_iter()
+                    .map(move |index| (covered_wallet_ids[index], transaction_id))
+            })
+            .collect::<Vec<_>>();
+
+        // Like for a single wallet, only full batches are measured.
+        let scan_rate =
+            more.then(|| measure_scan_rate(last_indexed_transaction_id - from + 1, started));
+
+        storage
+            .save_batch_relevant_transactions(
+                &covered_wallet_ids,
+                &relevant_transactions,
+                last_indexed_transaction_id,
+                scan_rate,
+                &mut tx,
+            )
+            .await
+            .context("save relevant transactions for wallets")?;
+
+        indexed_wallet_ids.extend(
+            relevant_transactions
+                .iter()
+                .map(|(wallet_id, _)| *wallet_id),
+        );
+        if more {
+            more_wallet_ids.extend(covered_wallet_ids.iter().copied());
+        }
+
+        debug!(
+            wallets_len = covered_wallet_ids.len(),
+            last_indexed_transaction_id,
+            relevant_transactions_len = relevant_transactions.len();
+            "wallets indexed"
+        );
+    }
+
+    tx.commit().await.context("commit database transaction")?;
+
+    for wallet_id in indexed_wallet_ids {
+        publisher
+            .publish(&WalletIndexed { wallet_id })
+            .await
+            .with_context(|| format!("publish WalletIndexed event for wallet ID {wallet_id}"))?;
+    }
+
+    // The above committed database transaction has released the locks again.
+    for wallet_id in backward_wallet_ids {
+        let more = index_wallet(
+            wallet_id,
+            transaction_batch_size,
+            max_transaction_id.clone(),
+            publisher,
+            storage,
+        )
+        .await?;
+
+        if more {
+            more_wallet_ids.insert(wallet_id);
+        }
+    }
+
+    Ok(more_wallet_ids)
+}
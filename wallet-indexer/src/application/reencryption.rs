@@ -0,0 +1,82 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::domain::storage::Storage;
+use indexer_common::error::StdErrorExt;
+use log::{info, warn};
+use serde::Deserialize;
+use std::{num::NonZeroUsize, time::Duration};
+use tokio::time::interval;
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct Config {
+    /// Interval between checks for viewing keys and webhook secrets not yet encrypted for the
+    /// current secret key.
+    #[serde(with = "humantime_serde")]
+    pub check_interval: Duration,
+
+    /// Maximum number of viewing keys or webhook secrets re-encrypted within a single database
+    /// transaction.
+    pub batch_size: NonZeroUsize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            check_interval: Duration::from_secs(60),
+            batch_size: NonZeroUsize::new(100).expect("100 is not zero"),
+        }
+    }
+}
+
+/// Periodically re-encrypt the viewing keys and webhook secrets which are not yet encrypted for
+/// the current secret key, e.g. after a key rotation, batch by batch until none are left. Failures
+/// are retried with the next check, hence never stop the wallet-indexer.
+pub async fn run(config: Config, storage: impl Storage) -> anyhow::Result<()> {
+    let Config {
+        check_interval,
+        batch_size,
+    } = config;
+
+    let mut check_interval = interval(check_interval);
+
+    loop {
+        check_interval.tick().await;
+
+        loop {
+            match storage.reencrypt_viewing_keys(batch_size).await {
+                Ok(0) => break,
+
+                Ok(count) => info!(count; "viewing keys re-encrypted"),
+
+                Err(error) => {
+                    warn!(error = error.as_chain(); "cannot re-encrypt viewing keys");
+                    break;
+                }
+            }
+        }
+
+        loop {
+            match storage.reencrypt_webhook_secrets(batch_size).await {
+                Ok(0) => break,
+
+                Ok(count) => info!(count; "webhook secrets re-encrypted"),
+
+                Err(error) => {
+                    warn!(error = error.as_chain(); "cannot re-encrypt webhook secrets");
+                    break;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,196 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    collections::{HashSet, VecDeque},
+    num::NonZeroUsize,
+    sync::Mutex,
+};
+use tokio::sync::Notify;
+use uuid::Uuid;
+
+/// In-memory schedule of the active wallets to be indexed. Wallets are scheduled when connected,
+/// for each indexed block and again after indexing as long as there may be more to index. A wallet
+/// is handed out at most once at a time, i.e. until [WalletSchedule::done] is called for it.
+#[derive(Debug, Default)]
+pub struct WalletSchedule {
+    state: Mutex<State>,
+    notify: Notify,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    active: HashSet<Uuid>,
+    queue: VecDeque<Uuid>,
+    queued: HashSet<Uuid>,
+    running: HashSet<Uuid>,
+    rerun: HashSet<Uuid>,
+}
+
+impl WalletSchedule {
+    /// Add the given wallet to the active ones and schedule it.
+    pub fn connect(&self, wallet_id: Uuid) {
+        let mut state = self.state.lock().expect("lock can be acquired");
+        state.active.insert(wallet_id);
+        self.schedule(&mut state, wallet_id);
+    }
+
+    /// Remove the given wallet from the active ones; it is no longer handed out.
+    pub fn disconnect(&self, wallet_id: Uuid) {
+        let mut state = self.state.lock().expect("lock can be acquired");
+        state.active.remove(&wallet_id);
+        state.rerun.remove(&wallet_id);
+    }
+
+    /// Replace the active wallets with the given ones, e.g. queried from the database to recover
+    /// from missed connect or disconnect messages, and schedule the ones not yet active.
+    pub fn reset(&self, wallet_ids: Vec<Uuid>) {
+        let mut state = self.state.lock().expect("lock can be acquired");
+
+        let wallet_ids = wallet_ids.into_iter().collect::<HashSet<_>>();
+        let new_wallet_ids = wallet_ids
+            .difference(&state.active)
+            .copied()
+            .collect::<Vec<_>>();
+        state.active = wallet_ids;
+
+        for wallet_id in new_wallet_ids {
+            self.schedule(&mut state, wallet_id);
+        }
+    }
+
+    /// Schedule all active wallets, e.g. because a block has been indexed.
+    pub fn schedule_all(&self) {
+        let mut state = self.state.lock().expect("lock can be acquired");
+
+        let wallet_ids = state.active.iter().copied().collect::<Vec<_>>();
+        for wallet_id in wallet_ids {
+            self.schedule(&mut state, wallet_id);
+        }
+    }
+
+    /// Wait for the next scheduled active wallet and hand it out.
+    pub async fn next(&self) -> Uuid {
+        self.next_batch(NonZeroUsize::MIN).await[0]
+    }
+
+    /// Wait for the next scheduled active wallets and hand out at least one and at most `limit` of
+    /// them.
+    pub async fn next_batch(&self, limit: NonZeroUsize) -> Vec<Uuid> {
+        loop {
+            // Create the future before checking the queue to not miss notifications in between.
+            let notified = self.notify.notified();
+
+            {
+                let mut state = self.state.lock().expect("lock can be acquired");
+
+                let mut wallet_ids = Vec::new();
+                while wallet_ids.len() < limit.get()
+                    && let Some(wallet_id) = state.queue.pop_front()
+                {
+                    state.queued.remove(&wallet_id);
+                    if state.active.contains(&wallet_id) {
+                        state.running.insert(wallet_id);
+                        wallet_ids.push(wallet_id);
+                    }
+                }
+
+                if !wallet_ids.is_empty() {
+                    return wallet_ids;
+                }
+            }
+
+            notified.await;
+        }
+    }
+
+    /// Mark the given handed out wallet as done and schedule it again if there may be more to
+    /// index or if it has been scheduled meanwhile.
+    pub fn done(&self, wallet_id: Uuid, more: bool) {
+        let mut state = self.state.lock().expect("lock can be acquired");
+        state.running.remove(&wallet_id);
+        if state.rerun.remove(&wallet_id) || more {
+            self.schedule(&mut state, wallet_id);
+        }
+    }
+
+    fn schedule(&self, state: &mut State, wallet_id: Uuid) {
+        if !state.active.contains(&wallet_id) {
+            return;
+        }
+
+        if state.running.contains(&wallet_id) {
+            state.rerun.insert(wallet_id);
+        } else if state.queued.insert(wallet_id) {
+            state.queue.push_back(wallet_id);
+            self.notify.notify_waiters();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::application::schedule::WalletSchedule;
+    use std::{num::NonZeroUsize, time::Duration};
+    use tokio::time::timeout;
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn test_wallet_schedule() {
+        let schedule = WalletSchedule::default();
+        let wallet_a = Uuid::now_v7();
+        let wallet_b = Uuid::now_v7();
+
+        schedule.connect(wallet_a);
+        schedule.connect(wallet_b);
+        schedule.connect(wallet_a);
+        assert_eq!(schedule.next().await, wallet_a);
+        assert_eq!(schedule.next().await, wallet_b);
+
+        // Scheduled while running, hence handed out again once done.
+        schedule.schedule_all();
+        schedule.done(wallet_a, false);
+        schedule.done(wallet_b, false);
+        assert_eq!(schedule.next().await, wallet_a);
+        assert_eq!(schedule.next().await, wallet_b);
+
+        // Disconnected wallets are no longer handed out.
+        schedule.done(wallet_a, true);
+        schedule.disconnect(wallet_a);
+        schedule.done(wallet_b, true);
+        assert_eq!(schedule.next().await, wallet_b);
+        schedule.done(wallet_b, false);
+        assert!(
+            timeout(Duration::from_millis(10), schedule.next())
+                .await
+                .is_err()
+        );
+
+        // Resetting schedules the wallets not yet active only.
+        schedule.reset(vec![wallet_a, wallet_b]);
+        assert_eq!(schedule.next().await, wallet_a);
+        assert!(
+            timeout(Duration::from_millis(10), schedule.next())
+                .await
+                .is_err()
+        );
+
+        // Batches are limited and hand out each wallet once.
+        schedule.done(wallet_a, true);
+        schedule.schedule_all();
+        let limit = NonZeroUsize::new(1).unwrap();
+        assert_eq!(schedule.next_batch(limit).await, vec![wallet_a]);
+        let limit = NonZeroUsize::new(10).unwrap();
+        assert_eq!(schedule.next_batch(limit).await, vec![wallet_b]);
+    }
+}
@@ -0,0 +1,160 @@
+// This file is part of midnight-indexer.
+// Copyright (C) Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::domain::{storage::Storage, wallet_owner};
+use anyhow::Context;
+use log::info;
+use serde::Deserialize;
+use std::{
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+use tokio::{sync::Notify, time::interval};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct Config {
+    /// Interval between heartbeats of this replica, which also refresh the live replicas.
+    #[serde(with = "humantime_serde")]
+    pub heartbeat_interval: Duration,
+
+    /// Replicas without a heartbeat within this TTL are considered dead and their wallets are
+    /// taken over by the live ones. Must comfortably exceed the heartbeat interval.
+    #[serde(with = "humantime_serde")]
+    pub replica_ttl: Duration,
+}
+
+/// The share of the wallets owned by this wallet-indexer replica, determined by the live replicas.
+#[derive(Debug)]
+pub struct Shard {
+    replica_id: Uuid,
+    replica_ids: RwLock<Vec<Uuid>>,
+    rebalanced: Notify,
+}
+
+impl Shard {
+    /// Create a shard for the given replica which owns no wallets until the live replicas have
+    /// been read, hence never indexes wallets owned by another replica after a restart.
+    pub fn new(replica_id: Uuid) -> Self {
+        Self {
+            replica_id,
+            replica_ids: RwLock::new(vec![]),
+            rebalanced: Notify::new(),
+        }
+    }
+
+    /// The ID of this replica.
+    pub fn replica_id(&self) -> Uuid {
+        self.replica_id
+    }
+
+    /// Whether the given wallet is owned by this replica.
+    pub fn owns(&self, wallet_id: Uuid) -> bool {
+        let replica_ids = self.replica_ids.read().expect("lock can be acquired");
+        wallet_owner(wallet_id, &replica_ids) == Some(self.replica_id)
+    }
+
+    /// Wait until the live replicas have changed, i.e. the wallets have been rebalanced.
+    pub async fn rebalanced(&self) {
+        self.rebalanced.notified().await
+    }
+
+    /// Replace the live replicas, always including this one, and notify if they have changed.
+    fn set_replica_ids(&self, mut replica_ids: Vec<Uuid>) -> bool {
+        if !replica_ids.contains(&self.replica_id) {
+            replica_ids.push(self.replica_id);
+        }
+        replica_ids.sort();
+
+        let mut current_replica_ids = self.replica_ids.write().expect("lock can be acquired");
+        let changed = *current_replica_ids != replica_ids;
+        if changed {
+            *current_replica_ids = replica_ids;
+            self.rebalanced.notify_one();
+        }
+
+        changed
+    }
+}
+
+/// Periodically save a heartbeat for this replica and refresh the live replicas of the given
+/// shard, thereby rebalancing the wallets when replicas join or die.
+pub async fn run(config: Config, shard: Arc<Shard>, storage: impl Storage) -> anyhow::Result<()> {
+    let Config {
+        heartbeat_interval,
+        replica_ttl,
+    } = config;
+
+    let mut heartbeat_interval = interval(heartbeat_interval);
+
+    loop {
+        heartbeat_interval.tick().await;
+
+        storage
+            .save_replica_heartbeat(shard.replica_id)
+            .await
+            .context("save replica heartbeat")?;
+
+        let replica_ids = storage
+            .live_replica_ids(replica_ttl)
+            .await
+            .context("get live replica IDs")?;
+
+        if shard.set_replica_ids(replica_ids) {
+            let replica_count = shard
+                .replica_ids
+                .read()
+                .expect("lock can be acquired")
+                .len();
+            info!(replica_id:% = shard.replica_id, replica_count; "wallets rebalanced");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::application::sharding::Shard;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_shard() {
+        let replica_a = Uuid::now_v7();
+        let replica_b = Uuid::now_v7();
+        let shard_a = Shard::new(replica_a);
+        let shard_b = Shard::new(replica_b);
+        let wallet_ids = (0..100).map(|_| Uuid::now_v7()).collect::<Vec<_>>();
+
+        // A shard owns nothing until the live replicas have been read.
+        assert!(!wallet_ids.iter().any(|&wallet_id| shard_a.owns(wallet_id)));
+
+        // A single replica owns all wallets.
+        assert!(shard_a.set_replica_ids(vec![]));
+        assert!(wallet_ids.iter().all(|&wallet_id| shard_a.owns(wallet_id)));
+
+        // Replicas with the same live replicas own disjoint shares covering all wallets.
+        assert!(shard_a.set_replica_ids(vec![replica_a, replica_b]));
+        assert!(shard_b.set_replica_ids(vec![replica_b, replica_a]));
+        assert!(!shard_a.set_replica_ids(vec![replica_b]));
+        assert!(
+            wallet_ids
+                .iter()
+                .all(|&wallet_id| shard_a.owns(wallet_id) != shard_b.owns(wallet_id))
+        );
+        assert!(wallet_ids.iter().any(|&wallet_id| shard_a.owns(wallet_id)));
+        assert!(wallet_ids.iter().any(|&wallet_id| shard_b.owns(wallet_id)));
+
+        // When a replica dies, the remaining ones take over its wallets.
+        assert!(shard_a.set_replica_ids(vec![]));
+        assert!(wallet_ids.iter().all(|&wallet_id| shard_a.owns(wallet_id)));
+    }
+}
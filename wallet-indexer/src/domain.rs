@@ -15,7 +15,10 @@ pub mod storage;
 
 use fastrace::trace;
 use indexer_common::domain::{ProtocolVersion, SerializedTransaction, ViewingKey, ledger};
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use sha2::{Digest, Sha256};
 use sqlx::prelude::FromRow;
+use uuid::Uuid;
 
 /// Relevant data of a wallet from the perspective of the Wallet Indexer.
 #[derive(Debug, Clone, PartialEq, Eq, FromRow)]
@@ -47,3 +50,48 @@ impl Transaction {
         Ok(transaction.relevant(wallet.viewing_key))
     }
 }
+
+/// Check the relevance of the given transactions for all the given wallets at once: each
+/// transaction is deserialized only once and trial-decrypted with all viewing keys, in parallel on
+/// the rayon worker pool. A transaction is only checked for a wallet if it has not yet been indexed
+/// for that wallet. Return the indices of the wallets each transaction is relevant for, in the
+/// order of the given transactions.
+#[trace]
+pub fn relevant_wallets(
+    transactions: &[Transaction],
+    wallets: &[Wallet],
+) -> Result<Vec<Vec<usize>>, ledger::Error> {
+    transactions
+        .par_iter()
+        .map(|transaction| {
+            let ledger_transaction = ledger::Transaction::deserialize(
+                &transaction.raw,
+                transaction.protocol_version.ledger_version(),
+            )?;
+
+            let relevant_wallets = wallets
+                .iter()
+                .enumerate()
+                .filter(|(_, wallet)| {
+                    transaction.id > wallet.last_indexed_transaction_id
+                        && ledger_transaction.relevant(wallet.viewing_key)
+                })
+                .map(|(index, _)| index)
+                .collect();
+
+            Ok(relevant_wallets)
+        })
+        .collect()
+}
+
+/// Determine the owner of the given wallet among the given wallet-indexer replicas by rendezvous
+/// hashing, i.e. the replica with the highest hash of wallet ID and replica ID. Hence, when a
+/// replica joins or leaves, only the wallets it gains or loses change their owner.
+pub fn wallet_owner(wallet_id: Uuid, replica_ids: &[Uuid]) -> Option<Uuid> {
+    replica_ids.iter().copied().max_by_key(|replica_id| {
+        let mut hasher = Sha256::new();
+        hasher.update(wallet_id.as_bytes());
+        hasher.update(replica_id.as_bytes());
+        <[u8; 32]>::from(hasher.finalize())
+    })
+}
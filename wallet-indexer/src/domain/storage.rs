@@ -37,6 +37,14 @@ where
         wallet_id: Uuid,
     ) -> Result<Option<SqlxTransaction<Self::Database>>, sqlx::Error>;
 
+    /// Try to acquire the application level locks for the given wallet IDs like
+    /// [Storage::acquire_lock], but all within a single transaction. Return that transaction and
+    /// the IDs of the wallets for which the lock could be acquired.
+    async fn acquire_locks(
+        &mut self,
+        wallet_ids: &[Uuid],
+    ) -> Result<(SqlxTransaction<Self::Database>, Vec<Uuid>), sqlx::Error>;
+
     /// Get at most `limit` transactions starting at the given `from` ID; it is supposed that the
     /// IDs are a gapless strictly monotonically increasing sequence.
     async fn get_transactions(
@@ -56,12 +64,25 @@ where
     ) -> Result<Vec<Transaction>, sqlx::Error>;
 
     /// For the given session ID, transactionally save the given relevant `transactions` and
-    /// update the last indexed transaction ID.
+    /// update the last indexed transaction ID as well as the scan rate, if measured.
     async fn save_relevant_transactions(
         &self,
         viewing_key: &ViewingKey,
         transactions: &[Transaction],
         last_indexed_transaction_id: u64,
+        scan_rate: Option<f64>,
+        tx: &mut SqlxTransaction<Self::Database>,
+    ) -> Result<(), sqlx::Error>;
+
+    /// For the given wallet IDs, save the given relevant transactions as pairs of wallet ID and
+    /// transaction ID and update the last indexed transaction ID of all these wallets as well as
+    /// their scan rate, if measured.
+    async fn save_batch_relevant_transactions(
+        &self,
+        wallet_ids: &[Uuid],
+        relevant_transactions: &[(Uuid, u64)],
+        last_indexed_transaction_id: u64,
+        scan_rate: Option<f64>,
         tx: &mut SqlxTransaction<Self::Database>,
     ) -> Result<(), sqlx::Error>;
 
@@ -77,10 +98,28 @@ where
     /// Get the IDs of active wallets, thereby deactivating outdated ones.
     async fn active_wallet_ids(&self, ttl: Duration) -> Result<Vec<Uuid>, sqlx::Error>;
 
+    /// Save a heartbeat for the given wallet-indexer replica, thereby registering it if needed.
+    async fn save_replica_heartbeat(&self, replica_id: Uuid) -> Result<(), sqlx::Error>;
+
+    /// Get the IDs of live wallet-indexer replicas, thereby deleting the ones without a heartbeat
+    /// within the given TTL.
+    async fn live_replica_ids(&self, ttl: Duration) -> Result<Vec<Uuid>, sqlx::Error>;
+
+    /// Delete the given wallet-indexer replica, e.g. when shutting down.
+    async fn delete_replica(&self, replica_id: Uuid) -> Result<(), sqlx::Error>;
+
     /// Get the wallet with the given ID.
     async fn get_wallet_by_id(
         &self,
         id: Uuid,
         tx: &mut SqlxTransaction<Self::Database>,
     ) -> Result<Wallet, sqlx::Error>;
+
+    /// Re-encrypt the viewing keys of at most `limit` wallets which are not yet encrypted for the
+    /// current secret key. Return the number of re-encrypted viewing keys.
+    async fn reencrypt_viewing_keys(&self, limit: NonZeroUsize) -> Result<usize, sqlx::Error>;
+
+    /// Re-encrypt the secrets of at most `limit` webhooks which are not yet encrypted for the
+    /// current secret key. Return the number of re-encrypted webhook secrets.
+    async fn reencrypt_webhook_secrets(&self, limit: NonZeroUsize) -> Result<usize, sqlx::Error>;
 }
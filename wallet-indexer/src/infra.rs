@@ -31,5 +31,7 @@ pub struct Config {
     #[serde(rename = "pub_sub")]
     pub pub_sub_config: indexer_common::infra::pub_sub::nats::Config,
 
-    pub secret: secrecy::SecretString,
+    /// The current secret key to encrypt stored sensitive data and any retired ones.
+    #[serde(flatten)]
+    pub cipher_config: indexer_common::cipher::Config,
 }
@@ -19,8 +19,12 @@ use chacha20poly1305::ChaCha20Poly1305;
 use derive_more::Debug;
 use fastrace::trace;
 use futures::TryStreamExt;
-use indexer_common::domain::{ByteVec, DecryptViewingKeyError, ViewingKey};
+use indexer_common::{
+    cipher::{Envelope, KeyId, Keyring},
+    domain::{ByteVec, DecryptViewingKeyError, ViewingKey},
+};
 use indoc::indoc;
+use itertools::Itertools;
 use sqlx::{
     QueryBuilder, Row,
     prelude::FromRow,
@@ -32,8 +36,7 @@ use std::{num::NonZeroUsize, time::Duration};
 /// features to select the appropriate database backend at build time.
 #[derive(Debug, Clone)]
 pub struct Storage {
-    #[debug(skip)]
-    cipher: ChaCha20Poly1305,
+    keyring: Keyring,
 
     #[cfg(feature = "cloud")]
     pool: indexer_common::infra::pool::postgres::PostgresPool,
@@ -45,18 +48,15 @@ pub struct Storage {
 impl Storage {
     #[cfg(feature = "cloud")]
     pub fn new(
-        cipher: ChaCha20Poly1305,
+        keyring: Keyring,
         pool: indexer_common::infra::pool::postgres::PostgresPool,
     ) -> Self {
-        Self { cipher, pool }
+        Self { keyring, pool }
     }
 
     #[cfg(feature = "standalone")]
-    pub fn new(
-        cipher: ChaCha20Poly1305,
-        pool: indexer_common::infra::pool::sqlite::SqlitePool,
-    ) -> Self {
-        Self { cipher, pool }
+    pub fn new(keyring: Keyring, pool: indexer_common::infra::pool::sqlite::SqlitePool) -> Self {
+        Self { keyring, pool }
     }
 }
 
@@ -73,24 +73,8 @@ impl domain::storage::Storage for Storage {
         &mut self,
         wallet_id: Uuid,
     ) -> Result<Option<SqlxTransaction<Self::Database>>, sqlx::Error> {
-        use std::hash::{DefaultHasher, Hash, Hasher};
-
         let mut tx = self.pool.begin().await?;
-
-        // Convert UUID to two i32 values by hashing to u64 and splitting into two.
-        let mut hasher = DefaultHasher::new();
-        wallet_id.hash(&mut hasher);
-        let hash = hasher.finish();
-        let high = (hash >> 32) as i32;
-        let low = hash as i32;
-
-        let lock_acquired = sqlx::query("SELECT pg_try_advisory_xact_lock($1, $2)")
-            .bind(high)
-            .bind(low)
-            .fetch_one(&mut *tx)
-            .await
-            .and_then(|row| row.try_get::<bool, _>(0))?;
-
+        let lock_acquired = try_advisory_xact_lock(wallet_id, &mut tx).await?;
         Ok(lock_acquired.then_some(tx))
     }
 
@@ -105,6 +89,34 @@ impl domain::storage::Storage for Storage {
         Ok(Some(tx))
     }
 
+    #[cfg(feature = "cloud")]
+    #[trace]
+    async fn acquire_locks(
+        &mut self,
+        wallet_ids: &[Uuid],
+    ) -> Result<(SqlxTransaction<Self::Database>, Vec<Uuid>), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let mut locked_wallet_ids = Vec::with_capacity(wallet_ids.len());
+        for &wallet_id in wallet_ids {
+            if try_advisory_xact_lock(wallet_id, &mut tx).await? {
+                locked_wallet_ids.push(wallet_id);
+            }
+        }
+
+        Ok((tx, locked_wallet_ids))
+    }
+
+    #[cfg(feature = "standalone")]
+    async fn acquire_locks(
+        &mut self,
+        wallet_ids: &[Uuid],
+    ) -> Result<(SqlxTransaction<Self::Database>, Vec<Uuid>), sqlx::Error> {
+        // See acquire_lock: in standalone mode "locking" is always successful.
+        let tx = self.pool.begin().await?;
+        Ok((tx, wallet_ids.to_vec()))
+    }
+
     #[trace(properties = { "from": "{from}", "limit": "{limit}" })]
     async fn get_transactions(
         &self,
@@ -166,12 +178,17 @@ impl domain::storage::Storage for Storage {
         viewing_key: &ViewingKey,
         transactions: &[Transaction],
         last_indexed_transaction_id: u64,
+        scan_rate: Option<f64>,
         tx: &mut SqlxTransaction<Self::Database>,
     ) -> Result<(), sqlx::Error> {
         let id = Uuid::now_v7();
         let viewing_key_hash = viewing_key.hash();
+        let data_key = self
+            .keyring
+            .generate_data_key(id.as_bytes())
+            .map_err(|error| sqlx::Error::Encode(error.into()))?;
         let viewing_key = viewing_key
-            .encrypt(id, &self.cipher)
+            .encrypt(id, &data_key.cipher)
             .map_err(|error| sqlx::Error::Encode(error.into()))?;
 
         let query = indoc! {"
@@ -179,14 +196,21 @@ impl domain::storage::Storage for Storage {
                 id,
                 viewing_key_hash,
                 viewing_key,
+                encryption_key_id,
+                data_key,
                 wanted_start_index,
                 first_indexed_transaction_id,
                 last_indexed_transaction_id,
-                last_active
+                last_active,
+                scan_rate,
+                relevant_transaction_count
             )
-            VALUES ($1, $2, $3, 0, 0, $4, $5)
+            VALUES ($1, $2, $3, $7, $8, 0, 0, $4, $5, $6, $9)
             ON CONFLICT (viewing_key_hash)
-            DO UPDATE SET last_indexed_transaction_id = $4
+            DO UPDATE SET
+                last_indexed_transaction_id = $4,
+                scan_rate = COALESCE($6, wallets.scan_rate),
+                relevant_transaction_count = wallets.relevant_transaction_count + $9
             RETURNING id
         "};
 
@@ -196,6 +220,10 @@ impl domain::storage::Storage for Storage {
             .bind(viewing_key)
             .bind(last_indexed_transaction_id as i64)
             .bind(OffsetDateTime::now_utc())
+            .bind(scan_rate)
+            .bind(data_key.key_id as i64)
+            .bind(&data_key.wrapped)
+            .bind(transactions.len() as i64)
             .fetch_one(&mut **tx)
             .await?
             .try_get::<Uuid, _>("id")?;
@@ -220,6 +248,72 @@ impl domain::storage::Storage for Storage {
         Ok(())
     }
 
+    #[trace(properties = { "last_indexed_transaction_id": "{last_indexed_transaction_id}" })]
+    async fn save_batch_relevant_transactions(
+        &self,
+        wallet_ids: &[Uuid],
+        relevant_transactions: &[(Uuid, u64)],
+        last_indexed_transaction_id: u64,
+        scan_rate: Option<f64>,
+        tx: &mut SqlxTransaction<Self::Database>,
+    ) -> Result<(), sqlx::Error> {
+        if wallet_ids.is_empty() {
+            return Ok(());
+        }
+
+        let mut query = QueryBuilder::new("UPDATE wallets SET last_indexed_transaction_id = ");
+        query.push_bind(last_indexed_transaction_id as i64);
+        query.push(", scan_rate = COALESCE(");
+        query.push_bind(scan_rate);
+        query.push(", scan_rate) WHERE id IN (");
+        let mut separated = query.separated(", ");
+        for &wallet_id in wallet_ids {
+            separated.push_bind(wallet_id);
+        }
+        separated.push_unseparated(")");
+        query.build().execute(&mut **tx).await?;
+
+        let relevant_transaction_counts = relevant_transactions
+            .iter()
+            .map(|(wallet_id, _)| wallet_id)
+            .counts();
+        for (wallet_id, count) in relevant_transaction_counts {
+            let query = indoc! {"
+                UPDATE wallets
+                SET relevant_transaction_count = relevant_transaction_count + $1
+                WHERE id = $2
+            "};
+
+            sqlx::query(query)
+                .bind(count as i64)
+                .bind(wallet_id)
+                .execute(&mut **tx)
+                .await?;
+        }
+
+        if !relevant_transactions.is_empty() {
+            let query = indoc! {"
+                INSERT INTO relevant_transactions (
+                    wallet_id,
+                    transaction_id
+                )
+            "};
+
+            QueryBuilder::new(query)
+                .push_values(
+                    relevant_transactions,
+                    |mut q, (wallet_id, transaction_id)| {
+                        q.push_bind(*wallet_id).push_bind(*transaction_id as i64);
+                    },
+                )
+                .build()
+                .execute(&mut **tx)
+                .await?;
+        }
+
+        Ok(())
+    }
+
     #[trace]
     async fn save_backward_relevant_transactions(
         &self,
@@ -230,13 +324,16 @@ impl domain::storage::Storage for Storage {
     ) -> Result<(), sqlx::Error> {
         let query = indoc! {"
             UPDATE wallets
-            SET first_indexed_transaction_id = $1
+            SET
+                first_indexed_transaction_id = $1,
+                relevant_transaction_count = relevant_transaction_count + $3
             WHERE id = $2
         "};
 
         sqlx::query(query)
             .bind(first_indexed_transaction_id as i64)
             .bind(wallet_id)
+            .bind(transactions.len() as i64)
             .execute(&mut **tx)
             .await?;
 
@@ -344,6 +441,80 @@ impl domain::storage::Storage for Storage {
         Ok(ids)
     }
 
+    #[trace(properties = { "replica_id": "{replica_id}" })]
+    async fn save_replica_heartbeat(&self, replica_id: Uuid) -> Result<(), sqlx::Error> {
+        // Heartbeats are written and compared with the DB time, hence replicas with skewed clocks
+        // do not consider each other dead.
+        #[cfg(feature = "cloud")]
+        let query = indoc! {"
+            INSERT INTO wallet_indexer_replicas (id, last_heartbeat)
+            VALUES ($1, NOW())
+            ON CONFLICT (id)
+            DO UPDATE SET last_heartbeat = NOW()
+        "};
+
+        #[cfg(feature = "standalone")]
+        let query = indoc! {"
+            INSERT INTO wallet_indexer_replicas (id, last_heartbeat)
+            VALUES ($1, CAST(unixepoch('subsec') * 1000 AS INTEGER))
+            ON CONFLICT (id)
+            DO UPDATE SET last_heartbeat = CAST(unixepoch('subsec') * 1000 AS INTEGER)
+        "};
+
+        sqlx::query(query)
+            .bind(replica_id)
+            .execute(&*self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    #[trace]
+    async fn live_replica_ids(&self, ttl: Duration) -> Result<Vec<Uuid>, sqlx::Error> {
+        #[cfg(feature = "cloud")]
+        let (query, ttl) = (
+            indoc! {"
+                DELETE FROM wallet_indexer_replicas
+                WHERE last_heartbeat < NOW() - make_interval(secs => $1)
+            "},
+            ttl.as_secs_f64(),
+        );
+
+        #[cfg(feature = "standalone")]
+        let (query, ttl) = (
+            indoc! {"
+                DELETE FROM wallet_indexer_replicas
+                WHERE last_heartbeat < CAST(unixepoch('subsec') * 1000 AS INTEGER) - $1
+            "},
+            ttl.as_millis() as i64,
+        );
+
+        sqlx::query(query).bind(ttl).execute(&*self.pool).await?;
+
+        let query = indoc! {"
+            SELECT id
+            FROM wallet_indexer_replicas
+            ORDER BY id
+        "};
+
+        sqlx::query_scalar(query).fetch_all(&*self.pool).await
+    }
+
+    #[trace(properties = { "replica_id": "{replica_id}" })]
+    async fn delete_replica(&self, replica_id: Uuid) -> Result<(), sqlx::Error> {
+        let query = indoc! {"
+            DELETE FROM wallet_indexer_replicas
+            WHERE id = $1
+        "};
+
+        sqlx::query(query)
+            .bind(replica_id)
+            .execute(&*self.pool)
+            .await?;
+
+        Ok(())
+    }
+
     #[trace(properties = { "id": "{id}" })]
     async fn get_wallet_by_id(
         &self,
@@ -354,6 +525,8 @@ impl domain::storage::Storage for Storage {
             SELECT
                 id,
                 viewing_key,
+                encryption_key_id,
+                data_key,
                 wanted_start_index,
                 first_indexed_transaction_id,
                 last_indexed_transaction_id
@@ -366,9 +539,205 @@ impl domain::storage::Storage for Storage {
             .fetch_one(&mut **tx)
             .await?;
 
-        domain::Wallet::try_from((wallet, &self.cipher))
+        let cipher = self
+            .keyring
+            .data_key_cipher(
+                wallet.encryption_key_id,
+                wallet.data_key.as_deref(),
+                wallet.id.as_bytes(),
+            )
+            .map_err(|error| sqlx::Error::Decode(error.into()))?;
+        domain::Wallet::try_from((wallet, &cipher))
             .map_err(|error| sqlx::Error::Decode(error.into()))
     }
+
+    #[trace(properties = { "limit": "{limit}" })]
+    async fn reencrypt_viewing_keys(&self, limit: NonZeroUsize) -> Result<usize, sqlx::Error> {
+        let mut query = QueryBuilder::new(indoc! {"
+            SELECT
+                id,
+                viewing_key,
+                encryption_key_id,
+                data_key
+            FROM wallets
+            WHERE
+        "});
+        push_reencryption_filter(&self.keyring, &mut query);
+        query
+            .push(" ORDER BY id LIMIT ")
+            .push_bind(limit.get() as i64);
+
+        let mut tx = self.pool.begin().await?;
+
+        let wallets = query
+            .build_query_as::<EncryptedViewingKey>()
+            .fetch_all(&mut *tx)
+            .await?;
+        let count = wallets.len();
+
+        for wallet in wallets {
+            let EncryptedViewingKey {
+                id,
+                viewing_key,
+                encryption_key_id,
+                data_key,
+            } = wallet;
+
+            let envelope = Envelope {
+                key_id: encryption_key_id,
+                data_key,
+                ciphertext: viewing_key,
+            };
+            let Envelope {
+                key_id,
+                data_key,
+                ciphertext,
+            } = self
+                .keyring
+                .reencrypt(envelope, id.as_bytes())
+                .map_err(|error| sqlx::Error::Decode(error.into()))?;
+
+            // Nothing else updates the viewing key, hence concurrent re-encryptions, e.g. by
+            // another replica, are harmless: each writes a consistent envelope.
+            let query = indoc! {"
+                UPDATE wallets
+                SET
+                    viewing_key = $1,
+                    encryption_key_id = $2,
+                    data_key = $3
+                WHERE id = $4
+            "};
+
+            sqlx::query(query)
+                .bind(ciphertext)
+                .bind(key_id as i64)
+                .bind(data_key)
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(count)
+    }
+
+    #[trace(properties = { "limit": "{limit}" })]
+    async fn reencrypt_webhook_secrets(&self, limit: NonZeroUsize) -> Result<usize, sqlx::Error> {
+        let mut query = QueryBuilder::new(indoc! {"
+            SELECT
+                id,
+                key,
+                secret,
+                encryption_key_id,
+                data_key
+            FROM webhooks
+            WHERE
+        "});
+        push_reencryption_filter(&self.keyring, &mut query);
+        query
+            .push(" ORDER BY id LIMIT ")
+            .push_bind(limit.get() as i64);
+
+        let mut tx = self.pool.begin().await?;
+
+        let webhooks = query
+            .build_query_as::<EncryptedWebhookSecret>()
+            .fetch_all(&mut *tx)
+            .await?;
+        let count = webhooks.len();
+
+        for webhook in webhooks {
+            let EncryptedWebhookSecret {
+                id,
+                key,
+                secret,
+                encryption_key_id,
+                data_key,
+            } = webhook;
+
+            // Like the indexer-api, use the webhook key as associated data.
+            let envelope = Envelope {
+                key_id: encryption_key_id,
+                data_key,
+                ciphertext: secret,
+            };
+            let Envelope {
+                key_id,
+                data_key,
+                ciphertext,
+            } = self
+                .keyring
+                .reencrypt(envelope, &key)
+                .map_err(|error| sqlx::Error::Decode(error.into()))?;
+
+            // Nothing else updates the secret, hence concurrent re-encryptions are harmless.
+            let query = indoc! {"
+                UPDATE webhooks
+                SET
+                    secret = $1,
+                    encryption_key_id = $2,
+                    data_key = $3
+                WHERE id = $4
+            "};
+
+            sqlx::query(query)
+                .bind(ciphertext)
+                .bind(key_id as i64)
+                .bind(data_key)
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(count)
+    }
+}
+
+/// Push the condition selecting the rows to be re-encrypted for the current key of the given
+/// keyring, i.e. the ones encrypted with another key or without data key. Rows encrypted with a
+/// key unknown to the keyring cannot be decrypted and are not selected, else they would stall the
+/// re-encryption of all subsequent rows.
+fn push_reencryption_filter<'a, DB>(keyring: &Keyring, query: &mut QueryBuilder<'a, DB>)
+where
+    DB: sqlx::Database,
+    i64: 'a + sqlx::Encode<'a, DB> + sqlx::Type<DB>,
+{
+    query
+        .push(" (encryption_key_id <> ")
+        .push_bind(keyring.current_key_id() as i64)
+        .push(" OR data_key IS NULL) AND encryption_key_id IN (");
+    let mut separated = query.separated(", ");
+    for key_id in keyring.key_ids() {
+        separated.push_bind(key_id as i64);
+    }
+    separated.push_unseparated(")");
+}
+
+/// Try to acquire a PostgreSQL advisory lock for the given wallet ID, held until the given
+/// transaction ends.
+#[cfg(feature = "cloud")]
+async fn try_advisory_xact_lock(
+    wallet_id: Uuid,
+    tx: &mut SqlxTransaction<sqlx::Postgres>,
+) -> Result<bool, sqlx::Error> {
+    use std::hash::{DefaultHasher, Hash, Hasher};
+
+    // Convert UUID to two i32 values by hashing to u64 and splitting into two.
+    let mut hasher = DefaultHasher::new();
+    wallet_id.hash(&mut hasher);
+    let hash = hasher.finish();
+    let high = (hash >> 32) as i32;
+    let low = hash as i32;
+
+    sqlx::query("SELECT pg_try_advisory_xact_lock($1, $2)")
+        .bind(high)
+        .bind(low)
+        .fetch_one(&mut **tx)
+        .await
+        .and_then(|row| row.try_get::<bool, _>(0))
 }
 
 /// Persistent wallet data.
@@ -378,6 +747,11 @@ pub struct Wallet {
 
     pub viewing_key: ByteVec,
 
+    #[sqlx(try_from = "i64")]
+    pub encryption_key_id: KeyId,
+
+    pub data_key: Option<Vec<u8>>,
+
     #[sqlx(try_from = "i64")]
     pub wanted_start_index: u64,
 
@@ -388,17 +762,52 @@ pub struct Wallet {
     pub last_indexed_transaction_id: u64,
 }
 
+/// Persistent encrypted viewing key of a wallet.
+#[derive(Debug, Clone, FromRow)]
+struct EncryptedViewingKey {
+    id: Uuid,
+
+    viewing_key: Vec<u8>,
+
+    #[sqlx(try_from = "i64")]
+    encryption_key_id: KeyId,
+
+    data_key: Option<Vec<u8>>,
+}
+
+/// Persistent encrypted secret of a webhook.
+#[derive(Debug, Clone, FromRow)]
+struct EncryptedWebhookSecret {
+    id: i64,
+
+    key: Vec<u8>,
+
+    secret: Vec<u8>,
+
+    #[sqlx(try_from = "i64")]
+    encryption_key_id: KeyId,
+
+    data_key: Option<Vec<u8>>,
+}
+
 #[cfg(all(test, feature = "standalone"))]
 mod tests {
     use crate::{domain::storage::Storage as _, infra::storage::Storage};
-    use chacha20poly1305::{ChaCha20Poly1305, Key, KeyInit};
-    use indexer_common::infra::{
-        migrations,
-        pool::sqlite::{Config, SqlitePool},
+    use chacha20poly1305::{
+        ChaCha20Poly1305, Key, KeyInit, Nonce,
+        aead::{Aead, Payload},
+    };
+    use indexer_common::{
+        cipher::Keyring,
+        domain::ViewingKey,
+        infra::{
+            migrations,
+            pool::sqlite::{Config, SqlitePool},
+        },
     };
     use indoc::indoc;
     use sqlx::types::{Uuid, time::OffsetDateTime};
-    use std::{error::Error as StdError, num::NonZeroUsize};
+    use std::{error::Error as StdError, num::NonZeroUsize, time::Duration};
 
     // Seed a single block so that transactions can satisfy their FK.
     async fn seed_block(pool: &SqlitePool) -> Result<i64, sqlx::Error> {
@@ -456,11 +865,33 @@ mod tests {
         Ok(())
     }
 
+    // Seed several wallets starting at transaction ID 0; their IDs double as their distinct
+    // viewing key hashes.
+    async fn seed_wallets(pool: &SqlitePool, ids: &[Uuid]) -> Result<(), sqlx::Error> {
+        let query = indoc! {"
+            INSERT INTO wallets (
+                id, viewing_key_hash, viewing_key,
+                wanted_start_index, first_indexed_transaction_id, last_indexed_transaction_id,
+                last_active, session_id
+            )
+            VALUES ($1, $2, X'00', 0, 0, 0, $3, NULL)
+        "};
+        for &id in ids {
+            sqlx::query(query)
+                .bind(id)
+                .bind(id.as_bytes().as_slice())
+                .bind(OffsetDateTime::now_utc())
+                .execute(&**pool)
+                .await?;
+        }
+        Ok(())
+    }
+
     async fn new_storage() -> Result<(Storage, SqlitePool), Box<dyn StdError>> {
         let pool = SqlitePool::new(Config::default()).await?;
         migrations::sqlite::run(&pool).await?;
         let cipher = ChaCha20Poly1305::new(Key::from_slice(&[0u8; 32]));
-        Ok((Storage::new(cipher, pool.clone()), pool))
+        Ok((Storage::new(Keyring::new(0, cipher), pool.clone()), pool))
     }
 
     /// Half-open `[from, to)` range, DESC order, filters `variant = 'Regular'`, honours limit.
@@ -581,12 +1012,17 @@ mod tests {
             .await?;
         tx.commit().await?;
 
-        let (first_indexed,): (i64,) =
-            sqlx::query_as("SELECT first_indexed_transaction_id FROM wallets WHERE id = $1")
-                .bind(wallet_id)
-                .fetch_one(&*pool)
-                .await?;
+        let query = indoc! {"
+            SELECT first_indexed_transaction_id, relevant_transaction_count
+            FROM wallets
+            WHERE id = $1
+        "};
+        let (first_indexed, relevant_count): (i64, i64) = sqlx::query_as(query)
+            .bind(wallet_id)
+            .fetch_one(&*pool)
+            .await?;
         assert_eq!(first_indexed, 50);
+        assert_eq!(relevant_count, 3);
 
         let mut ids = sqlx::query_as::<_, (i64,)>(
             "SELECT transaction_id FROM relevant_transactions WHERE wallet_id = $1",
@@ -602,6 +1038,222 @@ mod tests {
 
         Ok(())
     }
+
+    /// Relevant rows are inserted for multiple wallets at once and the last indexed transaction ID
+    /// is updated for the given wallets only.
+    #[tokio::test]
+    async fn save_batch_inserts_relevant_rows_for_all_wallets() -> Result<(), Box<dyn StdError>> {
+        let (storage, pool) = new_storage().await?;
+        let block_id = seed_block(&pool).await?;
+        for id in [1i64, 2, 3] {
+            seed_transaction(&pool, id, block_id, "Regular").await?;
+        }
+
+        let wallet_a = Uuid::now_v7();
+        let wallet_b = Uuid::now_v7();
+        let wallet_c = Uuid::now_v7();
+        seed_wallets(&pool, &[wallet_a, wallet_b, wallet_c]).await?;
+
+        let mut tx = pool.begin().await?;
+        storage
+            .save_batch_relevant_transactions(
+                &[wallet_a, wallet_b],
+                &[(wallet_a, 1), (wallet_a, 3), (wallet_b, 2)],
+                3,
+                Some(42.0),
+                &mut tx,
+            )
+            .await?;
+        tx.commit().await?;
+
+        for (wallet_id, expected_last_indexed, expected_scan_rate, expected_ids) in [
+            (wallet_a, 3, Some(42.0), vec![1, 3]),
+            (wallet_b, 3, Some(42.0), vec![2]),
+            (wallet_c, 0, None, vec![]),
+        ] {
+            let query = indoc! {"
+                SELECT last_indexed_transaction_id, scan_rate, relevant_transaction_count
+                FROM wallets
+                WHERE id = $1
+            "};
+            let (last_indexed, scan_rate, relevant_count): (i64, Option<f64>, i64) =
+                sqlx::query_as(query)
+                    .bind(wallet_id)
+                    .fetch_one(&*pool)
+                    .await?;
+            assert_eq!(last_indexed, expected_last_indexed);
+            assert_eq!(scan_rate, expected_scan_rate);
+            assert_eq!(relevant_count, expected_ids.len() as i64);
+
+            let mut ids = sqlx::query_as::<_, (i64,)>(
+                "SELECT transaction_id FROM relevant_transactions WHERE wallet_id = $1",
+            )
+            .bind(wallet_id)
+            .fetch_all(&*pool)
+            .await?
+            .into_iter()
+            .map(|(id,)| id)
+            .collect::<Vec<_>>();
+            ids.sort();
+            assert_eq!(ids, expected_ids);
+        }
+
+        Ok(())
+    }
+
+    /// Replicas are live while their heartbeats are within the TTL; expired ones are deleted.
+    #[tokio::test]
+    async fn live_replica_ids_expire_without_heartbeat() -> Result<(), Box<dyn StdError>> {
+        let (storage, pool) = new_storage().await?;
+
+        let replica_a = Uuid::now_v7();
+        let replica_b = Uuid::now_v7();
+        storage.save_replica_heartbeat(replica_a).await?;
+        storage.save_replica_heartbeat(replica_b).await?;
+        storage.save_replica_heartbeat(replica_a).await?;
+
+        let ttl = Duration::from_secs(60);
+        let mut replica_ids = vec![replica_a, replica_b];
+        replica_ids.sort();
+        assert_eq!(storage.live_replica_ids(ttl).await?, replica_ids);
+
+        // Let the heartbeat of replica_b expire; heartbeats are Unix milliseconds.
+        let query = indoc! {"
+            UPDATE wallet_indexer_replicas
+            SET last_heartbeat = last_heartbeat - 120000
+            WHERE id = $1
+        "};
+        sqlx::query(query).bind(replica_b).execute(&*pool).await?;
+        assert_eq!(storage.live_replica_ids(ttl).await?, vec![replica_a]);
+
+        let (replica_count,): (i64,) =
+            sqlx::query_as("SELECT COUNT(*) FROM wallet_indexer_replicas")
+                .fetch_one(&*pool)
+                .await?;
+        assert_eq!(replica_count, 1, "expired replica is deleted");
+
+        storage.delete_replica(replica_a).await?;
+        assert!(storage.live_replica_ids(ttl).await?.is_empty());
+
+        Ok(())
+    }
+
+    /// Viewing keys encrypted with a retired key are re-encrypted with the current one, after which
+    /// the retired key is no longer needed.
+    #[tokio::test]
+    async fn reencrypt_viewing_keys_with_current_key() -> Result<(), Box<dyn StdError>> {
+        let (storage, pool) = new_storage().await?;
+
+        let viewing_key = ViewingKey::from([1u8; 32]);
+        let mut tx = pool.begin().await?;
+        storage
+            .save_relevant_transactions(&viewing_key, &[], 0, None, &mut tx)
+            .await?;
+        tx.commit().await?;
+        let (id,): (Uuid,) = sqlx::query_as("SELECT id FROM wallets")
+            .fetch_one(&*pool)
+            .await?;
+
+        // Rotate the secret key, retiring key 0.
+        let cipher_0 = ChaCha20Poly1305::new(Key::from_slice(&[0u8; 32]));
+        let cipher_1 = ChaCha20Poly1305::new(Key::from_slice(&[1u8; 32]));
+        let keyring = Keyring::new(1, cipher_1.clone()).with_retired_key(0, cipher_0)?;
+        let storage = Storage::new(keyring, pool.clone());
+
+        let mut tx = pool.begin().await?;
+        let wallet = storage.get_wallet_by_id(id, &mut tx).await?;
+        tx.commit().await?;
+        assert_eq!(wallet.viewing_key, viewing_key);
+
+        assert_eq!(storage.reencrypt_viewing_keys(NonZeroUsize::MIN).await?, 1);
+        assert_eq!(storage.reencrypt_viewing_keys(NonZeroUsize::MIN).await?, 0);
+
+        // Drop the retired key.
+        let storage = Storage::new(Keyring::new(1, cipher_1), pool.clone());
+
+        let mut tx = pool.begin().await?;
+        let wallet = storage.get_wallet_by_id(id, &mut tx).await?;
+        tx.commit().await?;
+        assert_eq!(wallet.viewing_key, viewing_key);
+
+        Ok(())
+    }
+
+    /// Webhook secrets encrypted directly with a retired key, i.e. before envelope encryption, get
+    /// a data key wrapped with the current key; ones with an unknown key do not stall the others.
+    #[tokio::test]
+    async fn reencrypt_webhook_secrets_with_current_key() -> Result<(), Box<dyn StdError>> {
+        let (_, pool) = new_storage().await?;
+
+        let cipher_0 = ChaCha20Poly1305::new(Key::from_slice(&[0u8; 32]));
+        let cipher_1 = ChaCha20Poly1305::new(Key::from_slice(&[1u8; 32]));
+
+        // Webhook 1 with unknown key 7, webhook 2 with retired key 0.
+        for (id, encryption_key_id) in [(1i64, 7i64), (2, 0)] {
+            let key = [id as u8; 32];
+            let payload = Payload {
+                msg: b"secret",
+                aad: &key,
+            };
+            let mut secret = vec![0u8; 12];
+            secret.extend(cipher_0.encrypt(Nonce::from_slice(&[0u8; 12]), payload)?);
+
+            let query = indoc! {"
+                INSERT INTO webhooks (
+                    id, key, url, secret, transaction_id, created_at, encryption_key_id
+                )
+                VALUES ($1, $2, 'https://example.com', $3, 1, $4, $5)
+            "};
+            sqlx::query(query)
+                .bind(id)
+                .bind(key.as_slice())
+                .bind(secret)
+                .bind(OffsetDateTime::now_utc())
+                .bind(encryption_key_id)
+                .execute(&*pool)
+                .await?;
+        }
+
+        let keyring = Keyring::new(1, cipher_1.clone()).with_retired_key(0, cipher_0)?;
+        let storage = Storage::new(keyring, pool.clone());
+        assert_eq!(
+            storage.reencrypt_webhook_secrets(NonZeroUsize::MIN).await?,
+            1
+        );
+        assert_eq!(
+            storage.reencrypt_webhook_secrets(NonZeroUsize::MIN).await?,
+            0
+        );
+
+        let (secret, encryption_key_id, data_key): (Vec<u8>, i64, Option<Vec<u8>>) =
+            sqlx::query_as("SELECT secret, encryption_key_id, data_key FROM webhooks WHERE id = 2")
+                .fetch_one(&*pool)
+                .await?;
+        assert_eq!(encryption_key_id, 1);
+
+        // Decryptable without the retired key.
+        let key = [2u8; 32];
+        let cipher = Keyring::new(1, cipher_1).data_key_cipher(1, data_key.as_deref(), &key)?;
+        let payload = Payload {
+            msg: &secret[12..],
+            aad: &key,
+        };
+        assert_eq!(
+            cipher.decrypt(Nonce::from_slice(&secret[..12]), payload)?,
+            b"secret"
+        );
+
+        let (encryption_key_id,): (i64,) =
+            sqlx::query_as("SELECT encryption_key_id FROM webhooks WHERE id = 1")
+                .fetch_one(&*pool)
+                .await?;
+        assert_eq!(
+            encryption_key_id, 7,
+            "webhook with unknown key is left alone"
+        );
+
+        Ok(())
+    }
 }
 
 impl TryFrom<(Wallet, &ChaCha20Poly1305)> for domain::Wallet {
@@ -611,6 +1263,8 @@ impl TryFrom<(Wallet, &ChaCha20Poly1305)> for domain::Wallet {
         let Wallet {
             id,
             viewing_key,
+            encryption_key_id: _,
+            data_key: _,
             wanted_start_index,
             first_indexed_transaction_id,
             last_indexed_transaction_id,
@@ -40,7 +40,7 @@ async fn main() {
 async fn run() -> anyhow::Result<()> {
     use anyhow::Context;
     use indexer_common::{
-        cipher::make_cipher,
+        cipher::{KeyProvider, LocalKeyProvider},
         config::ConfigExt,
         infra::{ledger_db, migrations, pool, pub_sub},
         telemetry,
@@ -67,7 +67,7 @@ async fn run() -> anyhow::Result<()> {
         storage_config,
         ledger_db_config,
         pub_sub_config,
-        secret,
+        cipher_config,
     } = infra_config;
 
     let sigterm = signal(SignalKind::terminate()).expect("SIGTERM handler can be registered");
@@ -84,8 +84,11 @@ async fn run() -> anyhow::Result<()> {
             .context("run Postgres migrations")?;
     }
 
-    let cipher = make_cipher(secret).context("make cipher")?;
-    let storage = infra::storage::Storage::new(cipher, pool.clone());
+    let keyring = LocalKeyProvider::new(cipher_config)
+        .keyring()
+        .await
+        .context("make keyring")?;
+    let storage = infra::storage::Storage::new(keyring, pool.clone());
 
     ledger_db::init(ledger_db_config, pool);
 